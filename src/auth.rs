@@ -1,8 +1,15 @@
 //!
-//! Authentication for LLM backends (Vertex GCP OAuth2, Bearer token for other providers).
+//! Authentication for LLM backends (Vertex GCP OAuth2, AWS SigV4 for Bedrock,
+//! Bearer token for other providers).
 //!
 //! [RequestAuth] is the unified type used by the server; it is built from the
-//! provider's [crate::provider::AuthStrategy].
+//! provider's [crate::provider::AuthStrategy]. GCP tokens come from a service
+//! account key, from the metadata server with Workload Identity on GKE / Cloud
+//! Run, or from Application Default Credentials (a `gcloud` user login or key). Bedrock requests carry no token; each one is signed by
+//! [aws_sigv4::AwsSigV4Provider] when it is sent (see [Authorization]).
+//!
+//! Also provides helpers for the proxy's own client API keys: generation of
+//! random keys and the SHA-256 hashing used to store them in configuration.
 //!
 //! Authors:
 //!   Jaro <yarenty@gmail.com>
@@ -11,28 +18,56 @@
 
 /* --- uses ------------------------------------------------------------------------------------ */
 
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use hyper_util::client::legacy::connect::HttpConnector;
 use tokio::sync::Mutex;
 use yup_oauth2::authenticator::Authenticator;
+use rand::TryRngCore;
+use rand::rngs::OsRng;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
 use yup_oauth2::{ServiceAccountAuthenticator, ServiceAccountKey as OAuthKey, hyper_rustls};
 
 use crate::config::ServiceAccountKey;
 use crate::error::{ProxyError, Result};
 use crate::provider::AuthStrategy;
 
+pub mod aws_sigv4;
+pub mod secret_manager;
+
+use aws_sigv4::AwsSigV4Provider;
+
 /* --- request auth (provider-agnostic) -------------------------------------------------------- */
 
 ///
-/// Unified auth for outgoing LLM requests: GCP OAuth2 or static Bearer token.
+/// Unified auth for outgoing LLM requests: GCP OAuth2, AWS SigV4 or static Bearer token.
 ///
 /// Built from [AuthStrategy]; the server uses this to attach the correct header.
+#[derive(Clone)]
 pub enum RequestAuth {
     /// Google Cloud OAuth2 (Vertex AI).
     Gcp(Arc<GcpAuthProvider>),
     /// Static Bearer token (e.g. OpenAI-compatible, Mistral).
     Bearer(String),
+    /// AWS Signature Version 4 (Bedrock).
+    AwsSigV4(Arc<AwsSigV4Provider>),
+}
+
+///
+/// Credentials of one upstream request, resolved before it is sent.
+#[derive(Clone)]
+pub enum Authorization {
+    /// Value of the `Authorization` header (e.g. `Bearer <token>`).
+    Header(String),
+    /// Value of the `api-key` header (Azure OpenAI).
+    ApiKey(String),
+    /// Signer computing the headers from the request URL and body.
+    AwsSigV4(Arc<AwsSigV4Provider>),
 }
 
 impl RequestAuth {
@@ -40,23 +75,81 @@ impl RequestAuth {
     /// Build [RequestAuth] from the provider's auth strategy.
     pub async fn from_strategy(strategy: &AuthStrategy) -> Result<Self> {
         match strategy {
-            AuthStrategy::GcpOAuth2(key) => {
-                let provider = GcpAuthProvider::new(key).await?;
+            AuthStrategy::GcpOAuth2(_)
+            | AuthStrategy::WorkloadIdentity { .. }
+            | AuthStrategy::ApplicationDefault { .. } => {
+                let provider = GcpAuthProvider::new(strategy).await?;
                 Ok(Self::Gcp(Arc::new(provider)))
             }
             AuthStrategy::BearerToken(token) => Ok(Self::Bearer(token.clone())),
+            AuthStrategy::AwsSigV4 { region, access_key, secret_key, session_token } => {
+                let provider =
+                    AwsSigV4Provider::new(region, access_key, secret_key, session_token.as_deref());
+                Ok(Self::AwsSigV4(Arc::new(provider)))
+            }
         }
     }
 
     ///
-    /// Return the value for the `Authorization` header (e.g. `Bearer <token>`).
-    pub async fn authorization_header_value(&self) -> Result<String> {
+    /// Resolve the credentials of the next request, fetching a GCP token when needed.
+    pub async fn authorization(&self) -> Result<Authorization> {
         match self {
             Self::Gcp(gcp) => {
                 let token = gcp.get_access_token().await?;
-                Ok(format!("Bearer {}", token))
+                Ok(Authorization::Header(format!("Bearer {}", token)))
             }
-            Self::Bearer(t) => Ok(format!("Bearer {}", t)),
+            Self::Bearer(t) => Ok(Authorization::Header(format!("Bearer {}", t))),
+            Self::AwsSigV4(signer) => Ok(Authorization::AwsSigV4(signer.clone())),
+        }
+    }
+}
+
+impl Authorization {
+    ///
+    /// Add the authentication headers to a POST request.
+    ///
+    /// # Arguments
+    ///  * `request` - POST request to authenticate
+    ///  * `url` - request URL
+    ///  * `body` - exact body the request is sent with
+    ///
+    /// # Returns
+    ///  * Request with the `Authorization` (and for SigV4 the `x-amz-*`) headers
+    ///  * `ProxyError::Auth` if the request cannot be signed
+    pub fn apply(
+        &self,
+        request: reqwest::RequestBuilder,
+        url: &str,
+        body: &[u8],
+    ) -> Result<reqwest::RequestBuilder> {
+        match self {
+            Self::Header(value) => Ok(request.header(reqwest::header::AUTHORIZATION, value)),
+            Self::ApiKey(value) => Ok(request.header(API_KEY_HEADER, value)),
+            Self::AwsSigV4(signer) => Ok(signer
+                .sign("POST", url, body)?
+                .into_iter()
+                .fold(request, |request, (name, value)| request.header(name, value))),
+        }
+    }
+
+    ///
+    /// Value of the `Authorization` header.
+    ///
+    /// # Returns
+    ///  * Header value
+    ///  * `ProxyError::Auth` for AWS SigV4, which signs each request separately,
+    ///    and for `api-key` credentials
+    pub fn header_value(self) -> Result<String> {
+        match self {
+            Self::Header(value) => Ok(value),
+            Self::ApiKey(_) => Err(ProxyError::Auth(
+                "API key credentials are sent in the api-key header, not Authorization".to_string(),
+            )),
+            Self::AwsSigV4(_) => Err(ProxyError::Auth(
+                "AWS SigV4 requests are signed one by one and have no static Authorization \
+                 header"
+                    .to_string(),
+            )),
         }
     }
 }
@@ -67,10 +160,78 @@ impl RequestAuth {
 /// Google Cloud Platform authentication provider.
 ///
 /// Manages OAuth2 authentication flow for accessing Vertex AI services using
-/// service account credentials. Handles token generation and refresh automatically.
+/// service account credentials, Workload Identity or Application Default
+/// Credentials. Handles token generation and refresh automatically.
 pub struct GcpAuthProvider {
-    /** the OAuth2 authenticator instance for token management */
-    authenticator: Arc<Mutex<ServiceAccountAuth>>,
+    /** where access tokens come from */
+    source: TokenSource,
+}
+
+///
+/// Source of GCP access tokens.
+enum TokenSource {
+    /** the OAuth2 authenticator instance of a service account key */
+    ServiceAccount(Arc<Mutex<ServiceAccountAuth>>),
+    /** the metadata server token endpoint (Workload Identity) */
+    Metadata(MetadataTokenSource),
+    /** the OAuth2 refresh token of a `gcloud` user login (Application Default Credentials) */
+    RefreshToken(RefreshTokenSource),
+}
+
+///
+/// Access tokens fetched from the metadata server, cached until shortly before they expire.
+struct MetadataTokenSource {
+    /** HTTP client for the token endpoint */
+    client: reqwest::Client,
+    /** token endpoint URL */
+    token_url: String,
+    /** audience sent with the token request, empty for none */
+    audience: String,
+    /** last token and when it expires */
+    cached: Mutex<Option<(String, Instant)>>,
+}
+
+///
+/// Access tokens obtained with an OAuth2 refresh token, cached until shortly before they expire.
+struct RefreshTokenSource {
+    /** HTTP client for the token endpoint */
+    client: reqwest::Client,
+    /** token endpoint URL */
+    token_url: String,
+    /** OAuth2 client of the user credentials */
+    client_id: String,
+    /** secret of the OAuth2 client */
+    client_secret: String,
+    /** long-lived refresh token */
+    refresh_token: String,
+    /** last token and when it expires */
+    cached: Mutex<Option<(String, Instant)>>,
+}
+
+///
+/// `authorized_user` Application Default Credentials written by
+/// `gcloud auth application-default login`.
+#[derive(Deserialize)]
+struct AuthorizedUserCredentials {
+    /** OAuth2 client identifier */
+    client_id: String,
+    /** OAuth2 client secret */
+    client_secret: String,
+    /** refresh token of the user login */
+    refresh_token: String,
+    /** token endpoint, when not the Google default */
+    #[serde(default)]
+    token_uri: Option<String>,
+}
+
+///
+/// Token endpoint response of the metadata server or the OAuth2 token endpoint.
+#[derive(Deserialize)]
+struct TokenResponse {
+    /** OAuth2 access token */
+    access_token: String,
+    /** seconds until the token expires */
+    expires_in: u64,
 }
 
 /* --- constants ------------------------------------------------------------------------------ */
@@ -78,6 +239,40 @@ pub struct GcpAuthProvider {
 /** Google Cloud Platform scope for accessing cloud services */
 const CLOUD_PLATFORM_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
 
+/** Service account token endpoint of the GKE / Cloud Run metadata server */
+const METADATA_TOKEN_URL: &str =
+    "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+
+/** Header the metadata server requires on every request */
+const METADATA_FLAVOR_HEADER: &str = "Metadata-Flavor";
+
+/** Value of the `Metadata-Flavor` header */
+const METADATA_FLAVOR: &str = "Google";
+
+/** Timeout of a metadata server token request */
+const METADATA_TIMEOUT: Duration = Duration::from_secs(10);
+
+/** OAuth2 token endpoint used for refresh tokens without a `token_uri` */
+const GOOGLE_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+
+/** Environment variable naming the Application Default Credentials file */
+const GOOGLE_APPLICATION_CREDENTIALS_ENV: &str = "GOOGLE_APPLICATION_CREDENTIALS";
+
+/** Environment variable overriding the gcloud configuration directory */
+const CLOUDSDK_CONFIG_ENV: &str = "CLOUDSDK_CONFIG";
+
+/** File name of the Application Default Credentials in the gcloud configuration directory */
+const ADC_FILE_NAME: &str = "application_default_credentials.json";
+
+/** Metadata and refresh tokens are refreshed this long before they expire */
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+/** Number of random bytes in a generated client API key */
+const API_KEY_BYTES: usize = 32;
+
+/** Header carrying the API key of Azure OpenAI requests */
+const API_KEY_HEADER: &str = "api-key";
+
 /* --- start of code -------------------------------------------------------------------------- */
 
 // Type alias for the authenticator type returned by ServiceAccountAuthenticator::builder().build()
@@ -87,21 +282,53 @@ impl GcpAuthProvider {
     ///
     /// Create a new GCP authentication provider.
     ///
-    /// Initializes the OAuth2 authenticator with the provided service account
-    /// credentials. The authenticator will automatically handle token refresh
-    /// when needed.
+    /// With `GcpOAuth2` initializes the OAuth2 authenticator with the provided
+    /// service account credentials. With `WorkloadIdentity` tokens are fetched
+    /// from the metadata server (or the configured token URL) instead. With
+    /// `ApplicationDefault` the ADC file decides: a service account key is used
+    /// like `GcpOAuth2`, an `authorized_user` login through its refresh token.
+    /// Either way tokens are refreshed automatically when needed.
     ///
     /// # Arguments
-    ///  * `service_account_key` - Google Cloud service account credentials
+    ///  * `strategy` - `GcpOAuth2`, `WorkloadIdentity` or `ApplicationDefault` auth strategy
     ///
     /// # Returns
     ///  * New authentication provider instance
-    ///  * `ProxyError::Auth` if authenticator creation fails
-    pub async fn new(service_account_key: &ServiceAccountKey) -> Result<Self> {
-        let oauth_key = Self::convert_service_account_key(service_account_key);
-        let authenticator = Self::create_authenticator(oauth_key).await?;
+    ///  * `ProxyError::Auth` if authenticator creation fails or the strategy is not a GCP one
+    pub async fn new(strategy: &AuthStrategy) -> Result<Self> {
+        let source = match strategy {
+            AuthStrategy::GcpOAuth2(service_account_key) => {
+                let oauth_key = Self::convert_service_account_key(service_account_key);
+                let authenticator = Self::create_authenticator(oauth_key).await?;
+                TokenSource::ServiceAccount(Arc::new(Mutex::new(authenticator)))
+            }
+            AuthStrategy::WorkloadIdentity { audience, token_url } => {
+                let token_url = token_url.clone().unwrap_or_else(|| METADATA_TOKEN_URL.to_string());
+                tracing::info!(token_url = %token_url, "Using Workload Identity tokens");
+                let client =
+                    reqwest::Client::builder().timeout(METADATA_TIMEOUT).build().map_err(|e| {
+                        ProxyError::Auth(format!("Failed to create metadata client: {}", e))
+                    })?;
+                TokenSource::Metadata(MetadataTokenSource {
+                    client,
+                    token_url,
+                    audience: audience.clone(),
+                    cached: Mutex::new(None),
+                })
+            }
+            AuthStrategy::ApplicationDefault { credentials_file } => {
+                Self::application_default_source(credentials_file.as_deref()).await?
+            }
+            AuthStrategy::BearerToken(_) | AuthStrategy::AwsSigV4 { .. } => {
+                return Err(ProxyError::Auth(
+                    "GCP authentication needs a service account key, Workload Identity \
+                     or Application Default Credentials"
+                        .to_string(),
+                ));
+            }
+        };
 
-        Ok(Self { authenticator: Arc::new(Mutex::new(authenticator)) })
+        Ok(Self { source })
     }
 
     ///
@@ -115,8 +342,13 @@ impl GcpAuthProvider {
     ///  * Valid access token string
     ///  * `ProxyError::Auth` if token retrieval fails
     pub async fn get_access_token(&self) -> Result<String> {
+        let authenticator = match &self.source {
+            TokenSource::ServiceAccount(authenticator) => authenticator,
+            TokenSource::Metadata(metadata) => return metadata.get_access_token().await,
+            TokenSource::RefreshToken(refresh) => return refresh.get_access_token().await,
+        };
         let scopes = &[CLOUD_PLATFORM_SCOPE];
-        let guard = self.authenticator.lock().await;
+        let guard = authenticator.lock().await;
 
         let token = guard
             .token(scopes)
@@ -130,6 +362,64 @@ impl GcpAuthProvider {
             .map(|s| s.to_string())
     }
 
+    ///
+    /// Create the token source of an Application Default Credentials file.
+    ///
+    /// # Arguments
+    ///  * `credentials_file` - ADC file, `None` for the default location
+    ///
+    /// # Returns
+    ///  * Service account or refresh token source, depending on the file's `type`
+    ///  * `ProxyError::Auth` if the file cannot be read or has an unsupported type
+    async fn application_default_source(credentials_file: Option<&str>) -> Result<TokenSource> {
+        let path = match credentials_file {
+            Some(path) => PathBuf::from(path),
+            None => application_default_credentials_path()?,
+        };
+        let content = tokio::fs::read_to_string(&path).await.map_err(|e| {
+            ProxyError::Auth(format!(
+                "Failed to read Application Default Credentials {}: {}. \
+                 Run 'gcloud auth application-default login' or set {}.",
+                path.display(),
+                e,
+                GOOGLE_APPLICATION_CREDENTIALS_ENV
+            ))
+        })?;
+        let invalid = |e: serde_json::Error| {
+            ProxyError::Auth(format!(
+                "Invalid Application Default Credentials {}: {}",
+                path.display(),
+                e
+            ))
+        };
+        let credentials: serde_json::Value = serde_json::from_str(&content).map_err(invalid)?;
+
+        match credentials.get("type").and_then(serde_json::Value::as_str) {
+            Some("authorized_user") => {
+                let user: AuthorizedUserCredentials =
+                    serde_json::from_value(credentials).map_err(invalid)?;
+                tracing::info!(path = %path.display(), "Using Application Default Credentials (user)");
+                Ok(TokenSource::RefreshToken(RefreshTokenSource::new(user)?))
+            }
+            Some("service_account") => {
+                let oauth_key: OAuthKey = serde_json::from_value(credentials).map_err(invalid)?;
+                tracing::info!(
+                    path = %path.display(),
+                    client_email = %oauth_key.client_email,
+                    "Using Application Default Credentials (service account)"
+                );
+                let authenticator = Self::create_authenticator(oauth_key).await?;
+                Ok(TokenSource::ServiceAccount(Arc::new(Mutex::new(authenticator))))
+            }
+            other => Err(ProxyError::Auth(format!(
+                "Unsupported Application Default Credentials type {:?} in {}; \
+                 expected 'authorized_user' or 'service_account'",
+                other.unwrap_or_default(),
+                path.display()
+            ))),
+        }
+    }
+
     ///
     /// Convert internal service account key to OAuth2 library format.
     ///
@@ -177,3 +467,390 @@ impl GcpAuthProvider {
             .map_err(|e| ProxyError::Auth(format!("Failed to create authenticator: {}", e)))
     }
 }
+
+impl MetadataTokenSource {
+    ///
+    /// Get a valid access token, fetching a new one when the cached one is about to expire.
+    ///
+    /// # Returns
+    ///  * Valid access token string
+    ///  * `ProxyError::Auth` if the token endpoint cannot be reached or refuses the request
+    async fn get_access_token(&self) -> Result<String> {
+        // Held during the fetch so concurrent requests wait for one refresh
+        let mut cached = self.cached.lock().await;
+        if let Some((token, expires_at)) = cached.as_ref()
+            && Instant::now() + TOKEN_REFRESH_MARGIN < *expires_at
+        {
+            return Ok(token.clone());
+        }
+
+        let response = self.fetch_token().await?;
+        let expires_at = Instant::now() + Duration::from_secs(response.expires_in);
+        *cached = Some((response.access_token.clone(), expires_at));
+        Ok(response.access_token)
+    }
+
+    ///
+    /// Request a token from the token endpoint.
+    async fn fetch_token(&self) -> Result<TokenResponse> {
+        let mut url = reqwest::Url::parse(&self.token_url).map_err(|e| {
+            ProxyError::Auth(format!("Invalid metadata token URL '{}': {}", self.token_url, e))
+        })?;
+        if !self.audience.is_empty() {
+            url.query_pairs_mut().append_pair("audience", &self.audience);
+        }
+        let response = self
+            .client
+            .get(url)
+            .header(METADATA_FLAVOR_HEADER, METADATA_FLAVOR)
+            .send()
+            .await
+            .map_err(|e| {
+                ProxyError::Auth(format!(
+                    "Failed to reach metadata server {}: {}",
+                    self.token_url, e
+                ))
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ProxyError::Auth(format!(
+                "Metadata server returned {} for the access token: {}",
+                status, body
+            )));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| ProxyError::Auth(format!("Invalid metadata server token response: {}", e)))
+    }
+}
+
+impl RefreshTokenSource {
+    ///
+    /// Create a refresh token source for `authorized_user` credentials.
+    ///
+    /// # Arguments
+    ///  * `credentials` - parsed user credentials
+    ///
+    /// # Returns
+    ///  * New token source without a cached token
+    ///  * `ProxyError::Auth` if the HTTP client cannot be created
+    fn new(credentials: AuthorizedUserCredentials) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(METADATA_TIMEOUT)
+            .build()
+            .map_err(|e| ProxyError::Auth(format!("Failed to create token client: {}", e)))?;
+        Ok(Self {
+            client,
+            token_url: credentials.token_uri.unwrap_or_else(|| GOOGLE_TOKEN_URL.to_string()),
+            client_id: credentials.client_id,
+            client_secret: credentials.client_secret,
+            refresh_token: credentials.refresh_token,
+            cached: Mutex::new(None),
+        })
+    }
+
+    ///
+    /// Get a valid access token, refreshing it when the cached one is about to expire.
+    ///
+    /// # Returns
+    ///  * Valid access token string
+    ///  * `ProxyError::Auth` if the token endpoint cannot be reached or refuses the refresh token
+    async fn get_access_token(&self) -> Result<String> {
+        // Held during the fetch so concurrent requests wait for one refresh
+        let mut cached = self.cached.lock().await;
+        if let Some((token, expires_at)) = cached.as_ref()
+            && Instant::now() + TOKEN_REFRESH_MARGIN < *expires_at
+        {
+            return Ok(token.clone());
+        }
+
+        let response = self.fetch_token().await?;
+        let expires_at = Instant::now() + Duration::from_secs(response.expires_in);
+        *cached = Some((response.access_token.clone(), expires_at));
+        Ok(response.access_token)
+    }
+
+    ///
+    /// Exchange the refresh token for an access token.
+    async fn fetch_token(&self) -> Result<TokenResponse> {
+        let response = self
+            .client
+            .post(&self.token_url)
+            .json(&serde_json::json!({
+                "grant_type": "refresh_token",
+                "client_id": self.client_id,
+                "client_secret": self.client_secret,
+                "refresh_token": self.refresh_token,
+            }))
+            .send()
+            .await
+            .map_err(|e| {
+                ProxyError::Auth(format!(
+                    "Failed to reach token endpoint {}: {}",
+                    self.token_url, e
+                ))
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ProxyError::Auth(format!(
+                "Token endpoint returned {} for the refresh token: {}",
+                status, body
+            )));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| ProxyError::Auth(format!("Invalid token endpoint response: {}", e)))
+    }
+}
+
+///
+/// Location of the Application Default Credentials file.
+///
+/// `GOOGLE_APPLICATION_CREDENTIALS` when set, otherwise the file written by
+/// `gcloud auth application-default login`: `%APPDATA%\gcloud\` on Windows,
+/// `~/.config/gcloud/` elsewhere, or `$CLOUDSDK_CONFIG` when that is set.
+///
+/// # Returns
+///  * Path of the ADC file (not checked for existence)
+///  * `ProxyError::Auth` if neither variable is set and there is no home directory
+fn application_default_credentials_path() -> Result<PathBuf> {
+    let env_path =
+        |name| std::env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+    if let Some(path) = env_path(GOOGLE_APPLICATION_CREDENTIALS_ENV) {
+        return Ok(path);
+    }
+    let gcloud_dir = env_path(CLOUDSDK_CONFIG_ENV).or_else(|| {
+        if cfg!(windows) {
+            env_path("APPDATA").map(|dir| dir.join("gcloud"))
+        } else {
+            env_path("HOME").map(|dir| dir.join(".config").join("gcloud"))
+        }
+    });
+    gcloud_dir.map(|dir| dir.join(ADC_FILE_NAME)).ok_or_else(|| {
+        ProxyError::Auth(format!(
+            "Cannot locate Application Default Credentials: set {} or HOME",
+            GOOGLE_APPLICATION_CREDENTIALS_ENV
+        ))
+    })
+}
+
+/* --- client API keys ------------------------------------------------------------------------- */
+
+///
+/// Generate a new random client API key.
+///
+/// Uses the operating system's CSPRNG for 32 random bytes, encoded as
+/// unpadded base64url so the key is safe to use in headers and URLs.
+///
+/// # Returns
+///  * Plaintext API key
+///  * `ProxyError::Auth` if the system random source is unavailable
+pub fn generate_api_key() -> Result<String> {
+    let mut bytes = [0u8; API_KEY_BYTES];
+    OsRng
+        .try_fill_bytes(&mut bytes)
+        .map_err(|e| ProxyError::Auth(format!("Failed to generate random API key: {}", e)))?;
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+///
+/// Hash a client API key for storage in configuration.
+///
+/// # Arguments
+///  * `api_key` - plaintext API key
+///
+/// # Returns
+///  * Lowercase hex SHA-256 digest of the key
+pub fn hash_api_key(api_key: &str) -> String {
+    format!("{:x}", Sha256::digest(api_key.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::extract::{Query, State};
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::response::IntoResponse;
+    use axum::routing::{get, post};
+
+    use super::*;
+
+    /// Serve a mock metadata token endpoint, returning its token URL and request counter
+    async fn mock_metadata(expires_in: u64) -> (String, Arc<AtomicUsize>) {
+        async fn token(
+            State((calls, expires_in)): State<(Arc<AtomicUsize>, u64)>,
+            headers: HeaderMap,
+            Query(query): Query<HashMap<String, String>>,
+        ) -> axum::response::Response {
+            if headers.get(METADATA_FLAVOR_HEADER).is_none_or(|v| v != METADATA_FLAVOR) {
+                return StatusCode::FORBIDDEN.into_response();
+            }
+            let n = calls.fetch_add(1, Ordering::SeqCst) + 1;
+            let audience = query.get("audience").map_or("none", String::as_str);
+            axum::Json(serde_json::json!({
+                "access_token": format!("token-{}-{}", audience, n),
+                "expires_in": expires_in,
+                "token_type": "Bearer"
+            }))
+            .into_response()
+        }
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app =
+            axum::Router::new().route("/token", get(token)).with_state((calls.clone(), expires_in));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}/token", addr), calls)
+    }
+
+    fn workload_identity(audience: &str, token_url: String) -> AuthStrategy {
+        AuthStrategy::WorkloadIdentity {
+            audience: audience.to_string(),
+            token_url: Some(token_url),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_workload_identity_token_is_cached() {
+        let (token_url, calls) = mock_metadata(3600).await;
+        let provider = GcpAuthProvider::new(&workload_identity("", token_url)).await.unwrap();
+
+        assert_eq!(provider.get_access_token().await.unwrap(), "token-none-1");
+        assert_eq!(provider.get_access_token().await.unwrap(), "token-none-1");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_workload_identity_token_refreshed_before_expiry() {
+        let (token_url, calls) = mock_metadata(30).await;
+        let auth =
+            RequestAuth::from_strategy(&workload_identity("my-pool", token_url)).await.unwrap();
+
+        let header = || async { auth.authorization().await.unwrap().header_value().unwrap() };
+        assert_eq!(header().await, "Bearer token-my-pool-1");
+        assert_eq!(header().await, "Bearer token-my-pool-2");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_workload_identity_token_error() {
+        let (token_url, _) = mock_metadata(3600).await;
+        let missing = token_url.replace("/token", "/missing");
+        let provider = GcpAuthProvider::new(&workload_identity("", missing)).await.unwrap();
+
+        let error = provider.get_access_token().await.unwrap_err();
+        assert!(matches!(error, ProxyError::Auth(_)));
+        assert!(error.to_string().contains("404"));
+    }
+
+    /// Serve a mock OAuth2 token endpoint accepting one refresh token
+    async fn mock_token_endpoint() -> (String, Arc<AtomicUsize>) {
+        async fn token(
+            State(calls): State<Arc<AtomicUsize>>,
+            axum::Json(body): axum::Json<serde_json::Value>,
+        ) -> axum::response::Response {
+            if body["grant_type"] != "refresh_token"
+                || body["client_id"] != "client-id"
+                || body["client_secret"] != "client-secret"
+                || body["refresh_token"] != "refresh-token"
+            {
+                return (StatusCode::BAD_REQUEST, r#"{"error":"invalid_grant"}"#).into_response();
+            }
+            let n = calls.fetch_add(1, Ordering::SeqCst) + 1;
+            axum::Json(serde_json::json!({
+                "access_token": format!("user-token-{}", n),
+                "expires_in": 3599,
+                "token_type": "Bearer"
+            }))
+            .into_response()
+        }
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = axum::Router::new().route("/token", post(token)).with_state(calls.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}/token", addr), calls)
+    }
+
+    fn application_default(file: &tempfile::NamedTempFile) -> AuthStrategy {
+        AuthStrategy::ApplicationDefault {
+            credentials_file: Some(file.path().to_string_lossy().into_owned()),
+        }
+    }
+
+    fn adc_file(credentials: serde_json::Value) -> tempfile::NamedTempFile {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), credentials.to_string()).unwrap();
+        file
+    }
+
+    #[tokio::test]
+    async fn test_application_default_authorized_user_refresh_token() {
+        let (token_url, calls) = mock_token_endpoint().await;
+        let file = adc_file(serde_json::json!({
+            "type": "authorized_user",
+            "client_id": "client-id",
+            "client_secret": "client-secret",
+            "refresh_token": "refresh-token",
+            "token_uri": token_url
+        }));
+        let auth = RequestAuth::from_strategy(&application_default(&file)).await.unwrap();
+
+        let header = || async { auth.authorization().await.unwrap().header_value().unwrap() };
+        assert_eq!(header().await, "Bearer user-token-1");
+        assert_eq!(header().await, "Bearer user-token-1");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let revoked = adc_file(serde_json::json!({
+            "type": "authorized_user",
+            "client_id": "client-id",
+            "client_secret": "client-secret",
+            "refresh_token": "revoked",
+            "token_uri": token_url
+        }));
+        let provider = GcpAuthProvider::new(&application_default(&revoked)).await.unwrap();
+        let error = provider.get_access_token().await.unwrap_err();
+        assert!(error.to_string().contains("invalid_grant"));
+    }
+
+    #[tokio::test]
+    async fn test_application_default_rejects_unknown_type() {
+        let file = adc_file(serde_json::json!({"type": "external_account"}));
+        let error = GcpAuthProvider::new(&application_default(&file)).await.err().unwrap();
+        assert!(error.to_string().contains("external_account"));
+
+        let missing = AuthStrategy::ApplicationDefault {
+            credentials_file: Some("/nonexistent/adc.json".to_string()),
+        };
+        let error = GcpAuthProvider::new(&missing).await.err().unwrap();
+        assert!(error.to_string().contains("gcloud auth application-default login"));
+    }
+
+    #[test]
+    fn test_generate_api_key_is_unique_base64url() {
+        let first = generate_api_key().unwrap();
+        let second = generate_api_key().unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(URL_SAFE_NO_PAD.decode(&first).unwrap().len(), API_KEY_BYTES);
+        assert!(first.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    }
+
+    #[test]
+    fn test_hash_api_key_is_sha256_hex() {
+        assert_eq!(
+            hash_api_key("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//!
+//! Service account keys stored in GCP Secret Manager.
+//!
+//! With `auth.secret_manager_resource` the key is not read from disk or the
+//! environment: at startup the secret version is fetched from the Secret
+//! Manager REST API, authenticated with an access token of the metadata
+//! server (the workload's own identity on GKE, Cloud Run or Compute Engine).
+//! The key is fetched once per process and reused by configuration reloads.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::Deserialize;
+
+use super::GcpAuthProvider;
+use crate::config::{AuthConfig, ServiceAccountKey};
+use crate::error::{ProxyError, Result};
+use crate::provider::AuthStrategy;
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// Response of the Secret Manager `access` method.
+#[derive(Deserialize)]
+struct AccessSecretVersionResponse {
+    /** secret payload */
+    payload: SecretPayload,
+}
+
+///
+/// Payload of a secret version.
+#[derive(Deserialize)]
+struct SecretPayload {
+    /** base64-encoded secret data */
+    data: String,
+}
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** Secret Manager REST API used when `auth.secret_manager_endpoint` is unset */
+const DEFAULT_SECRET_MANAGER_ENDPOINT: &str = "https://secretmanager.googleapis.com";
+
+/** Timeout of the Secret Manager request */
+const SECRET_MANAGER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+/** Key fetched by this process, with the secret version it came from */
+static FETCHED_KEY: OnceLock<(String, ServiceAccountKey)> = OnceLock::new();
+
+///
+/// Service account key of `auth.secret_manager_resource`.
+///
+/// Blocks until the key is fetched; the request runs on its own thread and
+/// runtime so this can be called from synchronous configuration loading,
+/// inside or outside a Tokio runtime. Later calls for the same secret version
+/// return the key fetched first.
+///
+/// # Arguments
+///  * `auth` - auth section; `workload_identity.token_url` overrides the
+///    metadata server token endpoint
+///  * `resource` - secret version, e.g. `projects/p/secrets/sa-key/versions/latest`
+///
+/// # Returns
+///  * Parsed service account key
+///  * `ProxyError::Auth` if the metadata server or Secret Manager cannot be
+///    reached, refuses the request, or the secret is not a service account key
+pub fn load_service_account_key(auth: &AuthConfig, resource: &str) -> Result<ServiceAccountKey> {
+    if let Some((cached_resource, key)) = FETCHED_KEY.get()
+        && cached_resource == resource
+    {
+        return Ok(key.clone());
+    }
+
+    let endpoint = auth
+        .secret_manager_endpoint
+        .clone()
+        .unwrap_or_else(|| DEFAULT_SECRET_MANAGER_ENDPOINT.to_string());
+    let token_url = auth.workload_identity.token_url.clone();
+    let owned_resource = resource.to_string();
+    let key = std::thread::spawn(move || {
+        let runtime =
+            tokio::runtime::Builder::new_current_thread().enable_all().build().map_err(|e| {
+                ProxyError::Auth(format!("Failed to start Secret Manager client: {}", e))
+            })?;
+        runtime.block_on(fetch_service_account_key(&endpoint, &owned_resource, token_url))
+    })
+    .join()
+    .map_err(|_| ProxyError::Auth("Secret Manager client thread panicked".to_string()))??;
+
+    let _ = FETCHED_KEY.set((resource.to_string(), key.clone()));
+    Ok(key)
+}
+
+///
+/// Fetch a secret version and parse it as a service account key.
+///
+/// # Arguments
+///  * `endpoint` - Secret Manager REST API base URL
+///  * `resource` - secret version name
+///  * `token_url` - metadata server token endpoint (None = the default one)
+///
+/// # Returns
+///  * Parsed service account key
+///  * `ProxyError::Auth` if any step fails, naming the step
+async fn fetch_service_account_key(
+    endpoint: &str,
+    resource: &str,
+    token_url: Option<String>,
+) -> Result<ServiceAccountKey> {
+    let metadata = AuthStrategy::WorkloadIdentity { audience: String::new(), token_url };
+    let token = GcpAuthProvider::new(&metadata).await?.get_access_token().await.map_err(|e| {
+        ProxyError::Auth(format!(
+            "Cannot read secret '{}': no access token from the metadata server ({}). \
+             Secret Manager needs the workload's identity on GKE, Cloud Run or Compute \
+             Engine; elsewhere set auth.service_account_file or auth.service_account_json.",
+            resource, e
+        ))
+    })?;
+
+    let url = format!("{}/v1/{}:access", endpoint.trim_end_matches('/'), resource);
+    let client = reqwest::Client::builder()
+        .timeout(SECRET_MANAGER_TIMEOUT)
+        .build()
+        .map_err(|e| ProxyError::Auth(format!("Failed to create Secret Manager client: {}", e)))?;
+    let response = client.get(&url).bearer_auth(token).send().await.map_err(|e| {
+        ProxyError::Auth(format!("Failed to reach Secret Manager at {}: {}", url, e))
+    })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(ProxyError::Auth(format!(
+            "Secret Manager returned {} for '{}': {}",
+            status, resource, body
+        )));
+    }
+    let version: AccessSecretVersionResponse = response.json().await.map_err(|e| {
+        ProxyError::Auth(format!("Invalid Secret Manager response for '{}': {}", resource, e))
+    })?;
+    let data = STANDARD.decode(version.payload.data.trim()).map_err(|e| {
+        ProxyError::Auth(format!("Secret '{}' payload is not valid base64: {}", resource, e))
+    })?;
+    serde_json::from_slice(&data).map_err(|e| {
+        ProxyError::Auth(format!("Secret '{}' is not a service account key JSON: {}", resource, e))
+    })
+}
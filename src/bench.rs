@@ -0,0 +1,488 @@
+//!
+//! End-to-end latency benchmark (`modelmux bench`).
+//!
+//! Sends a fixed number of identical, minimal streaming chat completion
+//! requests at a fixed concurrency, either to the running proxy on
+//! `server.port` or directly to the configured upstream (bypassing the proxy
+//! HTTP layer), and reports time-to-first-token and time-to-last-token
+//! percentiles, throughput and error rate.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::fmt::Write;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use futures::StreamExt;
+use serde::Serialize;
+use serde_json::{Value, json};
+use tokio::task::JoinSet;
+
+use crate::auth::{Authorization, RequestAuth};
+use crate::config::Config;
+use crate::converter::OllamaConverter;
+use crate::converter::openai_to_anthropic::{OpenAiRequest, OpenAiToAnthropicConverter};
+use crate::error::{ProxyError, Result};
+use crate::provider::{LlmProviderBackend, LlmProviderConfig};
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// Options of `modelmux bench`.
+#[derive(Debug, Clone)]
+pub struct BenchOptions {
+    /// Number of requests to send
+    pub requests: usize,
+    /// Number of requests in flight at a time
+    pub concurrency: usize,
+    /// Model sent in the request body (None = the configured default)
+    pub model: Option<String>,
+    /// Send requests to the configured upstream instead of the running proxy
+    pub direct: bool,
+    /// Bearer token for the proxy (None = `auth.proxy_api_key`)
+    pub api_key: Option<String>,
+    /// Output format of the results
+    pub output: BenchOutput,
+    /// Print dummy results without sending any request
+    pub dry_run: bool,
+}
+
+///
+/// Output format of the benchmark results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BenchOutput {
+    /// Human-readable table
+    #[default]
+    Table,
+    /// JSON document
+    Json,
+}
+
+///
+/// Timing of one benchmark request.
+#[derive(Debug, Clone, Copy)]
+pub struct BenchSample {
+    /** time until the first content delta (None if none arrived) */
+    pub first_token: Option<Duration>,
+    /** time until the response was complete */
+    pub last_token: Duration,
+    /** whether the request succeeded */
+    pub success: bool,
+}
+
+///
+/// Latency percentiles in milliseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct LatencyStats {
+    /** median */
+    pub p50_ms: f64,
+    /** 95th percentile */
+    pub p95_ms: f64,
+    /** 99th percentile */
+    pub p99_ms: f64,
+    /** slowest request */
+    pub max_ms: f64,
+}
+
+///
+/// Results of a benchmark run.
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    /** where the requests were sent */
+    pub target: String,
+    /** requests sent */
+    pub requests: usize,
+    /** requests in flight at a time */
+    pub concurrency: usize,
+    /** failed requests */
+    pub errors: usize,
+    /** failed requests / requests */
+    pub error_rate: f64,
+    /** wall-clock duration of the run in seconds */
+    pub duration_secs: f64,
+    /** completed requests per second */
+    pub throughput_rps: f64,
+    /** time to first token of successful requests */
+    pub time_to_first_token: LatencyStats,
+    /** time to last token of successful requests */
+    pub time_to_last_token: LatencyStats,
+}
+
+///
+/// Where and how the benchmark requests are sent.
+struct BenchTarget {
+    /** request URL */
+    url: String,
+    /** JSON request body */
+    body: Vec<u8>,
+    /** authorization of each request */
+    authorization: Option<Authorization>,
+}
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** Prompt of every benchmark request, fixed so runs are comparable */
+const BENCH_PROMPT: &str = "Count from 1 to 10, separated by spaces.";
+
+/** Output token limit of every benchmark request */
+const BENCH_MAX_TOKENS: u32 = 32;
+
+/** Timeout of a single benchmark request */
+const BENCH_REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/** Requests sent when `--requests` is not given */
+pub const DEFAULT_BENCH_REQUESTS: usize = 20;
+
+/** Concurrency when `--concurrency` is not given */
+pub const DEFAULT_BENCH_CONCURRENCY: usize = 4;
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            requests: DEFAULT_BENCH_REQUESTS,
+            concurrency: DEFAULT_BENCH_CONCURRENCY,
+            model: None,
+            direct: false,
+            api_key: None,
+            output: BenchOutput::default(),
+            dry_run: false,
+        }
+    }
+}
+
+impl FromStr for BenchOutput {
+    type Err = ProxyError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "table" => Ok(BenchOutput::Table),
+            "json" => Ok(BenchOutput::Json),
+            _ => Err(ProxyError::Config(
+                format!("Invalid bench output '{}'. Valid values: table, json", s),
+                None,
+            )),
+        }
+    }
+}
+
+///
+/// Run the benchmark.
+///
+/// # Arguments
+///  * `options` - benchmark options
+///
+/// # Returns
+///  * Benchmark results (dummy ones with `dry_run`)
+///  * `ProxyError` if the configuration cannot be loaded or the upstream
+///    credentials cannot be obtained
+pub async fn run(options: &BenchOptions) -> Result<BenchReport> {
+    if options.dry_run {
+        return Ok(dry_run_report(options));
+    }
+
+    let config = Config::load()?;
+    let target = if options.direct {
+        upstream_target(&config, options).await?
+    } else {
+        proxy_target(&config, options)?
+    };
+    let client = reqwest::Client::builder()
+        .timeout(BENCH_REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| ProxyError::Http(format!("Failed to create HTTP client: {}", e), None))?;
+
+    let target = Arc::new(target);
+    let next = Arc::new(AtomicUsize::new(0));
+    let started = Instant::now();
+    let mut workers = JoinSet::new();
+    for _ in 0..options.concurrency.clamp(1, options.requests.max(1)) {
+        let (client, target, next) = (client.clone(), target.clone(), next.clone());
+        let requests = options.requests;
+        workers.spawn(async move {
+            let mut samples = Vec::new();
+            while next.fetch_add(1, Ordering::Relaxed) < requests {
+                samples.push(send_request(&client, &target).await);
+            }
+            samples
+        });
+    }
+
+    let mut samples = Vec::with_capacity(options.requests);
+    while let Some(worker) = workers.join_next().await {
+        samples.extend(
+            worker
+                .map_err(|e| ProxyError::Http(format!("Benchmark worker failed: {}", e), None))?,
+        );
+    }
+    Ok(build_report(&target.url, options, &samples, started.elapsed()))
+}
+
+///
+/// Request to the running proxy's chat completions endpoint.
+fn proxy_target(config: &Config, options: &BenchOptions) -> Result<BenchTarget> {
+    let request = bench_request(options);
+    let api_key = options.api_key.clone().or_else(|| config.auth.proxy_api_key.clone());
+    Ok(BenchTarget {
+        url: format!("http://127.0.0.1:{}/v1/chat/completions", config.server.port),
+        body: serde_json::to_vec(&request)?,
+        authorization: api_key.map(|key| Authorization::Header(format!("Bearer {}", key))),
+    })
+}
+
+///
+/// Request to the configured upstream, converted like a proxied one.
+async fn upstream_target(config: &Config, options: &BenchOptions) -> Result<BenchTarget> {
+    let provider = config
+        .llm_provider
+        .as_ref()
+        .ok_or_else(|| ProxyError::Config("No LLM provider configured".to_string(), None))?;
+    let mut request = bench_request(options);
+    request["model"] = Value::String(provider.display_model_name().to_string());
+
+    let body = match provider {
+        LlmProviderConfig::Vertex(_) | LlmProviderConfig::Bedrock(_) => {
+            let request: OpenAiRequest = serde_json::from_value(request)?;
+            OpenAiToAnthropicConverter::new(config.server.log_level)
+                .convert(request, provider.target_format(), None)?
+                .to_request_body()?
+        }
+        LlmProviderConfig::OpenAiCompatible(_) | LlmProviderConfig::AzureOpenAi(_) => request,
+        LlmProviderConfig::Ollama(_) => {
+            let request: OpenAiRequest = serde_json::from_value(request)?;
+            serde_json::to_value(
+                OllamaConverter::new().convert_request(request, provider.display_model_name())?,
+            )?
+        }
+    };
+    let authorization = match provider.api_key_header() {
+        Some(api_key) => Authorization::ApiKey(api_key.to_string()),
+        None => RequestAuth::from_strategy(provider.auth_strategy()).await?.authorization().await?,
+    };
+    Ok(BenchTarget {
+        url: provider.build_request_url(true),
+        body: serde_json::to_vec(&body)?,
+        authorization: Some(authorization),
+    })
+}
+
+///
+/// The fixed OpenAI request of every benchmark run.
+fn bench_request(options: &BenchOptions) -> Value {
+    let mut request = json!({
+        "messages": [{"role": "user", "content": BENCH_PROMPT}],
+        "max_tokens": BENCH_MAX_TOKENS,
+        "temperature": 0,
+        "stream": true,
+    });
+    if let Some(model) = &options.model {
+        request["model"] = Value::String(model.clone());
+    }
+    request
+}
+
+///
+/// Send one request and time its first content delta and its end.
+async fn send_request(client: &reqwest::Client, target: &BenchTarget) -> BenchSample {
+    let started = Instant::now();
+    let failed = |started: Instant| BenchSample {
+        first_token: None,
+        last_token: started.elapsed(),
+        success: false,
+    };
+
+    let request = client
+        .post(&target.url)
+        .header("Content-Type", "application/json")
+        .header("Accept", "text/event-stream");
+    let request = match &target.authorization {
+        Some(authorization) => match authorization.apply(request, &target.url, &target.body) {
+            Ok(request) => request,
+            Err(_) => return failed(started),
+        },
+        None => request,
+    };
+    let response = match request.body(target.body.clone()).send().await {
+        Ok(response) if response.status().is_success() => response,
+        _ => return failed(started),
+    };
+
+    let mut first_token = None;
+    let mut first_chunk = None;
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let Ok(chunk) = chunk else {
+            return failed(started);
+        };
+        first_chunk.get_or_insert_with(|| started.elapsed());
+        if first_token.is_none() && contains_delta(&chunk) {
+            first_token = Some(started.elapsed());
+        }
+    }
+    BenchSample {
+        first_token: first_token.or(first_chunk),
+        last_token: started.elapsed(),
+        success: true,
+    }
+}
+
+///
+/// Whether a stream chunk carries a delta (OpenAI chunk or Anthropic event).
+///
+/// Ollama streams carry no deltas; their first chunk counts as the first token.
+fn contains_delta(chunk: &[u8]) -> bool {
+    chunk.windows(b"\"delta\"".len()).any(|window| window == b"\"delta\"")
+}
+
+///
+/// Summarize the samples of a run.
+///
+/// # Arguments
+///  * `target` - where the requests were sent
+///  * `options` - benchmark options
+///  * `samples` - timing of every request
+///  * `elapsed` - wall-clock duration of the run
+fn build_report(
+    target: &str,
+    options: &BenchOptions,
+    samples: &[BenchSample],
+    elapsed: Duration,
+) -> BenchReport {
+    let successful: Vec<&BenchSample> = samples.iter().filter(|sample| sample.success).collect();
+    let errors = samples.len() - successful.len();
+    let duration_secs = elapsed.as_secs_f64();
+
+    BenchReport {
+        target: target.to_string(),
+        requests: samples.len(),
+        concurrency: options.concurrency,
+        errors,
+        error_rate: if samples.is_empty() { 0.0 } else { errors as f64 / samples.len() as f64 },
+        duration_secs,
+        throughput_rps: if duration_secs > 0.0 {
+            successful.len() as f64 / duration_secs
+        } else {
+            0.0
+        },
+        time_to_first_token: latency_stats(
+            successful.iter().filter_map(|sample| sample.first_token).collect(),
+        ),
+        time_to_last_token: latency_stats(
+            successful.iter().map(|sample| sample.last_token).collect(),
+        ),
+    }
+}
+
+///
+/// Nearest-rank percentiles of a set of latencies.
+fn latency_stats(mut latencies: Vec<Duration>) -> LatencyStats {
+    if latencies.is_empty() {
+        return LatencyStats::default();
+    }
+    latencies.sort();
+    let percentile = |p: f64| {
+        let rank = ((p / 100.0) * latencies.len() as f64).ceil() as usize;
+        latencies[rank.clamp(1, latencies.len()) - 1].as_secs_f64() * 1000.0
+    };
+    LatencyStats {
+        p50_ms: percentile(50.0),
+        p95_ms: percentile(95.0),
+        p99_ms: percentile(99.0),
+        max_ms: percentile(100.0),
+    }
+}
+
+///
+/// Report of made-up, deterministic samples, to check the output format.
+fn dry_run_report(options: &BenchOptions) -> BenchReport {
+    let samples: Vec<BenchSample> = (0..options.requests)
+        .map(|i| BenchSample {
+            first_token: Some(Duration::from_millis(200 + (i as u64 * 37) % 150)),
+            last_token: Duration::from_millis(600 + (i as u64 * 53) % 400),
+            success: i % 25 != 24,
+        })
+        .collect();
+    let batches = options.requests.div_ceil(options.concurrency.max(1)) as u64;
+    build_report("dry-run", options, &samples, Duration::from_millis(800 * batches))
+}
+
+///
+/// Render the results as a table.
+///
+/// # Arguments
+///  * `report` - benchmark results
+///
+/// # Returns
+///  * Table text, one line per metric
+pub fn render_table(report: &BenchReport) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "Target:       {}", report.target);
+    let _ = writeln!(
+        out,
+        "Requests:     {} ({} concurrent) in {:.2}s",
+        report.requests, report.concurrency, report.duration_secs
+    );
+    let _ = writeln!(out, "Throughput:   {:.2} req/s", report.throughput_rps);
+    let _ = writeln!(out, "Errors:       {} ({:.1}%)", report.errors, report.error_rate * 100.0);
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "{:<20} {:>10} {:>10} {:>10} {:>10}",
+        "Latency (ms)", "p50", "p95", "p99", "max"
+    );
+    for (name, stats) in [
+        ("Time to first token", &report.time_to_first_token),
+        ("Time to last token", &report.time_to_last_token),
+    ] {
+        let _ = writeln!(
+            out,
+            "{:<20} {:>10.1} {:>10.1} {:>10.1} {:>10.1}",
+            name, stats.p50_ms, stats.p95_ms, stats.p99_ms, stats.max_ms
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_stats_nearest_rank() {
+        let latencies = (1..=100).map(Duration::from_millis).collect();
+        let stats = latency_stats(latencies);
+        assert_eq!(stats.p50_ms, 50.0);
+        assert_eq!(stats.p95_ms, 95.0);
+        assert_eq!(stats.p99_ms, 99.0);
+        assert_eq!(stats.max_ms, 100.0);
+        assert_eq!(latency_stats(Vec::new()), LatencyStats::default());
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_output_formats() {
+        let options =
+            BenchOptions { requests: 50, concurrency: 5, dry_run: true, ..Default::default() };
+        let report = run(&options).await.unwrap();
+        assert_eq!(report.requests, 50);
+        assert_eq!(report.errors, 2);
+        assert!((report.error_rate - 0.04).abs() < 1e-9);
+        assert!(report.time_to_first_token.p50_ms < report.time_to_last_token.p50_ms);
+
+        let table = render_table(&report);
+        assert!(table.contains("Time to first token"));
+        assert!(table.contains("Errors:       2 (4.0%)"));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["target"], "dry-run");
+        assert!(json["time_to_last_token"]["p99_ms"].is_number());
+        assert!(json["throughput_rps"].as_f64().unwrap() > 0.0);
+    }
+}
@@ -0,0 +1,754 @@
+//!
+//! Response caching for the proxy.
+//!
+//! Provides a semantic similarity cache that reuses responses for prompts which
+//! are phrased differently but mean the same thing. The last user message of a
+//! request is embedded with a small local MiniLM model and compared against
+//! previously answered prompts using cosine similarity. Only prompts with the
+//! same scope are compared: the resolved model, the caller's API key and every
+//! message before the last user message (system prompt included) must match.
+//!
+//! Only deterministic requests are cached (`temperature = 0`, no tools,
+//! non-streaming). The embedding model is only available when the crate is
+//! built with the `semantic-cache` feature; without it the cache stays empty.
+//!
+//! Identical deterministic requests are answered by an exact-match cache of
+//! raw upstream `AnthropicResponse`s keyed by the SHA-256 of the converted
+//! request. Hits are converted to OpenAI format again, so converter changes
+//! take effect without invalidating the cache.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::config::CacheConfig;
+use crate::converter::anthropic_to_openai::AnthropicResponse;
+use crate::converter::openai_to_anthropic::{
+    AnthropicRequest, OpenAiContent, OpenAiMessage, OpenAiRequest,
+};
+use crate::error::Result;
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// Semantic similarity cache for non-streaming OpenAI responses.
+///
+/// Stores `(scope, embedding, response, inserted_at)` tuples and answers
+/// lookups with the nearest neighbour of the same scope whose similarity is
+/// above the configured threshold. Entries expire after the configured TTL;
+/// the oldest entry is evicted once `max_entries` is reached.
+pub struct SemanticCache {
+    /** minimum cosine similarity for a hit */
+    threshold: f64,
+    /** maximum number of cached entries */
+    max_entries: usize,
+    /** how long an entry stays valid */
+    ttl: Duration,
+    /** cached entries, oldest first */
+    entries: Mutex<VecDeque<SemanticCacheEntry>>,
+}
+
+///
+/// Single cached response with the embedding of the prompt that produced it.
+struct SemanticCacheEntry {
+    /** scope of the prompt, from [`SemanticCacheKey::scope`] */
+    scope: String,
+    /** embedding of the last user message */
+    embedding: Vec<f32>,
+    /** OpenAI-format response returned to the client */
+    response: Value,
+    /** time the entry was stored */
+    inserted_at: Instant,
+}
+
+///
+/// Semantic cache key of a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SemanticCacheKey {
+    /** hex SHA-256 of the model, caller and earlier messages; only equal scopes are compared */
+    pub scope: String,
+    /** text of the last user message, embedded for the similarity search */
+    pub text: String,
+}
+
+///
+/// Exact-match cache of raw upstream responses.
+///
+/// Entries expire after the configured TTL; the least recently used entry is
+/// evicted once `max_entries` is reached.
+pub struct AnthropicResponseCache {
+    /** maximum number of cached entries */
+    max_entries: usize,
+    /** how long an entry stays valid */
+    ttl: Duration,
+    /** cached entries and their use order */
+    entries: Mutex<ResponseCacheEntries>,
+}
+
+///
+/// Mutable state of the response cache.
+///
+/// Recency is tracked with a use counter: each entry remembers the tick of
+/// its last use, and `order` maps ticks back to keys, so marking an entry as
+/// used and finding the least recently used one are `O(log n)`.
+#[derive(Default)]
+struct ResponseCacheEntries {
+    /** responses, insertion times and last use ticks keyed by request hash */
+    responses: HashMap<String, (AnthropicResponse, Instant, u64)>,
+    /** request hashes by last use tick, least recently used first */
+    order: BTreeMap<u64, String>,
+    /** tick of the latest use */
+    tick: u64,
+}
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** Lazily loaded local embedding model (quantised all-MiniLM-L6-v2) */
+#[cfg(feature = "semantic-cache")]
+static EMBEDDING_MODEL: std::sync::OnceLock<Option<Mutex<fastembed::TextEmbedding>>> =
+    std::sync::OnceLock::new();
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+impl SemanticCache {
+    ///
+    /// Create a new semantic cache from configuration.
+    ///
+    /// # Arguments
+    ///  * `config` - cache configuration section
+    ///
+    /// # Returns
+    ///  * Empty semantic cache
+    pub fn new(config: &CacheConfig) -> Self {
+        Self {
+            threshold: config.semantic_threshold,
+            max_entries: config.semantic_max_entries,
+            ttl: Duration::from_secs(config.semantic_ttl_secs),
+            entries: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    ///
+    /// Check whether a request is eligible for semantic caching.
+    ///
+    /// Only requests with `temperature = 0`, no tools or legacy functions and
+    /// no streaming are deterministic enough to be answered from the cache.
+    ///
+    /// # Arguments
+    ///  * `request` - incoming OpenAI request
+    ///
+    /// # Returns
+    ///  * `true` if the request may be served from or stored in the cache
+    pub fn is_cacheable(request: &OpenAiRequest) -> bool {
+        request.temperature == Some(0.0)
+            && request.tools.as_ref().is_none_or(|tools| tools.is_empty())
+            && request.functions.as_ref().is_none_or(|functions| functions.is_empty())
+            && request.stream != Some(true)
+    }
+
+    ///
+    /// Compute the semantic cache key of a request.
+    ///
+    /// The last user message is the text compared by similarity; everything
+    /// that can change the answer to it beyond its wording is hashed into the
+    /// scope, so a cached answer is never returned to another model, caller,
+    /// system prompt, conversation or set of generation parameters (e.g.
+    /// `response_format` or `max_tokens`).
+    ///
+    /// # Arguments
+    ///  * `request` - incoming OpenAI request
+    ///  * `model` - model the request resolves to
+    ///  * `caller` - credential of the caller, e.g. the `Authorization` header
+    ///
+    /// # Returns
+    ///  * Cache key, or `None` if there is no user message text to embed
+    pub fn cache_key(
+        request: &OpenAiRequest,
+        model: &str,
+        caller: &str,
+    ) -> Option<SemanticCacheKey> {
+        let last_user = request.messages.iter().rposition(|m| m.role == "user")?;
+        let text = content_text(request.messages[last_user].content.as_ref()?);
+        if text.trim().is_empty() {
+            return None;
+        }
+
+        let mut hasher = Sha256::new();
+        hasher.update(model.as_bytes());
+        hasher.update([0]);
+        hasher.update(Sha256::digest(caller.as_bytes()));
+        hash_generation_params(&mut hasher, request);
+        for message in &request.messages[..last_user] {
+            hash_message(&mut hasher, message);
+        }
+        Some(SemanticCacheKey { scope: format!("{:x}", hasher.finalize()), text })
+    }
+
+    ///
+    /// Find the cached response closest to the given embedding.
+    ///
+    /// Expired entries are removed first.
+    ///
+    /// # Arguments
+    ///  * `scope` - scope of the incoming prompt
+    ///  * `embedding` - embedding of the incoming prompt
+    ///
+    /// # Returns
+    ///  * Cached response if the nearest neighbour of the scope is above the threshold
+    pub fn lookup(&self, scope: &str, embedding: &[f32]) -> Option<Value> {
+        let mut entries = self.entries.lock().ok()?;
+        entries.retain(|entry| entry.inserted_at.elapsed() < self.ttl);
+
+        let (similarity, entry) = entries
+            .iter()
+            .filter(|entry| entry.scope == scope)
+            .map(|entry| (cosine_similarity(embedding, &entry.embedding), entry))
+            .max_by(|a, b| a.0.total_cmp(&b.0))?;
+
+        if similarity < self.threshold {
+            return None;
+        }
+
+        tracing::debug!(
+            "Semantic cache hit (similarity {:.4}, age {}s)",
+            similarity,
+            entry.inserted_at.elapsed().as_secs()
+        );
+        Some(entry.response.clone())
+    }
+
+    ///
+    /// Store a response under the given prompt embedding.
+    ///
+    /// # Arguments
+    ///  * `scope` - scope of the prompt
+    ///  * `embedding` - embedding of the prompt
+    ///  * `response` - OpenAI-format response to cache
+    pub fn insert(&self, scope: String, embedding: Vec<f32>, response: Value) {
+        if self.max_entries == 0 {
+            return;
+        }
+
+        if let Ok(mut entries) = self.entries.lock() {
+            while entries.len() >= self.max_entries {
+                entries.pop_front();
+            }
+            entries.push_back(SemanticCacheEntry {
+                scope,
+                embedding,
+                response,
+                inserted_at: Instant::now(),
+            });
+        }
+    }
+
+    ///
+    /// Number of entries currently stored, expired ones included.
+    pub fn entry_count(&self) -> usize {
+        self.entries.lock().map(|entries| entries.len()).unwrap_or(0)
+    }
+
+    ///
+    /// Embed text with the local embedding model.
+    ///
+    /// The model is loaded on first use and kept for the lifetime of the
+    /// process. Inference runs on a blocking thread.
+    ///
+    /// # Arguments
+    ///  * `text` - text to embed
+    ///
+    /// # Returns
+    ///  * Embedding vector, or `None` if the model is unavailable
+    pub async fn embed(text: String) -> Option<Vec<f32>> {
+        tokio::task::spawn_blocking(move || embed_blocking(&text)).await.ok().flatten()
+    }
+}
+
+impl AnthropicResponseCache {
+    ///
+    /// Create a new response cache from configuration.
+    ///
+    /// # Arguments
+    ///  * `config` - cache configuration section
+    ///
+    /// # Returns
+    ///  * Empty response cache
+    pub fn new(config: &CacheConfig) -> Self {
+        Self {
+            max_entries: config.response_max_entries,
+            ttl: Duration::from_secs(config.response_ttl_secs),
+            entries: Mutex::new(ResponseCacheEntries::default()),
+        }
+    }
+
+    ///
+    /// Check whether a converted request is eligible for response caching.
+    ///
+    /// # Arguments
+    ///  * `request` - request about to be sent upstream
+    ///
+    /// # Returns
+    ///  * `true` for deterministic (`temperature = 0`), non-streaming requests
+    pub fn is_cacheable(request: &AnthropicRequest) -> bool {
+        request.temperature == 0.0 && !request.stream
+    }
+
+    ///
+    /// Compute the cache key of a converted request.
+    ///
+    /// The requested model is part of the key because it selects the upstream
+    /// endpoint and is not contained in the request body.
+    ///
+    /// # Arguments
+    ///  * `request` - request about to be sent upstream
+    ///  * `model` - model requested by the client, if any
+    ///
+    /// # Returns
+    ///  * Hex-encoded SHA-256 of the model and serialised request body
+    pub fn cache_key(request: &AnthropicRequest, model: Option<&str>) -> Result<String> {
+        let body = serde_json::to_vec(&request.to_request_body()?)?;
+        let mut hasher = Sha256::new();
+        hasher.update(model.unwrap_or_default().as_bytes());
+        hasher.update([0]);
+        hasher.update(&body);
+        if let Some(betas) = &request.anthropic_beta {
+            hasher.update([0]);
+            hasher.update(betas.join(",").as_bytes());
+        }
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+
+    ///
+    /// Find a cached response that has not expired.
+    ///
+    /// A hit marks the entry as most recently used; an expired entry is removed.
+    ///
+    /// # Arguments
+    ///  * `key` - cache key from [`cache_key`](Self::cache_key)
+    ///
+    /// # Returns
+    ///  * Copy of the cached upstream response
+    pub fn lookup(&self, key: &str) -> Option<AnthropicResponse> {
+        let mut guard = self.entries.lock().ok()?;
+        let entries = &mut *guard;
+        let (response, inserted_at, last_used) = entries.responses.get_mut(key)?;
+        entries.order.remove(last_used);
+        if inserted_at.elapsed() >= self.ttl {
+            entries.responses.remove(key);
+            return None;
+        }
+
+        entries.tick += 1;
+        *last_used = entries.tick;
+        entries.order.insert(entries.tick, key.to_string());
+        Some(response.clone())
+    }
+
+    ///
+    /// Store an upstream response.
+    ///
+    /// # Arguments
+    ///  * `key` - cache key from [`cache_key`](Self::cache_key)
+    ///  * `response` - upstream response to cache
+    pub fn insert(&self, key: String, response: AnthropicResponse) {
+        if self.max_entries == 0 {
+            return;
+        }
+
+        if let Ok(mut guard) = self.entries.lock() {
+            let entries = &mut *guard;
+            if let Some((_, _, last_used)) = entries.responses.remove(&key) {
+                entries.order.remove(&last_used);
+            }
+            while entries.responses.len() >= self.max_entries {
+                let Some((_, oldest)) = entries.order.pop_first() else {
+                    break;
+                };
+                entries.responses.remove(&oldest);
+            }
+            entries.tick += 1;
+            entries.order.insert(entries.tick, key.clone());
+            entries.responses.insert(key, (response, Instant::now(), entries.tick));
+        }
+    }
+
+    ///
+    /// Number of entries currently stored.
+    pub fn entry_count(&self) -> usize {
+        self.entries.lock().map(|entries| entries.responses.len()).unwrap_or(0)
+    }
+}
+
+///
+/// Join the text blocks of a message content.
+fn content_text(content: &OpenAiContent) -> String {
+    match content {
+        OpenAiContent::String(text) => text.clone(),
+        OpenAiContent::Array(blocks) => {
+            blocks.iter().filter_map(|block| block.text.as_deref()).collect::<Vec<_>>().join("\n")
+        }
+    }
+}
+
+///
+/// Feed the generation parameters of a request into a scope hash.
+///
+/// The cache only lives in memory, so the `Debug` form of each parameter is a
+/// stable enough encoding. Every field is terminated by a zero byte.
+fn hash_generation_params(hasher: &mut Sha256, request: &OpenAiRequest) {
+    for value in [
+        format!("{:?}", request.max_tokens),
+        format!("{:?}", request.temperature),
+        format!("{:?}", request.top_p),
+        format!("{:?}", request.top_k),
+        format!("{:?}", request.stop),
+        format!("{:?}", request.n),
+        format!("{:?}", request.response_format),
+        format!("{:?}", request.seed),
+        format!("{:?}", request.x_anthropic_beta),
+    ] {
+        hasher.update(value.as_bytes());
+        hasher.update([0]);
+    }
+}
+
+///
+/// Feed a message of the conversation history into a scope hash.
+///
+/// Every field is terminated by a zero byte so that adjacent fields cannot
+/// run into each other.
+fn hash_message(hasher: &mut Sha256, message: &OpenAiMessage) {
+    let mut field = |value: &str| {
+        hasher.update(value.as_bytes());
+        hasher.update([0]);
+    };
+    field(&message.role);
+    match &message.content {
+        Some(OpenAiContent::String(text)) => field(text),
+        Some(OpenAiContent::Array(blocks)) => {
+            for block in blocks {
+                field(&block.block_type);
+                field(block.text.as_deref().unwrap_or_default());
+                field(block.image_url.as_ref().map_or("", |image| image.url.as_str()));
+                field(block.source.as_ref().map_or("", |source| source.data.as_str()));
+            }
+        }
+        None => field(""),
+    }
+    for call in message.tool_calls.iter().flatten() {
+        field(&call.id);
+        field(&call.function.name);
+        field(&call.function.arguments.to_string());
+    }
+    if let Some(function) = &message.function_call {
+        field(&function.name);
+        field(&function.arguments.to_string());
+    }
+    field(message.tool_call_id.as_deref().unwrap_or_default());
+}
+
+///
+/// Compute the cosine similarity of two vectors.
+///
+/// # Arguments
+///  * `a` - first vector
+///  * `b` - second vector
+///
+/// # Returns
+///  * Similarity in `[-1.0, 1.0]`, or `0.0` for mismatched or zero vectors
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+
+    let (mut dot, mut norm_a, mut norm_b) = (0.0_f64, 0.0_f64, 0.0_f64);
+    for (x, y) in a.iter().zip(b) {
+        let (x, y) = (f64::from(*x), f64::from(*y));
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+///
+/// Run the embedding model synchronously.
+#[cfg(feature = "semantic-cache")]
+fn embed_blocking(text: &str) -> Option<Vec<f32>> {
+    use fastembed::{EmbeddingModel, InitOptions, TextEmbedding};
+
+    let model = EMBEDDING_MODEL
+        .get_or_init(|| {
+            match TextEmbedding::try_new(InitOptions::new(EmbeddingModel::AllMiniLML6V2Q)) {
+                Ok(model) => Some(Mutex::new(model)),
+                Err(e) => {
+                    tracing::warn!("Failed to load semantic cache embedding model: {}", e);
+                    None
+                }
+            }
+        })
+        .as_ref()?;
+
+    let mut model = model.lock().ok()?;
+    match model.embed(vec![text], None) {
+        Ok(embeddings) => embeddings.into_iter().next(),
+        Err(e) => {
+            tracing::warn!("Failed to embed prompt for semantic cache: {}", e);
+            None
+        }
+    }
+}
+
+///
+/// Embedding is unavailable without the `semantic-cache` feature.
+#[cfg(not(feature = "semantic-cache"))]
+fn embed_blocking(_text: &str) -> Option<Vec<f32>> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(threshold: f64, max_entries: usize) -> SemanticCache {
+        SemanticCache::new(&CacheConfig {
+            semantic_enabled: true,
+            semantic_threshold: threshold,
+            semantic_max_entries: max_entries,
+            ..Default::default()
+        })
+    }
+
+    fn response_cache(max_entries: usize, ttl_secs: u64) -> AnthropicResponseCache {
+        AnthropicResponseCache::new(&CacheConfig {
+            response_enabled: true,
+            response_max_entries: max_entries,
+            response_ttl_secs: ttl_secs,
+            ..Default::default()
+        })
+    }
+
+    fn anthropic_response(text: &str) -> AnthropicResponse {
+        serde_json::from_value(serde_json::json!({
+            "content": [{"type": "text", "text": text}],
+            "stop_reason": "end_turn",
+            "usage": {"input_tokens": 1, "output_tokens": 1}
+        }))
+        .unwrap()
+    }
+
+    fn anthropic_request(json: Value) -> AnthropicRequest {
+        crate::converter::OpenAiToAnthropicConverter::new(crate::config::LogLevel::Error)
+            .convert(request(json), crate::converter::TargetFormat::Vertex, None)
+            .unwrap()
+    }
+
+    fn cached_text(response: &AnthropicResponse) -> &str {
+        match &response.content[0] {
+            crate::converter::anthropic_to_openai::AnthropicContentBlock::Text { text } => text,
+            _ => panic!("expected text block"),
+        }
+    }
+
+    fn request(json: Value) -> OpenAiRequest {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[1.0, 0.0]) - 1.0).abs() < 1e-9);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]).abs() < 1e-9);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn test_lookup_respects_threshold() {
+        let cache = cache(0.97, 10);
+        cache.insert("s".to_string(), vec![1.0, 0.0], serde_json::json!({"id": "a"}));
+
+        assert_eq!(cache.lookup("s", &[0.99, 0.01]), Some(serde_json::json!({"id": "a"})));
+        assert_eq!(cache.lookup("s", &[0.5, 0.5]), None);
+    }
+
+    #[test]
+    fn test_lookup_only_matches_same_scope() {
+        let cache = cache(0.97, 10);
+        cache.insert("tenant-a".to_string(), vec![1.0, 0.0], serde_json::json!({"id": "a"}));
+
+        assert_eq!(cache.lookup("tenant-b", &[1.0, 0.0]), None);
+        assert_eq!(cache.lookup("tenant-a", &[1.0, 0.0]), Some(serde_json::json!({"id": "a"})));
+    }
+
+    #[test]
+    fn test_expired_entries_are_not_returned() {
+        let cache = SemanticCache::new(&CacheConfig {
+            semantic_enabled: true,
+            semantic_ttl_secs: 0,
+            ..Default::default()
+        });
+        cache.insert("s".to_string(), vec![1.0, 0.0], serde_json::json!(1));
+
+        assert_eq!(cache.lookup("s", &[1.0, 0.0]), None);
+        assert_eq!(cache.entry_count(), 0);
+    }
+
+    #[test]
+    fn test_insert_evicts_oldest() {
+        let cache = cache(0.97, 2);
+        cache.insert("s".to_string(), vec![1.0, 0.0, 0.0], serde_json::json!(1));
+        cache.insert("s".to_string(), vec![0.0, 1.0, 0.0], serde_json::json!(2));
+        cache.insert("s".to_string(), vec![0.0, 0.0, 1.0], serde_json::json!(3));
+
+        assert_eq!(cache.entry_count(), 2);
+        assert_eq!(cache.lookup("s", &[1.0, 0.0, 0.0]), None);
+        assert_eq!(cache.lookup("s", &[0.0, 0.0, 1.0]), Some(serde_json::json!(3)));
+    }
+
+    #[test]
+    fn test_is_cacheable() {
+        let deterministic = request(serde_json::json!({
+            "messages": [{"role": "user", "content": "hi"}],
+            "temperature": 0.0
+        }));
+        assert!(SemanticCache::is_cacheable(&deterministic));
+
+        let sampled = request(serde_json::json!({
+            "messages": [{"role": "user", "content": "hi"}],
+            "temperature": 0.7
+        }));
+        assert!(!SemanticCache::is_cacheable(&sampled));
+
+        let streaming = request(serde_json::json!({
+            "messages": [{"role": "user", "content": "hi"}],
+            "temperature": 0.0,
+            "stream": true
+        }));
+        assert!(!SemanticCache::is_cacheable(&streaming));
+
+        let functions = request(serde_json::json!({
+            "messages": [{"role": "user", "content": "hi"}],
+            "temperature": 0.0,
+            "functions": [{"name": "get_weather", "parameters": {"type": "object"}}]
+        }));
+        assert!(!SemanticCache::is_cacheable(&functions));
+    }
+
+    #[test]
+    fn test_cache_key_uses_last_user_message() {
+        let req = request(serde_json::json!({
+            "messages": [
+                {"role": "user", "content": "first"},
+                {"role": "assistant", "content": "reply"},
+                {"role": "user", "content": [{"type": "text", "text": "second"}]}
+            ]
+        }));
+        let key = SemanticCache::cache_key(&req, "claude", "Bearer a").unwrap();
+        assert_eq!(key.text, "second");
+    }
+
+    #[test]
+    fn test_cache_key_scope() {
+        let conversation = |system: &str, earlier: &str| {
+            request(serde_json::json!({
+                "messages": [
+                    {"role": "system", "content": system},
+                    {"role": "user", "content": earlier},
+                    {"role": "assistant", "content": "ok"},
+                    {"role": "user", "content": "and now?"}
+                ]
+            }))
+        };
+        let scope = |req: &OpenAiRequest, model: &str, caller: &str| {
+            SemanticCache::cache_key(req, model, caller).unwrap().scope
+        };
+
+        let base = conversation("be brief", "hello");
+        let same = scope(&base, "claude", "Bearer a");
+        assert_eq!(same, scope(&conversation("be brief", "hello"), "claude", "Bearer a"));
+        assert_ne!(same, scope(&base, "other-model", "Bearer a"));
+        assert_ne!(same, scope(&base, "claude", "Bearer b"));
+        assert_ne!(same, scope(&conversation("be verbose", "hello"), "claude", "Bearer a"));
+        assert_ne!(same, scope(&conversation("be brief", "goodbye"), "claude", "Bearer a"));
+
+        let with_params = |params: serde_json::Value| {
+            let mut req = serde_json::json!({"messages": [{"role": "user", "content": "hi"}]});
+            req.as_object_mut().unwrap().extend(params.as_object().unwrap().clone());
+            scope(&request(req), "claude", "Bearer a")
+        };
+        let plain = with_params(serde_json::json!({}));
+        for params in [
+            serde_json::json!({"response_format": {"type": "json_object"}}),
+            serde_json::json!({"max_tokens": 16}),
+            serde_json::json!({"stop": ["\n"]}),
+            serde_json::json!({"top_p": 0.5}),
+            serde_json::json!({"top_k": 5}),
+            serde_json::json!({"seed": 7}),
+        ] {
+            assert_ne!(plain, with_params(params.clone()), "{}", params);
+        }
+    }
+
+    #[test]
+    fn test_response_cache_key_and_eligibility() {
+        let req = anthropic_request(serde_json::json!({
+            "messages": [{"role": "user", "content": "hi"}],
+            "temperature": 0.0
+        }));
+        assert!(AnthropicResponseCache::is_cacheable(&req));
+
+        let key = AnthropicResponseCache::cache_key(&req, Some("claude")).unwrap();
+        assert_eq!(key, AnthropicResponseCache::cache_key(&req, Some("claude")).unwrap());
+        assert_ne!(key, AnthropicResponseCache::cache_key(&req, Some("other")).unwrap());
+
+        let sampled = anthropic_request(serde_json::json!({
+            "messages": [{"role": "user", "content": "hi"}],
+            "temperature": 0.7
+        }));
+        assert!(!AnthropicResponseCache::is_cacheable(&sampled));
+    }
+
+    #[test]
+    fn test_response_cache_eviction_and_ttl() {
+        let cache = response_cache(2, 3600);
+        cache.insert("a".to_string(), anthropic_response("A"));
+        cache.insert("b".to_string(), anthropic_response("B"));
+        cache.insert("c".to_string(), anthropic_response("C"));
+
+        assert_eq!(cache.entry_count(), 2);
+        assert!(cache.lookup("a").is_none());
+        assert_eq!(cached_text(&cache.lookup("c").unwrap()), "C");
+
+        let expired = response_cache(2, 0);
+        expired.insert("a".to_string(), anthropic_response("A"));
+        assert!(expired.lookup("a").is_none());
+        assert_eq!(expired.entry_count(), 0);
+    }
+
+    #[test]
+    fn test_response_cache_evicts_least_recently_used() {
+        let cache = response_cache(2, 3600);
+        cache.insert("a".to_string(), anthropic_response("A"));
+        cache.insert("b".to_string(), anthropic_response("B"));
+        assert!(cache.lookup("a").is_some());
+
+        cache.insert("c".to_string(), anthropic_response("C"));
+        assert!(cache.lookup("b").is_none());
+        assert_eq!(cached_text(&cache.lookup("a").unwrap()), "A");
+        assert_eq!(cached_text(&cache.lookup("c").unwrap()), "C");
+    }
+}
@@ -2,10 +2,22 @@
 //! CLI configuration commands for ModelMux.
 //!
 //! This module provides command-line interface commands for configuration management:
-//! - `config init` - Interactive configuration setup
-//! - `config show` - Display current configuration
+//! - `config init` - Interactive configuration setup (or `--non-interactive` from
+//!   `MODELMUX_SETUP_*` environment variables, with `--dry-run` to only print the result)
+//! - `config show` - Display current configuration, or print it as JSON, TOML or YAML
+//!   with `--format` (secrets redacted unless `--include-sensitive` is confirmed)
 //! - `config validate` - Validate configuration
+//! - `config export` - Print the configuration as `MODELMUX_*` environment variables or JSON
+//! - `config diff` - List the settings that differ from the built-in defaults
+//! - `config schema` - Print or write the JSON Schema of the configuration file
 //! - `config edit` - Edit configuration in default editor
+//! - `config backup` / `config restore` - Save and restore timestamped copies of the config file
+//! - `config migrate` - Convert a legacy `GCP_SERVICE_ACCOUNT_KEY` / `LLM_URL` environment setup
+//!   into a config file
+//! - `keygen` - Register a generated client API key in the config file
+//! - `completion` - Print a shell tab-completion script
+//! - `test-connection` - Fetch a real access token and send a 1-token completion upstream
+//! - `provider list` - Show the configured providers with their resolved request URLs
 //!
 //! Follows Single Responsibility Principle - handles only CLI configuration concerns.
 //!
@@ -16,14 +28,22 @@
 
 /* --- uses ------------------------------------------------------------------------------------ */
 
-use crate::config::paths;
-use crate::config::validation::ConfigValidator;
-use crate::config::{Config, LogLevel, StreamingMode};
+use crate::auth::{Authorization, RequestAuth};
+use crate::config::loader::{self, ConfigLoader, to_env_vars};
+use crate::config::validation::{Severity, ValidationIssue};
+use crate::config::{ApiKeyEntry, Config, LogLevel, REDACTED, StreamingMode};
+use crate::config::{migration, paths, schema};
+use crate::converter::OllamaConverter;
+use crate::converter::openai_to_anthropic::{OpenAiRequest, OpenAiToAnthropicConverter};
 use crate::error::{ProxyError, Result};
-use crate::provider::LlmProviderBackend;
+use crate::provider::{AuthStrategy, LlmProviderBackend, LlmProviderConfig};
+use serde_json::{Value, json};
 use std::fs;
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 
 /* --- types ----------------------------------------------------------------------------------- */
 
@@ -34,6 +54,233 @@ use std::process::Command;
 /// with user-friendly interfaces and comprehensive error handling.
 pub struct ConfigCli;
 
+///
+/// Options of the `config init` command.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InitOptions {
+    /// Overwrite an existing configuration without backing it up
+    pub no_backup: bool,
+    /// Read values from `MODELMUX_SETUP_*` environment variables instead of prompting
+    pub non_interactive: bool,
+    /// Print the generated configuration to stdout instead of writing it
+    pub dry_run: bool,
+}
+
+///
+/// Output format of the `config show` command.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ShowFormat {
+    /// Annotated human-readable summary
+    #[default]
+    Text,
+    /// Effective configuration as pretty-printed JSON
+    Json,
+    /// Effective configuration as TOML
+    Toml,
+    /// Effective configuration as YAML
+    Yaml,
+}
+
+///
+/// Options of the `config show` command.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ShowOptions {
+    /// Output format
+    pub format: ShowFormat,
+    /// Print secrets instead of `<redacted>` in machine-readable formats
+    pub include_sensitive: bool,
+}
+
+///
+/// Output format of the `config export` command.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// `export MODELMUX_*=value` lines for a shell or `.env` file
+    #[default]
+    Env,
+    /// Effective configuration as pretty-printed JSON
+    Json,
+}
+
+///
+/// Options of the `config export` command.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExportOptions {
+    /// Output format
+    pub format: ExportFormat,
+    /// Replace secrets and the service account file path with `<redacted>`
+    pub redact_secrets: bool,
+}
+
+///
+/// Output format of the `config diff` command.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiffFormat {
+    /// Aligned `field: default → current` lines
+    #[default]
+    Text,
+    /// Differences as a JSON array
+    Json,
+}
+
+///
+/// Options of the `config diff` command.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DiffOptions {
+    /// Output format
+    pub format: DiffFormat,
+}
+
+///
+/// Options of the `config schema` command.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaOptions {
+    /// Write the schema to this file instead of stdout
+    pub output: Option<PathBuf>,
+}
+
+///
+/// Field-by-field comparison of two configurations.
+///
+/// Both configurations are serialised and flattened into dotted field paths,
+/// so new settings are compared without updating a field list. Lists and maps
+/// without nested sections are compared as a whole.
+pub struct ConfigDiffer;
+
+///
+/// One setting that differs between two configurations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigDifference {
+    /// Dotted field path, e.g. `server.port`
+    pub field: String,
+    /// Displayed value in the base configuration
+    pub default: String,
+    /// Displayed value in the compared configuration
+    pub current: String,
+}
+
+///
+/// Options of the `config validate` command.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidateOptions {
+    /// Exit with the error code when only warnings were found
+    pub exit_on_warnings: bool,
+    /// Print nothing; only the exit code reports the result
+    pub quiet: bool,
+    /// Print the issues as a JSON array instead of the text report
+    pub json: bool,
+    /// Warn when a running server has seen requests above `limits.max_request_body_bytes`
+    pub check_size: bool,
+}
+
+///
+/// Options of the `config migrate` command.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MigrateOptions {
+    /// Print the generated configuration to stdout instead of writing it
+    pub dry_run: bool,
+}
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** Taplo directive pointing `config init` output at the schema written next to it */
+const SCHEMA_DIRECTIVE: &str = "#:schema ./config.schema.json\n\n";
+
+/** `config validate` exit code: no issues */
+const EXIT_VALID: i32 = 0;
+
+/** `config validate` exit code: errors, or warnings with `--exit-on-warnings` */
+const EXIT_ERRORS: i32 = 1;
+
+/** `config validate` exit code: warnings only */
+const EXIT_WARNINGS: i32 = 2;
+
+/** Number of configuration backups kept; older ones are deleted */
+const MAX_CONFIG_BACKUPS: usize = 10;
+
+/** File name suffix of configuration backups */
+const BACKUP_SUFFIX: &str = ".toml.bak";
+
+/** Prefix of environment variables read by `config init --non-interactive` */
+const SETUP_ENV_PREFIX: &str = "MODELMUX_SETUP_";
+
+/** File the service account key decoded by `config migrate` is written to */
+const MIGRATED_SERVICE_ACCOUNT_FILE: &str = "service-account.json";
+
+/** Fields shown as `(set)` / `(not set)` by `config diff` instead of their value */
+const DIFF_SECRET_FIELDS: &[&str] =
+    &["auth.service_account_json", "auth.proxy_api_key", "auth.api_keys", "admin.secret"];
+
+/** Top-level commands offered by shell completion */
+const COMPLETION_COMMANDS: &[&str] = &[
+    "config",
+    "doctor",
+    "validate",
+    "logs",
+    "keygen",
+    "completion",
+    "test-connection",
+    "bench",
+    "provider",
+    "--help",
+    "--version",
+];
+
+/** `config` subcommands offered by shell completion */
+const COMPLETION_CONFIG_SUBCOMMANDS: &[&str] =
+    &[
+    "init", "show", "validate", "export", "diff", "schema", "edit", "backup", "restore", "migrate",
+    "help",
+];
+
+/** Options completed after a command or `config` subcommand */
+const COMPLETION_OPTIONS: &[(&str, &[&str])] = &[
+    ("init", &["--no-backup", "--non-interactive", "--dry-run"]),
+    ("show", &["--format", "--include-sensitive", "--confirm-show-sensitive"]),
+    ("validate", &["--exit-on-warnings", "--quiet", "--json", "--check-size"]),
+    ("export", &["--format", "--redact-secrets"]),
+    ("diff", &["--format"]),
+    ("schema", &["--output"]),
+    ("restore", &["--file"]),
+    ("migrate", &["--dry-run"]),
+    ("keygen", &["--name", "--allowed-models"]),
+    ("logs", &["--follow", "-f"]),
+    ("provider", &["list"]),
+    (
+        "bench",
+        &[
+            "--requests",
+            "--concurrency",
+            "--model",
+            "--direct",
+            "--api-key",
+            "--output",
+            "--dry-run",
+        ],
+    ),
+];
+
+/** Prompt of the `test-connection` ping request */
+const PING_PROMPT: &str = "Say hi";
+
+/** Timeout of reading `/health` from a running server for `config validate --check-size` */
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/** Timeout of the `test-connection` ping request */
+const PING_TIMEOUT: Duration = Duration::from_secs(30);
+
+/** ANSI colour of validation errors (red) */
+const ANSI_RED: &str = "\x1b[31m";
+
+/** ANSI colour of validation warnings (amber) */
+const ANSI_AMBER: &str = "\x1b[33m";
+
+/** ANSI sequence ending a coloured span */
+const ANSI_RESET: &str = "\x1b[0m";
+
+/** Shells `completion` can generate scripts for */
+const COMPLETION_SHELLS: &[&str] = &["bash", "zsh", "fish", "powershell"];
+
 /* --- implementations --------------------------------------------------------------------- */
 
 impl ConfigCli {
@@ -41,55 +288,80 @@ impl ConfigCli {
     ///
     /// Provides an interactive setup wizard that guides users through
     /// configuration setup with intelligent defaults and validation.
+    /// With `non_interactive`, values are read from `MODELMUX_SETUP_*`
+    /// environment variables instead, so the command can run in pipelines.
+    /// An existing configuration file is backed up before it is overwritten.
+    /// The JSON Schema is written next to it and referenced from the file,
+    /// so editors using Taplo offer completion and validation.
+    ///
+    /// # Arguments
+    /// * `options` - backup, prompting and dry-run options
     ///
     /// # Returns
-    /// * `Ok(())` - Configuration successfully created
+    /// * `Ok(())` - Configuration successfully created (or printed with `dry_run`)
     /// * `Err(ProxyError)` - Configuration setup failed
-    pub fn init() -> Result<()> {
-        println!("🚀 ModelMux Configuration Setup");
-        println!("===============================");
-        println!();
-
-        // Check if config already exists
+    pub fn init(options: InitOptions) -> Result<()> {
         let config_file = paths::user_config_file()?;
-        if config_file.exists() {
-            println!("⚠️  Configuration file already exists at:");
-            println!("   {}", config_file.display());
+
+        let config = if options.non_interactive {
+            Self::gather_config_from_env(|name| std::env::var(name).ok())?
+        } else {
+            println!("🚀 ModelMux Configuration Setup");
+            println!("===============================");
             println!();
 
-            if !Self::confirm("Do you want to overwrite the existing configuration?")? {
-                println!("Configuration setup cancelled.");
-                return Ok(());
+            // Check if config already exists
+            if config_file.exists() && !options.dry_run {
+                println!("⚠️  Configuration file already exists at:");
+                println!("   {}", config_file.display());
+                if options.no_backup {
+                    println!("   It will be overwritten without a backup (--no-backup).");
+                } else {
+                    println!("   A backup will be saved before it is overwritten.");
+                }
+                println!();
             }
-        }
 
-        // Gather configuration interactively
-        let config = Self::gather_config_interactively()?;
+            Self::gather_config_interactively()?
+        };
+
+        let config_toml = toml::to_string_pretty(&config)
+            .map_err(|e| ProxyError::config_with_source("Failed to serialize configuration", e))?;
+
+        if options.dry_run {
+            if !options.non_interactive {
+                println!();
+            }
+            print!("{}", config_toml);
+            return Ok(());
+        }
 
         // Create config directory if it doesn't exist
-        let config_dir = config_file.parent().unwrap();
-        fs::create_dir_all(config_dir).map_err(|e| {
-            ProxyError::Config(format!(
-                "Failed to create config directory '{}': {}",
-                config_dir.display(),
-                e
-            ))
-        })?;
+        Self::create_config_dir(&config_file)?;
 
         // Write configuration file
-        let config_toml = toml::to_string_pretty(&config)
-            .map_err(|e| ProxyError::Config(format!("Failed to serialize configuration: {}", e)))?;
+        if config_file.exists() && !options.no_backup {
+            let backup_file = Self::backup_config_file(&config_file)?;
+            println!("💾 Previous configuration backed up to: {}", backup_file.display());
+        }
 
+        let config_toml = format!("{}{}", SCHEMA_DIRECTIVE, config_toml);
         fs::write(&config_file, config_toml).map_err(|e| {
-            ProxyError::Config(format!(
-                "Failed to write configuration file '{}': {}",
-                config_file.display(),
-                e
-            ))
+            ProxyError::config_with_source(
+                format!("Failed to write configuration file '{}'", config_file.display()),
+                e,
+            )
         })?;
 
         println!("✅ Configuration saved to: {}", config_file.display());
 
+        // The schema only helps editors, so failing to write it does not fail setup
+        let schema_file = paths::user_schema_file()?;
+        match schema::write_schema(&schema_file) {
+            Ok(()) => println!("📐 JSON Schema saved to: {}", schema_file.display()),
+            Err(e) => println!("⚠️  {}", e),
+        }
+
         // Handle service account setup if needed
         if let Some(ref sa_file) = config.auth.service_account_file {
             let sa_path = paths::expand_path(sa_file)?;
@@ -122,22 +394,37 @@ impl ConfigCli {
     ///
     /// Displays the current configuration in a readable format,
     /// showing the effective configuration after merging all sources.
+    /// JSON, TOML and YAML print the whole effective configuration instead,
+    /// suitable for `diff` or a config audit trail.
+    ///
+    /// # Arguments
+    /// * `options` - output format and whether secrets are shown
     ///
     /// # Returns
     /// * `Ok(())` - Configuration displayed successfully
     /// * `Err(ProxyError)` - Failed to load or display configuration
-    pub fn show() -> Result<()> {
+    pub fn show(options: ShowOptions) -> Result<()> {
+        // Load current configuration
+        let config = Config::load()?;
+
+        if options.format != ShowFormat::Text {
+            let output = Self::render_config(&config, options)?;
+            print!("{}", output);
+            if !output.ends_with('\n') {
+                println!();
+            }
+            return Ok(());
+        }
+
         println!("📋 Current ModelMux Configuration");
         println!("=================================");
         println!();
 
-        // Load current configuration
-        let config = Config::load()?;
-
         // Display configuration sections
         println!("Server Configuration:");
         println!("  Port: {}", config.server.port);
         println!("  Log Level: {:?}", config.server.log_level);
+        println!("  Log Format: {:?}", config.server.log_format);
         println!("  Enable Retries: {}", config.server.enable_retries);
         println!("  Max Retry Attempts: {}", config.server.max_retry_attempts);
         println!();
@@ -182,6 +469,9 @@ impl ConfigCli {
                 }
             }
         }
+        if let Some(ref file) = config.auth.service_account_key_file {
+            println!("  Service Account Key File: {}", file);
+        }
         if config.auth.service_account_json.is_some() {
             println!("  Service Account JSON: ✅ Inline JSON configured");
         }
@@ -201,6 +491,12 @@ impl ConfigCli {
 
         // Show configuration file locations
         println!("Configuration Sources:");
+        if let Some(path) = loader::custom_config_file()? {
+            let status = if path.exists() { "✅ exists" } else { "❌ not found" };
+            println!("  {} (custom, {}): {}", path.display(), loader::CONFIG_FILE_ENV, status);
+            println!("  User and system config files are skipped");
+            return Ok(());
+        }
         let config_paths = paths::config_file_paths();
         for (i, path) in config_paths.iter().enumerate() {
             let priority = match i {
@@ -216,58 +512,249 @@ impl ConfigCli {
         Ok(())
     }
 
+    ///
+    /// Serialise the configuration in a machine-readable format.
+    ///
+    /// # Arguments
+    /// * `config` - effective configuration
+    /// * `options` - output format and whether secrets are kept
+    ///
+    /// # Returns
+    /// * Serialised configuration
+    fn render_config(config: &Config, options: ShowOptions) -> Result<String> {
+        let mut config = config.clone();
+        if !options.include_sensitive {
+            config.redact_sensitive();
+        }
+
+        match options.format {
+            ShowFormat::Json | ShowFormat::Text => Ok(serde_json::to_string_pretty(&config)?),
+            ShowFormat::Toml => toml::to_string_pretty(&config).map_err(|e| {
+                ProxyError::Config(
+                    "Failed to serialise configuration as TOML".to_string(),
+                    Some(Box::new(e)),
+                )
+            }),
+            ShowFormat::Yaml => serde_yaml::to_string(&config).map_err(|e| {
+                ProxyError::Config(
+                    "Failed to serialise configuration as YAML".to_string(),
+                    Some(Box::new(e)),
+                )
+            }),
+        }
+    }
+
+    ///
+    /// Handle the `config export` command.
+    ///
+    /// Prints the effective configuration so it can be moved into a container
+    /// or CI environment: as `export` lines that [`ConfigLoader::with_env_vars`]
+    /// reads back, or as JSON.
+    ///
+    /// [`ConfigLoader::with_env_vars`]: crate::config::loader::ConfigLoader::with_env_vars
+    ///
+    /// # Arguments
+    /// * `options` - output format and whether secrets are redacted
+    ///
+    /// # Returns
+    /// * `Ok(())` - Configuration printed
+    /// * `Err(ProxyError)` - Failed to load or serialise configuration
+    pub fn export(options: ExportOptions) -> Result<()> {
+        let config = Config::load()?;
+        print!("{}", Self::render_export(&config, options)?);
+        Ok(())
+    }
+
+    ///
+    /// Serialise the configuration for `config export`.
+    ///
+    /// # Arguments
+    /// * `config` - effective configuration
+    /// * `options` - output format and whether secrets are redacted
+    ///
+    /// # Returns
+    /// * Newline-terminated output
+    fn render_export(config: &Config, options: ExportOptions) -> Result<String> {
+        let mut config = config.clone();
+        if options.redact_secrets {
+            config.redact_sensitive();
+            if config.auth.service_account_file.is_some() {
+                config.auth.service_account_file = Some(REDACTED.to_string());
+            }
+        }
+
+        match options.format {
+            ExportFormat::Env => Ok(to_env_vars(&config)
+                .into_iter()
+                .map(|(name, value)| format!("export {}={}\n", name, shell_quote(&value)))
+                .collect()),
+            ExportFormat::Json => Ok(format!("{}\n", serde_json::to_string_pretty(&config)?)),
+        }
+    }
+
+    ///
+    /// Handle the `config schema` command.
+    ///
+    /// Prints the JSON Schema (draft-07) of the configuration file, or writes
+    /// it to `options.output`. Editors use it to complete and check `config.toml`.
+    ///
+    /// # Arguments
+    /// * `options` - output file, stdout when `None`
+    ///
+    /// # Returns
+    /// * `Ok(())` - Schema printed or written
+    /// * `Err(ProxyError)` - Failed to serialise or write the schema
+    pub fn schema(options: SchemaOptions) -> Result<()> {
+        match options.output {
+            Some(path) => {
+                schema::write_schema(&path)?;
+                eprintln!("✅ JSON Schema written to: {}", path.display());
+            }
+            None => print!("{}", schema::render_schema()?),
+        }
+        Ok(())
+    }
+
+    ///
+    /// Handle the `config diff` command.
+    ///
+    /// Lists only the settings of the effective configuration that differ
+    /// from the built-in defaults, which shows at a glance what a config
+    /// file or `MODELMUX_*` variable changed.
+    ///
+    /// # Arguments
+    /// * `options` - output format
+    ///
+    /// # Returns
+    /// * `Ok(())` - Differences printed
+    /// * `Err(ProxyError)` - Failed to load or serialise configuration
+    pub fn diff(options: DiffOptions) -> Result<()> {
+        let config = Config::load()?;
+        let differences = ConfigDiffer::diff(&Config::default(), &config)?;
+
+        match options.format {
+            DiffFormat::Json => {
+                let json: Vec<Value> = differences
+                    .iter()
+                    .map(|d| json!({"field": d.field, "default": d.default, "current": d.current}))
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&json)?);
+            }
+            DiffFormat::Text if differences.is_empty() => {
+                println!("✅ Configuration matches the defaults");
+            }
+            DiffFormat::Text => {
+                println!("📋 Settings that differ from the defaults ({}):", differences.len());
+                for line in ConfigDiffer::render_text(&differences) {
+                    println!("{}", line);
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Handle the `config validate` command
     ///
     /// Performs comprehensive validation of the current configuration
     /// and provides detailed feedback about any issues found.
     ///
+    /// # Arguments
+    /// * `options` - Output mode and warning handling
+    ///
     /// # Returns
-    /// * `Ok(())` - Configuration is valid
-    /// * `Err(ProxyError)` - Configuration validation failed
-    pub fn validate() -> Result<()> {
-        println!("🔍 Validating ModelMux Configuration");
-        println!("====================================");
-        println!();
+    /// * Process exit code: `0` without issues, `1` for errors (or warnings
+    ///   with `--exit-on-warnings`), `2` for warnings only
+    pub fn validate(options: ValidateOptions) -> i32 {
+        let text = !options.quiet && !options.json;
+        if text {
+            println!("🔍 Validating ModelMux Configuration");
+            println!("====================================");
+            println!();
 
-        // Load configuration
-        print!("Loading configuration... ");
-        io::stdout().flush().unwrap();
+            // Load configuration
+            print!("Loading configuration... ");
+            io::stdout().flush().unwrap();
+        }
+
+        // Load the .env file before the configuration so the variables it sets
+        // can be listed; a failure is reported by `Config::load` below
+        let dotenv_variables = ConfigLoader::new()
+            .with_dotenv()
+            .map(|loader| loader.dotenv_variables().to_vec())
+            .unwrap_or_default();
 
         let config = match Config::load() {
             Ok(config) => {
-                println!("✅ Loaded");
+                if text {
+                    println!("✅ Loaded");
+                    if !dotenv_variables.is_empty() {
+                        println!(
+                            "   .env file {} set: {}",
+                            loader::dotenv_file().display(),
+                            dotenv_variables.join(", ")
+                        );
+                    }
+                }
                 config
             }
             Err(e) => {
-                println!("❌ Failed");
-                println!();
-                println!("Configuration loading failed:");
-                println!("{}", e);
-                return Err(e);
+                if text {
+                    println!("❌ Failed");
+                    println!();
+                    println!("Configuration loading failed:");
+                    println!("{}", e);
+                } else if options.json {
+                    let issue = ValidationIssue {
+                        field: "config".to_string(),
+                        severity: Severity::Error,
+                        code: "CONFIG_LOAD_FAILED",
+                        message: e.to_string(),
+                    };
+                    Self::print_issues_json(&[issue]);
+                }
+                return EXIT_ERRORS;
             }
         };
 
         // Validate configuration
+        let mut issues = config.validate_with_issues();
+        if options.check_size {
+            match Self::observed_max_request_bytes(config.server.port) {
+                Some(observed) => {
+                    issues.extend(body_limit_issue(config.limits.max_request_body_bytes, observed))
+                }
+                None if text => println!(
+                    "⏭️  Size check skipped: no ModelMux server answers on port {}",
+                    config.server.port
+                ),
+                None => {}
+            }
+        }
+        let exit_code = validation_exit_code(&issues, options.exit_on_warnings);
+        if options.quiet {
+            return exit_code;
+        }
+        if options.json {
+            Self::print_issues_json(&issues);
+            return exit_code;
+        }
+
         print!("Validating configuration... ");
         io::stdout().flush().unwrap();
-
-        let validation_result = ConfigValidator::new(&config).validate();
-
-        match validation_result {
-            Ok(()) => {
-                println!("✅ Valid");
-                println!();
-                println!("🎉 Configuration validation passed!");
-                println!("Your ModelMux configuration is ready to use.");
-            }
-            Err(e) => {
-                println!("❌ Invalid");
-                println!();
-                println!("Configuration validation failed:");
-                println!("{}", e);
-                return Err(e);
+        let has_errors = issues.iter().any(|issue| issue.severity == Severity::Error);
+        println!("{}", if has_errors { "❌ Invalid" } else { "✅ Valid" });
+        if !issues.is_empty() {
+            println!();
+            for issue in &issues {
+                println!("{}", Self::issue_line(issue));
             }
         }
+        println!();
+        println!("{}", validation_summary(&issues));
+        if has_errors {
+            println!("Please fix these issues and try again.");
+            return exit_code;
+        }
 
         // Additional checks
         println!();
@@ -277,13 +764,19 @@ impl ConfigCli {
         print!("Testing service account access... ");
         io::stdout().flush().unwrap();
 
-        match config.load_service_account_key() {
-            Ok(_) => {
-                println!("✅ Service account loaded successfully");
-            }
-            Err(e) => {
-                println!("❌ Service account loading failed");
-                println!("   Error: {}", e);
+        if config.auth.use_workload_identity {
+            println!("⏭️  Skipped, tokens come from the metadata server (Workload Identity)");
+        } else if config.auth.use_application_default_credentials {
+            println!("⏭️  Skipped, tokens come from Application Default Credentials");
+        } else {
+            match config.load_service_account_key() {
+                Ok(_) => {
+                    println!("✅ Service account loaded successfully");
+                }
+                Err(e) => {
+                    println!("❌ Service account loading failed");
+                    println!("   Error: {}", e);
+                }
             }
         }
 
@@ -311,7 +804,42 @@ impl ConfigCli {
             println!("    • {} → {}", name, url);
         }
 
-        Ok(())
+        exit_code
+    }
+
+    ///
+    /// One line describing a validation issue for terminal output.
+    ///
+    /// Errors are red and warnings amber when stdout is a terminal and
+    /// `NO_COLOR` is not set.
+    ///
+    /// # Arguments
+    /// * `issue` - issue to describe
+    ///
+    /// # Returns
+    /// * `<marker> [<code>] <field>: <message>`
+    pub fn issue_line(issue: &ValidationIssue) -> String {
+        let color = io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+        Self::render_issue(issue, color)
+    }
+
+    ///
+    /// Describe a validation issue, optionally wrapped in its ANSI colour.
+    fn render_issue(issue: &ValidationIssue, color: bool) -> String {
+        let (marker, ansi) = match issue.severity {
+            Severity::Error => ("❌", ANSI_RED),
+            Severity::Warning => ("⚠️ ", ANSI_AMBER),
+        };
+        let line = format!("{} [{}] {}: {}", marker, issue.code, issue.field, issue.message);
+        if color { format!("{}{}{}", ansi, line, ANSI_RESET) } else { line }
+    }
+
+    /// Print validation issues as a JSON array
+    fn print_issues_json(issues: &[ValidationIssue]) {
+        match serde_json::to_string_pretty(issues) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("Error: failed to serialize validation issues: {}", e),
+        }
     }
 
     /// Handle the `config edit` command
@@ -329,18 +857,11 @@ impl ConfigCli {
         if !config_file.exists() {
             println!("Configuration file doesn't exist. Creating example configuration...");
 
-            let config_dir = config_file.parent().unwrap();
-            fs::create_dir_all(config_dir).map_err(|e| {
-                ProxyError::Config(format!(
-                    "Failed to create config directory '{}': {}",
-                    config_dir.display(),
-                    e
-                ))
-            })?;
+            Self::create_config_dir(&config_file)?;
 
             let example_config = Config::example_toml();
             fs::write(&config_file, example_config).map_err(|e| {
-                ProxyError::Config(format!("Failed to create example configuration: {}", e))
+                ProxyError::config_with_source("Failed to create example configuration", e)
             })?;
         }
 
@@ -375,17 +896,20 @@ impl ConfigCli {
         command.arg(&config_file);
 
         let status = command.status().map_err(|e| {
-            ProxyError::Config(format!(
-                "Failed to launch editor '{}': {}\n\
+            ProxyError::Config(
+                format!(
+                    "Failed to launch editor '{}': {}\n\
                  \n\
                  You can also edit the configuration file manually:\n\
                  {}\n\
                  \n\
                  Or set the EDITOR environment variable to your preferred editor.",
-                editor,
-                e,
-                config_file.display()
-            ))
+                    editor,
+                    e,
+                    config_file.display()
+                ),
+                None,
+            )
         })?;
 
         if status.success() {
@@ -398,57 +922,872 @@ impl ConfigCli {
         Ok(())
     }
 
-    /* --- private helper methods ---------------------------------------------------------- */
+    /// Handle the `config backup` command
+    ///
+    /// Copies the user configuration file to `config.{timestamp}.toml.bak` in the
+    /// same directory. Only the newest 10 backups are kept.
+    ///
+    /// # Returns
+    /// * `Ok(())` - Backup created
+    /// * `Err(ProxyError)` - Config file missing or backup could not be written
+    pub fn backup() -> Result<()> {
+        let config_file = paths::user_config_file()?;
+        if !config_file.exists() {
+            return Err(ProxyError::Config(
+                format!(
+                    "Configuration file not found at '{}'. Nothing to back up.",
+                    config_file.display()
+                ),
+                None,
+            ));
+        }
 
-    /// Gather configuration through interactive prompts
-    fn gather_config_interactively() -> Result<Config> {
-        let mut config = Config::default();
+        let backup_file = Self::backup_config_file(&config_file)?;
+        println!("💾 Configuration backed up to: {}", backup_file.display());
+        Ok(())
+    }
 
-        // Server configuration
-        println!("📡 Server Configuration");
-        println!("======================");
+    /// Handle the `config restore` command
+    ///
+    /// Without a file, lists the available backups (newest first). With a file,
+    /// checks that it is valid configuration, backs up the current config file
+    /// and replaces it with the given backup.
+    ///
+    /// # Arguments
+    /// * `backup_file` - backup to restore, or `None` to list backups
+    ///
+    /// # Returns
+    /// * `Ok(())` - Backups listed or configuration restored
+    /// * `Err(ProxyError)` - Backup missing, invalid, or not restorable
+    pub fn restore(backup_file: Option<&Path>) -> Result<()> {
+        let config_file = paths::user_config_file()?;
 
-        config.server.port = Self::prompt_number("HTTP server port", config.server.port, 1, 65535)?;
+        let Some(backup_file) = backup_file else {
+            let backups = Self::list_backups(&config_file)?;
+            if backups.is_empty() {
+                println!("No configuration backups found for {}", config_file.display());
+                return Ok(());
+            }
 
-        config.server.log_level = Self::prompt_log_level(
-            "Logging level (trace/debug/info/warn/error)",
-            config.server.log_level,
-        )?;
+            println!("Available configuration backups (newest first):");
+            for backup in backups.iter().rev() {
+                println!("  {}", backup.display());
+            }
+            println!();
+            println!("Restore one with: modelmux config restore --file <path>");
+            return Ok(());
+        };
 
-        config.server.enable_retries = Self::prompt_bool(
-            "Enable automatic retries for rate limits",
-            config.server.enable_retries,
-        )?;
+        if let Some(previous) = Self::restore_config_file(&config_file, backup_file)? {
+            println!("💾 Previous configuration backed up to: {}", previous.display());
+        }
+        println!("✅ Configuration restored from: {}", backup_file.display());
+        println!("Run 'modelmux config validate' to verify the restored configuration.");
+        Ok(())
+    }
 
-        if config.server.enable_retries {
-            config.server.max_retry_attempts = Self::prompt_number(
-                "Maximum retry attempts",
-                config.server.max_retry_attempts,
-                1,
-                10,
-            )?;
+    /// Handle the `config migrate` command
+    ///
+    /// Converts a legacy environment variable setup (`GCP_SERVICE_ACCOUNT_KEY`,
+    /// `LLM_URL`, `LLM_CHAT_ENDPOINT`, `LLM_MODEL`, ...) into a configuration
+    /// file. The base64 key is decoded to `service-account.json` in the config
+    /// directory. A diff against the current config file is printed and
+    /// confirmed before anything is written, and the result is validated.
+    ///
+    /// # Arguments
+    /// * `options` - dry-run option
+    ///
+    /// # Returns
+    /// * `Ok(i32)` - Process exit code: the `config validate` result after a
+    ///   migration, `0` with `--dry-run`, nothing to migrate or a declined prompt
+    /// * `Err(ProxyError)` - Legacy variables invalid or files not writable
+    pub fn migrate(options: MigrateOptions) -> Result<i32> {
+        // Legacy setups often keep their variables in a .env file
+        ConfigLoader::new().with_dotenv()?;
+
+        let config_file = paths::user_config_file()?;
+        let key_file = config_file.with_file_name(MIGRATED_SERVICE_ACCOUNT_FILE);
+
+        let Some(migration) =
+            migration::migrate_legacy_env(|name| std::env::var(name).ok(), &key_file)?
+        else {
+            println!("No legacy configuration found: GCP_SERVICE_ACCOUNT_KEY is not set.");
+            return Ok(EXIT_VALID);
+        };
+
+        let config_toml = toml::to_string_pretty(&migration.config)
+            .map_err(|e| ProxyError::config_with_source("Failed to serialize configuration", e))?;
+
+        if options.dry_run {
+            print!("{}", config_toml);
+            return Ok(EXIT_VALID);
         }
 
-        println!();
+        if key_file.exists() {
+            let existing = fs::read_to_string(&key_file).unwrap_or_default();
+            if existing.trim() != migration.service_account_json.trim() {
+                return Err(ProxyError::Config(
+                    format!(
+                        "'{}' already exists with a different key. Move it away and run \
+                         'modelmux config migrate' again.",
+                        key_file.display()
+                    ),
+                    None,
+                ));
+            }
+        }
 
-        // Provider configuration note
-        println!("🤖 LLM Provider Configuration");
-        println!("=============================");
-        println!("Provider configuration is handled via environment variables.");
-        println!("Current provider will be detected automatically from:");
-        println!("  LLM_PROVIDER=vertex (default)");
-        println!("  VERTEX_PROJECT, VERTEX_LOCATION, VERTEX_MODEL_ID, etc.");
-        println!("  or LLM_URL for direct URL override");
+        println!("🔄 ModelMux Legacy Configuration Migration");
+        println!("==========================================");
         println!();
-        println!("For detailed provider setup, see the documentation or run:");
-        println!("  modelmux --help");
+        println!("Service account key ({}) will be written to:", migration.client_email);
+        println!("   {}", key_file.display());
+        println!();
+        println!("Changes to {}:", config_file.display());
+        let current = fs::read_to_string(&config_file).unwrap_or_default();
+        print!("{}", migration::line_diff(&current, &config_toml));
         println!();
 
-        // Authentication configuration
-        println!("🔐 Authentication Configuration");
-        println!("===============================");
+        if !Self::prompt_bool("Write these files?", false)? {
+            println!("Migration cancelled; nothing was written.");
+            return Ok(EXIT_VALID);
+        }
 
-        let use_file =
+        Self::create_config_dir(&config_file)?;
+
+        fs::write(&key_file, &migration.service_account_json).map_err(|e| {
+            ProxyError::config_with_source(
+                format!("Failed to write service account key '{}'", key_file.display()),
+                e,
+            )
+        })?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&key_file, fs::Permissions::from_mode(0o600)).map_err(|e| {
+                ProxyError::config_with_source(
+                    format!("Failed to restrict permissions of '{}'", key_file.display()),
+                    e,
+                )
+            })?;
+        }
+        println!("🔑 Service account key saved to: {}", key_file.display());
+
+        if config_file.exists() {
+            let backup_file = Self::backup_config_file(&config_file)?;
+            println!("💾 Previous configuration backed up to: {}", backup_file.display());
+        }
+        fs::write(&config_file, config_toml).map_err(|e| {
+            ProxyError::config_with_source(
+                format!("Failed to write configuration file '{}'", config_file.display()),
+                e,
+            )
+        })?;
+        println!("✅ Configuration saved to: {}", config_file.display());
+        println!(
+            "Unset GCP_SERVICE_ACCOUNT_KEY and the other legacy variables so they no longer \
+             override the file."
+        );
+        println!();
+
+        Ok(Self::validate(ValidateOptions::default()))
+    }
+
+    /// Handle the `completion <shell>` command
+    ///
+    /// Builds a static completion script for the known commands, `config`
+    /// subcommands and their options.
+    ///
+    /// # Arguments
+    /// * `shell` - target shell: bash, zsh, fish or powershell
+    ///
+    /// # Returns
+    /// * `Ok(String)` - Completion script to print
+    /// * `Err(ProxyError)` - Unsupported shell
+    pub fn completion(shell: &str) -> Result<String> {
+        match shell {
+            "bash" => Ok(Self::bash_completion()),
+            "zsh" => Ok(format!(
+                "#compdef modelmux\n\nautoload -U bashcompinit\nbashcompinit\n\n{}",
+                Self::bash_completion()
+            )),
+            "fish" => Ok(Self::fish_completion()),
+            "powershell" => Ok(Self::powershell_completion()),
+            _ => Err(ProxyError::Config(
+                format!(
+                    "Unsupported shell '{}'. Supported shells: {}",
+                    shell,
+                    COMPLETION_SHELLS.join(", ")
+                ),
+                None,
+            )),
+        }
+    }
+
+    /// Bash completion script (also loaded by zsh through `bashcompinit`)
+    fn bash_completion() -> String {
+        let options: String = COMPLETION_OPTIONS
+            .iter()
+            .map(|(name, options)| {
+                format!("        {}) opts=\"{}\" ;;\n", name, options.join(" "))
+            })
+            .collect();
+
+        format!(
+            r#"_modelmux() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}"
+    local opts=""
+
+    if [[ $COMP_CWORD -eq 1 ]]; then
+        COMPREPLY=($(compgen -W "{commands}" -- "$cur"))
+        return
+    fi
+
+    case "${{COMP_WORDS[1]}}" in
+        config)
+            if [[ $COMP_CWORD -eq 2 ]]; then
+                COMPREPLY=($(compgen -W "{config}" -- "$cur"))
+                return
+            fi
+            ;;
+        completion)
+            if [[ $COMP_CWORD -eq 2 ]]; then
+                COMPREPLY=($(compgen -W "{shells}" -- "$cur"))
+            fi
+            return
+            ;;
+    esac
+
+    local command="${{COMP_WORDS[1]}}"
+    [[ $command == config ]] && command="${{COMP_WORDS[2]}}"
+    case "$command" in
+{options}    esac
+    COMPREPLY=($(compgen -W "$opts" -- "$cur"))
+}}
+
+complete -F _modelmux modelmux
+"#,
+            commands = COMPLETION_COMMANDS.join(" "),
+            config = COMPLETION_CONFIG_SUBCOMMANDS.join(" "),
+            shells = COMPLETION_SHELLS.join(" "),
+            options = options,
+        )
+    }
+
+    /// Fish completion script
+    fn fish_completion() -> String {
+        let mut script = String::from("complete -c modelmux -f\n");
+        for command in COMPLETION_COMMANDS.iter().filter(|c| !c.starts_with('-')) {
+            script.push_str(&format!(
+                "complete -c modelmux -n __fish_use_subcommand -a {}\n",
+                command
+            ));
+        }
+        script.push_str("complete -c modelmux -n __fish_use_subcommand -l help\n");
+        script.push_str("complete -c modelmux -n __fish_use_subcommand -l version\n");
+        script.push_str(&format!(
+            "complete -c modelmux -n '__fish_seen_subcommand_from config' -a '{}'\n",
+            COMPLETION_CONFIG_SUBCOMMANDS.join(" ")
+        ));
+        script.push_str(&format!(
+            "complete -c modelmux -n '__fish_seen_subcommand_from completion' -a '{}'\n",
+            COMPLETION_SHELLS.join(" ")
+        ));
+        for (name, options) in COMPLETION_OPTIONS {
+            for option in options.iter().filter_map(|option| option.strip_prefix("--")) {
+                script.push_str(&format!(
+                    "complete -c modelmux -n '__fish_seen_subcommand_from {}' -l {}\n",
+                    name, option
+                ));
+            }
+        }
+        script
+    }
+
+    /// PowerShell completion script
+    fn powershell_completion() -> String {
+        let quote = |words: &[&str]| {
+            words.iter().map(|word| format!("'{}'", word)).collect::<Vec<_>>().join(", ")
+        };
+        let options: String = COMPLETION_OPTIONS
+            .iter()
+            .map(|(name, options)| format!("        '{}' = @({})\n", name, quote(options)))
+            .collect();
+
+        format!(
+            r#"Register-ArgumentCompleter -Native -CommandName modelmux -ScriptBlock {{
+    param($wordToComplete, $commandAst, $cursorPosition)
+
+    $words = @($commandAst.CommandElements | ForEach-Object {{ $_.ToString() }})
+    if ($wordToComplete) {{ $words = $words[0..($words.Count - 2)] }}
+
+    $options = @{{
+{options}    }}
+
+    $candidates = switch ($words.Count) {{
+        1 {{ @({commands}) }}
+        2 {{
+            switch ($words[1]) {{
+                'config' {{ @({config}) }}
+                'completion' {{ @({shells}) }}
+                default {{ $options[$words[1]] }}
+            }}
+        }}
+        default {{
+            if ($words[1] -eq 'config') {{ $options[$words[2]] }} else {{ $options[$words[1]] }}
+        }}
+    }}
+
+    $candidates | Where-Object {{ $_ -like "$wordToComplete*" }} | ForEach-Object {{
+        [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)
+    }}
+}}
+"#,
+            commands = quote(COMPLETION_COMMANDS),
+            config = quote(COMPLETION_CONFIG_SUBCOMMANDS),
+            shells = quote(COMPLETION_SHELLS),
+            options = options,
+        )
+    }
+
+    /// Append a client API key entry to the configuration file in use
+    ///
+    /// That is the custom file of `--config` / `MODELMUX_CONFIG_FILE` when set,
+    /// as in `Config::load`, and the user configuration file otherwise.
+    /// Adds a `[[auth.api_keys]]` table at the end of the file so existing
+    /// comments and formatting are preserved. The resulting file is parsed
+    /// before writing to make sure it is still valid configuration.
+    ///
+    /// # Arguments
+    /// * `entry` - API key entry (name, hash, allowed models) to register
+    ///
+    /// # Returns
+    /// * `Ok(PathBuf)` - Path of the updated configuration file
+    /// * `Err(ProxyError)` - Config file missing, invalid, or not writable
+    pub fn append_api_key(entry: &ApiKeyEntry) -> Result<PathBuf> {
+        let config_file = match loader::custom_config_file()? {
+            Some(path) => path,
+            None => paths::user_config_file()?,
+        };
+        if !config_file.exists() {
+            return Err(ProxyError::Config(
+                format!(
+                    "Configuration file not found at '{}'.\n\
+                 Run 'modelmux config init' first, or add the key_hash manually.",
+                    config_file.display()
+                ),
+                None,
+            ));
+        }
+
+        Self::append_api_key_to_file(&config_file, entry)?;
+        Ok(config_file)
+    }
+
+    /// Handle the `test-connection` command
+    ///
+    /// Loads the configuration, fetches a real access token and sends a
+    /// 1-token completion ("Say hi") to the configured upstream, so the whole
+    /// auth → HTTP → response path is exercised. Each failing step is reported
+    /// with the error and what to check.
+    ///
+    /// # Returns
+    /// * Process exit code: `0` when the upstream answered, `1` otherwise
+    pub async fn test_connection() -> i32 {
+        println!("🔌 Testing ModelMux Connection");
+        println!("==============================");
+        println!();
+
+        print!("Loading configuration... ");
+        io::stdout().flush().unwrap();
+        let config = match Config::load() {
+            Ok(config) => {
+                println!("✅ Loaded");
+                config
+            }
+            Err(e) => {
+                return Self::connection_failed(
+                    "Configuration loading failed",
+                    &e.to_string(),
+                    "Run 'modelmux config validate' to find the problem.",
+                );
+            }
+        };
+        let Some(provider) = config.llm_provider.as_ref() else {
+            return Self::connection_failed(
+                "No LLM provider configured",
+                "the configuration names no upstream",
+                "Run 'modelmux config init' to set up a provider.",
+            );
+        };
+
+        let url = provider.build_request_url(false);
+        println!("   Endpoint: {}", url);
+        match provider.auth_strategy() {
+            AuthStrategy::GcpOAuth2(key) => println!("   Service account: {}", key.client_email),
+            AuthStrategy::WorkloadIdentity { .. } => {
+                println!("   Service account: metadata server default (Workload Identity)")
+            }
+            AuthStrategy::ApplicationDefault { credentials_file } => println!(
+                "   Credentials: Application Default Credentials ({})",
+                credentials_file.as_deref().unwrap_or("default location")
+            ),
+            AuthStrategy::BearerToken(_) if provider.api_key_header().is_some() => {
+                println!("   Credentials: API key (api-key header)")
+            }
+            AuthStrategy::BearerToken(_) => println!("   Credentials: API key (Bearer token)"),
+            AuthStrategy::AwsSigV4 { access_key, .. } => {
+                println!("   Credentials: AWS access key {} (SigV4)", access_key)
+            }
+        }
+
+        print!("Fetching access token... ");
+        io::stdout().flush().unwrap();
+        let token = match provider.api_key_header() {
+            Some(api_key) => Ok(Authorization::ApiKey(api_key.to_string())),
+            None => match RequestAuth::from_strategy(provider.auth_strategy()).await {
+                Ok(auth) => auth.authorization().await,
+                Err(e) => Err(e),
+            },
+        };
+        let authorization = match token {
+            Ok(authorization) => {
+                println!("✅ Token received");
+                authorization
+            }
+            Err(e) => {
+                return Self::connection_failed(
+                    "Access token fetch failed",
+                    &e.to_string(),
+                    "Check that the service account key is valid and not disabled, and that the \
+                     machine can reach oauth2.googleapis.com (or the metadata server).",
+                );
+            }
+        };
+
+        let body = match Self::ping_request_body(&config, provider)
+            .and_then(|body| Ok(serde_json::to_vec(&body)?))
+        {
+            Ok(body) => body,
+            Err(e) => {
+                return Self::connection_failed(
+                    "Building the ping request failed",
+                    &e.to_string(),
+                    "Please report this as a bug.",
+                );
+            }
+        };
+
+        print!("Sending ping request... ");
+        io::stdout().flush().unwrap();
+        let started = Instant::now();
+        let request = reqwest::Client::new()
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .timeout(PING_TIMEOUT);
+        let request = match authorization.apply(request, &url, &body) {
+            Ok(request) => request,
+            Err(e) => {
+                return Self::connection_failed(
+                    "Signing the ping request failed",
+                    &e.to_string(),
+                    "Check AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY and AWS_REGION.",
+                );
+            }
+        };
+        let sent = request.body(body).send().await;
+        let response = match sent {
+            Ok(response) => response,
+            Err(e) => {
+                return Self::connection_failed(
+                    "HTTP request failed",
+                    &e.to_string(),
+                    "Check the network connection, proxy settings and the endpoint URL \
+                     (region and project).",
+                );
+            }
+        };
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_default();
+            return Self::connection_failed(
+                &format!("Upstream returned {}", status),
+                error_body.trim(),
+                Self::status_suggestion(status.as_u16()),
+            );
+        }
+        if let Err(e) = response.json::<Value>().await {
+            return Self::connection_failed(
+                "Response is not valid JSON",
+                &e.to_string(),
+                "Check that the endpoint URL points at the model itself, not at a proxy or \
+                 login page.",
+            );
+        }
+
+        println!("✅ Connection OK (latency: {}ms)", started.elapsed().as_millis());
+        0
+    }
+
+    /* --- private helper methods ---------------------------------------------------------- */
+
+    /// Largest request body seen by a ModelMux server running on this machine
+    ///
+    /// Reads `max_request_bytes` from `GET /health` on `127.0.0.1:<port>`;
+    /// `None` when no server answers or the response has no such metric.
+    fn observed_max_request_bytes(port: u16) -> Option<u64> {
+        let address = std::net::SocketAddr::from(([127, 0, 0, 1], port));
+        let mut stream =
+            std::net::TcpStream::connect_timeout(&address, HEALTH_CHECK_TIMEOUT).ok()?;
+        stream.set_read_timeout(Some(HEALTH_CHECK_TIMEOUT)).ok()?;
+        write!(
+            stream,
+            "GET /health HTTP/1.1\r\nHost: 127.0.0.1:{}\r\nConnection: close\r\n\r\n",
+            port
+        )
+        .ok()?;
+        let mut response = String::new();
+        io::Read::read_to_string(&mut stream, &mut response).ok()?;
+        let (_, body) = response.split_once("\r\n\r\n")?;
+        let health: Value = serde_json::from_str(body).ok()?;
+        health["metrics"]["max_request_bytes"].as_u64()
+    }
+
+    /// Minimal 1-token completion request in the upstream's format
+    ///
+    /// Vertex AI and Bedrock requests go through the OpenAI → Anthropic
+    /// converter like proxied ones, Ollama requests through the OpenAI →
+    /// Ollama converter; OpenAI-compatible and Azure OpenAI upstreams take
+    /// the OpenAI request.
+    fn ping_request_body(config: &Config, provider: &LlmProviderConfig) -> Result<Value> {
+        let request = json!({
+            "model": provider.display_model_name(),
+            "messages": [{"role": "user", "content": PING_PROMPT}],
+            "max_tokens": 1,
+        });
+        match provider {
+            LlmProviderConfig::Vertex(_) | LlmProviderConfig::Bedrock(_) => {
+                let request: OpenAiRequest = serde_json::from_value(request)?;
+                let anthropic = OpenAiToAnthropicConverter::new(config.server.log_level)
+                    .convert(request, provider.target_format(), None)?;
+                anthropic.to_request_body()
+            }
+            LlmProviderConfig::OpenAiCompatible(_) | LlmProviderConfig::AzureOpenAi(_) => {
+                Ok(request)
+            }
+            LlmProviderConfig::Ollama(_) => {
+                let request: OpenAiRequest = serde_json::from_value(request)?;
+                let ollama = OllamaConverter::new()
+                    .convert_request(request, provider.display_model_name())?;
+                Ok(serde_json::to_value(ollama)?)
+            }
+        }
+    }
+
+    /// What to check when the upstream rejects the ping request
+    fn status_suggestion(status: u16) -> &'static str {
+        match status {
+            401 => "The access token was rejected. Check the service account key or API key.",
+            403 => {
+                "The credentials lack permission. Grant the service account the \
+                 'Vertex AI User' role (roles/aiplatform.user) on the project."
+            }
+            404 => {
+                "The model was not found. Check the project, region and model ID, and that \
+                 the model is enabled in the Vertex AI Model Garden for this region."
+            }
+            429 => "Quota exceeded. Wait and retry, or request a higher quota for the model.",
+            500..=599 => "The upstream is having problems. Retry in a few minutes.",
+            _ => "Check the request details in the error above.",
+        }
+    }
+
+    /// Print a failed `test-connection` step and return the failure exit code
+    fn connection_failed(step: &str, error: &str, suggestion: &str) -> i32 {
+        println!("❌ Failed");
+        println!();
+        println!("{}:", step);
+        println!("   Error: {}", error);
+        println!();
+        println!("💡 {}", suggestion);
+        1
+    }
+
+    ///
+    /// Handle the `provider list` command.
+    ///
+    /// Prints the primary and failover providers with the URLs requests are
+    /// actually sent to, so the combined effect of the config file and
+    /// environment variables can be checked without starting the server.
+    ///
+    /// # Returns
+    ///  * Exit code: 0 when listed, 1 when the configuration does not load
+    pub fn list_providers() -> i32 {
+        match Config::load() {
+            Ok(config) => {
+                print!("{}", Self::render_providers(&config));
+                0
+            }
+            Err(e) => {
+                eprintln!("❌ Configuration loading failed: {}", e);
+                eprintln!();
+                eprintln!("💡 Run 'modelmux config validate' to find the problem.");
+                1
+            }
+        }
+    }
+
+    /// Provider listing printed by `provider list`
+    fn render_providers(config: &Config) -> String {
+        let mut providers: Vec<(String, &LlmProviderConfig, &str)> =
+            [("Primary", &config.llm_provider), ("Failover", &config.failover_provider)]
+                .into_iter()
+                .filter_map(|(role, provider)| {
+                    provider.as_ref().map(|p| (role.to_string(), p, p.display_model_name()))
+                })
+                .collect();
+        let mut routes: Vec<_> = config.route_providers.iter().collect();
+        routes.sort_by_key(|(name, _)| name.as_str());
+        providers.extend(routes.into_iter().map(|(name, route)| {
+            (format!("Route {}", name), &route.provider_config, route.display_name.as_str())
+        }));
+        if providers.is_empty() {
+            return "No LLM provider configured. Run 'modelmux config init' to set up a \
+                    provider.\n"
+                .to_string();
+        }
+
+        let mut output = String::new();
+        for (role, provider, model) in providers {
+            output.push_str(&format!("{} provider: {}\n", role, provider.id()));
+            output.push_str(&format!("  Model:         {}\n", model));
+            output.push_str(&format!("  URL:           {}\n", provider.build_request_url(false)));
+            output.push_str(&format!("  Streaming URL: {}\n", provider.build_request_url(true)));
+            output.push_str(&format!(
+                "  Auth:          {}\n",
+                Self::describe_auth(provider.auth_strategy())
+            ));
+            output.push('\n');
+        }
+        output
+    }
+
+    /// Authentication of a provider, without its secrets
+    fn describe_auth(strategy: &AuthStrategy) -> String {
+        match strategy {
+            AuthStrategy::GcpOAuth2(key) => format!("GCP OAuth2 ({})", key.client_email),
+            AuthStrategy::WorkloadIdentity { .. } => {
+                "GCP Workload Identity (metadata server)".to_string()
+            }
+            AuthStrategy::ApplicationDefault { .. } => {
+                "GCP Application Default Credentials".to_string()
+            }
+            AuthStrategy::BearerToken(_) => format!("Bearer token {}", REDACTED),
+            AuthStrategy::AwsSigV4 { region, .. } => {
+                format!("AWS SigV4 ({}, access key {})", region, REDACTED)
+            }
+        }
+    }
+
+    /// Append an API key entry to a specific configuration file
+    fn append_api_key_to_file(config_file: &Path, entry: &ApiKeyEntry) -> Result<()> {
+        let existing = fs::read_to_string(config_file).map_err(|e| {
+            ProxyError::config_with_source(
+                format!("Failed to read configuration file '{}'", config_file.display()),
+                e,
+            )
+        })?;
+
+        let table = toml::to_string(entry)
+            .map_err(|e| ProxyError::config_with_source("Failed to serialize API key entry", e))?;
+        let separator = if existing.is_empty() || existing.ends_with('\n') { "" } else { "\n" };
+        let updated = format!("{}{}\n[[auth.api_keys]]\n{}", existing, separator, table);
+
+        toml::from_str::<Config>(&updated).map_err(|e| {
+            ProxyError::Config(
+                format!(
+                    "Cannot add API key to '{}': resulting file would be invalid: {}\n\
+                 Add the key_hash manually under [[auth.api_keys]].",
+                    config_file.display(),
+                    e
+                ),
+                None,
+            )
+        })?;
+
+        fs::write(config_file, updated).map_err(|e| {
+            ProxyError::config_with_source(
+                format!("Failed to write configuration file '{}'", config_file.display()),
+                e,
+            )
+        })
+    }
+
+    /// Copy a configuration file to a timestamped backup next to it
+    ///
+    /// Deletes the oldest backups when more than `MAX_CONFIG_BACKUPS` exist.
+    fn backup_config_file(config_file: &Path) -> Result<PathBuf> {
+        let timestamp = chrono::Utc::now().format("%Y%m%d-%H%M%S-%3f");
+        let backup_file = config_file.with_file_name(format!(
+            "{}.{}{}",
+            Self::config_file_stem(config_file),
+            timestamp,
+            BACKUP_SUFFIX
+        ));
+
+        fs::copy(config_file, &backup_file).map_err(|e| {
+            ProxyError::config_with_source(
+                format!("Failed to back up configuration to '{}'", backup_file.display()),
+                e,
+            )
+        })?;
+
+        let backups = Self::list_backups(config_file)?;
+        let excess = backups.len().saturating_sub(MAX_CONFIG_BACKUPS);
+        for old_backup in &backups[..excess] {
+            if let Err(e) = fs::remove_file(old_backup) {
+                println!("⚠️  Failed to delete old backup '{}': {}", old_backup.display(), e);
+            }
+        }
+
+        Ok(backup_file)
+    }
+
+    /// List backups of a configuration file, oldest first
+    fn list_backups(config_file: &Path) -> Result<Vec<PathBuf>> {
+        let Some(config_dir) = config_file.parent().filter(|dir| dir.exists()) else {
+            return Ok(Vec::new());
+        };
+
+        let prefix = format!("{}.", Self::config_file_stem(config_file));
+        let entries = fs::read_dir(config_dir).map_err(|e| {
+            ProxyError::config_with_source(
+                format!("Failed to read config directory '{}'", config_dir.display()),
+                e,
+            )
+        })?;
+
+        // Timestamps are fixed-width, so name order is chronological order
+        let mut backups: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(&prefix) && name.ends_with(BACKUP_SUFFIX))
+            })
+            .collect();
+        backups.sort();
+        Ok(backups)
+    }
+
+    /// Replace a configuration file with a backup, backing up the current file first
+    ///
+    /// Returns the backup of the replaced file, if there was one.
+    fn restore_config_file(config_file: &Path, backup_file: &Path) -> Result<Option<PathBuf>> {
+        let contents = fs::read_to_string(backup_file).map_err(|e| {
+            ProxyError::config_with_source(
+                format!("Failed to read backup '{}'", backup_file.display()),
+                e,
+            )
+        })?;
+
+        toml::from_str::<Config>(&contents).map_err(|e| {
+            ProxyError::config_with_source(
+                format!("Backup '{}' is not a valid configuration file", backup_file.display()),
+                e,
+            )
+        })?;
+
+        let previous = if config_file.exists() {
+            Some(Self::backup_config_file(config_file)?)
+        } else {
+            None
+        };
+
+        fs::write(config_file, contents).map_err(|e| {
+            ProxyError::config_with_source(
+                format!("Failed to write configuration file '{}'", config_file.display()),
+                e,
+            )
+        })?;
+
+        Ok(previous)
+    }
+
+    /// Create the directory of a configuration file if it doesn't exist
+    fn create_config_dir(config_file: &Path) -> Result<()> {
+        let config_dir = config_file.parent().ok_or_else(|| {
+            ProxyError::Config(
+                format!("Configuration file '{}' has no parent directory", config_file.display()),
+                None,
+            )
+        })?;
+        fs::create_dir_all(config_dir).map_err(|e| {
+            ProxyError::config_with_source(
+                format!("Failed to create config directory '{}'", config_dir.display()),
+                e,
+            )
+        })
+    }
+
+    /// File name of a configuration file without its extension (`config` for `config.toml`)
+    fn config_file_stem(config_file: &Path) -> String {
+        config_file
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "config".to_string())
+    }
+
+    /// Gather configuration through interactive prompts
+    fn gather_config_interactively() -> Result<Config> {
+        let mut config = Config::default();
+
+        // Server configuration
+        println!("📡 Server Configuration");
+        println!("======================");
+
+        config.server.port = Self::prompt_number("HTTP server port", config.server.port, 1, 65535)?;
+
+        config.server.log_level = Self::prompt_log_level(
+            "Logging level (trace/debug/info/warn/error)",
+            config.server.log_level,
+        )?;
+
+        config.server.enable_retries = Self::prompt_bool(
+            "Enable automatic retries for rate limits",
+            config.server.enable_retries,
+        )?;
+
+        if config.server.enable_retries {
+            config.server.max_retry_attempts = Self::prompt_number(
+                "Maximum retry attempts",
+                config.server.max_retry_attempts,
+                1,
+                10,
+            )?;
+        }
+
+        println!();
+
+        // Provider configuration note
+        println!("🤖 LLM Provider Configuration");
+        println!("=============================");
+        println!("Provider configuration is handled via environment variables.");
+        println!("Current provider will be detected automatically from:");
+        println!("  LLM_PROVIDER=vertex (default)");
+        println!("  VERTEX_PROJECT, VERTEX_LOCATION, VERTEX_MODEL_ID, etc.");
+        println!("  or LLM_URL for direct URL override");
+        println!();
+        println!("For detailed provider setup, see the documentation or run:");
+        println!("  modelmux --help");
+        println!();
+
+        // Authentication configuration
+        println!("🔐 Authentication Configuration");
+        println!("===============================");
+
+        let use_file =
             Self::prompt_bool("Use service account file (recommended for development)", true)?;
 
         if use_file {
@@ -497,16 +1836,133 @@ impl ConfigCli {
         Ok(config)
     }
 
+    /// Build configuration from `MODELMUX_SETUP_*` variables
+    ///
+    /// Mirrors the interactive wizard: every value has the same default and
+    /// range, except the service account file, which has no default and must
+    /// be set.
+    ///
+    /// # Arguments
+    /// * `lookup` - returns the value of a variable, if set
+    ///
+    /// # Returns
+    /// * `Ok(Config)` - Configuration built from the variables
+    /// * `Err(ProxyError)` - A required variable is missing or a value is invalid
+    fn gather_config_from_env(lookup: impl Fn(&str) -> Option<String>) -> Result<Config> {
+        let mut config = Config::default();
+        let var = |name: &str| {
+            let name = format!("{}{}", SETUP_ENV_PREFIX, name);
+            let value = lookup(&name).map(|value| value.trim().to_string());
+            (name, value.filter(|value| !value.is_empty()))
+        };
+
+        let (name, value) = var("PORT");
+        config.server.port = Self::setup_number(&name, value, config.server.port, 1, 65535)?;
+
+        if let (name, Some(value)) = var("LOG_LEVEL") {
+            config.server.log_level = value.parse().map_err(|_| {
+                ProxyError::Config(
+                    format!(
+                        "Invalid {} value '{}'. Valid options: trace, debug, info, warn, error",
+                        name, value
+                    ),
+                    None,
+                )
+            })?;
+        }
+
+        if let (name, Some(value)) = var("ENABLE_RETRIES") {
+            config.server.enable_retries = match value.to_lowercase().as_str() {
+                "y" | "yes" | "true" | "1" => true,
+                "n" | "no" | "false" | "0" => false,
+                _ => {
+                    return Err(ProxyError::Config(
+                        format!("Invalid {} value '{}'. Use true or false", name, value),
+                        None,
+                    ));
+                }
+            };
+        }
+
+        let (name, value) = var("MAX_RETRY_ATTEMPTS");
+        config.server.max_retry_attempts =
+            Self::setup_number(&name, value, config.server.max_retry_attempts, 1, 10)?;
+
+        match var("SERVICE_ACCOUNT_FILE") {
+            (_, Some(value)) => config.auth.service_account_file = Some(value),
+            (name, None) => {
+                return Err(ProxyError::Config(
+                    format!(
+                        "{} is required in non-interactive mode (path to the service account key)",
+                        name
+                    ),
+                    None,
+                ));
+            }
+        }
+
+        if let (name, Some(value)) = var("STREAMING_MODE") {
+            config.streaming.mode = value.parse().map_err(|_| {
+                ProxyError::Config(
+                    format!(
+                        "Invalid {} value '{}'. Valid options: auto, never, standard, buffered, always",
+                        name, value
+                    ),
+                    None,
+                )
+            })?;
+        }
+
+        let (name, value) = var("STREAMING_BUFFER_SIZE");
+        config.streaming.buffer_size =
+            Self::setup_number(&name, value, config.streaming.buffer_size, 1024, 10 * 1024 * 1024)?;
+
+        let (name, value) = var("STREAMING_CHUNK_TIMEOUT_MS");
+        config.streaming.chunk_timeout_ms =
+            Self::setup_number(&name, value, config.streaming.chunk_timeout_ms, 100, 60000)?;
+
+        Ok(config)
+    }
+
+    /// Parse a numeric setup variable within range, or keep the default when unset
+    fn setup_number<T>(name: &str, value: Option<String>, default: T, min: T, max: T) -> Result<T>
+    where
+        T: std::fmt::Display + std::str::FromStr + PartialOrd + Copy,
+        <T as std::str::FromStr>::Err: std::fmt::Display,
+    {
+        let Some(value) = value else {
+            return Ok(default);
+        };
+
+        let number = value.parse::<T>().map_err(|e| {
+            ProxyError::Config(format!("Invalid {} value '{}': {}", name, value, e), None)
+        })?;
+        if number < min || number > max {
+            return Err(ProxyError::Config(
+                format!("{} must be between {} and {}, got {}", name, min, max, number),
+                None,
+            ));
+        }
+        Ok(number)
+    }
+
+    /// Flush a prompt written with `print!` before reading the answer
+    fn flush_prompt() -> Result<()> {
+        io::stdout()
+            .flush()
+            .map_err(|e| ProxyError::config_with_source("Failed to write prompt", e))
+    }
+
     /// Prompt for a string value with a specific default
     fn prompt_string_with_default(prompt: &str, current: &str, default: &str) -> Result<String> {
         let display_current = if current.is_empty() { default } else { current };
         print!("{} [{}]: ", prompt, display_current);
-        io::stdout().flush().unwrap();
+        Self::flush_prompt()?;
 
         let mut input = String::new();
         io::stdin()
             .read_line(&mut input)
-            .map_err(|e| ProxyError::Config(format!("Failed to read input: {}", e)))?;
+            .map_err(|e| ProxyError::config_with_source("Failed to read input", e))?;
 
         let input = input.trim();
         if input.is_empty() { Ok(display_current.to_string()) } else { Ok(input.to_string()) }
@@ -520,12 +1976,12 @@ impl ConfigCli {
     {
         loop {
             print!("{} ({}-{}) [{}]: ", prompt, min, max, current);
-            io::stdout().flush().unwrap();
+            Self::flush_prompt()?;
 
             let mut input = String::new();
             io::stdin()
                 .read_line(&mut input)
-                .map_err(|e| ProxyError::Config(format!("Failed to read input: {}", e)))?;
+                .map_err(|e| ProxyError::config_with_source("Failed to read input", e))?;
 
             let input = input.trim();
             if input.is_empty() {
@@ -552,12 +2008,12 @@ impl ConfigCli {
         loop {
             let default_str = if default { "Y/n" } else { "y/N" };
             print!("{} ({}): ", prompt, default_str);
-            io::stdout().flush().unwrap();
+            Self::flush_prompt()?;
 
             let mut input = String::new();
             io::stdin()
                 .read_line(&mut input)
-                .map_err(|e| ProxyError::Config(format!("Failed to read input: {}", e)))?;
+                .map_err(|e| ProxyError::config_with_source("Failed to read input", e))?;
 
             let input = input.trim().to_lowercase();
             match input.as_str() {
@@ -573,12 +2029,12 @@ impl ConfigCli {
     fn prompt_log_level(prompt: &str, default: LogLevel) -> Result<LogLevel> {
         loop {
             print!("{} [{:?}]: ", prompt, default);
-            io::stdout().flush().unwrap();
+            Self::flush_prompt()?;
 
             let mut input = String::new();
             io::stdin()
                 .read_line(&mut input)
-                .map_err(|e| ProxyError::Config(format!("Failed to read input: {}", e)))?;
+                .map_err(|e| ProxyError::config_with_source("Failed to read input", e))?;
 
             let input = input.trim();
             if input.is_empty() {
@@ -598,12 +2054,12 @@ impl ConfigCli {
     fn prompt_streaming_mode(prompt: &str, default: StreamingMode) -> Result<StreamingMode> {
         loop {
             print!("{} [{:?}]: ", prompt, default);
-            io::stdout().flush().unwrap();
+            Self::flush_prompt()?;
 
             let mut input = String::new();
             io::stdin()
                 .read_line(&mut input)
-                .map_err(|e| ProxyError::Config(format!("Failed to read input: {}", e)))?;
+                .map_err(|e| ProxyError::config_with_source("Failed to read input", e))?;
 
             let input = input.trim();
             if input.is_empty() {
@@ -620,18 +2076,227 @@ impl ConfigCli {
             }
         }
     }
+}
+
+/* --- tests ------------------------------------------------------------------------------- */
 
-    /// Prompt for confirmation
-    fn confirm(message: &str) -> Result<bool> {
-        Self::prompt_bool(message, false)
+///
+/// Exit code of `config validate` for a set of validation issues.
+///
+/// # Arguments
+///  * `issues` - issues returned by the validator
+///  * `exit_on_warnings` - treat warnings like errors
+///
+/// # Returns
+///  * `0` without issues, `1` for errors or promoted warnings, `2` for warnings only
+fn validation_exit_code(issues: &[ValidationIssue], exit_on_warnings: bool) -> i32 {
+    if issues.iter().any(|issue| issue.severity == Severity::Error) {
+        EXIT_ERRORS
+    } else if issues.is_empty() {
+        EXIT_VALID
+    } else if exit_on_warnings {
+        EXIT_ERRORS
+    } else {
+        EXIT_WARNINGS
     }
 }
 
-/* --- tests ------------------------------------------------------------------------------- */
+///
+/// Warning for a request body limit below the largest request seen.
+///
+/// # Arguments
+///  * `limit` - `limits.max_request_body_bytes`
+///  * `observed` - `max_request_bytes` reported by a running server
+///
+/// # Returns
+///  * `BODY_LIMIT_BELOW_OBSERVED` warning, or `None` when the limit is large enough
+fn body_limit_issue(limit: usize, observed: u64) -> Option<ValidationIssue> {
+    (observed > limit as u64).then(|| ValidationIssue {
+        field: "limits.max_request_body_bytes".to_string(),
+        severity: Severity::Warning,
+        code: "BODY_LIMIT_BELOW_OBSERVED",
+        message: format!(
+            "max_request_body_bytes is {} but the running server has seen a {} byte request; \
+             requests that large will be rejected with 413 after a restart",
+            limit, observed
+        ),
+    })
+}
+
+///
+/// One-line summary of validation issues, e.g. `❌ 2 errors, 1 warning`.
+fn validation_summary(issues: &[ValidationIssue]) -> String {
+    let errors = issues.iter().filter(|issue| issue.severity == Severity::Error).count();
+    let warnings = issues.len() - errors;
+    let plural = |count: usize, noun: &str| {
+        format!("{} {}{}", count, noun, if count == 1 { "" } else { "s" })
+    };
+    format!(
+        "{} {}, {}",
+        if errors == 0 { "✅" } else { "❌" },
+        plural(errors, "error"),
+        plural(warnings, "warning")
+    )
+}
+
+///
+/// Quote a value for a POSIX shell, leaving plain words unquoted.
+fn shell_quote(value: &str) -> String {
+    let plain = !value.is_empty()
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || "@%+=:,./_-".contains(c));
+    if plain { value.to_string() } else { format!("'{}'", value.replace('\'', "'\\''")) }
+}
+
+impl FromStr for ExportFormat {
+    type Err = ProxyError;
+
+    /// Parse from string (case-insensitive).
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "env" => Ok(ExportFormat::Env),
+            "json" => Ok(ExportFormat::Json),
+            _ => Err(ProxyError::Config(
+                format!("Invalid export format '{}'. Valid formats are: env, json", s),
+                None,
+            )),
+        }
+    }
+}
+
+impl ConfigDiffer {
+    ///
+    /// Compare two configurations field by field.
+    ///
+    /// # Arguments
+    /// * `base` - configuration to compare against, usually the defaults
+    /// * `current` - effective configuration
+    ///
+    /// # Returns
+    /// * Differing fields sorted by path; secrets show only whether they are set
+    pub fn diff(base: &Config, current: &Config) -> Result<Vec<ConfigDifference>> {
+        let mut base_fields = Vec::new();
+        Self::flatten("", &serde_json::to_value(base)?, &mut base_fields);
+        let mut current_fields = Vec::new();
+        Self::flatten("", &serde_json::to_value(current)?, &mut current_fields);
+
+        let base_fields: std::collections::BTreeMap<_, _> = base_fields.into_iter().collect();
+        let current_fields: std::collections::BTreeMap<_, _> = current_fields.into_iter().collect();
+        let mut fields: Vec<&String> = base_fields.keys().chain(current_fields.keys()).collect();
+        fields.sort();
+        fields.dedup();
+
+        Ok(fields
+            .into_iter()
+            .filter_map(|field| {
+                let default = base_fields.get(field).unwrap_or(&Value::Null);
+                let current = current_fields.get(field).unwrap_or(&Value::Null);
+                (default != current).then(|| ConfigDifference {
+                    field: field.clone(),
+                    default: Self::display_value(field, default),
+                    current: Self::display_value(field, current),
+                })
+            })
+            .collect())
+    }
+
+    ///
+    /// Format differences as aligned `field: default → current` lines.
+    ///
+    /// # Arguments
+    /// * `differences` - differences from [`diff`](Self::diff)
+    ///
+    /// # Returns
+    /// * One indented line per difference
+    pub fn render_text(differences: &[ConfigDifference]) -> Vec<String> {
+        let width = differences.iter().map(|d| d.field.len() + 1).max().unwrap_or(0);
+        differences
+            .iter()
+            .map(|d| format!("  {:<width$} {} → {}", format!("{}:", d.field), d.default, d.current))
+            .collect()
+    }
+
+    ///
+    /// Collect the leaf values of a JSON value under dotted paths.
+    ///
+    /// Objects are descended into; arrays and empty objects are leaves.
+    fn flatten(prefix: &str, value: &Value, out: &mut Vec<(String, Value)>) {
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                for (key, item) in map {
+                    let path =
+                        if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                    if DIFF_SECRET_FIELDS.contains(&path.as_str()) {
+                        out.push((path, item.clone()));
+                    } else {
+                        Self::flatten(&path, item, out);
+                    }
+                }
+            }
+            _ => out.push((prefix.to_string(), value.clone())),
+        }
+    }
+
+    ///
+    /// Display form of a field value.
+    ///
+    /// Secrets become `(set)` / `(not set)`, missing values `(none)`, and
+    /// strings are shown without JSON quotes.
+    fn display_value(field: &str, value: &Value) -> String {
+        let is_set = match value {
+            Value::Null => false,
+            Value::Array(items) => !items.is_empty(),
+            _ => true,
+        };
+        if DIFF_SECRET_FIELDS.contains(&field) {
+            return if is_set { "(set)" } else { "(not set)" }.to_string();
+        }
+        match value {
+            Value::Null => "(none)".to_string(),
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+}
+
+impl FromStr for DiffFormat {
+    type Err = ProxyError;
+
+    /// Parse from string (case-insensitive).
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(DiffFormat::Text),
+            "json" => Ok(DiffFormat::Json),
+            _ => Err(ProxyError::Config(
+                format!("Invalid diff format '{}'. Valid formats are: text, json", s),
+                None,
+            )),
+        }
+    }
+}
+
+impl FromStr for ShowFormat {
+    type Err = ProxyError;
+
+    /// Parse from string (case-insensitive).
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(ShowFormat::Text),
+            "json" => Ok(ShowFormat::Json),
+            "toml" => Ok(ShowFormat::Toml),
+            "yaml" | "yml" => Ok(ShowFormat::Yaml),
+            _ => Err(ProxyError::Config(
+                format!("Invalid output format '{}'. Valid formats are: text, json, toml, yaml", s),
+                None,
+            )),
+        }
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::DiscoveryProvider;
+    use crate::config::loader::ConfigLoader;
 
     // Note: Most CLI tests would require mocking stdin/stdout,
     // which is complex. Here we test the parts we can test easily.
@@ -642,9 +2307,344 @@ mod tests {
         let _cli = ConfigCli;
     }
 
+    #[test]
+    fn test_config_diff_lists_only_changed_fields() {
+        let mut config = Config::default();
+        config.server.port = 8080;
+        let differences = ConfigDiffer::diff(&Config::default(), &config).unwrap();
+        let lines = ConfigDiffer::render_text(&differences);
+        assert_eq!(lines, vec!["  server.port: 3000 → 8080".to_string()]);
+
+        config.auth.service_account_json = Some("{\"private_key\": \"secret\"}".to_string());
+        let differences = ConfigDiffer::diff(&Config::default(), &config).unwrap();
+        let secret = differences.iter().find(|d| d.field == "auth.service_account_json").unwrap();
+        assert_eq!((secret.default.as_str(), secret.current.as_str()), ("(not set)", "(set)"));
+        assert!(ConfigDiffer::diff(&config, &config).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_append_api_key_to_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_file = temp_dir.path().join("config.toml");
+        fs::write(&config_file, Config::example_toml()).unwrap();
+
+        let entry = ApiKeyEntry {
+            name: "ci".to_string(),
+            key_hash: "a".repeat(64),
+            allowed_models: vec!["claude-sonnet".to_string()],
+        };
+        ConfigCli::append_api_key_to_file(&config_file, &entry).unwrap();
+
+        let contents = fs::read_to_string(&config_file).unwrap();
+        assert!(contents.starts_with("# ModelMux Configuration"));
+        let config: Config = toml::from_str(&contents).unwrap();
+        assert_eq!(config.auth.api_keys.len(), 1);
+        assert_eq!(config.auth.api_keys[0].name, "ci");
+        assert_eq!(config.auth.api_keys[0].allowed_models, vec!["claude-sonnet".to_string()]);
+
+        // A custom config file gets the key instead of the user config file
+        let custom_file = temp_dir.path().join("custom.toml");
+        fs::write(&custom_file, Config::example_toml()).unwrap();
+        let written = temp_env::with_var(loader::CONFIG_FILE_ENV, Some(&custom_file), || {
+            ConfigCli::append_api_key(&entry).unwrap()
+        });
+        assert_eq!(written, custom_file);
+        let config: Config = toml::from_str(&fs::read_to_string(&custom_file).unwrap()).unwrap();
+        assert_eq!(config.auth.api_keys.len(), 1);
+    }
+
+    #[test]
+    fn test_backup_prunes_and_restore_round_trips() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_file = temp_dir.path().join("config.toml");
+        fs::write(&config_file, Config::example_toml()).unwrap();
+
+        // Pre-existing backups with old timestamps
+        for i in 0..MAX_CONFIG_BACKUPS {
+            let name = format!("config.20200101-0000{:02}-000{}", i, BACKUP_SUFFIX);
+            fs::write(temp_dir.path().join(name), Config::example_toml()).unwrap();
+        }
+        fs::write(temp_dir.path().join("other.toml.bak"), "").unwrap();
+
+        let backup = ConfigCli::backup_config_file(&config_file).unwrap();
+        let backups = ConfigCli::list_backups(&config_file).unwrap();
+        assert_eq!(backups.len(), MAX_CONFIG_BACKUPS);
+        assert_eq!(backups.last(), Some(&backup));
+        let oldest = format!("config.20200101-000000-000{}", BACKUP_SUFFIX);
+        assert!(!temp_dir.path().join(oldest).exists());
+
+        fs::write(&config_file, "[server]\nport = 9999\n").unwrap();
+        let previous = ConfigCli::restore_config_file(&config_file, &backup).unwrap();
+        assert!(previous.is_some());
+        assert_eq!(fs::read_to_string(&config_file).unwrap(), Config::example_toml());
+
+        let invalid = temp_dir.path().join("broken.toml.bak");
+        fs::write(&invalid, "server = [").unwrap();
+        assert!(ConfigCli::restore_config_file(&config_file, &invalid).is_err());
+    }
+
+    #[test]
+    fn test_validation_exit_code_and_summary() {
+        let issue = |severity| ValidationIssue {
+            field: "server.port".to_string(),
+            severity,
+            code: "PORT_INVALID",
+            message: "message".to_string(),
+        };
+        let warnings = vec![issue(Severity::Warning); 3];
+        let mixed = vec![issue(Severity::Error), issue(Severity::Error), issue(Severity::Warning)];
+
+        assert_eq!(validation_exit_code(&[], false), EXIT_VALID);
+        assert_eq!(validation_exit_code(&[], true), EXIT_VALID);
+        assert_eq!(validation_exit_code(&warnings, false), EXIT_WARNINGS);
+        assert_eq!(validation_exit_code(&warnings, true), EXIT_ERRORS);
+        assert_eq!(validation_exit_code(&mixed, false), EXIT_ERRORS);
+
+        assert_eq!(validation_summary(&[]), "✅ 0 errors, 0 warnings");
+        assert_eq!(validation_summary(&warnings), "✅ 0 errors, 3 warnings");
+        assert_eq!(validation_summary(&mixed), "❌ 2 errors, 1 warning");
+        assert_eq!(validation_summary(&mixed[1..]), "❌ 1 error, 1 warning");
+    }
+
+    #[test]
+    fn test_body_limit_issue() {
+        assert!(body_limit_issue(10_485_760, 2_048).is_none());
+        assert!(body_limit_issue(2_048, 2_048).is_none());
+        let issue = body_limit_issue(1_024, 2_048).unwrap();
+        assert_eq!(issue.severity, Severity::Warning);
+        assert_eq!(issue.code, "BODY_LIMIT_BELOW_OBSERVED");
+        assert!(issue.message.contains("2048 byte request"));
+    }
+
     // Integration tests would go here, but they'd need:
     // - Temporary directories
     // - Mocked stdin/stdout
     // - Environment setup
     // These are better handled as separate integration tests
+
+    #[test]
+    fn test_gather_config_from_env() {
+        let vars = std::collections::HashMap::from([
+            ("MODELMUX_SETUP_PORT", "8080"),
+            ("MODELMUX_SETUP_LOG_LEVEL", "debug"),
+            ("MODELMUX_SETUP_SERVICE_ACCOUNT_FILE", "/etc/modelmux/sa.json"),
+            ("MODELMUX_SETUP_STREAMING_MODE", "buffered"),
+        ]);
+        let config =
+            ConfigCli::gather_config_from_env(|name| vars.get(name).map(|v| v.to_string()))
+                .unwrap();
+
+        assert_eq!(config.server.port, 8080);
+        assert_eq!(config.server.log_level, LogLevel::Debug);
+        assert_eq!(config.auth.service_account_file.as_deref(), Some("/etc/modelmux/sa.json"));
+        assert_eq!(config.streaming.mode, StreamingMode::Buffered);
+        assert_eq!(config.streaming.buffer_size, Config::default().streaming.buffer_size);
+    }
+
+    #[test]
+    fn test_gather_config_from_env_errors_name_variable() {
+        let missing = ConfigCli::gather_config_from_env(|_| None).unwrap_err();
+        assert!(missing.to_string().contains("MODELMUX_SETUP_SERVICE_ACCOUNT_FILE"));
+
+        let invalid = ConfigCli::gather_config_from_env(|name| match name {
+            "MODELMUX_SETUP_PORT" => Some("0".to_string()),
+            _ => Some("/tmp/sa.json".to_string()),
+        })
+        .unwrap_err();
+        assert!(invalid.to_string().contains("MODELMUX_SETUP_PORT"));
+    }
+
+    #[test]
+    fn test_render_config_redacts_sensitive() {
+        let mut config: Config = toml::from_str(Config::example_toml()).unwrap();
+        config.auth.service_account_json = Some("{\"private_key\": \"secret\"}".to_string());
+        config.auth.api_keys.push(ApiKeyEntry {
+            name: "ci".to_string(),
+            key_hash: "a".repeat(64),
+            allowed_models: Vec::new(),
+        });
+        config.admin.secret = Some("admin-secret".to_string());
+
+        for format in [ShowFormat::Json, ShowFormat::Toml, ShowFormat::Yaml] {
+            let options = ShowOptions { format, include_sensitive: false };
+            let output = ConfigCli::render_config(&config, options).unwrap();
+            assert!(output.contains(REDACTED), "{:?} output not redacted", format);
+            assert!(!output.contains("admin-secret"));
+            assert!(!output.contains(&"a".repeat(64)));
+            assert!(!output.contains("private_key"));
+        }
+
+        let options = ShowOptions { format: ShowFormat::Toml, include_sensitive: true };
+        let output = ConfigCli::render_config(&config, options).unwrap();
+        let round_trip: Config = toml::from_str(&output).unwrap();
+        assert_eq!(round_trip.admin.secret.as_deref(), Some("admin-secret"));
+        assert_eq!(round_trip.server.port, config.server.port);
+
+        let options = ShowOptions { format: ShowFormat::Yaml, include_sensitive: true };
+        let output = ConfigCli::render_config(&config, options).unwrap();
+        let round_trip: Config = serde_yaml::from_str(&output).unwrap();
+        assert_eq!(round_trip.admin.secret.as_deref(), Some("admin-secret"));
+        assert_eq!(round_trip.server.port, config.server.port);
+
+        let options = ShowOptions { format: ShowFormat::Json, include_sensitive: true };
+        let output = ConfigCli::render_config(&config, options).unwrap();
+        let round_trip: Config = serde_json::from_str(&output).unwrap();
+        assert_eq!(round_trip.auth.api_keys[0].key_hash, "a".repeat(64));
+    }
+
+    ///
+    /// Parse `export NAME=value` lines written by `render_export`.
+    fn parse_export_lines(output: &str) -> Vec<(String, Option<String>)> {
+        output
+            .lines()
+            .map(|line| {
+                let (name, value) = line.strip_prefix("export ").unwrap().split_once('=').unwrap();
+                let value = match value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+                    Some(quoted) => quoted.replace("'\\''", "'"),
+                    None => value.to_string(),
+                };
+                (name.to_string(), Some(value))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_render_providers_shows_resolved_urls() {
+        use crate::config::VertexConfig;
+        use crate::provider::VertexProvider;
+
+        let vertex = VertexConfig {
+            project: Some("my-project".to_string()),
+            region: Some("europe-west1".to_string()),
+            location: Some("europe-west1".to_string()),
+            publisher: Some("anthropic".to_string()),
+            model: Some("claude-sonnet-4@20250514".to_string()),
+            ..Default::default()
+        };
+        let provider = temp_env::with_vars_unset(["LLM_URL", "LLM_MODEL"], || {
+            VertexProvider::from_config_or_env_with_auth(
+                AuthStrategy::BearerToken("secret-token".to_string()),
+                Some(&vertex),
+            )
+            .unwrap()
+        });
+        let config = Config {
+            llm_provider: Some(LlmProviderConfig::Vertex(provider)),
+            ..Default::default()
+        };
+
+        let output = ConfigCli::render_providers(&config);
+        let resource = "https://europe-west1-aiplatform.googleapis.com/v1/projects/my-project/\
+                        locations/europe-west1/publishers/anthropic/models/claude-sonnet-4@20250514";
+        assert!(output.contains("Primary provider: vertex"), "{}", output);
+        assert!(output.contains(&format!("{}:rawPredict", resource)), "{}", output);
+        assert!(output.contains(&format!("{}:streamRawPredict", resource)), "{}", output);
+        assert!(output.contains(&format!("Bearer token {}", REDACTED)));
+        assert!(!output.contains("secret-token"));
+        assert!(!output.contains("Failover"));
+
+        let empty = ConfigCli::render_providers(&Config::default());
+        assert!(empty.starts_with("No LLM provider configured"));
+    }
+
+    #[test]
+    fn test_render_export_env_round_trip() {
+        let mut config = ConfigLoader::new().with_defaults().build_base().unwrap();
+        config.server.port = 9191;
+        config.server.log_level = LogLevel::Debug;
+        config.server.circuit_breaker_threshold = 0.25;
+        config.streaming.mode = StreamingMode::Never;
+        config.streaming.sse_retry_ms = Some(1500);
+        config.auth.service_account_json = Some("{\"private_key\": \"it's secret\"}".to_string());
+        config.admin.secret = Some("admin secret".to_string());
+        config.discovery.provider =
+            Some(DiscoveryProvider::Consul { url: "http://consul:8500".to_string() });
+        config.converter.extra_anthropic_fields.insert("top_k".to_string(), json!(5));
+        config.cors.allowed_origins =
+            vec!["https://app.example.com".to_string(), "https://admin.example.com".to_string()];
+        config.limits.max_messages = 42;
+
+        let options = ExportOptions { format: ExportFormat::Env, redact_secrets: false };
+        let output = ConfigCli::render_export(&config, options).unwrap();
+        assert!(output.contains("export MODELMUX_SERVER_PORT=9191\n"));
+        assert!(output.contains("export MODELMUX_ADMIN_SECRET='admin secret'\n"));
+
+        temp_env::with_vars(parse_export_lines(&output), || {
+            let loaded =
+                ConfigLoader::new().with_defaults().with_env_vars().unwrap().build_base().unwrap();
+            assert_eq!(
+                serde_json::to_value(&loaded).unwrap(),
+                serde_json::to_value(&config).unwrap()
+            );
+        });
+    }
+
+    #[test]
+    fn test_render_export_redacts_secrets() {
+        let mut config: Config = toml::from_str(Config::example_toml()).unwrap();
+        config.auth.service_account_file = Some("/secrets/sa.json".to_string());
+        config.auth.service_account_json = Some("{\"private_key\": \"secret\"}".to_string());
+        config.admin.secret = Some("admin-secret".to_string());
+
+        for format in [ExportFormat::Env, ExportFormat::Json] {
+            let options = ExportOptions { format, redact_secrets: true };
+            let output = ConfigCli::render_export(&config, options).unwrap();
+            assert!(output.contains(REDACTED), "{:?} output not redacted", format);
+            assert!(!output.contains("/secrets/sa.json"));
+            assert!(!output.contains("private_key"));
+            assert!(!output.contains("admin-secret"));
+        }
+
+        let options = ExportOptions { format: ExportFormat::Json, redact_secrets: false };
+        let output = ConfigCli::render_export(&config, options).unwrap();
+        let round_trip: Config = serde_json::from_str(&output).unwrap();
+        assert_eq!(round_trip.auth.service_account_file.as_deref(), Some("/secrets/sa.json"));
+        assert_eq!("ENV".parse::<ExportFormat>().unwrap(), ExportFormat::Env);
+        assert!("yaml".parse::<ExportFormat>().is_err());
+    }
+
+    #[test]
+    fn test_render_issue_colours_by_severity() {
+        let mut issue = ValidationIssue {
+            field: "server.port".to_string(),
+            severity: Severity::Error,
+            code: "PORT_INVALID",
+            message: "Port must be greater than 0".to_string(),
+        };
+        assert_eq!(
+            ConfigCli::render_issue(&issue, false),
+            "❌ [PORT_INVALID] server.port: Port must be greater than 0"
+        );
+        let red = ConfigCli::render_issue(&issue, true);
+        assert!(red.starts_with(ANSI_RED) && red.ends_with(ANSI_RESET));
+
+        issue.severity = Severity::Warning;
+        assert!(ConfigCli::render_issue(&issue, true).starts_with(ANSI_AMBER));
+    }
+
+    #[test]
+    fn test_show_format_parse() {
+        assert_eq!("YML".parse::<ShowFormat>().unwrap(), ShowFormat::Yaml);
+        assert!("xml".parse::<ShowFormat>().is_err());
+    }
+
+    #[test]
+    fn test_connection_status_suggestions() {
+        assert!(ConfigCli::status_suggestion(403).contains("roles/aiplatform.user"));
+        assert!(ConfigCli::status_suggestion(404).contains("model ID"));
+        assert!(ConfigCli::status_suggestion(503).contains("Retry"));
+    }
+
+    #[test]
+    fn test_completion_scripts() {
+        let bash = ConfigCli::completion("bash").unwrap();
+        assert!(bash.contains("_modelmux()"));
+        assert!(bash.contains("complete -F _modelmux modelmux"));
+        assert!(bash.contains("init) opts=\"--no-backup --non-interactive --dry-run\" ;;"));
+
+        assert!(ConfigCli::completion("zsh").unwrap().starts_with("#compdef modelmux"));
+        assert!(ConfigCli::completion("fish").unwrap().contains("-a keygen"));
+        assert!(ConfigCli::completion("powershell").unwrap().contains("'restore' = @('--file')"));
+        assert!(ConfigCli::completion("tcsh").is_err());
+    }
 }
@@ -11,6 +11,10 @@
 //!    `%PROGRAMDATA%/modelmux/config.toml` on Windows)
 //! 5. Built-in defaults (lowest priority)
 //!
+//! Variables from a `.env` file (`MODELMUX_DOTENV_FILE`, or `./.env` when it
+//! exists) are loaded into the environment first, without overriding
+//! variables that are already set, so they count as environment variables.
+//!
 //! Follows the Builder pattern (Open/Closed Principle) and Single Responsibility
 //! Principle - handles only configuration loading concerns.
 //!
@@ -22,12 +26,15 @@
 /* --- uses ------------------------------------------------------------------------------------ */
 
 use crate::config::paths;
-use crate::config::{AuthConfig, Config, ServerConfig, StreamingConfig};
+use crate::config::{
+    AuthConfig, Config, DiscoveryProvider, ServerConfig, StreamingConfig, VertexConfig,
+    default_consul_url,
+};
 use crate::error::{ProxyError, Result};
 
 use std::collections::HashMap;
 use std::env;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /* --- types ----------------------------------------------------------------------------------- */
 
@@ -43,8 +50,21 @@ pub struct ConfigLoader {
     env_overrides: HashMap<String, String>,
     /// Whether defaults have been applied
     defaults_applied: bool,
+    /// Variables set from the `.env` file (those not already in the environment)
+    dotenv_variables: Vec<String>,
 }
 
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** Environment variable naming the `.env` file to load instead of `./.env` */
+pub const DOTENV_FILE_ENV: &str = "MODELMUX_DOTENV_FILE";
+
+/** Environment variable naming a config file to load instead of the user and system ones */
+pub const CONFIG_FILE_ENV: &str = "MODELMUX_CONFIG_FILE";
+
+/** `.env` file loaded from the current directory when it exists */
+pub const DOTENV_FILE: &str = ".env";
+
 /* --- implementations --------------------------------------------------------------------- */
 
 impl ConfigLoader {
@@ -69,7 +89,12 @@ impl ConfigLoader {
     /// # }
     /// ```
     pub fn new() -> Self {
-        Self { config: Config::default(), env_overrides: HashMap::new(), defaults_applied: false }
+        Self {
+            config: Config::default(),
+            env_overrides: HashMap::new(),
+            defaults_applied: false,
+            dotenv_variables: Vec::new(),
+        }
     }
 
     /// Apply built-in default values
@@ -190,7 +215,6 @@ impl ConfigLoader {
     /// # Returns
     /// * `Ok(Self)` - Config loaded successfully
     /// * `Err(ProxyError)` - Failed to load or parse config file
-    #[allow(dead_code)]
     pub fn with_config_file<P: AsRef<Path>>(mut self, path: P) -> Result<Self> {
         let path = path.as_ref();
         tracing::debug!("Loading custom config from: {}", path.display());
@@ -198,6 +222,71 @@ impl ConfigLoader {
         Ok(self)
     }
 
+    /// Load a `.env` file into the environment
+    ///
+    /// Sets the variables of the file that are not already set (the existing
+    /// environment wins), then applies the `MODELMUX_*` ones like
+    /// [`with_env_vars`](Self::with_env_vars). Other variables (`LLM_URL`,
+    /// `VERTEX_*`, ...) are read from the environment by the providers.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the `.env` file
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - File loaded, see [`dotenv_variables`](Self::dotenv_variables)
+    /// * `Err(ProxyError)` - File missing, unreadable or malformed, or an invalid value
+    pub fn with_dotenv_file<P: AsRef<Path>>(mut self, path: P) -> Result<Self> {
+        let path = path.as_ref();
+        tracing::debug!("Loading .env file from: {}", path.display());
+        let dotenv_error = |e| {
+            ProxyError::config_with_source(
+                format!("Failed to load .env file '{}'", path.display()),
+                e,
+            )
+        };
+
+        let mut loaded = Vec::new();
+        for entry in dotenvy::from_path_iter(path).map_err(dotenv_error)? {
+            let (key, _) = entry.map_err(dotenv_error)?;
+            if env::var_os(&key).is_none() && !loaded.contains(&key) {
+                loaded.push(key);
+            }
+        }
+        dotenvy::from_path(path).map_err(dotenv_error)?;
+
+        for (key, value) in env::vars() {
+            if key.starts_with("MODELMUX_") {
+                self.env_overrides.insert(key, value);
+            }
+        }
+        self.apply_env_overrides()?;
+
+        self.dotenv_variables = loaded;
+        Ok(self)
+    }
+
+    /// Load the `.env` file, if any
+    ///
+    /// Loads the file named by `MODELMUX_DOTENV_FILE` (which must exist), or
+    /// `./.env` when it exists.
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - File loaded or none found
+    /// * `Err(ProxyError)` - The file could not be loaded
+    pub fn with_dotenv(self) -> Result<Self> {
+        let explicit = env::var_os(DOTENV_FILE_ENV).is_some_and(|path| !path.is_empty());
+        let path = dotenv_file();
+        if explicit || path.exists() { self.with_dotenv_file(path) } else { Ok(self) }
+    }
+
+    /// Variables set from the `.env` file by [`with_dotenv_file`](Self::with_dotenv_file)
+    ///
+    /// Variables already present in the environment are not listed, as the
+    /// file does not override them.
+    pub fn dotenv_variables(&self) -> &[String] {
+        &self.dotenv_variables
+    }
+
     /// Apply environment variable overrides
     ///
     /// Loads configuration values from environment variables using the
@@ -242,6 +331,7 @@ impl ConfigLoader {
         if !self.defaults_applied {
             return Err(ProxyError::Config(
                 "Configuration loader must call with_defaults() before build()".to_string(),
+                None,
             ));
         }
 
@@ -271,6 +361,7 @@ impl ConfigLoader {
         if !self.defaults_applied {
             return Err(ProxyError::Config(
                 "Configuration loader must call with_defaults() before build()".to_string(),
+                None,
             ));
         }
 
@@ -296,17 +387,17 @@ impl ConfigLoader {
 
         // Read file contents
         let contents = std::fs::read_to_string(path).map_err(|e| {
-            ProxyError::Config(format!(
-                "Failed to read configuration file '{}': {}",
-                path.display(),
-                e
-            ))
+            ProxyError::config_with_source(
+                format!("Failed to read configuration file '{}'", path.display()),
+                e,
+            )
         })?;
 
         // Parse TOML
         let file_config: Config = toml::from_str(&contents).map_err(|e| {
-            ProxyError::Config(format!(
-                "Failed to parse TOML configuration file '{}': {}\n\
+            ProxyError::Config(
+                format!(
+                    "Failed to parse TOML configuration file '{}': {}\n\
                  \n\
                  Please check the syntax of your configuration file.\n\
                  Common issues:\n\
@@ -317,9 +408,11 @@ impl ConfigLoader {
                  You can validate your TOML syntax at: https://www.toml-lint.com/\n\
                  \n\
                  Run 'modelmux config validate' for more details.",
-                path.display(),
-                e
-            ))
+                    path.display(),
+                    e
+                ),
+                None,
+            )
         })?;
 
         // Merge configuration (file config overrides current config)
@@ -339,16 +432,64 @@ impl ConfigLoader {
             self.config.llm_provider = other.llm_provider;
         }
 
+        if other.failover_vertex.is_some() {
+            self.config.failover_vertex = other.failover_vertex;
+        }
+
         // Merge vertex config if present
         if other.vertex.is_some() {
             self.config.vertex = other.vertex;
         }
 
+        // Merge OpenAI-compatible provider config if present
+        if other.openai_compatible.is_some() {
+            self.config.openai_compatible = other.openai_compatible;
+        }
+
+        // Merge Azure OpenAI provider config if present
+        if other.azure_openai.is_some() {
+            self.config.azure_openai = other.azure_openai;
+        }
+
         // Merge auth config
         self.merge_auth_config(other.auth);
 
         // Merge streaming config
         self.merge_streaming_config(other.streaming);
+
+        // Cache and HTTP client configs are taken as a whole (section defaults apply when absent)
+        self.config.cache = other.cache;
+        self.config.http_client = other.http_client;
+
+        // Merge usage cost table (later files add to or replace model prices)
+        self.config.usage.pricing.extend(other.usage.pricing);
+
+        // Merge admin config
+        if other.admin.secret.is_some() {
+            self.config.admin.secret = other.admin.secret;
+        }
+        self.config.admin.enable_config_endpoint = other.admin.enable_config_endpoint;
+
+        // Discovery and A/B test configs are taken as a whole
+        self.config.discovery = other.discovery;
+        self.config.ab_test = other.ab_test;
+        self.config.converter = other.converter;
+        self.config.jobs = other.jobs;
+        self.config.idempotency = other.idempotency;
+        self.config.debug = other.debug;
+        self.config.telemetry = other.telemetry;
+        self.config.queue = other.queue;
+        self.config.upload = other.upload;
+        self.config.limits = other.limits;
+        self.config.cors = other.cors;
+        self.config.audit_log = other.audit_log;
+        self.config.health = other.health;
+        self.config.prompt = other.prompt;
+
+        // Merge model aliases, routes and overrides (later files add to or replace entries)
+        self.config.model_aliases.extend(other.model_aliases);
+        self.config.model_routes.extend(other.model_routes);
+        self.config.models.extend(other.models);
     }
 
     /// Merge server configuration
@@ -361,11 +502,18 @@ impl ConfigLoader {
         // For enums, we need to check if they're different from default
         // Since we can't easily detect "explicitly set", we always merge
         self.config.server.log_level = other.log_level;
+        self.config.server.log_format = other.log_format;
         self.config.server.enable_retries = other.enable_retries;
 
         if other.max_retry_attempts != ServerConfig::default().max_retry_attempts {
             self.config.server.max_retry_attempts = other.max_retry_attempts;
         }
+
+        self.config.server.circuit_breaker_enabled = other.circuit_breaker_enabled;
+        self.config.server.circuit_breaker_threshold = other.circuit_breaker_threshold;
+        self.config.server.circuit_breaker_window_secs = other.circuit_breaker_window_secs;
+        self.config.server.circuit_breaker_cooldown_secs = other.circuit_breaker_cooldown_secs;
+        self.config.server.circuit_breaker_min_requests = other.circuit_breaker_min_requests;
     }
 
     /// Merge authentication configuration
@@ -378,6 +526,42 @@ impl ConfigLoader {
             self.config.auth.service_account_json = other.service_account_json;
         }
 
+        if other.service_account_key_file.is_some() {
+            self.config.auth.service_account_key_file = other.service_account_key_file;
+        }
+
+        if other.secret_manager_resource.is_some() {
+            self.config.auth.secret_manager_resource = other.secret_manager_resource;
+        }
+
+        if other.secret_manager_endpoint.is_some() {
+            self.config.auth.secret_manager_endpoint = other.secret_manager_endpoint;
+        }
+
+        if !other.api_keys.is_empty() {
+            self.config.auth.api_keys = other.api_keys;
+        }
+
+        if other.proxy_api_key.is_some() {
+            self.config.auth.proxy_api_key = other.proxy_api_key;
+        }
+
+        if other.use_workload_identity {
+            self.config.auth.use_workload_identity = true;
+        }
+
+        if other.workload_identity.audience.is_some() {
+            self.config.auth.workload_identity.audience = other.workload_identity.audience;
+        }
+
+        if other.workload_identity.token_url.is_some() {
+            self.config.auth.workload_identity.token_url = other.workload_identity.token_url;
+        }
+
+        if other.use_application_default_credentials {
+            self.config.auth.use_application_default_credentials = true;
+        }
+
         // Always merge strategy
         self.config.auth.strategy = other.strategy;
     }
@@ -393,6 +577,49 @@ impl ConfigLoader {
         if other.chunk_timeout_ms != StreamingConfig::default().chunk_timeout_ms {
             self.config.streaming.chunk_timeout_ms = other.chunk_timeout_ms;
         }
+
+        if other.sse_retry_ms.is_some() {
+            self.config.streaming.sse_retry_ms = other.sse_retry_ms;
+        }
+
+        if other.keepalive_interval_secs != StreamingConfig::default().keepalive_interval_secs {
+            self.config.streaming.keepalive_interval_secs = other.keepalive_interval_secs;
+        }
+
+        if other.heartbeat_interval_ms != StreamingConfig::default().heartbeat_interval_ms {
+            self.config.streaming.heartbeat_interval_ms = other.heartbeat_interval_ms;
+        }
+
+        if other.sse_buffer_size != StreamingConfig::default().sse_buffer_size {
+            self.config.streaming.sse_buffer_size = other.sse_buffer_size;
+        }
+
+        if other.max_dropped_chunks_before_abort
+            != StreamingConfig::default().max_dropped_chunks_before_abort
+        {
+            self.config.streaming.max_dropped_chunks_before_abort =
+                other.max_dropped_chunks_before_abort;
+        }
+
+        self.config.streaming.cancel_on_disconnect = other.cancel_on_disconnect;
+        self.config.streaming.dedup_enabled = other.dedup_enabled;
+
+        if other.dedup_window_ms != StreamingConfig::default().dedup_window_ms {
+            self.config.streaming.dedup_window_ms = other.dedup_window_ms;
+        }
+
+        if other.request_timeout_secs != StreamingConfig::default().request_timeout_secs {
+            self.config.streaming.request_timeout_secs = other.request_timeout_secs;
+        }
+
+        if other.first_token_timeout_secs != StreamingConfig::default().first_token_timeout_secs {
+            self.config.streaming.first_token_timeout_secs = other.first_token_timeout_secs;
+        }
+
+        if !other.fan_out_models.is_empty() {
+            self.config.streaming.fan_out_models = other.fan_out_models;
+        }
+        self.config.streaming.fan_out_strategy = other.fan_out_strategy;
     }
 
     /// Apply environment variable overrides to current configuration
@@ -402,27 +629,49 @@ impl ConfigLoader {
                 // Server configuration
                 "MODELMUX_SERVER_PORT" => {
                     self.config.server.port = value.parse().map_err(|e| {
-                        ProxyError::Config(format!(
-                            "Invalid MODELMUX_SERVER_PORT value '{}': {}\n\
+                        ProxyError::Config(
+                            format!(
+                                "Invalid MODELMUX_SERVER_PORT value '{}': {}\n\
                              Port must be a number between 1 and 65535.",
-                            value, e
-                        ))
+                                value, e
+                            ),
+                            None,
+                        )
                     })?;
                 }
                 "MODELMUX_SERVER_LOG_LEVEL" => {
                     self.config.server.log_level = value.parse()?;
                 }
+                "MODELMUX_SERVER_LOG_FORMAT" => {
+                    self.config.server.log_format = value.parse()?;
+                }
                 "MODELMUX_SERVER_ENABLE_RETRIES" => {
                     self.config.server.enable_retries = parse_bool_env(value, key)?;
                 }
                 "MODELMUX_SERVER_MAX_RETRY_ATTEMPTS" => {
                     self.config.server.max_retry_attempts = value.parse().map_err(|e| {
-                        ProxyError::Config(format!(
-                            "Invalid MODELMUX_SERVER_MAX_RETRY_ATTEMPTS value '{}': {}",
-                            value, e
-                        ))
+                        ProxyError::config_with_source(
+                            format!("Invalid MODELMUX_SERVER_MAX_RETRY_ATTEMPTS value '{}'", value),
+                            e,
+                        )
                     })?;
                 }
+                "MODELMUX_SERVER_CIRCUIT_BREAKER_ENABLED" => {
+                    self.config.server.circuit_breaker_enabled = parse_bool_env(value, key)?;
+                }
+                "MODELMUX_SERVER_CIRCUIT_BREAKER_THRESHOLD" => {
+                    self.config.server.circuit_breaker_threshold = parse_number_env(value, key)?;
+                }
+                "MODELMUX_SERVER_CIRCUIT_BREAKER_WINDOW_SECS" => {
+                    self.config.server.circuit_breaker_window_secs = parse_number_env(value, key)?;
+                }
+                "MODELMUX_SERVER_CIRCUIT_BREAKER_COOLDOWN_SECS" => {
+                    self.config.server.circuit_breaker_cooldown_secs =
+                        parse_number_env(value, key)?;
+                }
+                "MODELMUX_SERVER_CIRCUIT_BREAKER_MIN_REQUESTS" => {
+                    self.config.server.circuit_breaker_min_requests = parse_number_env(value, key)?;
+                }
 
                 // Authentication configuration
                 "MODELMUX_AUTH_SERVICE_ACCOUNT_FILE" => {
@@ -431,6 +680,31 @@ impl ConfigLoader {
                 "MODELMUX_AUTH_SERVICE_ACCOUNT_JSON" => {
                     self.config.auth.service_account_json = Some(value.clone());
                 }
+                "MODELMUX_AUTH_SERVICE_ACCOUNT_KEY_FILE" => {
+                    self.config.auth.service_account_key_file = Some(value.clone());
+                }
+                "MODELMUX_AUTH_PROXY_API_KEY" => {
+                    self.config.auth.proxy_api_key = Some(value.clone());
+                }
+                "MODELMUX_AUTH_USE_WORKLOAD_IDENTITY" => {
+                    self.config.auth.use_workload_identity = parse_bool_env(value, key)?;
+                }
+                "MODELMUX_AUTH_WORKLOAD_IDENTITY_AUDIENCE" => {
+                    self.config.auth.workload_identity.audience = Some(value.clone());
+                }
+                "MODELMUX_AUTH_WORKLOAD_IDENTITY_TOKEN_URL" => {
+                    self.config.auth.workload_identity.token_url = Some(value.clone());
+                }
+                "MODELMUX_AUTH_USE_ADC" => {
+                    self.config.auth.use_application_default_credentials =
+                        parse_bool_env(value, key)?;
+                }
+                "MODELMUX_AUTH_SECRET_MANAGER_RESOURCE" => {
+                    self.config.auth.secret_manager_resource = Some(value.clone());
+                }
+                "MODELMUX_AUTH_SECRET_MANAGER_ENDPOINT" => {
+                    self.config.auth.secret_manager_endpoint = Some(value.clone());
+                }
 
                 // Streaming configuration
                 "MODELMUX_STREAMING_MODE" => {
@@ -438,20 +712,360 @@ impl ConfigLoader {
                 }
                 "MODELMUX_STREAMING_BUFFER_SIZE" => {
                     self.config.streaming.buffer_size = value.parse().map_err(|e| {
-                        ProxyError::Config(format!(
-                            "Invalid MODELMUX_STREAMING_BUFFER_SIZE value '{}': {}",
-                            value, e
-                        ))
+                        ProxyError::config_with_source(
+                            format!("Invalid MODELMUX_STREAMING_BUFFER_SIZE value '{}'", value),
+                            e,
+                        )
                     })?;
                 }
                 "MODELMUX_STREAMING_CHUNK_TIMEOUT_MS" => {
                     self.config.streaming.chunk_timeout_ms = value.parse().map_err(|e| {
-                        ProxyError::Config(format!(
-                            "Invalid MODELMUX_STREAMING_CHUNK_TIMEOUT_MS value '{}': {}",
-                            value, e
-                        ))
+                        ProxyError::config_with_source(
+                            format!(
+                                "Invalid MODELMUX_STREAMING_CHUNK_TIMEOUT_MS value '{}'",
+                                value
+                            ),
+                            e,
+                        )
+                    })?;
+                }
+
+                "MODELMUX_STREAMING_SSE_RETRY_MS" => {
+                    self.config.streaming.sse_retry_ms = Some(parse_number_env(value, key)?);
+                }
+                "MODELMUX_STREAMING_KEEPALIVE_INTERVAL_SECS" => {
+                    self.config.streaming.keepalive_interval_secs = parse_number_env(value, key)?;
+                }
+                "MODELMUX_STREAMING_HEARTBEAT_INTERVAL_MS" => {
+                    self.config.streaming.heartbeat_interval_ms = parse_number_env(value, key)?;
+                }
+                "MODELMUX_STREAMING_SSE_BUFFER_SIZE" => {
+                    self.config.streaming.sse_buffer_size = parse_number_env(value, key)?;
+                }
+                "MODELMUX_STREAMING_MAX_DROPPED_CHUNKS_BEFORE_ABORT" => {
+                    self.config.streaming.max_dropped_chunks_before_abort =
+                        parse_number_env(value, key)?;
+                }
+                "MODELMUX_STREAMING_CANCEL_ON_DISCONNECT" => {
+                    self.config.streaming.cancel_on_disconnect = parse_bool_env(value, key)?;
+                }
+                "MODELMUX_STREAMING_DEDUP_ENABLED" => {
+                    self.config.streaming.dedup_enabled = parse_bool_env(value, key)?;
+                }
+                "MODELMUX_STREAMING_DEDUP_WINDOW_MS" => {
+                    self.config.streaming.dedup_window_ms = parse_number_env(value, key)?;
+                }
+                "MODELMUX_STREAMING_REQUEST_TIMEOUT_SECS" => {
+                    self.config.streaming.request_timeout_secs = parse_number_env(value, key)?;
+                }
+                "MODELMUX_STREAMING_FIRST_TOKEN_TIMEOUT_SECS" => {
+                    self.config.streaming.first_token_timeout_secs = parse_number_env(value, key)?;
+                }
+                "MODELMUX_STREAMING_FAN_OUT_MODELS" => {
+                    self.config.streaming.fan_out_models = parse_list_env(value);
+                }
+                "MODELMUX_STREAMING_FAN_OUT_STRATEGY" => {
+                    self.config.streaming.fan_out_strategy = value.parse()?;
+                }
+
+                // Cache configuration
+                "MODELMUX_CACHE_SEMANTIC_ENABLED" => {
+                    self.config.cache.semantic_enabled = parse_bool_env(value, key)?;
+                }
+                "MODELMUX_CACHE_SEMANTIC_THRESHOLD" => {
+                    self.config.cache.semantic_threshold = value.parse().map_err(|e| {
+                        ProxyError::config_with_source(
+                            format!("Invalid MODELMUX_CACHE_SEMANTIC_THRESHOLD value '{}'", value),
+                            e,
+                        )
                     })?;
                 }
+                "MODELMUX_CACHE_SEMANTIC_MAX_ENTRIES" => {
+                    self.config.cache.semantic_max_entries = value.parse().map_err(|e| {
+                        ProxyError::config_with_source(
+                            format!(
+                                "Invalid MODELMUX_CACHE_SEMANTIC_MAX_ENTRIES value '{}'",
+                                value
+                            ),
+                            e,
+                        )
+                    })?;
+                }
+                "MODELMUX_CACHE_SEMANTIC_TTL_SECS" => {
+                    self.config.cache.semantic_ttl_secs = parse_number_env(value, key)?;
+                }
+                "MODELMUX_CACHE_RESPONSE_ENABLED" => {
+                    self.config.cache.response_enabled = parse_bool_env(value, key)?;
+                }
+                "MODELMUX_CACHE_RESPONSE_MAX_ENTRIES" => {
+                    self.config.cache.response_max_entries = parse_number_env(value, key)?;
+                }
+                "MODELMUX_CACHE_RESPONSE_TTL_SECS" => {
+                    self.config.cache.response_ttl_secs = parse_number_env(value, key)?;
+                }
+
+                // HTTP client configuration
+                "MODELMUX_HTTP_CLIENT_ADAPTIVE_TIMEOUT" => {
+                    self.config.http_client.adaptive_timeout = parse_bool_env(value, key)?;
+                }
+                "MODELMUX_HTTP_CLIENT_BASE_TIMEOUT_MS" => {
+                    self.config.http_client.base_timeout_ms = parse_number_env(value, key)?;
+                }
+                "MODELMUX_HTTP_CLIENT_MS_PER_TOKEN_STREAMING" => {
+                    self.config.http_client.ms_per_token_streaming = parse_number_env(value, key)?;
+                }
+                "MODELMUX_HTTP_CLIENT_MS_PER_TOKEN_NONSTREAMING" => {
+                    self.config.http_client.ms_per_token_nonstreaming =
+                        parse_number_env(value, key)?;
+                }
+                "MODELMUX_HTTP_CLIENT_MAX_TIMEOUT_MS" => {
+                    self.config.http_client.max_timeout_ms = parse_number_env(value, key)?;
+                }
+                "MODELMUX_HTTP_CLIENT_CONNECT_TIMEOUT_SECS" => {
+                    self.config.http_client.connect_timeout_secs = parse_number_env(value, key)?;
+                }
+                "MODELMUX_HTTP_CLIENT_READ_TIMEOUT_SECS" => {
+                    self.config.http_client.read_timeout_secs = parse_number_env(value, key)?;
+                }
+                "MODELMUX_HTTP_CLIENT_STREAMING_DESERIALISE" => {
+                    self.config.http_client.streaming_deserialise = parse_bool_env(value, key)?;
+                }
+
+                // Vertex AI configuration
+                "MODELMUX_EMBEDDING_MODEL" => {
+                    self.config.vertex.get_or_insert_with(VertexConfig::default).embedding_model =
+                        Some(value.clone());
+                }
+                "MODELMUX_VERTEX_MAX_CONTEXT_TOKENS" => {
+                    self.config
+                        .vertex
+                        .get_or_insert_with(VertexConfig::default)
+                        .max_context_tokens = Some(parse_number_env(value, key)?);
+                }
+
+                // Admin configuration
+                "MODELMUX_ADMIN_SECRET" => {
+                    self.config.admin.secret = Some(value.clone());
+                }
+                "MODELMUX_ADMIN_ENABLE_CONFIG_ENDPOINT" => {
+                    self.config.admin.enable_config_endpoint = parse_bool_env(value, key)?;
+                }
+
+                // Service discovery configuration
+                "MODELMUX_DISCOVERY_PROVIDER" => {
+                    self.config.discovery.provider = match value.to_lowercase().as_str() {
+                        "static" => Some(DiscoveryProvider::Static),
+                        // Keep a URL already set by MODELMUX_DISCOVERY_CONSUL_URL or the config file
+                        "consul" => match self.config.discovery.provider.take() {
+                            Some(consul @ DiscoveryProvider::Consul { .. }) => Some(consul),
+                            _ => Some(DiscoveryProvider::Consul { url: default_consul_url() }),
+                        },
+                        _ => {
+                            return Err(ProxyError::Config(
+                                format!(
+                                    "Invalid value for {}: '{}'. Valid values are: static, consul",
+                                    key, value
+                                ),
+                                None,
+                            ));
+                        }
+                    };
+                }
+                "MODELMUX_DISCOVERY_CONSUL_URL" => {
+                    self.config.discovery.provider =
+                        Some(DiscoveryProvider::Consul { url: value.clone() });
+                }
+                "MODELMUX_DISCOVERY_SERVICE_NAME" => {
+                    self.config.discovery.service_name = value.clone();
+                }
+                "MODELMUX_DISCOVERY_REFRESH_INTERVAL_SECS" => {
+                    self.config.discovery.refresh_interval_secs = parse_number_env(value, key)?;
+                }
+                "MODELMUX_DISCOVERY_ENDPOINT_SCHEME" => {
+                    self.config.discovery.endpoint_scheme = value.to_lowercase();
+                }
+
+                // Converter configuration
+                "MODELMUX_CONVERTER_EXTRA_ANTHROPIC_FIELDS" => {
+                    self.config.converter.extra_anthropic_fields = serde_json::from_str(value)
+                        .map_err(|e| {
+                            ProxyError::config_with_source(
+                                format!("Invalid {} value: expected a JSON object", key),
+                                e,
+                            )
+                        })?;
+                }
+                "MODELMUX_CONVERTER_ALLOW_CLIENT_EXTRA_FIELDS" => {
+                    self.config.converter.allow_client_extra_fields = parse_bool_env(value, key)?;
+                }
+                "MODELMUX_CONVERTER_INCLUDE_REASONING_IN_RESPONSE" => {
+                    self.config.converter.include_reasoning_in_response =
+                        parse_bool_env(value, key)?;
+                }
+                "MODELMUX_CONVERTER_RESPONSE_NORMALIZATION_SET_SYSTEM_FINGERPRINT" => {
+                    self.config.converter.response_normalization.set_system_fingerprint =
+                        Some(value.clone());
+                }
+                "MODELMUX_CONVERTER_RESPONSE_NORMALIZATION_FORCE_OBJECT_TYPE" => {
+                    self.config.converter.response_normalization.force_object_type =
+                        Some(value.clone());
+                }
+                "MODELMUX_CONVERTER_RESPONSE_NORMALIZATION_ENSURE_CHOICES_NOT_EMPTY" => {
+                    self.config.converter.response_normalization.ensure_choices_not_empty =
+                        parse_bool_env(value, key)?;
+                }
+                "MODELMUX_CONVERTER_RESPONSE_NORMALIZATION_ROUND_CREATED_TO_SECONDS" => {
+                    self.config.converter.response_normalization.round_created_to_seconds =
+                        parse_bool_env(value, key)?;
+                }
+
+                // A/B test configuration
+                "MODELMUX_AB_TEST_ENABLED" => {
+                    self.config.ab_test.enabled = parse_bool_env(value, key)?;
+                }
+
+                // Async job configuration
+                "MODELMUX_JOBS_WORKERS" => {
+                    self.config.jobs.workers = parse_number_env(value, key)?;
+                }
+                "MODELMUX_JOBS_TTL_SECS" => {
+                    self.config.jobs.ttl_secs = parse_number_env(value, key)?;
+                }
+                "MODELMUX_JOBS_CALLBACK_ALLOWED_HOSTS" => {
+                    self.config.jobs.callback_allowed_hosts = parse_list_env(value);
+                }
+
+                // Idempotency configuration
+                "MODELMUX_IDEMPOTENCY_STREAMING_REPLAY_TTL_SECS" => {
+                    self.config.idempotency.streaming_replay_ttl_secs =
+                        parse_number_env(value, key)?;
+                }
+
+                // Debug configuration
+                "MODELMUX_DEBUG_LOG_STREAMING_EVENTS" => {
+                    self.config.debug.log_streaming_events = parse_bool_env(value, key)?;
+                }
+
+                // Telemetry configuration
+                "MODELMUX_TELEMETRY_PROMETHEUS_EXEMPLARS" => {
+                    self.config.telemetry.prometheus_exemplars = parse_bool_env(value, key)?;
+                }
+                "MODELMUX_TELEMETRY_ENABLED" => {
+                    self.config.telemetry.enabled = parse_bool_env(value, key)?;
+                }
+                "MODELMUX_TELEMETRY_ENDPOINT" => {
+                    self.config.telemetry.endpoint = value.clone();
+                }
+                "MODELMUX_TELEMETRY_SERVICE_NAME" => {
+                    self.config.telemetry.service_name = value.clone();
+                }
+
+                // Request queue configuration
+                "MODELMUX_QUEUE_ENABLED" => {
+                    self.config.queue.enabled = parse_bool_env(value, key)?;
+                }
+                "MODELMUX_QUEUE_MAX_CONCURRENT" => {
+                    self.config.queue.max_concurrent = parse_number_env(value, key)?;
+                }
+                "MODELMUX_QUEUE_MAX_WAIT_MS" => {
+                    self.config.queue.max_wait_ms = parse_number_env(value, key)?;
+                }
+                "MODELMUX_QUEUE_CAPACITY" => {
+                    self.config.queue.capacity = parse_number_env(value, key)?;
+                }
+
+                // File upload configuration
+                "MODELMUX_UPLOAD_MAX_FILE_BYTES" => {
+                    self.config.upload.max_file_bytes = parse_number_env(value, key)?;
+                }
+
+                // Failover Vertex AI endpoint
+                key if key.starts_with("MODELMUX_FAILOVER_") => {
+                    let failover = self.config.failover_vertex.get_or_insert_with(Default::default);
+                    let field = match key {
+                        "MODELMUX_FAILOVER_URL" => &mut failover.url,
+                        "MODELMUX_FAILOVER_REGION" => &mut failover.region,
+                        "MODELMUX_FAILOVER_PROJECT" => &mut failover.project,
+                        "MODELMUX_FAILOVER_LOCATION" => &mut failover.location,
+                        "MODELMUX_FAILOVER_PUBLISHER" => &mut failover.publisher,
+                        "MODELMUX_FAILOVER_MODEL" => &mut failover.model,
+                        _ => {
+                            tracing::debug!("Ignoring unknown environment variable: {}", key);
+                            continue;
+                        }
+                    };
+                    *field = Some(value.clone());
+                }
+
+                // Request limits configuration
+                "MODELMUX_LIMITS_MAX_BODY_BYTES" => {
+                    self.config.limits.max_request_body_bytes = parse_number_env(value, key)?;
+                }
+                "MODELMUX_LIMITS_MAX_MESSAGES" => {
+                    self.config.limits.max_messages = parse_number_env(value, key)?;
+                }
+                "MODELMUX_LIMITS_MAX_N" => {
+                    self.config.limits.max_n = parse_number_env(value, key)?;
+                }
+                "MODELMUX_LIMITS_N_PARTIAL_ALLOWED" => {
+                    self.config.limits.n_partial_allowed = parse_bool_env(value, key)?;
+                }
+                "MODELMUX_LIMITS_MAX_BATCH_BYTES" => {
+                    self.config.limits.max_batch_bytes = parse_number_env(value, key)?;
+                }
+
+                // System prompt configuration
+                "MODELMUX_PROMPT_SYSTEM_PROMPT" => {
+                    self.config.prompt.system_prompt = Some(value.clone());
+                }
+                "MODELMUX_PROMPT_SYSTEM_PROMPT_MODE" => {
+                    self.config.prompt.system_prompt_mode = value.parse()?;
+                }
+
+                // CORS configuration
+                "MODELMUX_CORS_ALLOWED_ORIGINS" => {
+                    self.config.cors.allowed_origins = parse_list_env(value);
+                }
+                "MODELMUX_CORS_ALLOWED_METHODS" => {
+                    self.config.cors.allowed_methods = parse_list_env(value);
+                }
+                "MODELMUX_CORS_ALLOWED_HEADERS" => {
+                    self.config.cors.allowed_headers = parse_list_env(value);
+                }
+                "MODELMUX_CORS_ALLOW_CREDENTIALS" => {
+                    self.config.cors.allow_credentials = parse_bool_env(value, key)?;
+                }
+                "MODELMUX_CORS_MAX_AGE_SECS" => {
+                    self.config.cors.max_age_secs = parse_number_env(value, key)?;
+                }
+
+                // Audit log configuration
+                "MODELMUX_AUDIT_LOG_ENABLED" => {
+                    self.config.audit_log.enabled = parse_bool_env(value, key)?;
+                }
+                "MODELMUX_AUDIT_LOG_PATH" => {
+                    self.config.audit_log.path = value.clone();
+                }
+                "MODELMUX_AUDIT_LOG_MAX_SIZE_MB" => {
+                    self.config.audit_log.max_size_mb = parse_number_env(value, key)?;
+                }
+                "MODELMUX_AUDIT_LOG_MAX_FILES" => {
+                    self.config.audit_log.max_files = parse_number_env(value, key)?;
+                }
+                "MODELMUX_AUDIT_LOG_INCLUDE_RESPONSE_BODY" => {
+                    self.config.audit_log.include_response_body = parse_bool_env(value, key)?;
+                }
+                "MODELMUX_AUDIT_LOG_REDACT_USER_CONTENT" => {
+                    self.config.audit_log.redact_user_content = parse_bool_env(value, key)?;
+                }
+
+                // Health check configuration
+                "MODELMUX_HEALTH_DEEP_CHECK" => {
+                    self.config.health.deep_check = parse_bool_env(value, key)?;
+                }
+                "MODELMUX_HEALTH_DEEP_CHECK_INTERVAL_SECS" => {
+                    self.config.health.deep_check_interval_secs = parse_number_env(value, key)?;
+                }
 
                 // LLM Provider configuration (delegate to provider)
                 key if key.starts_with("MODELMUX_LLM_PROVIDER_") => {
@@ -472,7 +1086,7 @@ impl ConfigLoader {
                         "PORT environment variable is deprecated. Please use MODELMUX_SERVER_PORT."
                     );
                     self.config.server.port = value.parse().map_err(|e| {
-                        ProxyError::Config(format!("Invalid PORT value '{}': {}", value, e))
+                        ProxyError::config_with_source(format!("Invalid PORT value '{}'", value), e)
                     })?;
                 }
 
@@ -493,27 +1107,329 @@ impl Default for ConfigLoader {
     }
 }
 
+/// Environment variables reproducing a configuration
+///
+/// Inverse of the `MODELMUX_*` overrides read by
+/// [`ConfigLoader::with_env_vars`]: applying the returned variables on top of
+/// the defaults yields the same values. Settings without an environment
+/// variable (API keys, model lists, regions, pricing, A/B variants) and the
+/// provider variables read outside the loader (`LLM_PROVIDER`, `VERTEX_*`,
+/// `OPENAI_*`) are not included. Optional settings are included only when set.
+///
+/// # Arguments
+/// * `config` - configuration to export
+///
+/// # Returns
+/// * Variable names and values, in the order of the configuration sections
+pub fn to_env_vars(config: &Config) -> Vec<(&'static str, String)> {
+    let mut vars = Vec::new();
+    let mut set = |name: &'static str, value: String| vars.push((name, value));
+    let list = |items: &[String]| items.join(",");
+
+    let server = &config.server;
+    set("MODELMUX_SERVER_PORT", server.port.to_string());
+    set("MODELMUX_SERVER_LOG_LEVEL", serde_name(&server.log_level));
+    set("MODELMUX_SERVER_LOG_FORMAT", serde_name(&server.log_format));
+    set("MODELMUX_SERVER_ENABLE_RETRIES", server.enable_retries.to_string());
+    set("MODELMUX_SERVER_MAX_RETRY_ATTEMPTS", server.max_retry_attempts.to_string());
+    set("MODELMUX_SERVER_CIRCUIT_BREAKER_ENABLED", server.circuit_breaker_enabled.to_string());
+    set("MODELMUX_SERVER_CIRCUIT_BREAKER_THRESHOLD", server.circuit_breaker_threshold.to_string());
+    set(
+        "MODELMUX_SERVER_CIRCUIT_BREAKER_WINDOW_SECS",
+        server.circuit_breaker_window_secs.to_string(),
+    );
+    set(
+        "MODELMUX_SERVER_CIRCUIT_BREAKER_COOLDOWN_SECS",
+        server.circuit_breaker_cooldown_secs.to_string(),
+    );
+    set(
+        "MODELMUX_SERVER_CIRCUIT_BREAKER_MIN_REQUESTS",
+        server.circuit_breaker_min_requests.to_string(),
+    );
+
+    let auth = &config.auth;
+    if let Some(file) = &auth.service_account_file {
+        set("MODELMUX_AUTH_SERVICE_ACCOUNT_FILE", file.clone());
+    }
+    if let Some(json) = &auth.service_account_json {
+        set("MODELMUX_AUTH_SERVICE_ACCOUNT_JSON", json.clone());
+    }
+    if let Some(file) = &auth.service_account_key_file {
+        set("MODELMUX_AUTH_SERVICE_ACCOUNT_KEY_FILE", file.clone());
+    }
+    if let Some(key) = &auth.proxy_api_key {
+        set("MODELMUX_AUTH_PROXY_API_KEY", key.clone());
+    }
+    set("MODELMUX_AUTH_USE_WORKLOAD_IDENTITY", auth.use_workload_identity.to_string());
+    if let Some(audience) = &auth.workload_identity.audience {
+        set("MODELMUX_AUTH_WORKLOAD_IDENTITY_AUDIENCE", audience.clone());
+    }
+    if let Some(token_url) = &auth.workload_identity.token_url {
+        set("MODELMUX_AUTH_WORKLOAD_IDENTITY_TOKEN_URL", token_url.clone());
+    }
+    set("MODELMUX_AUTH_USE_ADC", auth.use_application_default_credentials.to_string());
+    if let Some(resource) = &auth.secret_manager_resource {
+        set("MODELMUX_AUTH_SECRET_MANAGER_RESOURCE", resource.clone());
+    }
+    if let Some(endpoint) = &auth.secret_manager_endpoint {
+        set("MODELMUX_AUTH_SECRET_MANAGER_ENDPOINT", endpoint.clone());
+    }
+
+    let streaming = &config.streaming;
+    set("MODELMUX_STREAMING_MODE", serde_name(&streaming.mode));
+    set("MODELMUX_STREAMING_BUFFER_SIZE", streaming.buffer_size.to_string());
+    set("MODELMUX_STREAMING_CHUNK_TIMEOUT_MS", streaming.chunk_timeout_ms.to_string());
+    if let Some(retry_ms) = streaming.sse_retry_ms {
+        set("MODELMUX_STREAMING_SSE_RETRY_MS", retry_ms.to_string());
+    }
+    set(
+        "MODELMUX_STREAMING_KEEPALIVE_INTERVAL_SECS",
+        streaming.keepalive_interval_secs.to_string(),
+    );
+    set(
+        "MODELMUX_STREAMING_HEARTBEAT_INTERVAL_MS",
+        streaming.heartbeat_interval_ms.to_string(),
+    );
+    set("MODELMUX_STREAMING_SSE_BUFFER_SIZE", streaming.sse_buffer_size.to_string());
+    set(
+        "MODELMUX_STREAMING_MAX_DROPPED_CHUNKS_BEFORE_ABORT",
+        streaming.max_dropped_chunks_before_abort.to_string(),
+    );
+    set("MODELMUX_STREAMING_CANCEL_ON_DISCONNECT", streaming.cancel_on_disconnect.to_string());
+    set("MODELMUX_STREAMING_DEDUP_ENABLED", streaming.dedup_enabled.to_string());
+    set("MODELMUX_STREAMING_DEDUP_WINDOW_MS", streaming.dedup_window_ms.to_string());
+    set("MODELMUX_STREAMING_REQUEST_TIMEOUT_SECS", streaming.request_timeout_secs.to_string());
+    set(
+        "MODELMUX_STREAMING_FIRST_TOKEN_TIMEOUT_SECS",
+        streaming.first_token_timeout_secs.to_string(),
+    );
+    set("MODELMUX_STREAMING_FAN_OUT_MODELS", list(&streaming.fan_out_models));
+    set("MODELMUX_STREAMING_FAN_OUT_STRATEGY", serde_name(&streaming.fan_out_strategy));
+
+    let cache = &config.cache;
+    set("MODELMUX_CACHE_SEMANTIC_ENABLED", cache.semantic_enabled.to_string());
+    set("MODELMUX_CACHE_SEMANTIC_THRESHOLD", cache.semantic_threshold.to_string());
+    set("MODELMUX_CACHE_SEMANTIC_MAX_ENTRIES", cache.semantic_max_entries.to_string());
+    set("MODELMUX_CACHE_SEMANTIC_TTL_SECS", cache.semantic_ttl_secs.to_string());
+    set("MODELMUX_CACHE_RESPONSE_ENABLED", cache.response_enabled.to_string());
+    set("MODELMUX_CACHE_RESPONSE_MAX_ENTRIES", cache.response_max_entries.to_string());
+    set("MODELMUX_CACHE_RESPONSE_TTL_SECS", cache.response_ttl_secs.to_string());
+
+    let http = &config.http_client;
+    set("MODELMUX_HTTP_CLIENT_ADAPTIVE_TIMEOUT", http.adaptive_timeout.to_string());
+    set("MODELMUX_HTTP_CLIENT_BASE_TIMEOUT_MS", http.base_timeout_ms.to_string());
+    set("MODELMUX_HTTP_CLIENT_MS_PER_TOKEN_STREAMING", http.ms_per_token_streaming.to_string());
+    set(
+        "MODELMUX_HTTP_CLIENT_MS_PER_TOKEN_NONSTREAMING",
+        http.ms_per_token_nonstreaming.to_string(),
+    );
+    set("MODELMUX_HTTP_CLIENT_MAX_TIMEOUT_MS", http.max_timeout_ms.to_string());
+    set("MODELMUX_HTTP_CLIENT_CONNECT_TIMEOUT_SECS", http.connect_timeout_secs.to_string());
+    set("MODELMUX_HTTP_CLIENT_READ_TIMEOUT_SECS", http.read_timeout_secs.to_string());
+    set("MODELMUX_HTTP_CLIENT_STREAMING_DESERIALISE", http.streaming_deserialise.to_string());
+
+    if let Some(vertex) = &config.vertex {
+        if let Some(model) = &vertex.embedding_model {
+            set("MODELMUX_EMBEDDING_MODEL", model.clone());
+        }
+        if let Some(tokens) = vertex.max_context_tokens {
+            set("MODELMUX_VERTEX_MAX_CONTEXT_TOKENS", tokens.to_string());
+        }
+    }
+
+    if let Some(secret) = &config.admin.secret {
+        set("MODELMUX_ADMIN_SECRET", secret.clone());
+    }
+    set("MODELMUX_ADMIN_ENABLE_CONFIG_ENDPOINT", config.admin.enable_config_endpoint.to_string());
+
+    let discovery = &config.discovery;
+    match &discovery.provider {
+        Some(DiscoveryProvider::Static) => set("MODELMUX_DISCOVERY_PROVIDER", "static".to_string()),
+        Some(DiscoveryProvider::Consul { url }) => {
+            set("MODELMUX_DISCOVERY_CONSUL_URL", url.clone())
+        }
+        None => {}
+    }
+    set("MODELMUX_DISCOVERY_SERVICE_NAME", discovery.service_name.clone());
+    set("MODELMUX_DISCOVERY_REFRESH_INTERVAL_SECS", discovery.refresh_interval_secs.to_string());
+    set("MODELMUX_DISCOVERY_ENDPOINT_SCHEME", discovery.endpoint_scheme.clone());
+
+    let converter = &config.converter;
+    if !converter.extra_anthropic_fields.is_empty() {
+        set(
+            "MODELMUX_CONVERTER_EXTRA_ANTHROPIC_FIELDS",
+            serde_json::Value::Object(converter.extra_anthropic_fields.clone()).to_string(),
+        );
+    }
+    set(
+        "MODELMUX_CONVERTER_ALLOW_CLIENT_EXTRA_FIELDS",
+        converter.allow_client_extra_fields.to_string(),
+    );
+    set(
+        "MODELMUX_CONVERTER_INCLUDE_REASONING_IN_RESPONSE",
+        converter.include_reasoning_in_response.to_string(),
+    );
+    let normalization = &converter.response_normalization;
+    if let Some(fingerprint) = &normalization.set_system_fingerprint {
+        set(
+            "MODELMUX_CONVERTER_RESPONSE_NORMALIZATION_SET_SYSTEM_FINGERPRINT",
+            fingerprint.clone(),
+        );
+    }
+    if let Some(object_type) = &normalization.force_object_type {
+        set("MODELMUX_CONVERTER_RESPONSE_NORMALIZATION_FORCE_OBJECT_TYPE", object_type.clone());
+    }
+    set(
+        "MODELMUX_CONVERTER_RESPONSE_NORMALIZATION_ENSURE_CHOICES_NOT_EMPTY",
+        normalization.ensure_choices_not_empty.to_string(),
+    );
+    set(
+        "MODELMUX_CONVERTER_RESPONSE_NORMALIZATION_ROUND_CREATED_TO_SECONDS",
+        normalization.round_created_to_seconds.to_string(),
+    );
+
+    set("MODELMUX_AB_TEST_ENABLED", config.ab_test.enabled.to_string());
+    set("MODELMUX_JOBS_WORKERS", config.jobs.workers.to_string());
+    set("MODELMUX_JOBS_TTL_SECS", config.jobs.ttl_secs.to_string());
+    set("MODELMUX_JOBS_CALLBACK_ALLOWED_HOSTS", list(&config.jobs.callback_allowed_hosts));
+    set(
+        "MODELMUX_IDEMPOTENCY_STREAMING_REPLAY_TTL_SECS",
+        config.idempotency.streaming_replay_ttl_secs.to_string(),
+    );
+    set("MODELMUX_DEBUG_LOG_STREAMING_EVENTS", config.debug.log_streaming_events.to_string());
+
+    let telemetry = &config.telemetry;
+    set("MODELMUX_TELEMETRY_PROMETHEUS_EXEMPLARS", telemetry.prometheus_exemplars.to_string());
+    set("MODELMUX_TELEMETRY_ENABLED", telemetry.enabled.to_string());
+    set("MODELMUX_TELEMETRY_ENDPOINT", telemetry.endpoint.clone());
+    set("MODELMUX_TELEMETRY_SERVICE_NAME", telemetry.service_name.clone());
+
+    let queue = &config.queue;
+    set("MODELMUX_QUEUE_ENABLED", queue.enabled.to_string());
+    set("MODELMUX_QUEUE_MAX_CONCURRENT", queue.max_concurrent.to_string());
+    set("MODELMUX_QUEUE_MAX_WAIT_MS", queue.max_wait_ms.to_string());
+    set("MODELMUX_QUEUE_CAPACITY", queue.capacity.to_string());
+
+    set("MODELMUX_UPLOAD_MAX_FILE_BYTES", config.upload.max_file_bytes.to_string());
+
+    if let Some(failover) = &config.failover_vertex {
+        for (name, value) in [
+            ("MODELMUX_FAILOVER_URL", &failover.url),
+            ("MODELMUX_FAILOVER_REGION", &failover.region),
+            ("MODELMUX_FAILOVER_PROJECT", &failover.project),
+            ("MODELMUX_FAILOVER_LOCATION", &failover.location),
+            ("MODELMUX_FAILOVER_PUBLISHER", &failover.publisher),
+            ("MODELMUX_FAILOVER_MODEL", &failover.model),
+        ] {
+            if let Some(value) = value {
+                set(name, value.clone());
+            }
+        }
+    }
+
+    set("MODELMUX_LIMITS_MAX_BODY_BYTES", config.limits.max_request_body_bytes.to_string());
+    set("MODELMUX_LIMITS_MAX_MESSAGES", config.limits.max_messages.to_string());
+    set("MODELMUX_LIMITS_MAX_N", config.limits.max_n.to_string());
+    set("MODELMUX_LIMITS_N_PARTIAL_ALLOWED", config.limits.n_partial_allowed.to_string());
+    set("MODELMUX_LIMITS_MAX_BATCH_BYTES", config.limits.max_batch_bytes.to_string());
+
+    if let Some(system_prompt) = &config.prompt.system_prompt {
+        set("MODELMUX_PROMPT_SYSTEM_PROMPT", system_prompt.clone());
+    }
+    set("MODELMUX_PROMPT_SYSTEM_PROMPT_MODE", serde_name(&config.prompt.system_prompt_mode));
+    let cors = &config.cors;
+    set("MODELMUX_CORS_ALLOWED_ORIGINS", list(&cors.allowed_origins));
+    set("MODELMUX_CORS_ALLOWED_METHODS", list(&cors.allowed_methods));
+    set("MODELMUX_CORS_ALLOWED_HEADERS", list(&cors.allowed_headers));
+    set("MODELMUX_CORS_ALLOW_CREDENTIALS", cors.allow_credentials.to_string());
+    set("MODELMUX_CORS_MAX_AGE_SECS", cors.max_age_secs.to_string());
+
+    let audit = &config.audit_log;
+    set("MODELMUX_AUDIT_LOG_ENABLED", audit.enabled.to_string());
+    set("MODELMUX_AUDIT_LOG_PATH", audit.path.clone());
+    set("MODELMUX_AUDIT_LOG_MAX_SIZE_MB", audit.max_size_mb.to_string());
+    set("MODELMUX_AUDIT_LOG_MAX_FILES", audit.max_files.to_string());
+    set("MODELMUX_AUDIT_LOG_INCLUDE_RESPONSE_BODY", audit.include_response_body.to_string());
+    set("MODELMUX_AUDIT_LOG_REDACT_USER_CONTENT", audit.redact_user_content.to_string());
+
+    let health = &config.health;
+    set("MODELMUX_HEALTH_DEEP_CHECK", health.deep_check.to_string());
+    set("MODELMUX_HEALTH_DEEP_CHECK_INTERVAL_SECS", health.deep_check_interval_secs.to_string());
+
+    vars
+}
+
 /* --- utility functions ------------------------------------------------------------------- */
 
+/// Path of the `.env` file: `MODELMUX_DOTENV_FILE`, or `.env` in the current directory
+pub fn dotenv_file() -> PathBuf {
+    env::var_os(DOTENV_FILE_ENV)
+        .filter(|path| !path.is_empty())
+        .map_or_else(|| PathBuf::from(DOTENV_FILE), PathBuf::from)
+}
+
+/// Custom configuration file from `MODELMUX_CONFIG_FILE`, when set
+///
+/// A leading `~` or `$VAR` in the path is expanded like in
+/// [`paths::expand_path`].
+///
+/// # Returns
+/// * `Ok(Some(path))` - The variable names a config file
+/// * `Ok(None)` - The variable is unset or empty
+/// * `Err(ProxyError)` - The path cannot be expanded
+pub fn custom_config_file() -> Result<Option<PathBuf>> {
+    match env::var(CONFIG_FILE_ENV) {
+        Ok(path) if !path.is_empty() => paths::expand_path(&path).map(Some),
+        _ => Ok(None),
+    }
+}
+
 /// Parse boolean value from environment variable
 fn parse_bool_env(value: &str, var_name: &str) -> Result<bool> {
     match value.to_lowercase().as_str() {
         "true" | "yes" | "1" | "on" | "enabled" => Ok(true),
         "false" | "no" | "0" | "off" | "disabled" => Ok(false),
-        _ => Err(ProxyError::Config(format!(
-            "Invalid boolean value for {}: '{}'\n\
+        _ => Err(ProxyError::Config(
+            format!(
+                "Invalid boolean value for {}: '{}'\n\
              Valid values: true/false, yes/no, 1/0, on/off, enabled/disabled",
-            var_name, value
-        ))),
+                var_name, value
+            ),
+            None,
+        )),
     }
 }
 
+/// Name of a unit enum variant as written in configuration files (e.g. `info`)
+fn serde_name<T: serde::Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
+
+/// Parse numeric value from environment variable
+fn parse_number_env<T>(value: &str, var_name: &str) -> Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    value.parse().map_err(|e| {
+        ProxyError::Config(format!("Invalid {} value '{}': {}", var_name, value, e), None)
+    })
+}
+
+/// Parse a comma-separated list from an environment variable, skipping empty entries
+fn parse_list_env(value: &str) -> Vec<String> {
+    value.split(',').map(str::trim).filter(|item| !item.is_empty()).map(String::from).collect()
+}
+
 /* --- tests ------------------------------------------------------------------------------- */
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{LogLevel, StreamingMode};
+    use crate::config::{FanOutStrategy, LogLevel, StreamingMode, SystemPromptMode};
     use std::fs;
     use tempfile::TempDir;
 
@@ -566,6 +1482,27 @@ mode = "standard"
                 ("MODELMUX_SERVER_PORT", Some("9090")),
                 ("MODELMUX_SERVER_LOG_LEVEL", Some("error")),
                 ("MODELMUX_STREAMING_MODE", Some("never")),
+                ("MODELMUX_STREAMING_HEARTBEAT_INTERVAL_MS", Some("0")),
+                ("MODELMUX_STREAMING_FAN_OUT_MODELS", Some("sonnet, sonnet-eu")),
+                ("MODELMUX_STREAMING_FAN_OUT_STRATEGY", Some("return_all")),
+                ("MODELMUX_SERVER_CIRCUIT_BREAKER_ENABLED", Some("false")),
+                ("MODELMUX_SERVER_CIRCUIT_BREAKER_THRESHOLD", Some("0.75")),
+                ("MODELMUX_LIMITS_MAX_BODY_BYTES", Some("1048576")),
+                ("MODELMUX_LIMITS_MAX_MESSAGES", Some("50")),
+                ("MODELMUX_LIMITS_MAX_N", Some("4")),
+                ("MODELMUX_LIMITS_N_PARTIAL_ALLOWED", Some("true")),
+                ("MODELMUX_LIMITS_MAX_BATCH_BYTES", Some("2097152")),
+                ("MODELMUX_PROMPT_SYSTEM_PROMPT", Some("Be brief.")),
+                ("MODELMUX_PROMPT_SYSTEM_PROMPT_MODE", Some("Replace")),
+                (
+                    "MODELMUX_CORS_ALLOWED_ORIGINS",
+                    Some("https://app.example.com, https://admin.example.com,"),
+                ),
+                ("MODELMUX_FAILOVER_REGION", Some("us-east5")),
+                ("MODELMUX_AUDIT_LOG_ENABLED", Some("true")),
+                ("MODELMUX_AUDIT_LOG_MAX_FILES", Some("3")),
+                ("MODELMUX_AUTH_USE_WORKLOAD_IDENTITY", Some("true")),
+                ("MODELMUX_AUTH_USE_ADC", Some("yes")),
                 (
                     "MODELMUX_AUTH_SERVICE_ACCOUNT_JSON",
                     Some(
@@ -584,8 +1521,81 @@ mode = "standard"
                 assert_eq!(config.server.port, 9090);
                 assert!(matches!(config.server.log_level, LogLevel::Error));
                 assert!(matches!(config.streaming.mode, StreamingMode::Never));
+                assert_eq!(config.streaming.heartbeat_interval_ms, 0);
+                assert_eq!(config.streaming.fan_out_models, vec!["sonnet", "sonnet-eu"]);
+                assert_eq!(config.streaming.fan_out_strategy, FanOutStrategy::ReturnAll);
+                assert!(!config.server.circuit_breaker_enabled);
+                assert_eq!(config.server.circuit_breaker_threshold, 0.75);
+                assert_eq!(config.limits.max_request_body_bytes, 1_048_576);
+                assert_eq!(config.limits.max_messages, 50);
+                assert_eq!(config.limits.max_n, 4);
+                assert!(config.limits.n_partial_allowed);
+                assert_eq!(config.limits.max_batch_bytes, 2_097_152);
+                assert_eq!(config.prompt.system_prompt.as_deref(), Some("Be brief."));
+                assert_eq!(config.prompt.system_prompt_mode, SystemPromptMode::Replace);
+                assert_eq!(
+                    config.cors.allowed_origins,
+                    vec!["https://app.example.com", "https://admin.example.com"]
+                );
+                assert_eq!(
+                    config.failover_vertex.and_then(|vertex| vertex.region).as_deref(),
+                    Some("us-east5")
+                );
+                assert!(config.auth.use_workload_identity);
+                assert!(config.auth.use_application_default_credentials);
+                assert!(config.audit_log.enabled);
+                assert_eq!(config.audit_log.max_files, 3);
+            },
+        );
+    }
+
+    #[test]
+    fn test_dotenv_file_overrides_defaults() {
+        let temp_dir = TempDir::new().unwrap();
+        let dotenv = temp_dir.path().join("modelmux.env");
+        fs::write(
+            &dotenv,
+            "# legacy settings\n\
+             MODELMUX_SERVER_PORT=4242\n\
+             MODELMUX_STREAMING_MODE=buffered\n\
+             MODELMUX_SERVER_LOG_LEVEL=warn\n\
+             MODELMUX_TEST_DOTENV_ONLY=\"from file\"\n",
+        )
+        .unwrap();
+
+        temp_env::with_vars(
+            [
+                ("MODELMUX_SERVER_PORT", None),
+                ("MODELMUX_STREAMING_MODE", None),
+                ("MODELMUX_TEST_DOTENV_ONLY", None),
+                ("MODELMUX_SERVER_LOG_LEVEL", Some("error")),
+                (DOTENV_FILE_ENV, Some(dotenv.to_str().unwrap())),
+            ],
+            || {
+                let loader =
+                    ConfigLoader::new().with_defaults().with_dotenv().expect("Should load .env");
+                assert_eq!(
+                    loader.dotenv_variables(),
+                    [
+                        "MODELMUX_SERVER_PORT",
+                        "MODELMUX_STREAMING_MODE",
+                        "MODELMUX_TEST_DOTENV_ONLY"
+                    ]
+                );
+                let config = loader.build_base().unwrap();
+
+                assert_eq!(config.server.port, 4242);
+                assert!(matches!(config.streaming.mode, StreamingMode::Buffered));
+                // Variables already in the environment are not overridden
+                assert!(matches!(config.server.log_level, LogLevel::Error));
+                assert_eq!(env::var("MODELMUX_TEST_DOTENV_ONLY").unwrap(), "from file");
             },
         );
+
+        temp_env::with_var(DOTENV_FILE_ENV, Some(temp_dir.path().join("missing.env")), || {
+            let err = ConfigLoader::new().with_defaults().with_dotenv().err().unwrap();
+            assert!(err.to_string().contains("missing.env"));
+        });
     }
 
     #[test]
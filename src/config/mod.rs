@@ -14,6 +14,8 @@
 //! - `paths.rs` - Platform-native path resolution (SRP)
 //! - `validation.rs` - Configuration validation (SRP)
 //! - `cli.rs` - CLI configuration commands (SRP)
+//! - `overlay.rs` - Runtime overlays with `PartialConfig` (SRP)
+//! - `schema.rs` - JSON Schema of the configuration file (SRP)
 //!
 //! Authors:
 //!   Jaro <yarenty@gmail.com>
@@ -25,16 +27,28 @@
 pub mod cli;
 pub mod loader;
 pub mod migration;
+pub mod overlay;
 pub mod paths;
+pub mod schema;
 pub mod validation;
 
 /* --- uses ------------------------------------------------------------------------------------ */
 
 use crate::error::{ProxyError, Result};
-use crate::provider::{AuthStrategy, LlmProviderBackend, LlmProviderConfig};
+use crate::provider::{
+    AuthStrategy, AzureOpenAiProvider, BedrockProvider, LlmProviderBackend, LlmProviderConfig,
+    OllamaProvider, OpenAiCompatibleProvider, VertexProvider,
+};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::str::FromStr;
 
+#[allow(unused_imports)]
+pub use overlay::PartialConfig;
+#[allow(unused_imports)]
+pub use validation::{Severity, ValidationIssue};
+
 /* --- types ----------------------------------------------------------------------------------- */
 
 ///
@@ -42,7 +56,7 @@ use std::str::FromStr;
 ///
 /// This replaces the old Config struct with TOML-compatible fields
 /// and better organization following configuration best practices.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[derive(Default)]
 pub struct Config {
     /// HTTP server configuration
@@ -54,10 +68,156 @@ pub struct Config {
     /// Vertex AI provider configuration (optional; env vars used if not set)
     #[serde(default)]
     pub vertex: Option<VertexConfig>,
+    /// Vertex AI endpoint requests fail over to when the primary keeps failing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failover_vertex: Option<VertexConfig>,
+    /// OpenAI-compatible provider configuration (optional; env vars used if not set)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub openai_compatible: Option<OpenAiCompatibleConfig>,
+    /// Azure OpenAI Service provider configuration (optional; env vars used if not set)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub azure_openai: Option<AzureOpenAiConfig>,
+    /// Response cache configuration
+    #[serde(default)]
+    pub cache: CacheConfig,
+    /// Outgoing HTTP client configuration
+    #[serde(default)]
+    pub http_client: HttpClientConfig,
+    /// Usage reporting configuration
+    #[serde(default)]
+    pub usage: UsageConfig,
+    /// Administrative endpoint configuration
+    #[serde(default)]
+    pub admin: AdminConfig,
+    /// Service discovery for upstream endpoints
+    #[serde(default)]
+    pub discovery: DiscoveryConfig,
+    /// A/B traffic splitting between two models
+    #[serde(default)]
+    pub ab_test: AbTestConfig,
+    /// Request conversion configuration
+    #[serde(default)]
+    pub converter: ConverterConfig,
+    /// Asynchronous chat completion jobs
+    #[serde(default)]
+    pub jobs: JobsConfig,
+    /// Idempotency-Key handling
+    #[serde(default)]
+    pub idempotency: IdempotencyConfig,
+    /// Debugging aids, not meant for production
+    #[serde(default)]
+    pub debug: DebugConfig,
+    /// Metrics and trace export options
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+    /// Priority queue for chat completions
+    #[serde(default)]
+    pub queue: QueueConfig,
+    /// File uploads of `POST /v1/files/upload-and-chat`
+    #[serde(default)]
+    pub upload: UploadConfig,
+    /// Size limits of JSON request bodies
+    #[serde(default)]
+    pub limits: RequestLimitsConfig,
+    /// Cross-origin requests from browsers
+    #[serde(default)]
+    pub cors: CorsConfig,
+    /// Audit trail of prompts and responses
+    #[serde(default)]
+    pub audit_log: AuditLogConfig,
+    /// Deep health check of the upstream
+    #[serde(default)]
+    pub health: HealthConfig,
+    /// System prompt injected into every chat completion
+    #[serde(default)]
+    pub prompt: PromptConfig,
+    /// Alternative model names clients may send, mapped to the model they stand for
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub model_aliases: HashMap<String, String>,
+    /// Model names served by their own Vertex AI endpoint, e.g. in another GCP project
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub model_routes: HashMap<String, ModelRouteConfig>,
+    /// Settings that differ for one model, by the model name clients send
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub models: HashMap<String, ModelOverrides>,
 
     /// LLM provider configuration (loaded separately, not serialized)
     #[serde(skip)]
     pub llm_provider: Option<LlmProviderConfig>,
+
+    /// Failover LLM provider built from `failover_vertex` (loaded separately, not serialized)
+    #[serde(skip)]
+    pub failover_provider: Option<LlmProviderConfig>,
+
+    /// Upstreams built from `model_routes`, by route name (loaded separately, not serialized)
+    #[serde(skip)]
+    pub route_providers: HashMap<String, ModelRoute>,
+}
+
+///
+/// Model route configuration.
+///
+/// Used in `[model_routes.<name>]`: requests for model `<name>` are sent to
+/// this Vertex AI endpoint instead of the `[vertex]` one. Vertex fields left
+/// unset are inherited from `[vertex]`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ModelRouteConfig {
+    /// Model name reported in responses (default: the route name)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    /// GCP project ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// Vertex region
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    /// Vertex location
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// Model publisher
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    /// Vertex model ID (e.g. claude-3-5-haiku@20241022)
+    #[serde(default, alias = "model_id", skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Full URL override (alternative to region/project/location/publisher/model)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Service account key file of the route's project (default: the `[auth]` credentials)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_account_file: Option<String>,
+}
+
+///
+/// Per-model configuration overrides.
+///
+/// Used in `[models."<name>"]`: requests for model `<name>` use these settings
+/// instead of the request's own values or the global configuration. Fields
+/// left unset change nothing.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ModelOverrides {
+    /// `max_tokens` sent upstream, replacing the request's value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens_override: Option<u32>,
+    /// `temperature` sent upstream, replacing the request's value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature_override: Option<f64>,
+    /// Streaming mode of requests for the model, replacing `streaming.mode`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub streaming_mode: Option<StreamingMode>,
+    /// Text appended to the system prompt of requests for the model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt_append: Option<String>,
+}
+
+///
+/// Upstream of a `[model_routes]` entry, built when the configuration is loaded.
+#[derive(Debug, Clone)]
+pub struct ModelRoute {
+    /// Provider requests for the route are sent to, with the route's auth strategy
+    pub provider_config: LlmProviderConfig,
+    /// Model name reported in responses
+    pub display_name: String,
 }
 
 ///
@@ -65,7 +225,7 @@ pub struct Config {
 ///
 /// Used in `[[vertex.models]]` to define multiple routable models.
 /// The `name` field is the OpenAI-facing model alias clients use in `"model": "..."` requests.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct VertexModelEntry {
     /// OpenAI-facing alias (e.g. "claude-opus", "claude-sonnet")
     pub name: String,
@@ -94,7 +254,7 @@ pub struct VertexModelEntry {
 ///
 /// Can be set in TOML under `[vertex]` or via environment variables
 /// (VERTEX_PROJECT, VERTEX_REGION, etc.). Config file takes precedence over env.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct VertexConfig {
     /// GCP project ID
     #[serde(alias = "project_id")]
@@ -118,13 +278,79 @@ pub struct VertexConfig {
     /// Each entry may override individual fields; unset fields inherit from the parent [vertex] block.
     #[serde(default)]
     pub models: Vec<VertexModelEntry>,
+    /// Single region all requests are routed to when `regions` is empty
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary_region: Option<String>,
+    /// Regions requests are spread over by weight, each with its own quota
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regions: Vec<VertexRegionConfig>,
+    /// Text embedding model served at `/v1/embeddings` (default: text-embedding-004)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_model: Option<String>,
+    /// Largest estimated prompt in tokens; overrides the context window of the model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_context_tokens: Option<u32>,
+}
+
+///
+/// OpenAI-compatible provider configuration.
+///
+/// Can be set in TOML under `[openai_compatible]` or via environment variables
+/// (OPENAI_API_BASE, OPENAI_MODEL). The API key is only read from OPENAI_API_KEY.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct OpenAiCompatibleConfig {
+    /// Base URL of the upstream, with or without `/v1` (e.g. https://api.openai.com/v1)
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Model used for requests that do not name one
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+///
+/// Azure OpenAI Service provider configuration.
+///
+/// Can be set in TOML under `[azure_openai]` or via environment variables
+/// (AZURE_OPENAI_ENDPOINT, AZURE_OPENAI_DEPLOYMENT, AZURE_OPENAI_API_VERSION).
+/// The API key is only read from AZURE_OPENAI_API_KEY.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct AzureOpenAiConfig {
+    /// Resource endpoint (e.g. https://my-resource.openai.azure.com)
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// Deployment requests are sent to
+    #[serde(default)]
+    pub deployment: Option<String>,
+    /// `api-version` query parameter of the requests
+    #[serde(default)]
+    pub api_version: Option<String>,
+}
+
+///
+/// A Vertex AI region requests can be routed to.
+///
+/// Used in `[[vertex.regions]]`. The region's project and region replace the
+/// ones of the request URL, so every configured model is served from it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct VertexRegionConfig {
+    /// Vertex region (e.g. us-east5), also used as the location
+    pub region: String,
+    /// GCP project whose quota the region's requests use
+    #[serde(default)]
+    pub project: String,
+    /// Relative share of traffic
+    #[serde(default = "default_region_weight")]
+    pub weight: u32,
+    /// Vertex model ID replacing the requested model in this region
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_override: Option<String>,
 }
 
 ///
 /// HTTP server configuration.
 ///
 /// Groups all server-related settings for better organization.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ServerConfig {
     /// HTTP server port number
     #[serde(default = "default_port")]
@@ -138,13 +364,31 @@ pub struct ServerConfig {
     /// Maximum retry attempts for quota errors
     #[serde(default = "default_max_retry_attempts")]
     pub max_retry_attempts: u32,
+    /// Log output format
+    #[serde(default = "default_log_format")]
+    pub log_format: LogFormat,
+    /// Stop sending requests to a failing Vertex AI backend for a while
+    #[serde(default = "default_circuit_breaker_enabled")]
+    pub circuit_breaker_enabled: bool,
+    /// Failure rate (0-1) in the rolling window that opens the circuit
+    #[serde(default = "default_circuit_breaker_threshold")]
+    pub circuit_breaker_threshold: f64,
+    /// Length of the rolling window in seconds
+    #[serde(default = "default_circuit_breaker_window_secs")]
+    pub circuit_breaker_window_secs: u64,
+    /// Seconds the circuit stays open before a probe request is let through
+    #[serde(default = "default_circuit_breaker_cooldown_secs")]
+    pub circuit_breaker_cooldown_secs: u64,
+    /// Requests needed in the window before the failure rate is acted on
+    #[serde(default = "default_circuit_breaker_min_requests")]
+    pub circuit_breaker_min_requests: u32,
 }
 
 ///
 /// Authentication configuration.
 ///
 /// Supports multiple authentication methods with secure defaults.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AuthConfig {
     /// Path to Google Cloud service account JSON file
     /// Supports tilde expansion (~/.config/modelmux/service-account.json)
@@ -156,16 +400,87 @@ pub struct AuthConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub service_account_json: Option<String>,
 
+    /// Path to a PEM file holding the private key of service_account_file
+    /// Replaces the `private_key` of the JSON file, which may then be left out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_account_key_file: Option<String>,
+
+    /// Secret Manager secret version holding the service account JSON,
+    /// e.g. `projects/my-project/secrets/sa-key/versions/latest`
+    /// Fetched at startup with a metadata server token; takes precedence over file and JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_manager_resource: Option<String>,
+
+    /// Secret Manager API base URL (unset = https://secretmanager.googleapis.com)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_manager_endpoint: Option<String>,
+
     /// Authentication strategy (for future extensibility)
     #[serde(skip, default = "default_auth_strategy")]
     pub strategy: AuthStrategy,
+
+    /// Client API keys accepted by the proxy (stored as SHA-256 hashes)
+    /// Generate entries with `modelmux keygen`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub api_keys: Vec<ApiKeyEntry>,
+
+    /// Bearer token clients must send to use the `/v1/*` endpoints
+    /// Unset = the proxy is open to anyone who can reach it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy_api_key: Option<String>,
+
+    /// Get Vertex AI tokens from the GKE / Cloud Run metadata server (Workload Identity)
+    /// service_account_file/json are not needed when enabled
+    #[serde(default)]
+    pub use_workload_identity: bool,
+
+    /// Workload Identity token request settings
+    #[serde(default)]
+    pub workload_identity: WorkloadIdentityConfig,
+
+    /// Get Vertex AI tokens from Application Default Credentials: the file in
+    /// GOOGLE_APPLICATION_CREDENTIALS or the one written by
+    /// `gcloud auth application-default login`
+    /// service_account_file/json are not needed when enabled
+    #[serde(default)]
+    pub use_application_default_credentials: bool,
+}
+
+///
+/// Workload Identity configuration.
+///
+/// Used in `[auth.workload_identity]` when `auth.use_workload_identity` is set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct WorkloadIdentityConfig {
+    /// Audience sent with the token request (unset = default service account token)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audience: Option<String>,
+    /// Token endpoint (unset = the metadata server's default service account token URL)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_url: Option<String>,
+}
+
+///
+/// Client API key entry.
+///
+/// Used in `[[auth.api_keys]]`. Only the SHA-256 hash of the key is stored;
+/// the plaintext key is shown once by `modelmux keygen` and never persisted.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ApiKeyEntry {
+    /// Human-readable name of the client (e.g. "ci-pipeline")
+    pub name: String,
+    /// Lowercase hex SHA-256 hash of the API key
+    pub key_hash: String,
+    /// Model aliases this key may use (empty means all models)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_models: Vec<String>,
 }
 
 ///
 /// Streaming configuration.
 ///
 /// Controls how the proxy handles streaming responses for different clients.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct StreamingConfig {
     /// Streaming mode selection
     #[serde(default = "default_streaming_mode")]
@@ -178,13 +493,508 @@ pub struct StreamingConfig {
     /// Timeout for streaming chunks (in milliseconds)
     #[serde(default = "default_chunk_timeout")]
     pub chunk_timeout_ms: u64,
+
+    /// Reconnection interval suggested to SSE clients via `retry:` frames (in milliseconds)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sse_retry_ms: Option<u64>,
+
+    /// Backend silence after which a `retry:` hint is sent again (in seconds)
+    #[serde(default = "default_keepalive_interval")]
+    pub keepalive_interval_secs: u64,
+
+    /// Interval of `: keep-alive` SSE comments sent during a stream (in milliseconds, 0 = off)
+    #[serde(default = "default_heartbeat_interval_ms")]
+    pub heartbeat_interval_ms: u64,
+
+    /// Number of SSE events buffered for a client before sends wait
+    #[serde(default = "default_sse_buffer_size")]
+    pub sse_buffer_size: usize,
+
+    /// Abort the upstream request after this many chunks dropped in a row (0 = never)
+    #[serde(default = "default_max_dropped_chunks_before_abort")]
+    pub max_dropped_chunks_before_abort: usize,
+
+    /// Abort the upstream request as soon as the client disconnects
+    #[serde(default = "default_cancel_on_disconnect")]
+    pub cancel_on_disconnect: bool,
+
+    /// Answer identical concurrent non-streaming requests with one upstream request
+    #[serde(default)]
+    pub dedup_enabled: bool,
+
+    /// Time after the first request in which identical requests join it (in milliseconds)
+    #[serde(default = "default_dedup_window_ms")]
+    pub dedup_window_ms: u64,
+
+    /// Timeout for a whole streaming upstream request (in seconds)
+    #[serde(default = "default_stream_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+
+    /// Time to wait for the first chunk of a streaming response (in seconds)
+    #[serde(default = "default_first_token_timeout_secs")]
+    pub first_token_timeout_secs: u64,
+
+    /// Models a streaming request for any of them is sent to in parallel
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fan_out_models: Vec<String>,
+
+    /// How the streams of a fan-out request are combined
+    #[serde(default)]
+    pub fan_out_strategy: FanOutStrategy,
+}
+
+///
+/// Response cache configuration.
+///
+/// Controls the semantic similarity cache that answers near-duplicate
+/// deterministic requests without calling the upstream provider, and the
+/// exact-match cache of raw upstream responses.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CacheConfig {
+    /// Enable the semantic similarity cache (requires the `semantic-cache` feature)
+    #[serde(default)]
+    pub semantic_enabled: bool,
+
+    /// Minimum cosine similarity for a cached response to be reused
+    #[serde(default = "default_semantic_threshold")]
+    pub semantic_threshold: f64,
+
+    /// Maximum number of entries kept in the semantic cache
+    #[serde(default = "default_semantic_max_entries")]
+    pub semantic_max_entries: usize,
+
+    /// How long a semantically cached response stays valid (in seconds)
+    #[serde(default = "default_semantic_ttl_secs")]
+    pub semantic_ttl_secs: u64,
+
+    /// Cache raw upstream responses of identical deterministic requests
+    #[serde(default)]
+    pub response_enabled: bool,
+
+    /// Maximum number of entries kept in the response cache
+    #[serde(default = "default_response_max_entries")]
+    pub response_max_entries: usize,
+
+    /// How long a cached upstream response stays valid (in seconds)
+    #[serde(default = "default_response_ttl_secs")]
+    pub response_ttl_secs: u64,
+}
+
+///
+/// Outgoing HTTP client configuration.
+///
+/// Controls timeouts for requests sent to the upstream LLM provider.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HttpClientConfig {
+    /// Scale the per-request timeout with the requested `max_tokens`
+    #[serde(default = "default_adaptive_timeout")]
+    pub adaptive_timeout: bool,
+
+    /// Fixed part of the adaptive timeout (in milliseconds)
+    #[serde(default = "default_base_timeout_ms")]
+    pub base_timeout_ms: u64,
+
+    /// Additional time allowed per requested token for streaming requests (in milliseconds)
+    #[serde(default = "default_ms_per_token")]
+    pub ms_per_token_streaming: f64,
+
+    /// Additional time allowed per requested token for non-streaming requests (in milliseconds)
+    #[serde(default = "default_ms_per_token")]
+    pub ms_per_token_nonstreaming: f64,
+
+    /// Upper bound for the adaptive timeout (in milliseconds)
+    #[serde(default = "default_max_timeout_ms")]
+    pub max_timeout_ms: u64,
+
+    /// Time allowed for establishing an upstream connection (in seconds)
+    #[serde(default = "default_connect_timeout_secs")]
+    pub connect_timeout_secs: u64,
+
+    /// Default timeout for a whole upstream request (in seconds)
+    #[serde(default = "default_read_timeout_secs")]
+    pub read_timeout_secs: u64,
+
+    /// Parse non-streaming upstream responses while they arrive (experimental)
+    #[serde(default)]
+    pub streaming_deserialise: bool,
+}
+
+///
+/// Usage reporting configuration.
+///
+/// Holds the optional cost table used to estimate spend in `GET /v1/usage`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct UsageConfig {
+    /// Per-model token prices, keyed by model name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub pricing: HashMap<String, ModelPricing>,
+}
+
+///
+/// Token prices for a single model.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ModelPricing {
+    /// Price in USD per million input tokens
+    pub input_per_million_usd: f64,
+
+    /// Price in USD per million output tokens
+    pub output_per_million_usd: f64,
+}
+
+///
+/// Administrative endpoint configuration.
+///
+/// Admin endpoints are disabled unless a shared secret is configured.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct AdminConfig {
+    /// Shared secret expected in the `X-Admin-Secret` header
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+
+    /// Serve the running configuration at `GET /admin/config` (default: false)
+    #[serde(default)]
+    pub enable_config_endpoint: bool,
+}
+
+///
+/// Service discovery configuration.
+///
+/// When a discovery provider is configured, upstream requests are spread across
+/// the healthy endpoints registered for `service_name` instead of the host in
+/// the configured Vertex AI URL.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DiscoveryConfig {
+    /// Discovery backend (unset = static endpoint from the provider config)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<DiscoveryProvider>,
+
+    /// Service name to look up in the catalog
+    #[serde(default = "default_discovery_service_name")]
+    pub service_name: String,
+
+    /// How often to refresh the endpoint list (in seconds)
+    #[serde(default = "default_discovery_refresh_interval")]
+    pub refresh_interval_secs: u64,
+
+    /// Scheme of the discovered endpoints: https or http
+    #[serde(default = "default_discovery_endpoint_scheme")]
+    pub endpoint_scheme: String,
+}
+
+///
+/// Service discovery backend.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum DiscoveryProvider {
+    /// Use the endpoint from the provider configuration
+    Static,
+    /// Poll the Consul health API for passing service instances
+    Consul {
+        /// Consul agent base URL
+        #[serde(default = "default_consul_url")]
+        url: String,
+    },
+}
+
+///
+/// A/B traffic splitting configuration.
+///
+/// When enabled, every chat completion is assigned to variant A or B by
+/// weighted random selection and sent to that variant's model.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct AbTestConfig {
+    /// Enable traffic splitting
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// First variant
+    #[serde(default)]
+    pub variant_a: AbVariantConfig,
+
+    /// Second variant
+    #[serde(default)]
+    pub variant_b: AbVariantConfig,
+}
+
+///
+/// A single A/B test variant.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AbVariantConfig {
+    /// Model name requests are routed to (a `[[vertex.models]]` name or the default model)
+    #[serde(default)]
+    pub model: String,
+
+    /// Relative share of traffic
+    #[serde(default = "default_ab_weight")]
+    pub weight: u32,
+}
+
+///
+/// Request conversion configuration.
+///
+/// Allows injecting Anthropic request fields that modelmux does not model yet
+/// (beta flags, experimental parameters) without code changes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ConverterConfig {
+    /// Top-level fields merged into every Anthropic request
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub extra_anthropic_fields: serde_json::Map<String, serde_json::Value>,
+
+    /// Accept additional fields from clients via the `x-extra-anthropic` header
+    #[serde(default)]
+    pub allow_client_extra_fields: bool,
+
+    /// Forward extended thinking blocks as `reasoning_content` on the response message
+    #[serde(default)]
+    pub include_reasoning_in_response: bool,
+
+    /// Adjustments applied to complete OpenAI responses
+    #[serde(default)]
+    pub response_normalization: ResponseNormalizationConfig,
+}
+
+///
+/// Response normalization configuration.
+///
+/// Small adjustments to complete OpenAI responses for clients with strict
+/// response parsing. Everything is disabled by default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ResponseNormalizationConfig {
+    /// Static `system_fingerprint` added to every response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub set_system_fingerprint: Option<String>,
+
+    /// Replacement for the `object` field (normally `chat.completion`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub force_object_type: Option<String>,
+
+    /// Add an empty assistant choice when a response has no choices
+    #[serde(default)]
+    pub ensure_choices_not_empty: bool,
+
+    /// Convert millisecond `created` timestamps to seconds
+    #[serde(default)]
+    pub round_created_to_seconds: bool,
+}
+
+///
+/// Asynchronous job configuration.
+///
+/// Controls the worker pool behind `POST /v1/chat/completions/async`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JobsConfig {
+    /// Number of concurrent job workers
+    #[serde(default = "default_job_workers")]
+    pub workers: usize,
+
+    /// Seconds a finished job is kept before it expires
+    #[serde(default = "default_job_ttl_secs")]
+    pub ttl_secs: u64,
+
+    /// Hosts `callback_url` may point to; empty disables callbacks
+    #[serde(default)]
+    pub callback_allowed_hosts: Vec<String>,
+}
+
+///
+/// Idempotency configuration.
+///
+/// Streaming requests carrying an `Idempotency-Key` header are recorded so a
+/// retried request joins or replays the original stream.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct IdempotencyConfig {
+    /// Seconds a finished stream can still be replayed
+    #[serde(default = "default_streaming_replay_ttl_secs")]
+    pub streaming_replay_ttl_secs: u64,
+}
+
+///
+/// Debugging configuration.
+///
+/// Options here are too verbose for production and only take effect when the
+/// log level is `debug` or `trace`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DebugConfig {
+    /// Log every SSE event sent to clients under the `modelmux_stream` target
+    #[serde(default)]
+    pub log_streaming_events: bool,
+}
+
+///
+/// Request queue configuration.
+///
+/// Queues chat completions by `X-Priority` so background traffic cannot
+/// starve interactive users.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct QueueConfig {
+    /// Queue chat completions instead of running them immediately
+    #[serde(default)]
+    pub enabled: bool,
+    /// Chat completions running at once
+    #[serde(default = "default_queue_max_concurrent")]
+    pub max_concurrent: usize,
+    /// Milliseconds a request may wait before it is rejected with 503
+    #[serde(default = "default_queue_max_wait_ms")]
+    pub max_wait_ms: u64,
+    /// Requests waiting per priority level before new ones are rejected
+    #[serde(default = "default_queue_capacity")]
+    pub capacity: usize,
+}
+
+///
+/// File upload configuration.
+///
+/// Uploaded files are held in memory only and never written to disk.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UploadConfig {
+    /// Largest accepted file in bytes
+    #[serde(default = "default_upload_max_file_bytes")]
+    pub max_file_bytes: usize,
+}
+
+///
+/// Request size limits.
+///
+/// Guards the JSON endpoints (`/v1/chat/completions`, its async variant and
+/// `/v1/embeddings`) against payloads large enough to exhaust memory.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RequestLimitsConfig {
+    /// Largest accepted request body in bytes; larger bodies get `413`
+    #[serde(default = "default_limits_max_request_body_bytes")]
+    pub max_request_body_bytes: usize,
+    /// Most messages accepted in one chat completion request
+    #[serde(default = "default_limits_max_messages")]
+    pub max_messages: usize,
+    /// Most completions (`n`) one chat completion request may ask for
+    #[serde(default = "default_limits_max_n")]
+    pub max_n: u32,
+    /// Return the completions that succeeded when some of an `n > 1` request fail
+    #[serde(default)]
+    pub n_partial_allowed: bool,
+    /// Largest batch output file accepted by `/v1/batches/convert`; larger files get `413`
+    #[serde(default = "default_limits_max_batch_bytes")]
+    pub max_batch_bytes: usize,
+}
+
+///
+/// CORS policy of the HTTP API.
+///
+/// Without `allowed_origins` (or with `["*"]`) any origin is allowed, as in
+/// earlier releases.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CorsConfig {
+    /// Origins allowed to call the API, e.g. `https://app.example.com`
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+    /// Methods allowed in cross-origin requests; empty allows the requested method
+    #[serde(default)]
+    pub allowed_methods: Vec<String>,
+    /// Request headers allowed in cross-origin requests; empty allows the requested headers
+    #[serde(default)]
+    pub allowed_headers: Vec<String>,
+    /// Allow cookies and `Authorization` headers in cross-origin requests
+    #[serde(default)]
+    pub allow_credentials: bool,
+    /// Seconds browsers may cache preflight responses; 0 omits the header
+    #[serde(default = "default_cors_max_age_secs")]
+    pub max_age_secs: u64,
+}
+
+///
+/// Audit log of API requests.
+///
+/// One JSON object per line for every `POST` to the API, written off the
+/// request path. The file is rotated by size.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AuditLogConfig {
+    /// Write the audit log
+    #[serde(default)]
+    pub enabled: bool,
+    /// Audit log file; empty writes `audit.log` to the log directory
+    #[serde(default)]
+    pub path: String,
+    /// Size in MB at which the file is rotated
+    #[serde(default = "default_audit_log_max_size_mb")]
+    pub max_size_mb: u64,
+    /// Files kept, the current one included; older ones are deleted
+    #[serde(default = "default_audit_log_max_files")]
+    pub max_files: u32,
+    /// Record response bodies (streamed responses as their SSE text)
+    #[serde(default)]
+    pub include_response_body: bool,
+    /// Replace message content with `[REDACTED]`
+    #[serde(default)]
+    pub redact_user_content: bool,
+}
+
+///
+/// Deep health check of `/health`.
+///
+/// The background check needs a build with the `deep-health` feature.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HealthConfig {
+    /// Periodically send a one-token request to Vertex AI and report the result
+    #[serde(default)]
+    pub deep_check: bool,
+    /// Seconds between deep checks
+    #[serde(default = "default_deep_check_interval_secs")]
+    pub deep_check_interval_secs: u64,
+}
+
+///
+/// System prompt enforced by the operator.
+///
+/// The template may use `{user_system}` (the client's system prompt, with
+/// `prepend` and `append`), `{model}` (the requested model) and `{timestamp}`
+/// (the current UTC time, RFC 3339).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct PromptConfig {
+    /// System prompt template added to every chat completion
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// How the template is combined with the client's system prompt
+    #[serde(default)]
+    pub system_prompt_mode: SystemPromptMode,
+}
+
+///
+/// Telemetry configuration.
+///
+/// Trace export needs a build with the `telemetry` feature.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TelemetryConfig {
+    /// Serve `/metrics` as OpenMetrics with trace ID exemplars on latency buckets
+    #[serde(default)]
+    pub prometheus_exemplars: bool,
+    /// Export tracing spans to an OpenTelemetry collector
+    #[serde(default)]
+    pub enabled: bool,
+    /// Span exporter
+    #[serde(default)]
+    pub exporter: TelemetryExporter,
+    /// Collector endpoint receiving the spans
+    #[serde(default = "default_telemetry_endpoint")]
+    pub endpoint: String,
+    /// `service.name` resource attribute of the exported spans
+    #[serde(default = "default_telemetry_service_name")]
+    pub service_name: String,
+}
+
+///
+/// Span exporter of the OpenTelemetry integration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TelemetryExporter {
+    /// OTLP over HTTP with protobuf payloads
+    #[default]
+    Otlp,
 }
 
 ///
 /// Streaming mode configuration.
 ///
 /// Controls how the proxy handles streaming responses for different clients.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum StreamingMode {
     /// Auto-detect client and choose appropriate streaming mode
@@ -195,15 +1005,42 @@ pub enum StreamingMode {
     Standard,
     /// Use buffered streaming for better client compatibility
     Buffered,
-    /// Always stream, regardless of client
+    /// Always stream from the upstream; `stream: false` clients get the
+    /// stream collected into one JSON response
     Always,
 }
 
+///
+/// How a fan-out request combines the streams of its models.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FanOutStrategy {
+    /// Forward the first stream to produce a content delta and drop the others
+    #[default]
+    ReturnFastest,
+    /// Interleave all streams, tagging each event with its model (evaluation mode)
+    ReturnAll,
+}
+
+///
+/// How the configured system prompt is combined with the client's.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SystemPromptMode {
+    /// Configured prompt first, then the client's
+    #[default]
+    Prepend,
+    /// Client's prompt first, then the configured one
+    Append,
+    /// Configured prompt only; the client's is dropped
+    Replace,
+}
+
 ///
 /// Logging level enumeration.
 ///
 /// Defines available log levels compatible with tracing crate.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum LogLevel {
     #[serde(alias = "trace")]
@@ -218,6 +1055,22 @@ pub enum LogLevel {
     Error,
 }
 
+///
+/// Log output format.
+///
+/// Selects the `tracing-subscriber` formatter used for stdout and log files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable multi-field lines (default)
+    #[serde(alias = "text")]
+    Pretty,
+    /// One JSON object per line for log aggregation; secrets are redacted
+    Json,
+    /// Abbreviated single-line output
+    Compact,
+}
+
 ///
 /// Google Cloud service account key structure.
 ///
@@ -233,6 +1086,8 @@ pub struct ServiceAccountKey {
     /// Unique identifier for the private key
     pub private_key_id: String,
     /// PEM-encoded private key for signing
+    /// May be left out when it comes from `auth.service_account_key_file`
+    #[serde(default)]
     pub private_key: String,
     /// Service account email address
     pub client_email: String,
@@ -251,6 +1106,14 @@ pub struct ServiceAccountKey {
     pub universe_domain: Option<String>,
 }
 
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** Replacement of secrets in redacted configuration output */
+pub const REDACTED: &str = "<redacted>";
+
+/** Vertex AI text embedding model used when `vertex.embedding_model` is unset */
+const DEFAULT_EMBEDDING_MODEL: &str = "text-embedding-004";
+
 /* --- defaults -------------------------------------------------------------------------------- */
 
 /// Default HTTP port
@@ -263,6 +1126,11 @@ fn default_log_level() -> LogLevel {
     LogLevel::Info
 }
 
+/// Default log format
+fn default_log_format() -> LogFormat {
+    LogFormat::Pretty
+}
+
 /// Default retry behavior
 fn default_enable_retries() -> bool {
     true
@@ -273,6 +1141,31 @@ fn default_max_retry_attempts() -> u32 {
     3
 }
 
+/// Default circuit breaker activation
+fn default_circuit_breaker_enabled() -> bool {
+    true
+}
+
+/// Default failure rate that opens the circuit
+fn default_circuit_breaker_threshold() -> f64 {
+    0.5
+}
+
+/// Default circuit breaker window in seconds
+fn default_circuit_breaker_window_secs() -> u64 {
+    60
+}
+
+/// Default circuit breaker cooldown in seconds
+fn default_circuit_breaker_cooldown_secs() -> u64 {
+    30
+}
+
+/// Default minimum requests before the circuit can open
+fn default_circuit_breaker_min_requests() -> u32 {
+    10
+}
+
 /// Default authentication strategy
 pub fn default_auth_strategy() -> AuthStrategy {
     // Use GcpOAuth2 with a placeholder key that will be replaced during loading
@@ -293,23 +1186,223 @@ pub fn default_auth_strategy() -> AuthStrategy {
     AuthStrategy::GcpOAuth2(Box::new(placeholder_key))
 }
 
-/// Default streaming mode
-fn default_streaming_mode() -> StreamingMode {
-    StreamingMode::Auto
+/// Default streaming mode
+fn default_streaming_mode() -> StreamingMode {
+    StreamingMode::Auto
+}
+
+/// Default streaming buffer size (64KB)
+fn default_buffer_size() -> usize {
+    65536
+}
+
+/// Default chunk timeout (5 seconds)
+fn default_chunk_timeout() -> u64 {
+    5000
+}
+
+/// Default backend silence before re-sending the SSE retry hint (in seconds)
+fn default_keepalive_interval() -> u64 {
+    30
+}
+
+/// Default interval of SSE keep-alive comments (15 seconds)
+fn default_heartbeat_interval_ms() -> u64 {
+    15_000
+}
+
+/// Default number of SSE events buffered per client
+fn default_sse_buffer_size() -> usize {
+    100
+}
+
+/// Default number of chunks dropped in a row before the upstream request is aborted
+fn default_max_dropped_chunks_before_abort() -> usize {
+    10
+}
+
+/// Default for aborting the upstream request when the client disconnects (enabled)
+fn default_cancel_on_disconnect() -> bool {
+    true
+}
+
+/// Default time in milliseconds in which identical requests are coalesced
+fn default_dedup_window_ms() -> u64 {
+    5000
+}
+
+/// Default timeout of a streaming upstream request (5 minutes, as `read_timeout_secs`)
+fn default_stream_request_timeout_secs() -> u64 {
+    300
+}
+
+/// Default wait for the first chunk of a streaming response (1 minute)
+fn default_first_token_timeout_secs() -> u64 {
+    60
+}
+
+/// Default semantic cache similarity threshold
+fn default_semantic_threshold() -> f64 {
+    0.97
+}
+
+/// Default semantic cache capacity
+fn default_semantic_max_entries() -> usize {
+    200
+}
+
+/// Default semantic cache entry lifetime (1 hour)
+fn default_semantic_ttl_secs() -> u64 {
+    3600
+}
+
+/// Default response cache capacity
+fn default_response_max_entries() -> usize {
+    1000
+}
+
+/// Default response cache entry lifetime (1 hour)
+fn default_response_ttl_secs() -> u64 {
+    3600
+}
+
+/// Default adaptive timeout behavior
+fn default_adaptive_timeout() -> bool {
+    true
+}
+
+/// Default fixed part of the adaptive timeout (30 seconds)
+fn default_base_timeout_ms() -> u64 {
+    30_000
+}
+
+/// Default time allowed per requested token (50ms)
+fn default_ms_per_token() -> f64 {
+    50.0
+}
+
+/// Default upper bound for the adaptive timeout (5 minutes)
+fn default_max_timeout_ms() -> u64 {
+    300_000
+}
+
+/// Default upstream connection timeout (10 seconds)
+fn default_connect_timeout_secs() -> u64 {
+    10
+}
+
+/// Default upstream request timeout (5 minutes)
+fn default_read_timeout_secs() -> u64 {
+    300
+}
+
+/// Default service name looked up in the service catalog
+fn default_discovery_service_name() -> String {
+    "vertex-proxy".to_string()
+}
+
+/// Default service discovery refresh interval (in seconds)
+fn default_discovery_refresh_interval() -> u64 {
+    60
+}
+
+/// Default scheme of discovered endpoints
+fn default_discovery_endpoint_scheme() -> String {
+    "https".to_string()
+}
+
+/// Default Consul agent URL
+pub fn default_consul_url() -> String {
+    "http://localhost:8500".to_string()
+}
+
+/// Default A/B variant weight (even split)
+fn default_ab_weight() -> u32 {
+    50
+}
+
+fn default_region_weight() -> u32 {
+    1
+}
+
+fn default_queue_max_concurrent() -> usize {
+    16
+}
+
+fn default_queue_max_wait_ms() -> u64 {
+    30_000
+}
+
+fn default_queue_capacity() -> usize {
+    1024
+}
+
+fn default_upload_max_file_bytes() -> usize {
+    5 * 1024 * 1024
+}
+
+fn default_limits_max_request_body_bytes() -> usize {
+    10 * 1024 * 1024
+}
+
+fn default_limits_max_messages() -> usize {
+    500
+}
+
+fn default_limits_max_n() -> u32 {
+    8
+}
+
+fn default_limits_max_batch_bytes() -> usize {
+    256 * 1024 * 1024
+}
+
+/// Default preflight cache time of browsers in seconds
+fn default_cors_max_age_secs() -> u64 {
+    3600
+}
+
+/// Default size in MB at which the audit log is rotated
+fn default_audit_log_max_size_mb() -> u64 {
+    100
+}
+
+/// Default number of audit log files kept
+fn default_audit_log_max_files() -> u32 {
+    10
+}
+
+/// Default seconds between deep health checks
+fn default_deep_check_interval_secs() -> u64 {
+    60
+}
+
+/// Default OTLP/HTTP trace endpoint of a local collector
+fn default_telemetry_endpoint() -> String {
+    "http://localhost:4318/v1/traces".to_string()
 }
 
-/// Default streaming buffer size (64KB)
-fn default_buffer_size() -> usize {
-    65536
+/// Default `service.name` of exported spans
+fn default_telemetry_service_name() -> String {
+    "modelmux".to_string()
 }
 
-/// Default chunk timeout (5 seconds)
-fn default_chunk_timeout() -> u64 {
-    5000
+/// Default number of async job workers
+fn default_job_workers() -> usize {
+    4
 }
 
-/* --- implementations --------------------------------------------------------------------- */
+/// Default async job retention (1 hour)
+fn default_job_ttl_secs() -> u64 {
+    3600
+}
 
+/// Default replay window for finished streams (5 minutes)
+fn default_streaming_replay_ttl_secs() -> u64 {
+    300
+}
+
+/* --- implementations --------------------------------------------------------------------- */
 
 impl Default for ServerConfig {
     fn default() -> Self {
@@ -318,26 +1411,243 @@ impl Default for ServerConfig {
             log_level: default_log_level(),
             enable_retries: default_enable_retries(),
             max_retry_attempts: default_max_retry_attempts(),
+            log_format: default_log_format(),
+            circuit_breaker_enabled: default_circuit_breaker_enabled(),
+            circuit_breaker_threshold: default_circuit_breaker_threshold(),
+            circuit_breaker_window_secs: default_circuit_breaker_window_secs(),
+            circuit_breaker_cooldown_secs: default_circuit_breaker_cooldown_secs(),
+            circuit_breaker_min_requests: default_circuit_breaker_min_requests(),
         }
     }
 }
 
+impl AuthConfig {
+    /// Workload Identity auth strategy, when enabled
+    ///
+    /// # Returns
+    /// * `Some(AuthStrategy::WorkloadIdentity)` with `use_workload_identity` set, `None` otherwise
+    pub fn workload_identity_strategy(&self) -> Option<AuthStrategy> {
+        self.use_workload_identity.then(|| AuthStrategy::WorkloadIdentity {
+            audience: self.workload_identity.audience.clone().unwrap_or_default(),
+            token_url: self.workload_identity.token_url.clone(),
+        })
+    }
+
+    /// Application Default Credentials auth strategy, when enabled
+    ///
+    /// # Returns
+    /// * `Some(AuthStrategy::ApplicationDefault)` with `use_application_default_credentials`
+    ///   set, `None` otherwise
+    pub fn application_default_strategy(&self) -> Option<AuthStrategy> {
+        self.use_application_default_credentials
+            .then_some(AuthStrategy::ApplicationDefault { credentials_file: None })
+    }
+}
+
 impl Default for AuthConfig {
     fn default() -> Self {
         Self {
             service_account_file: None,
             service_account_json: None,
+            service_account_key_file: None,
+            secret_manager_resource: None,
+            secret_manager_endpoint: None,
             strategy: default_auth_strategy(),
+            api_keys: Vec::new(),
+            proxy_api_key: None,
+            use_workload_identity: false,
+            workload_identity: WorkloadIdentityConfig::default(),
+            use_application_default_credentials: false,
         }
     }
 }
 
+impl ServiceAccountKey {
+    /// Replace the private key, e.g. with one stored in a separate file
+    ///
+    /// # Arguments
+    /// * `key` - PEM-encoded private key
+    ///
+    /// # Returns
+    /// * The key with `private_key` replaced
+    pub fn with_private_key(self, key: String) -> Self {
+        Self { private_key: key, ..self }
+    }
+}
+
 impl Default for StreamingConfig {
     fn default() -> Self {
         Self {
             mode: default_streaming_mode(),
             buffer_size: default_buffer_size(),
             chunk_timeout_ms: default_chunk_timeout(),
+            sse_retry_ms: None,
+            keepalive_interval_secs: default_keepalive_interval(),
+            heartbeat_interval_ms: default_heartbeat_interval_ms(),
+            sse_buffer_size: default_sse_buffer_size(),
+            max_dropped_chunks_before_abort: default_max_dropped_chunks_before_abort(),
+            cancel_on_disconnect: default_cancel_on_disconnect(),
+            dedup_enabled: false,
+            dedup_window_ms: default_dedup_window_ms(),
+            request_timeout_secs: default_stream_request_timeout_secs(),
+            first_token_timeout_secs: default_first_token_timeout_secs(),
+            fan_out_models: Vec::new(),
+            fan_out_strategy: FanOutStrategy::default(),
+        }
+    }
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            semantic_enabled: false,
+            semantic_threshold: default_semantic_threshold(),
+            semantic_max_entries: default_semantic_max_entries(),
+            semantic_ttl_secs: default_semantic_ttl_secs(),
+            response_enabled: false,
+            response_max_entries: default_response_max_entries(),
+            response_ttl_secs: default_response_ttl_secs(),
+        }
+    }
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            provider: None,
+            service_name: default_discovery_service_name(),
+            refresh_interval_secs: default_discovery_refresh_interval(),
+            endpoint_scheme: default_discovery_endpoint_scheme(),
+        }
+    }
+}
+
+impl Default for JobsConfig {
+    fn default() -> Self {
+        Self {
+            workers: default_job_workers(),
+            ttl_secs: default_job_ttl_secs(),
+            callback_allowed_hosts: Vec::new(),
+        }
+    }
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_concurrent: default_queue_max_concurrent(),
+            max_wait_ms: default_queue_max_wait_ms(),
+            capacity: default_queue_capacity(),
+        }
+    }
+}
+
+impl Default for UploadConfig {
+    fn default() -> Self {
+        Self { max_file_bytes: default_upload_max_file_bytes() }
+    }
+}
+
+impl Default for RequestLimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_request_body_bytes: default_limits_max_request_body_bytes(),
+            max_messages: default_limits_max_messages(),
+            max_n: default_limits_max_n(),
+            n_partial_allowed: false,
+            max_batch_bytes: default_limits_max_batch_bytes(),
+        }
+    }
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            allowed_methods: Vec::new(),
+            allowed_headers: Vec::new(),
+            allow_credentials: false,
+            max_age_secs: default_cors_max_age_secs(),
+        }
+    }
+}
+
+impl Default for AuditLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: String::new(),
+            max_size_mb: default_audit_log_max_size_mb(),
+            max_files: default_audit_log_max_files(),
+            include_response_body: false,
+            redact_user_content: false,
+        }
+    }
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self { deep_check: false, deep_check_interval_secs: default_deep_check_interval_secs() }
+    }
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            prometheus_exemplars: false,
+            enabled: false,
+            exporter: TelemetryExporter::default(),
+            endpoint: default_telemetry_endpoint(),
+            service_name: default_telemetry_service_name(),
+        }
+    }
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self { streaming_replay_ttl_secs: default_streaming_replay_ttl_secs() }
+    }
+}
+
+impl VertexConfig {
+    /// Regions requests are routed to.
+    ///
+    /// `regions` when set; otherwise `primary_region` as a single region of the
+    /// configured project; otherwise empty (requests keep their configured URL).
+    pub fn effective_regions(&self) -> Vec<VertexRegionConfig> {
+        if !self.regions.is_empty() {
+            return self.regions.clone();
+        }
+        self.primary_region
+            .iter()
+            .map(|region| VertexRegionConfig {
+                region: region.clone(),
+                project: self.project.clone().unwrap_or_default(),
+                weight: default_region_weight(),
+                model_override: None,
+            })
+            .collect()
+    }
+}
+
+impl Default for AbVariantConfig {
+    fn default() -> Self {
+        Self { model: String::new(), weight: default_ab_weight() }
+    }
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        Self {
+            adaptive_timeout: default_adaptive_timeout(),
+            base_timeout_ms: default_base_timeout_ms(),
+            ms_per_token_streaming: default_ms_per_token(),
+            ms_per_token_nonstreaming: default_ms_per_token(),
+            max_timeout_ms: default_max_timeout_ms(),
+            connect_timeout_secs: default_connect_timeout_secs(),
+            read_timeout_secs: default_read_timeout_secs(),
+            streaming_deserialise: false,
         }
     }
 }
@@ -352,6 +1662,9 @@ impl Config {
     ///    `%PROGRAMDATA%/modelmux/config.toml` on Windows)
     /// 5. Built-in defaults (lowest priority)
     ///
+    /// When `MODELMUX_CONFIG_FILE` (set by the `--config <path>` flag) names a
+    /// file, it is loaded instead of the user and system config files.
+    ///
     /// # Returns
     /// * `Ok(Config)` - Successfully loaded configuration
     /// * `Err(ProxyError)` - Configuration loading or validation failed
@@ -377,26 +1690,94 @@ impl Config {
             );
         }
 
-        // First load using the new system for most settings
-        let mut base_config = loader::ConfigLoader::new()
-            .with_defaults()
-            .with_system_config()?
-            .with_user_config()?
-            .with_env_vars()?
-            .build_base()?;
+        // First load using the new system for most settings. A custom config
+        // file (MODELMUX_CONFIG_FILE or --config) replaces the user and system ones
+        let config_loader = loader::ConfigLoader::new().with_defaults();
+        let config_loader = match loader::custom_config_file()? {
+            Some(path) => config_loader.with_config_file(path)?,
+            None => config_loader.with_system_config()?.with_user_config()?,
+        };
+        let mut base_config = config_loader.with_dotenv()?.with_env_vars()?.build_base()?;
+
+        // OpenAI-compatible upstreams authenticate with an API key, not a service account
+        if LlmProviderConfig::selects_openai_compatible(base_config.openai_compatible.as_ref()) {
+            base_config.llm_provider = Some(LlmProviderConfig::OpenAiCompatible(
+                OpenAiCompatibleProvider::from_config_or_env(
+                    base_config.openai_compatible.as_ref(),
+                )?,
+            ));
+            return Ok(base_config);
+        }
+
+        // Azure OpenAI deployments authenticate with an API key as well
+        if LlmProviderConfig::selects_azure_openai(base_config.azure_openai.as_ref()) {
+            base_config.llm_provider = Some(LlmProviderConfig::AzureOpenAi(
+                AzureOpenAiProvider::from_config_or_env(base_config.azure_openai.as_ref())?,
+            ));
+            return Ok(base_config);
+        }
+
+        // Bedrock requests are signed with AWS credentials from the environment
+        if LlmProviderConfig::selects_bedrock() {
+            base_config.llm_provider =
+                Some(LlmProviderConfig::Bedrock(BedrockProvider::from_env()?));
+            return Ok(base_config);
+        }
+
+        // A local Ollama server needs no credentials at all
+        if LlmProviderConfig::selects_ollama() {
+            base_config.llm_provider = Some(LlmProviderConfig::Ollama(OllamaProvider::from_env()?));
+            return Ok(base_config);
+        }
 
-        // Load service account key from auth config to avoid circular dependency
-        let service_account_key = Self::load_service_account_key_from_auth(&base_config.auth)?;
+        // Workload Identity and Application Default Credentials need no key; otherwise
+        // load the service account key from auth config to avoid circular dependency
+        let keyless_strategy = base_config
+            .auth
+            .workload_identity_strategy()
+            .or_else(|| base_config.auth.application_default_strategy());
+        let auth_strategy = match keyless_strategy {
+            Some(strategy) => strategy,
+            None => AuthStrategy::GcpOAuth2(Box::new(Self::load_service_account_key_from_auth(
+                &base_config.auth,
+            )?)),
+        };
+        base_config.auth.strategy = auth_strategy.clone();
+
+        if let Some(failover) = base_config.failover_vertex.as_ref() {
+            base_config.failover_provider =
+                Some(LlmProviderConfig::Vertex(VertexProvider::from_failover_config(
+                    auth_strategy.clone(),
+                    failover,
+                    base_config.vertex.as_ref(),
+                )?));
+        }
+        base_config.route_providers = Self::build_model_routes(&base_config, &auth_strategy)?;
 
         // Then load provider config (from vertex config, env vars, or .env)
-        base_config.llm_provider = Some(LlmProviderConfig::from_config_or_env_with_key(
-            service_account_key,
+        base_config.llm_provider = Some(LlmProviderConfig::from_config_or_env_with_auth(
+            auth_strategy,
             base_config.vertex.as_ref(),
         )?);
 
         Ok(base_config)
     }
 
+    ///
+    /// Load configuration (legacy entry point).
+    ///
+    /// Older releases read configuration from environment variables only via
+    /// `Config::from_env()`. Environment variables are now one layer of
+    /// [`Config::load`], so this simply delegates to it.
+    ///
+    /// # Returns
+    /// * Same as [`Config::load`]
+    #[deprecated(since = "1.3.5", note = "use Config::load() instead")]
+    #[allow(dead_code)]
+    pub fn from_env() -> Result<Self> {
+        Self::load()
+    }
+
     /// Get the build URL for API requests
     pub fn build_predict_url(&self, is_streaming: bool) -> String {
         self.llm_provider
@@ -407,12 +1788,16 @@ impl Config {
 
     /// Build the Vertex AI request URL for a specific model name.
     ///
-    /// Looks up the model in `[vertex.models]` by name (case-insensitive). Falls back to
-    /// the default provider URL if the name is not found or is empty.
+    /// Resolves `[model_aliases]` first, then looks up the model in `[vertex.models]` by
+    /// name (case-insensitive). An alias target that is not a named entry is used as the
+    /// model ID of the default model's publisher. Falls back to the default provider URL
+    /// if the name is not found or is empty.
+    // Nested `if let` blocks are intentional here (see CHANGELOG 1.3.3).
+    #[allow(clippy::collapsible_if)]
     pub fn build_predict_url_for_model(&self, model_name: Option<&str>, is_streaming: bool) -> String {
-        if let Some(name) = model_name {
+        if let Some(name) = model_name.map(|name| self.resolve_model_alias(name)) {
             if !name.is_empty() {
-                if let Some(LlmProviderConfig::Vertex(_)) = self.llm_provider.as_ref() {
+                if let Some(LlmProviderConfig::Vertex(vertex)) = self.llm_provider.as_ref() {
                     if let Some(vertex_cfg) = self.vertex.as_ref() {
                         if let Some(url) = crate::provider::VertexProvider::build_url_for_named_model(
                             name, vertex_cfg, is_streaming,
@@ -420,34 +1805,279 @@ impl Config {
                             return url;
                         }
                     }
+                    if self.is_alias_target(name) {
+                        return vertex.build_url_for_model_id(name, is_streaming);
+                    }
                 }
             }
         }
         self.build_predict_url(is_streaming)
     }
 
+    /// Model an alias from `[model_aliases]` stands for.
+    ///
+    /// Aliases match case-insensitively; names that are not aliases are
+    /// returned unchanged.
+    pub fn resolve_model_alias<'a>(&'a self, model_name: &'a str) -> &'a str {
+        self.model_aliases
+            .get(model_name)
+            .or_else(|| {
+                self.model_aliases
+                    .iter()
+                    .find(|(alias, _)| alias.eq_ignore_ascii_case(model_name))
+                    .map(|(_, target)| target)
+            })
+            .map_or(model_name, |target| target.trim())
+    }
+
+    /// Whether `model_name` is the target of a `[model_aliases]` entry.
+    fn is_alias_target(&self, model_name: &str) -> bool {
+        self.model_aliases.values().any(|target| target.trim() == model_name)
+    }
+
+    /// Context window in tokens of the model serving `model_name`.
+    ///
+    /// Returns `None` for providers other than Vertex AI and for models
+    /// without a known context window.
+    pub fn context_window_for_model(&self, model_name: &str) -> Option<u32> {
+        let Some(LlmProviderConfig::Vertex(_)) = self.llm_provider.as_ref() else {
+            return None;
+        };
+        let url = self.build_predict_url_for_model(Some(model_name), false);
+        crate::provider::VertexProvider::context_window_for_url(&url)
+    }
+
+    /// `[models]` overrides of a requested model.
+    ///
+    /// Model names match exactly first, then case-insensitively.
+    pub fn model_overrides_for(&self, model: Option<&str>) -> Option<&ModelOverrides> {
+        let model = model?.trim();
+        self.models.get(model).or_else(|| {
+            self.models
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(model))
+                .map(|(_, overrides)| overrides)
+        })
+    }
+
+    /// Output token limit of the model serving a request.
+    ///
+    /// Uses the `[vertex.models]` entry named `model_name` when there is one,
+    /// otherwise the default Vertex AI model. Returns `None` for other
+    /// providers and for models without a known limit.
+    pub fn max_output_tokens_for_model(&self, model_name: Option<&str>) -> Option<u32> {
+        let Some(LlmProviderConfig::Vertex(vertex)) = self.llm_provider.as_ref() else {
+            return None;
+        };
+        let model_name = model_name.map(|name| self.resolve_model_alias(name));
+        let entry = model_name.filter(|name| !name.is_empty()).and_then(|name| {
+            self.vertex.as_ref()?.models.iter().find(|e| e.name.eq_ignore_ascii_case(name))
+        });
+        match entry {
+            Some(entry) => match entry.url.as_deref() {
+                Some(url) => crate::provider::VertexProvider::max_output_tokens_for_url(url),
+                None => {
+                    crate::provider::VertexProvider::max_output_tokens_for_model(entry.model.trim())
+                }
+            },
+            None => match model_name.filter(|name| self.is_alias_target(name)) {
+                Some(model_id) => {
+                    crate::provider::VertexProvider::max_output_tokens_for_model(model_id)
+                }
+                None => vertex.max_output_tokens,
+            },
+        }
+    }
+
+    /// Largest prompt in tokens sent to the Vertex AI model.
+    ///
+    /// `vertex.max_context_tokens` when set, otherwise the context window of
+    /// the default Vertex AI model. Returns `None` for other providers and for
+    /// models without a known context window.
+    pub fn max_context_tokens(&self) -> Option<u32> {
+        let Some(LlmProviderConfig::Vertex(vertex)) = self.llm_provider.as_ref() else {
+            return None;
+        };
+        self.vertex.as_ref().and_then(|config| config.max_context_tokens).or_else(|| {
+            crate::provider::VertexProvider::context_window_for_url(&vertex.predict_resource_url)
+        })
+    }
+
+    /// Vertex AI text embedding model served at `/v1/embeddings`.
+    pub fn embedding_model(&self) -> &str {
+        self.vertex
+            .as_ref()
+            .and_then(|vertex| vertex.embedding_model.as_deref())
+            .unwrap_or(DEFAULT_EMBEDDING_MODEL)
+    }
+
+    /// Build the Vertex AI `predict` URL of the embedding model.
+    ///
+    /// Returns `None` unless the LLM provider is Vertex AI.
+    pub fn build_embedding_url(&self) -> Option<String> {
+        match self.llm_provider.as_ref() {
+            Some(LlmProviderConfig::Vertex(vertex)) => {
+                Some(vertex.build_embedding_url(self.embedding_model()))
+            }
+            _ => None,
+        }
+    }
+
     /// Return the OpenAI-facing model names for all configured models.
     ///
-    /// Always contains at least the default model. Named entries from `[[vertex.models]]`
-    /// are appended in declaration order.
+    /// Always contains at least the default model, followed by its Vertex AI
+    /// model ID when that differs. Named entries from `[[vertex.models]]` are
+    /// appended in declaration order, then each `[model_aliases]` entry
+    /// (sorted) together with the model it stands for, then the
+    /// `[model_routes]` names (sorted).
     pub fn list_model_names(&self) -> Vec<String> {
         let default = self.llm_model().to_string();
         let mut names = vec![default];
+        let mut push = |name: &str| {
+            if !name.is_empty() && !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        };
+        if let Some(LlmProviderConfig::Vertex(vertex)) = self.llm_provider.as_ref()
+            && let Some(model_id) = vertex.model_id()
+        {
+            push(model_id);
+        }
         if let Some(vertex_cfg) = self.vertex.as_ref() {
             for entry in &vertex_cfg.models {
-                if !names.contains(&entry.name) {
-                    names.push(entry.name.clone());
-                }
+                push(&entry.name);
             }
         }
+        let mut aliases: Vec<_> = self.model_aliases.iter().collect();
+        aliases.sort();
+        for (alias, target) in aliases {
+            push(alias);
+            push(target.trim());
+        }
+        let mut routes: Vec<_> = self.route_providers.keys().collect();
+        routes.sort();
+        for route in routes {
+            push(route);
+        }
         names
     }
 
+    /// Configured spelling of a model name, if the configuration knows it
+    ///
+    /// Known names are those of [`list_model_names`](Self::list_model_names),
+    /// the display names of `[model_routes]` and the `[models]` overrides;
+    /// they match case-insensitively.
+    ///
+    /// # Returns
+    /// * Configured name, or `None` for a name the configuration does not mention
+    pub fn known_model_name(&self, model_name: &str) -> Option<&str> {
+        let name = model_name.trim();
+        let vertex_model_id = match self.llm_provider.as_ref() {
+            Some(LlmProviderConfig::Vertex(vertex)) => vertex.model_id(),
+            _ => None,
+        };
+        std::iter::once(self.llm_model())
+            .chain(vertex_model_id)
+            .chain(self.vertex.iter().flat_map(|v| v.models.iter().map(|m| m.name.as_str())))
+            .chain(self.model_aliases.iter().flat_map(|(a, t)| [a.as_str(), t.trim()]))
+            .chain(
+                self.route_providers
+                    .iter()
+                    .flat_map(|(name, route)| [name.as_str(), route.display_name.as_str()]),
+            )
+            .chain(self.models.keys().map(String::as_str))
+            .find(|known| !known.is_empty() && known.eq_ignore_ascii_case(name))
+    }
+
+    /// Route of a requested model from `[model_routes]`
+    ///
+    /// Resolves `[model_aliases]` first; route names match case-insensitively.
+    ///
+    /// # Returns
+    /// * Route name and upstream, or `None` when the model is not routed
+    pub fn model_route(&self, model_name: Option<&str>) -> Option<(&str, &ModelRoute)> {
+        let name = self.resolve_model_alias(model_name?.trim());
+        if name.is_empty() {
+            return None;
+        }
+        self.route_providers
+            .get_key_value(name)
+            .or_else(|| {
+                self.route_providers.iter().find(|(route, _)| route.eq_ignore_ascii_case(name))
+            })
+            .map(|(route, upstream)| (route.as_str(), upstream))
+    }
+
+    /// Model name reported in responses to a request for `model_name`
+    ///
+    /// The `display_name` of the model's `[model_routes]` entry, otherwise
+    /// [`llm_model`](Self::llm_model).
+    pub fn response_model(&self, model_name: Option<&str>) -> &str {
+        match self.model_route(model_name) {
+            Some((_, route)) => &route.display_name,
+            None => self.llm_model(),
+        }
+    }
+
     /// Display model name for OpenAI-compatible API responses
     pub fn llm_model(&self) -> &str {
         self.llm_provider.as_ref().map(|p| p.display_model_name()).unwrap_or("unknown")
     }
 
+    /// Build the upstreams of `[model_routes]`
+    ///
+    /// # Arguments
+    /// * `config` - configuration with the routes and the primary `[vertex]` section
+    /// * `auth_strategy` - auth of routes without their own `service_account_file`
+    ///
+    /// # Returns
+    /// * Upstreams by route name
+    /// * `Err(ProxyError)` - A route's URL or service account key cannot be loaded
+    fn build_model_routes(
+        config: &Config,
+        auth_strategy: &AuthStrategy,
+    ) -> Result<HashMap<String, ModelRoute>> {
+        config
+            .model_routes
+            .iter()
+            .map(|(name, route)| {
+                let auth = match &route.service_account_file {
+                    Some(file) => {
+                        let route_auth = AuthConfig {
+                            service_account_file: Some(file.clone()),
+                            ..Default::default()
+                        };
+                        AuthStrategy::GcpOAuth2(Box::new(Self::load_service_account_key_from_auth(
+                            &route_auth,
+                        )?))
+                    }
+                    None => auth_strategy.clone(),
+                };
+                let vertex = VertexConfig {
+                    project: route.project.clone(),
+                    region: route.region.clone(),
+                    location: route.location.clone(),
+                    publisher: route.publisher.clone(),
+                    model: route.model.clone(),
+                    url: route.url.clone(),
+                    ..Default::default()
+                };
+                let provider = VertexProvider::from_override_config(
+                    auth,
+                    &vertex,
+                    config.vertex.as_ref(),
+                    &format!("[model_routes.{}]", name),
+                )?;
+                let display_name = route.display_name.clone().unwrap_or_else(|| name.clone());
+                let route = ModelRoute {
+                    provider_config: LlmProviderConfig::Vertex(provider),
+                    display_name,
+                };
+                Ok((name.clone(), route))
+            })
+            .collect()
+    }
+
     /// Legacy method for loading service account key (for backward compatibility)
     #[allow(dead_code)]
     pub fn load_service_account_key_standalone() -> Result<ServiceAccountKey> {
@@ -458,12 +2088,29 @@ impl Config {
     }
 
     /// Load service account key from provided auth config (to avoid circular dependency)
+    ///
+    /// A Secret Manager resource is tried first; when it cannot be fetched the
+    /// inline JSON or file is used instead, if configured.
     pub fn load_service_account_key_from_auth(auth: &AuthConfig) -> Result<ServiceAccountKey> {
+        if let Some(ref resource) = auth.secret_manager_resource {
+            match crate::auth::secret_manager::load_service_account_key(auth, resource) {
+                Ok(key) => return Ok(key),
+                Err(e)
+                    if auth.service_account_json.is_some()
+                        || auth.service_account_file.is_some() =>
+                {
+                    tracing::warn!("{}; using the configured service account key instead", e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
         if let Some(ref json_str) = auth.service_account_json {
             // Load from inline JSON
             serde_json::from_str(json_str).map_err(|e| {
-                ProxyError::Config(format!(
-                    "Failed to parse inline service account JSON: {}\n\
+                ProxyError::Config(
+                    format!(
+                        "Failed to parse inline service account JSON: {}\n\
                      \n\
                      The JSON appears to be malformed. Please verify:\n\
                      1. All required fields are present\n\
@@ -471,15 +2118,18 @@ impl Config {
                      3. No extra commas or missing quotes\n\
                      \n\
                      Run 'modelmux config validate' for more details.",
-                    e
-                ))
+                        e
+                    ),
+                    None,
+                )
             })
         } else if let Some(ref file_path) = auth.service_account_file {
             // Load from file
             let expanded_path = paths::expand_path(file_path)?;
             let file_contents = std::fs::read_to_string(&expanded_path).map_err(|e| {
-                ProxyError::Config(format!(
-                    "Failed to read service account file '{}': {}\n\
+                ProxyError::Config(
+                    format!(
+                        "Failed to read service account file '{}': {}\n\
                      \n\
                      To fix this:\n\
                      1. Verify the file exists and is readable\n\
@@ -489,16 +2139,19 @@ impl Config {
                      Example:\n\
                        ls -la '{}'\n\
                        chmod 600 '{}'",
-                    expanded_path.display(),
-                    e,
-                    expanded_path.display(),
-                    expanded_path.display()
-                ))
+                        expanded_path.display(),
+                        e,
+                        expanded_path.display(),
+                        expanded_path.display()
+                    ),
+                    None,
+                )
             })?;
 
-            serde_json::from_str(&file_contents).map_err(|e| {
-                ProxyError::Config(format!(
-                    "Failed to parse service account file '{}': {}\n\
+            let key: ServiceAccountKey = serde_json::from_str(&file_contents).map_err(|e| {
+                ProxyError::Config(
+                    format!(
+                        "Failed to parse service account file '{}': {}\n\
                      \n\
                      The file appears to contain invalid JSON. Please verify:\n\
                      1. The file was downloaded correctly from Google Cloud\n\
@@ -506,10 +2159,19 @@ impl Config {
                      3. The file is a valid service account key JSON\n\
                      \n\
                      Run 'modelmux config validate' for more details.",
-                    expanded_path.display(),
-                    e
-                ))
-            })
+                        expanded_path.display(),
+                        e
+                    ),
+                    None,
+                )
+            })?;
+
+            match auth.service_account_key_file {
+                Some(ref key_file) => {
+                    Ok(key.with_private_key(Self::read_private_key_file(key_file)?))
+                }
+                None => Ok(key),
+            }
         } else {
             Err(ProxyError::Config(
                 "No service account configuration found.\n\
@@ -520,10 +2182,38 @@ impl Config {
                  \n\
                  Run 'modelmux config init' for interactive setup."
                     .to_string(),
+                None,
             ))
         }
     }
 
+    /// Read the PEM private key of `auth.service_account_key_file`
+    ///
+    /// # Arguments
+    /// * `file_path` - Path of the key file, with tilde expansion
+    ///
+    /// # Returns
+    /// * `Ok(String)` - PEM-encoded private key
+    /// * `Err(ProxyError)` - The file cannot be read
+    fn read_private_key_file(file_path: &str) -> Result<String> {
+        let expanded_path = paths::expand_path(file_path)?;
+        std::fs::read_to_string(&expanded_path).map_err(|e| {
+            ProxyError::Config(
+                format!(
+                    "Failed to read service account key file '{}': {}\n\
+                     \n\
+                     To fix this:\n\
+                     1. Verify the file exists and is readable\n\
+                     2. Check file permissions (should be 600 or similar)\n\
+                     3. Ensure the path is correct",
+                    expanded_path.display(),
+                    e
+                ),
+                None,
+            )
+        })
+    }
+
     /// Validate the current configuration
     ///
     /// Performs comprehensive validation of all configuration values,
@@ -531,17 +2221,48 @@ impl Config {
     /// network settings.
     ///
     /// # Returns
-    /// * `Ok(())` - Configuration is valid
+    /// * `Ok(issues)` - Configuration is valid; `issues` holds the warnings, if any
     /// * `Err(ProxyError)` - Configuration validation failed with details
-    pub fn validate(&self) -> Result<()> {
+    pub fn validate(&self) -> Result<Vec<ValidationIssue>> {
         validation::ConfigValidator::new(self).validate()
     }
 
+    /// Replace secrets with `<redacted>`
+    ///
+    /// Covers inline service account JSON, client API key hashes, the proxy
+    /// API key and the admin secret. The service account file path is kept.
+    pub fn redact_sensitive(&mut self) {
+        if self.auth.service_account_json.is_some() {
+            self.auth.service_account_json = Some(REDACTED.to_string());
+        }
+        for entry in &mut self.auth.api_keys {
+            entry.key_hash = REDACTED.to_string();
+        }
+        if self.auth.proxy_api_key.is_some() {
+            self.auth.proxy_api_key = Some(REDACTED.to_string());
+        }
+        if self.admin.secret.is_some() {
+            self.admin.secret = Some(REDACTED.to_string());
+        }
+    }
+
+    /// Validate the current configuration and collect every issue
+    ///
+    /// Runs the same rules as `validate` but returns warnings as well as
+    /// errors, each with the offending field and a stable code.
+    ///
+    /// # Returns
+    /// * All validation issues; empty when the configuration is clean
+    pub fn validate_with_issues(&self) -> Vec<validation::ValidationIssue> {
+        validation::ConfigValidator::new(self).validate_with_issues()
+    }
+
     /// Load service account key from the configured source
     ///
     /// Loads the Google Cloud service account key from either:
-    /// 1. Inline JSON (auth.service_account_json)
-    /// 2. File path (auth.service_account_file)
+    /// 1. GCP Secret Manager (auth.secret_manager_resource)
+    /// 2. Inline JSON (auth.service_account_json)
+    /// 3. File path (auth.service_account_file)
     ///
     /// # Returns
     /// * `Ok(ServiceAccountKey)` - Successfully loaded and parsed key
@@ -568,12 +2289,28 @@ port = 3000
 # Logging level: trace, debug, info, warn, error (default: info)
 log_level = "info"
 
+# Log format: pretty, json, compact (default: pretty). json writes one object
+# per line with timestamp, level, target, message and fields, and redacts
+# access tokens, API keys and service account credentials.
+log_format = "pretty"
+
 # Enable automatic retries for quota/rate limit errors (default: true)
 enable_retries = true
 
 # Maximum number of retry attempts (default: 3)
 max_retry_attempts = 3
 
+# Circuit breaker: when at least circuit_breaker_min_requests Vertex AI requests
+# were made in the last circuit_breaker_window_secs and the share of them that
+# failed (5xx, connection errors, timeouts) reaches circuit_breaker_threshold,
+# requests fail fast with 503 for circuit_breaker_cooldown_secs. Then one probe
+# request decides whether the circuit closes again.
+circuit_breaker_enabled = true
+circuit_breaker_threshold = 0.5
+circuit_breaker_window_secs = 60
+circuit_breaker_cooldown_secs = 30
+circuit_breaker_min_requests = 10
+
 # Logs are written to stdout AND to <user_data_dir>/logs/ with daily
 # rotation, keeping ~30 days (roughly the last month). No knobs here —
 # tracing-appender handles it.
@@ -583,9 +2320,40 @@ max_retry_attempts = 3
 # Supports tilde (~) expansion
 service_account_file = "~/.config/modelmux/service-account.json"
 
+# Keep the private key in a separate PEM file (e.g. on a hardware-secured mount);
+# it replaces the private_key of service_account_file
+# service_account_key_file = "/secure/modelmux/private-key.pem"
+
 # Alternative: Inline service account JSON (for containers)
 # service_account_json = '{"type": "service_account", ...}'
 
+# Alternative: fetch the service account JSON from GCP Secret Manager at startup,
+# using the metadata server's token (GKE, Cloud Run, Compute Engine).
+# Takes precedence; file/JSON above are used if the fetch fails
+# (env: MODELMUX_AUTH_SECRET_MANAGER_RESOURCE)
+# secret_manager_resource = "projects/my-project/secrets/sa-key/versions/latest"
+
+# Client API keys (SHA-256 hashes). Generate with `modelmux keygen --name <name>`.
+# [[auth.api_keys]]
+# name = "my-service"
+# key_hash = "<sha256 hex>"
+# allowed_models = ["claude-sonnet"]   # optional, empty = all models
+
+# Require "Authorization: Bearer <key>" on every /v1/* request; /health and
+# /metrics stay open (env: MODELMUX_AUTH_PROXY_API_KEY)
+# proxy_api_key = "change-me"
+
+# On GKE / Cloud Run: get tokens from the metadata server (Workload Identity)
+# instead of a service account key (env: MODELMUX_AUTH_USE_WORKLOAD_IDENTITY)
+# use_workload_identity = true
+# [auth.workload_identity]
+# audience = "..."    # optional
+# token_url = "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token"
+
+# Locally: use Application Default Credentials from GOOGLE_APPLICATION_CREDENTIALS or
+# `gcloud auth application-default login` instead of a key (env: MODELMUX_AUTH_USE_ADC)
+# use_application_default_credentials = true
+
 [streaming]
 # Streaming mode: auto, never, standard, buffered, always (default: auto)
 # - auto: detect client and choose appropriate mode
@@ -601,6 +2369,316 @@ buffer_size = 65536
 # Timeout for streaming chunks in milliseconds (default: 5000)
 chunk_timeout_ms = 5000
 
+# Reconnection interval suggested to SSE clients with a `retry:` frame at the
+# start of each stream (default: unset = no hint). Also sent in the
+# X-SSE-Retry-Ms response header.
+# sse_retry_ms = 10000
+
+# Re-send the retry hint after this many seconds without backend events (default: 30)
+keepalive_interval_secs = 30
+
+# Send a `: keep-alive` SSE comment this often during a stream, so load balancers
+# (nginx, AWS ALB) do not close connections that go quiet during long responses.
+# Clients ignore comment lines (default: 15000, 0 = off)
+heartbeat_interval_ms = 15000
+
+# Events buffered for each SSE client (default: 100). When the buffer is full,
+# a chunk waits up to chunk_timeout_ms for the client and is dropped after that.
+sse_buffer_size = 100
+
+# Abort the upstream request once this many chunks were dropped in a row
+# because the client is too slow (default: 10, 0 = never abort)
+max_dropped_chunks_before_abort = 10
+
+# Abort the upstream request as soon as a streaming client disconnects, instead
+# of reading (and paying for) the rest of the response (default: true)
+cancel_on_disconnect = true
+
+# Send identical non-streaming requests arriving while the first one is still
+# running upstream only once; the others get a copy of its response. Requests
+# join for dedup_window_ms after the first one started (default: false, 5000)
+dedup_enabled = false
+dedup_window_ms = 5000
+
+# Give up on a streaming upstream request after request_timeout_secs in total, or
+# when its first chunk takes longer than first_token_timeout_secs; the client gets
+# 504 Gateway Timeout when nothing was streamed yet (default: 300, 60)
+request_timeout_secs = 300
+first_token_timeout_secs = 60
+
+# Send a streaming request for any of these models to all of them in parallel
+# (model routes, [[vertex.models]] names or the default model). With
+# fan_out_strategy = "return_fastest" the first stream to produce content is
+# forwarded and the others are dropped; "return_all" interleaves every stream as
+# {"provider": "<model>", "delta": {...}} events for evaluation
+# (default: no fan-out, "return_fastest")
+# fan_out_models = ["claude-sonnet", "claude-sonnet-eu"]
+# fan_out_strategy = "return_fastest"
+
+[cache]
+# Reuse responses for semantically similar prompts (default: false).
+# Only deterministic requests (temperature = 0, no tools, non-streaming)
+# are cached. A response is only reused for the same model, API key, system
+# prompt and earlier messages. Requires a build with the `semantic-cache` feature.
+semantic_enabled = false
+
+# Minimum cosine similarity to treat two prompts as equivalent (default: 0.97)
+semantic_threshold = 0.97
+
+# Maximum number of cached responses (default: 200)
+semantic_max_entries = 200
+
+# Seconds a semantically cached response is reused (default: 3600)
+semantic_ttl_secs = 3600
+
+# Reuse upstream responses for identical requests (default: false).
+# Only deterministic (temperature = 0), non-streaming requests are cached. The
+# raw Anthropic response is stored and converted again on every hit.
+response_enabled = false
+
+# Maximum number of cached upstream responses; the least recently used one is
+# evicted first (default: 1000)
+response_max_entries = 1000
+
+# Seconds a cached upstream response is reused (default: 3600)
+response_ttl_secs = 3600
+
+[http_client]
+# Scale the upstream request timeout with the requested max_tokens (default: true).
+# timeout = base_timeout_ms + max_tokens * ms_per_token_*, capped at max_timeout_ms
+adaptive_timeout = true
+
+# Fixed part of the timeout in milliseconds (default: 30000)
+base_timeout_ms = 30000
+
+# Extra milliseconds allowed per requested token (default: 50)
+ms_per_token_streaming = 50.0
+ms_per_token_nonstreaming = 50.0
+
+# Upper bound in milliseconds (default: 300000)
+max_timeout_ms = 300000
+
+# Seconds allowed for connecting to the upstream, so unreachable endpoints
+# fail fast (default: 10)
+connect_timeout_secs = 10
+
+# Timeout in seconds for a whole upstream request when adaptive_timeout is
+# disabled (default: 300)
+read_timeout_secs = 300
+
+# Experimental: parse non-streaming upstream responses while they are received
+# instead of buffering the whole body first (default: false)
+streaming_deserialise = false
+
+[admin]
+# Shared secret for admin endpoints such as GET /v1/usage, sent in the
+# X-Admin-Secret header. Admin endpoints are disabled when unset.
+# secret = "change-me"
+
+# Serve the running configuration (secrets redacted) at GET /admin/config and
+# the active config sources at GET /admin/config/sources (default: false)
+# enable_config_endpoint = false
+
+# Optional cost table used to estimate spend in GET /v1/usage.
+# [usage.pricing."claude-3-5-sonnet@20241022"]
+# input_per_million_usd = 3.0
+# output_per_million_usd = 15.0
+
+[discovery]
+# Resolve upstream endpoints from a service catalog (default: static endpoint
+# from the provider config). Requests are spread round-robin across healthy
+# instances; removed instances finish in-flight requests before being dropped.
+# provider = { type = "consul", url = "http://localhost:8500" }
+
+# Service name registered in the catalog (default: "vertex-proxy")
+service_name = "vertex-proxy"
+
+# How often to refresh the endpoint list in seconds (default: 60)
+refresh_interval_secs = 60
+
+# Scheme used to reach the discovered instances; requests carry the upstream
+# credentials, so only use http inside a trusted network (default: "https")
+endpoint_scheme = "https"
+
+[converter]
+# Extra top-level fields merged into every Anthropic request, e.g. beta flags
+# or an anthropic_version override. max_tokens and messages cannot be set here.
+# extra_anthropic_fields = { anthropic_version = "vertex-2023-10-16" }
+
+# Let clients add fields with an `x-extra-anthropic: {"key": "value"}` header
+# (merged over the fields above; default: false)
+allow_client_extra_fields = false
+
+# Forward Claude's extended thinking as a `reasoning_content` field on the
+# response message and stream deltas; dropped otherwise (default: false)
+include_reasoning_in_response = false
+
+[converter.response_normalization]
+# Static system_fingerprint added to every complete response
+# set_system_fingerprint = "fp_modelmux"
+
+# Replace the object field of complete responses (default: "chat.completion")
+# force_object_type = "chat.completion"
+
+# Add an empty assistant choice when a response has no choices (default: false)
+ensure_choices_not_empty = false
+
+# Convert millisecond created timestamps to seconds (default: false)
+round_created_to_seconds = false
+
+[ab_test]
+# Split chat traffic between two models (default: false). The chosen variant
+# is returned in the X-Variant response header; requests carrying the same
+# X-Session-ID header always get the same variant. Per-variant statistics are
+# available at GET /admin/ab-test/stats (requires the admin secret).
+enabled = false
+# variant_a = { model = "claude-sonnet", weight = 70 }
+# variant_b = { model = "claude-opus", weight = 30 }
+
+[jobs]
+# Workers processing POST /v1/chat/completions/async requests (default: 4)
+workers = 4
+
+# Seconds a finished job stays available at GET /v1/jobs/{id} (default: 3600)
+ttl_secs = 3600
+
+# Hosts a job's callback_url may point to (default: none, callbacks disabled).
+# Hosts resolving to loopback, link-local or private addresses are always refused.
+# callback_allowed_hosts = ["hooks.example.com"]
+
+[idempotency]
+# Streaming requests with an Idempotency-Key header are recorded (first 60s of
+# the stream). A retry with the same key joins the running stream, or replays
+# it if finished within this many seconds (default: 300).
+streaming_replay_ttl_secs = 300
+
+[debug]
+# Log every SSE event sent to clients (type, first 200 chars, sequence number and
+# ms since the previous event) at DEBUG level under the `modelmux_stream` target,
+# e.g. RUST_LOG=modelmux_stream=debug. Ignored unless log_level is debug or trace.
+# Too verbose for production (default: false).
+log_streaming_events = false
+
+[telemetry]
+# Serve GET /metrics in the OpenMetrics format, attaching the trace ID of the
+# request's W3C traceparent header as an exemplar to latency histogram buckets.
+# Requires a scraper that accepts OpenMetrics (default: false).
+prometheus_exemplars = false
+# Export request spans (modelmux.chat_completion with its auth and upstream
+# request children) to an OpenTelemetry collector. Requires a build with the
+# `telemetry` feature (default: false).
+enabled = false
+# Span exporter; only otlp (OTLP over HTTP) is supported (default: otlp)
+exporter = "otlp"
+# Collector endpoint receiving the spans (default: http://localhost:4318/v1/traces)
+endpoint = "http://localhost:4318/v1/traces"
+# service.name attribute of the exported spans (default: modelmux)
+service_name = "modelmux"
+
+[queue]
+# Queue chat completions by the X-Priority header (high, normal, low) so agent
+# fan-out cannot starve interactive users. high requires the X-Admin-Secret
+# header; other requests asking for it are queued as normal (default: false).
+enabled = false
+# Chat completions running at once; streams count until streaming starts (default: 16)
+max_concurrent = 16
+# Requests still queued after this many ms get 503 with Retry-After (default: 30000)
+max_wait_ms = 30000
+# Requests waiting per priority before new ones are rejected (default: 1024)
+capacity = 1024
+
+[upload]
+# Largest file accepted by POST /v1/files/upload-and-chat in bytes; files are
+# kept in memory only (default: 5242880, 5 MiB)
+max_file_bytes = 5242880
+
+[limits]
+# Largest JSON body of /v1/chat/completions, its async variant and /v1/embeddings
+# in bytes; larger bodies get 413 (default: 10485760, 10 MiB)
+max_request_body_bytes = 10485760
+# Most messages in one chat completion request; more get 400 (default: 500)
+max_messages = 500
+# Most completions (n) in one chat completion request; each is a separate
+# upstream request, and more get 400 (default: 8)
+max_n = 8
+# Return the completions that succeeded when some upstream requests of an
+# n > 1 request fail, instead of the first error (default: false)
+n_partial_allowed = false
+# Largest Anthropic batch output file of POST /v1/batches/convert in bytes; files
+# above max_request_body_bytes are converted as they arrive (default: 268435456, 256 MiB)
+max_batch_bytes = 268435456
+
+[cors]
+# Origins browsers may call the API from; empty or ["*"] allows any origin (default: [])
+allowed_origins = []
+# Methods and request headers allowed cross-origin; empty allows whatever the
+# browser asks for (default: [])
+allowed_methods = []
+allowed_headers = []
+# Allow cookies and Authorization headers cross-origin; needs explicit origins,
+# browsers reject credentials with "*" (default: false)
+allow_credentials = false
+# Seconds browsers may cache preflight responses; 0 omits the header (default: 3600)
+max_age_secs = 3600
+
+[audit_log]
+# Append one JSON line per POST request (timestamp, request ID, model, messages)
+# to an audit file, written in the background (default: false)
+enabled = false
+# Audit file; empty writes audit.log to the log directory (default: "")
+path = ""
+# Rotate the file at this size in MB, keeping max_files files (default: 100, 10)
+max_size_mb = 100
+max_files = 10
+# Also record response bodies; streamed responses as SSE text (default: false)
+include_response_body = false
+# Replace message content with "[REDACTED]" (default: false)
+redact_user_content = false
+
+[health]
+# Send a one-token request to Vertex AI every deep_check_interval_secs and report
+# the result under "vertex_ai" in /health, which returns 503 while Vertex AI is
+# unreachable; needs a build with --features deep-health (default: false, 60)
+deep_check = false
+deep_check_interval_secs = 60
+
+[prompt]
+# System prompt added to every chat completion; {user_system} is the client's
+# system prompt, {model} the requested model and {timestamp} the current UTC
+# time (default: none)
+# system_prompt = "You are a helpful assistant. Do not discuss competitors."
+# prepend, append or replace the client's system prompt (default: prepend)
+system_prompt_mode = "prepend"
+
+# Alternative model names accepted in requests and listed by GET /v1/models,
+# mapped to a Vertex AI model ID or a [[vertex.models]] name (default: none)
+[model_aliases]
+# claude-3-sonnet = "claude-3-5-sonnet@20241022"
+
+# Model names sent to their own Vertex AI endpoint, e.g. in another GCP project with
+# its own quota. Unset fields inherit from [vertex]; service_account_file defaults to
+# the [auth] credentials, display_name (reported in responses) to the route name.
+# [model_routes.claude-fast]
+# project = "team-a-project"
+# model = "claude-3-5-haiku@20241022"
+# [model_routes.claude-smart]
+# project = "team-b-project"
+# region = "us-east5"
+# model = "claude-opus-4@20250514"
+# service_account_file = "~/.config/modelmux/team-b.json"
+
+# Settings for one model name as sent by clients, e.g. a larger output budget for
+# the biggest model. max_tokens_override and temperature_override replace the
+# request's values, streaming_mode replaces streaming.mode and system_prompt_append
+# is added to the end of the system prompt (default: none)
+# [models."claude-haiku"]
+# max_tokens_override = 2000
+# [models."claude-opus"]
+# max_tokens_override = 32000
+# temperature_override = 0.2
+# streaming_mode = "buffered"
+# system_prompt_append = "Think step by step."
+
 # Vertex AI provider (optional - can also use env vars or .env)
 [vertex]
 project = "your-gcp-project"
@@ -610,6 +2688,13 @@ publisher = "anthropic"
 model = "claude-3-5-sonnet@20241022"
 # Or use full URL override instead:
 # url = "https://europe-west1-aiplatform.googleapis.com/v1/projects/MY_PROJECT/locations/europe-west1/publishers/anthropic/models/claude-3-5-sonnet@20241022"
+# Text embedding model served at POST /v1/embeddings, from the same project and
+# location (default: text-embedding-004; env: MODELMUX_EMBEDDING_MODEL)
+# embedding_model = "text-embedding-004"
+# Largest estimated prompt in tokens; longer prompts get 400 context_length_exceeded
+# without reaching Vertex AI (default: the model's context window, 200000 for Claude;
+# env: MODELMUX_VERTEX_MAX_CONTEXT_TOKENS)
+# max_context_tokens = 200000
 
 # Optional: add extra models that clients can request by name.
 # Fields not set here inherit from the [vertex] block above.
@@ -621,6 +2706,47 @@ model = "claude-3-5-sonnet@20241022"
 # name = "claude-sonnet"
 # model = "claude-sonnet-4@20250514"
 
+# Optional: spread requests over several regions (independent quotas), picked
+# at random by weight. A region's model_override replaces the requested model
+# there, e.g. to try a new model version in one region. The region serving a
+# request is returned in the X-Provider-Region header.
+# [[vertex.regions]]
+# region = "europe-west1"
+# project = "your-gcp-project"
+# weight = 3
+#
+# [[vertex.regions]]
+# region = "us-east5"
+# project = "your-other-project"
+# weight = 1
+# model_override = "claude-sonnet-4@20250514"
+#
+# With a single region, primary_region = "us-east5" routes all requests there.
+
+# Optional: when the primary endpoint still fails with 5xx, connection errors or
+# timeouts after its retries, the request gets one more round of retries here.
+# Unset fields are taken from [vertex]. Env: MODELMUX_FAILOVER_URL,
+# MODELMUX_FAILOVER_REGION, _PROJECT, _LOCATION, _PUBLISHER, _MODEL.
+# [failover_vertex]
+# region = "us-east5"
+# location = "us-east5"
+
+# Optional: OpenAI-compatible provider instead of Vertex AI. Requests are
+# forwarded unchanged to <base_url>/v1/chat/completions with OPENAI_API_KEY as
+# Bearer token. Selected when base_url is set, unless LLM_PROVIDER says otherwise.
+# [openai_compatible]
+# base_url = "https://api.openai.com/v1"   # env: OPENAI_API_BASE
+# model = "gpt-4o-mini"                    # used when requests name no model
+
+# Optional: Azure OpenAI Service deployment. Requests are forwarded unchanged to
+# <endpoint>/openai/deployments/<deployment>/chat/completions with
+# AZURE_OPENAI_API_KEY in the api-key header. Selected when endpoint is set,
+# unless LLM_PROVIDER says otherwise.
+# [azure_openai]
+# endpoint = "https://my-resource.openai.azure.com"   # env: AZURE_OPENAI_ENDPOINT
+# deployment = "gpt-4o"                               # env: AZURE_OPENAI_DEPLOYMENT
+# api_version = "2024-10-21"                          # env: AZURE_OPENAI_API_VERSION
+
 # Alternative: use environment variables (including from .env file):
 # LLM_PROVIDER=vertex
 # VERTEX_PROJECT=your-gcp-project
@@ -662,10 +2788,72 @@ impl FromStr for LogLevel {
             "info" => Ok(LogLevel::Info),
             "warn" | "warning" => Ok(LogLevel::Warn),
             "error" => Ok(LogLevel::Error),
-            _ => Err(ProxyError::Config(format!(
-                "Invalid log level '{}'. Valid levels are: trace, debug, info, warn, error",
-                s
-            ))),
+            _ => Err(ProxyError::Config(
+                format!(
+                    "Invalid log level '{}'. Valid levels are: trace, debug, info, warn, error",
+                    s
+                ),
+                None,
+            )),
+        }
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = ProxyError;
+
+    /// Parse from string (case-insensitive).
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pretty" | "text" => Ok(LogFormat::Pretty),
+            "json" => Ok(LogFormat::Json),
+            "compact" => Ok(LogFormat::Compact),
+            _ => Err(ProxyError::Config(
+                format!(
+                    "Invalid log format '{}'. Valid formats are: pretty (text), json, compact",
+                    s
+                ),
+                None,
+            )),
+        }
+    }
+}
+
+impl FromStr for SystemPromptMode {
+    type Err = ProxyError;
+
+    /// Parse from string (case-insensitive).
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "prepend" => Ok(SystemPromptMode::Prepend),
+            "append" => Ok(SystemPromptMode::Append),
+            "replace" => Ok(SystemPromptMode::Replace),
+            _ => Err(ProxyError::Config(
+                format!(
+                    "Invalid system prompt mode '{}'. Valid modes are: prepend, append, replace",
+                    s
+                ),
+                None,
+            )),
+        }
+    }
+}
+
+impl FromStr for FanOutStrategy {
+    type Err = ProxyError;
+
+    /// Parse from string (case-insensitive).
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "return_fastest" | "fastest" => Ok(FanOutStrategy::ReturnFastest),
+            "return_all" | "all" => Ok(FanOutStrategy::ReturnAll),
+            _ => Err(ProxyError::Config(
+                format!(
+                    "Invalid fan-out strategy '{}'. Valid strategies are: return_fastest, return_all",
+                    s
+                ),
+                None,
+            )),
         }
     }
 }
@@ -677,20 +2865,23 @@ impl FromStr for StreamingMode {
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
             "auto" => Ok(StreamingMode::Auto),
+
             "never" | "false" | "no" => Ok(StreamingMode::Never),
             "standard" | "normal" => Ok(StreamingMode::Standard),
             "buffered" | "buffer" => Ok(StreamingMode::Buffered),
             "always" | "true" | "yes" => Ok(StreamingMode::Always),
-            _ => Err(ProxyError::Config(format!(
-                "Invalid streaming mode '{}'. Valid modes are: auto, never, standard, buffered, always",
-                s
-            ))),
+            _ => Err(ProxyError::Config(
+                format!(
+                    "Invalid streaming mode '{}'. Valid modes are: auto, never, standard, buffered, always",
+                    s
+                ),
+                None,
+            )),
         }
     }
 }
 
 impl StreamingMode {
-
     /// Check if this mode supports streaming
     #[allow(dead_code)]
     pub fn is_streaming(&self) -> bool {
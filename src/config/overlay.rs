@@ -0,0 +1,258 @@
+//!
+//! Partial configuration overlays applied at runtime.
+//!
+//! Library users embedding modelmux can keep one base `Config` and derive
+//! variants from it, e.g. per-tenant model selection, without reloading
+//! configuration files:
+//!
+//! ```rust
+//! use modelmux::config::{Config, PartialConfig};
+//!
+//! let base = Config::default();
+//! let overlay = PartialConfig::from_toml("[server]\nport = 4000\n").unwrap();
+//!
+//! let mut tenant = base.clone();
+//! tenant.merge(overlay).unwrap();
+//! assert_eq!(tenant.server.port, 4000);
+//! assert_eq!(tenant.server.log_level, base.server.log_level);
+//! ```
+//!
+//! Each section of a `PartialConfig` holds only the keys to change. Tables
+//! are merged key by key, any other value (including arrays such as
+//! `vertex.models`) replaces the base value as a whole.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::config::Config;
+use crate::error::{ProxyError, Result};
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+/// Keys of one configuration section
+#[allow(dead_code)]
+pub type PartialSection = Map<String, Value>;
+
+///
+/// Configuration overlay with the same sections as `Config`.
+///
+/// Sections left `None` keep the base configuration unchanged. Overlays use
+/// the same keys as config files and can be read from TOML or JSON.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PartialConfig {
+    /// HTTP server overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server: Option<PartialSection>,
+    /// Authentication overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<PartialSection>,
+    /// Streaming behavior overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub streaming: Option<PartialSection>,
+    /// Vertex AI provider overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vertex: Option<PartialSection>,
+    /// Failover Vertex AI endpoint overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failover_vertex: Option<PartialSection>,
+    /// OpenAI-compatible provider overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub openai_compatible: Option<PartialSection>,
+    /// Azure OpenAI provider overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub azure_openai: Option<PartialSection>,
+    /// Response cache overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache: Option<PartialSection>,
+    /// Outgoing HTTP client overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_client: Option<PartialSection>,
+    /// Usage reporting overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<PartialSection>,
+    /// Administrative endpoint overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admin: Option<PartialSection>,
+    /// Service discovery overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discovery: Option<PartialSection>,
+    /// A/B test overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ab_test: Option<PartialSection>,
+    /// Request conversion overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub converter: Option<PartialSection>,
+    /// Async job overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jobs: Option<PartialSection>,
+    /// Idempotency-Key handling overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency: Option<PartialSection>,
+    /// Debugging overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debug: Option<PartialSection>,
+    /// Metrics export overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub telemetry: Option<PartialSection>,
+    /// Request queue overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue: Option<PartialSection>,
+    /// File upload overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upload: Option<PartialSection>,
+    /// Request limit overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<PartialSection>,
+    /// CORS policy overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cors: Option<PartialSection>,
+    /// Audit log overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audit_log: Option<PartialSection>,
+    /// Health check overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<PartialSection>,
+    /// System prompt overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<PartialSection>,
+    /// Model aliases to add or replace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_aliases: Option<PartialSection>,
+    /// Model routes to add or replace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_routes: Option<PartialSection>,
+    /// Per-model overrides to add or replace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub models: Option<PartialSection>,
+}
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+impl PartialConfig {
+    ///
+    /// Parse an overlay from JSON.
+    ///
+    /// # Arguments
+    ///  * `json` - JSON object keyed by section, e.g. `{"server": {"port": 4000}}`
+    ///
+    /// # Returns
+    ///  * Parsed overlay, or `ProxyError::Config` for invalid JSON or unknown sections
+    #[allow(dead_code)]
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| ProxyError::config_with_source("Invalid JSON configuration overlay", e))
+    }
+
+    ///
+    /// Parse an overlay from TOML.
+    ///
+    /// # Arguments
+    ///  * `toml` - TOML document with the same sections as a config file
+    ///
+    /// # Returns
+    ///  * Parsed overlay, or `ProxyError::Config` for invalid TOML or unknown sections
+    #[allow(dead_code)]
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        toml::from_str(toml)
+            .map_err(|e| ProxyError::config_with_source("Invalid TOML configuration overlay", e))
+    }
+}
+
+impl Config {
+    ///
+    /// Apply an overlay on top of this configuration.
+    ///
+    /// Keys present in the overlay replace the current values; everything
+    /// else, including the loaded LLM provider, is kept. On error the
+    /// configuration is left unchanged.
+    ///
+    /// # Arguments
+    ///  * `overlay` - sections and keys to change
+    ///
+    /// # Returns
+    ///  * `Ok(())`, or `ProxyError::Config` when an overlay value has the wrong type
+    #[allow(dead_code)]
+    pub fn merge(&mut self, overlay: PartialConfig) -> Result<()> {
+        let mut merged = serde_json::to_value(&*self)?;
+        merge_values(&mut merged, serde_json::to_value(overlay)?);
+
+        let mut merged: Config = serde_json::from_value(merged).map_err(|e| {
+            ProxyError::config_with_source("Configuration overlay does not match the schema", e)
+        })?;
+        merged.llm_provider = self.llm_provider.take();
+        merged.failover_provider = self.failover_provider.take();
+        merged.route_providers = std::mem::take(&mut self.route_providers);
+        merged.auth.strategy = self.auth.strategy.clone();
+        *self = merged;
+        Ok(())
+    }
+}
+
+///
+/// Recursively merge `overlay` into `base`.
+///
+/// Objects are merged key by key; any other overlay value replaces the base.
+#[allow(dead_code)]
+fn merge_values(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::StreamingMode;
+
+    #[test]
+    fn test_merge_keeps_unset_fields() {
+        let mut config = Config::default();
+        config.server.max_retry_attempts = 7;
+        config.admin.secret = Some("secret".to_string());
+
+        let overlay = PartialConfig::from_json(
+            r#"{"server": {"port": 4000}, "streaming": {"mode": "never"},
+                "vertex": {"model": "claude-opus-4@20250514", "region": "us-east5"}}"#,
+        )
+        .unwrap();
+        config.merge(overlay).unwrap();
+
+        assert_eq!(config.server.port, 4000);
+        assert_eq!(config.server.max_retry_attempts, 7);
+        assert_eq!(config.streaming.mode, StreamingMode::Never);
+        assert_eq!(config.admin.secret.as_deref(), Some("secret"));
+        let vertex = config.vertex.as_ref().unwrap();
+        assert_eq!(vertex.model.as_deref(), Some("claude-opus-4@20250514"));
+        assert!(vertex.models.is_empty());
+    }
+
+    #[test]
+    fn test_overlay_errors() {
+        let overlay = PartialConfig::from_toml("[server]\nport = \"not a port\"\n").unwrap();
+        let mut config = Config::default();
+        assert!(config.merge(overlay).is_err());
+        assert_eq!(config.server.port, Config::default().server.port);
+
+        assert!(PartialConfig::from_toml("[unknown]\nkey = 1\n").is_err());
+        assert!(PartialConfig::from_json("{").is_err());
+    }
+}
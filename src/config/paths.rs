@@ -37,6 +37,8 @@ const ORGANIZATION: &str = "com";
 /// Linux/Windows only)
 #[cfg(not(target_os = "macos"))]
 const ORG_NAME: &str = "SkyCorp";
+/// File name of the configuration JSON Schema
+const SCHEMA_FILE_NAME: &str = "config.schema.json";
 
 /* --- public functions ------------------------------------------------------------------------ */
 
@@ -145,7 +147,7 @@ pub fn system_config_dir() -> Result<PathBuf> {
     #[cfg(windows)]
     {
         std::env::var("PROGRAMDATA").map(|path| PathBuf::from(path).join(APP_NAME)).map_err(|_| {
-            ProxyError::Config("PROGRAMDATA environment variable not found".to_string())
+            ProxyError::Config("PROGRAMDATA environment variable not found".to_string(), None)
         })
     }
 }
@@ -166,6 +168,18 @@ pub fn user_config_file() -> Result<PathBuf> {
     Ok(user_config_dir()?.join("config.toml"))
 }
 
+/// Get the user configuration schema file path
+///
+/// Returns the path of the JSON Schema written next to the user
+/// configuration file, e.g. `~/.config/modelmux/config.schema.json`.
+///
+/// # Returns
+/// * `Ok(PathBuf)` - Path to the configuration schema file
+/// * `Err(ProxyError)` - Unable to determine the config directory
+pub fn user_schema_file() -> Result<PathBuf> {
+    Ok(user_config_dir()?.join(SCHEMA_FILE_NAME))
+}
+
 /// Get the system configuration file path
 ///
 /// Returns the full path to the system-wide configuration file:
@@ -258,6 +272,7 @@ pub fn expand_path<P: AsRef<Path>>(path: P) -> Result<PathBuf> {
         } else {
             return Err(ProxyError::Config(
                 "Unable to determine user home directory for tilde expansion".to_string(),
+                None,
             ));
         }
     }
@@ -265,10 +280,10 @@ pub fn expand_path<P: AsRef<Path>>(path: P) -> Result<PathBuf> {
     // Handle environment variable expansion (Unix-style)
     if path_str.contains('$') {
         let expanded = shellexpand::full(&path_str).map_err(|e| {
-            ProxyError::Config(format!(
-                "Failed to expand environment variables in path '{}': {}",
-                path_str, e
-            ))
+            ProxyError::config_with_source(
+                format!("Failed to expand environment variables in path '{}'", path_str),
+                e,
+            )
         })?;
         return Ok(PathBuf::from(expanded.as_ref()));
     }
@@ -294,30 +309,33 @@ pub fn validate_config_file<P: AsRef<Path>>(path: P) -> Result<()> {
     let path = path.as_ref();
 
     if !path.exists() {
-        return Err(ProxyError::Config(format!(
-            "Configuration file '{}' does not exist",
-            path.display()
-        )));
+        return Err(ProxyError::Config(
+            format!("Configuration file '{}' does not exist", path.display()),
+            None,
+        ));
     }
 
     if !path.is_file() {
-        return Err(ProxyError::Config(format!(
-            "Configuration path '{}' exists but is not a regular file",
-            path.display()
-        )));
+        return Err(ProxyError::Config(
+            format!("Configuration path '{}' exists but is not a regular file", path.display()),
+            None,
+        ));
     }
 
     // Test readability by attempting to open
     std::fs::File::open(path).map_err(|e| {
-        ProxyError::Config(format!(
-            "Configuration file '{}' exists but cannot be read: {}\n\
+        ProxyError::Config(
+            format!(
+                "Configuration file '{}' exists but cannot be read: {}\n\
              \n\
              Please check file permissions. The file should be readable by the current user.\n\
              You can fix this with: chmod 644 '{}'",
-            path.display(),
-            e,
-            path.display()
-        ))
+                path.display(),
+                e,
+                path.display()
+            ),
+            None,
+        )
     })?;
 
     Ok(())
@@ -426,6 +444,7 @@ fn home_dir() -> Result<PathBuf> {
              \n\
              Please ensure your user account has a valid home directory."
                 .to_string(),
+            None,
         )
     })
 }
@@ -442,6 +461,7 @@ fn get_project_dirs() -> Result<ProjectDirs> {
              \n\
              Please ensure your user account has a valid home directory."
                 .to_string(),
+            None,
         )
     })
 }
@@ -452,26 +472,29 @@ fn ensure_directory_exists<P: AsRef<Path>>(path: P) -> Result<()> {
 
     if path.exists() {
         if !path.is_dir() {
-            return Err(ProxyError::Config(format!(
-                "Path '{}' exists but is not a directory",
-                path.display()
-            )));
+            return Err(ProxyError::Config(
+                format!("Path '{}' exists but is not a directory", path.display()),
+                None,
+            ));
         }
         return Ok(());
     }
 
     // Create directory and all parent directories
     std::fs::create_dir_all(path).map_err(|e| {
-        ProxyError::Config(format!(
-            "Failed to create configuration directory '{}': {}\n\
+        ProxyError::Config(
+            format!(
+                "Failed to create configuration directory '{}': {}\n\
              \n\
              Please ensure:\n\
              1. You have write permissions to the parent directory\n\
              2. There's sufficient disk space\n\
              3. No conflicting files exist in the path",
-            path.display(),
-            e
-        ))
+                path.display(),
+                e
+            ),
+            None,
+        )
     })?;
 
     Ok(())
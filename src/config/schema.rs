@@ -0,0 +1,109 @@
+//!
+//! JSON Schema of the configuration file.
+//!
+//! The schema is generated from the `Config` types with `schemars`, so field
+//! descriptions come from their doc comments and defaults from their serde
+//! defaults. Editors with TOML schema support (e.g. Taplo, used by the VS Code
+//! "Even Better TOML" extension) use it for completion and validation of
+//! `config.toml`.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use schemars::generate::SchemaSettings;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+use crate::config::Config;
+use crate::error::{ProxyError, Result};
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/// Title of the generated schema
+const SCHEMA_TITLE: &str = "ModelMux configuration";
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+/// Generate the JSON Schema (draft-07) of the configuration file
+///
+/// # Returns
+/// * Schema of `Config`, with nested sections under `definitions`
+pub fn config_schema() -> Value {
+    let generator = SchemaSettings::draft07().into_generator();
+    let mut schema = generator.into_root_schema_for::<Config>().to_value();
+    schema["title"] = Value::String(SCHEMA_TITLE.to_string());
+    schema
+}
+
+/// Render the configuration schema as pretty-printed JSON
+///
+/// # Returns
+/// * Newline-terminated JSON text
+/// * `ProxyError::Serialization` if the schema cannot be serialised
+pub fn render_schema() -> Result<String> {
+    Ok(format!("{}\n", serde_json::to_string_pretty(&config_schema())?))
+}
+
+/// Write the configuration schema to a file
+///
+/// # Arguments
+/// * `path` - file to write; its parent directory must exist
+///
+/// # Returns
+/// * `Ok(())` - Schema written
+/// * `Err(ProxyError)` - Unable to write the file
+pub fn write_schema(path: &Path) -> Result<()> {
+    fs::write(path, render_schema()?).map_err(|e| {
+        ProxyError::config_with_source(
+            format!("Failed to write JSON Schema '{}'", path.display()),
+            e,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_is_valid_draft07_json() {
+        let rendered = render_schema().unwrap();
+        let schema: Value = serde_json::from_str(&rendered).unwrap();
+
+        assert_eq!(schema["$schema"], "http://json-schema.org/draft-07/schema#");
+        assert_eq!(schema["title"], SCHEMA_TITLE);
+        assert_eq!(schema["type"], "object");
+        assert!(schema["definitions"]["ServerConfig"].is_object());
+        assert!(schema["definitions"]["StreamingMode"].is_object());
+    }
+
+    #[test]
+    fn test_schema_distinguishes_required_and_optional_fields() {
+        let schema = config_schema();
+        let required: Vec<&str> =
+            schema["required"].as_array().unwrap().iter().filter_map(Value::as_str).collect();
+
+        // Sections without a serde default must be present; the others may be left out
+        for section in ["server", "auth", "streaming"] {
+            assert!(required.contains(&section), "{} should be required", section);
+        }
+        for section in ["vertex", "cache", "limits", "prompt", "model_routes"] {
+            assert!(!required.contains(&section), "{} should be optional", section);
+            assert!(schema["properties"][section].is_object(), "{} is missing", section);
+        }
+        // Fields built at load time are not part of the file
+        assert!(schema["properties"].get("llm_provider").is_none());
+
+        let limits = &schema["definitions"]["RequestLimitsConfig"]["properties"]["max_n"];
+        assert_eq!(limits["default"], 8);
+        assert_eq!(
+            limits["description"],
+            "Most completions (`n`) one chat completion request may ask for"
+        );
+    }
+}
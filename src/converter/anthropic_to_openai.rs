@@ -14,8 +14,12 @@
 
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncBufRead;
+use tokio_stream::{Stream, StreamExt};
 
 use crate::config::LogLevel;
+use crate::error::{ProxyError, Result};
 
 /* --- types ----------------------------------------------------------------------------------- */
 
@@ -24,7 +28,7 @@ use crate::config::LogLevel;
 ///
 /// Represents a complete response from Anthropic's Claude API containing
 /// generated content, usage statistics, and completion status.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct AnthropicResponse {
     /** content blocks generated by the model */
     pub content: Vec<AnthropicContentBlock>,
@@ -38,9 +42,9 @@ pub struct AnthropicResponse {
 ///
 /// Anthropic content block within a response.
 ///
-/// Represents individual content elements that can be either text
-/// or tool usage instructions with appropriate type tagging.
-#[derive(Debug, Deserialize)]
+/// Represents individual content elements that can be text, tool usage
+/// instructions or extended thinking with appropriate type tagging.
+#[derive(Debug, Clone, Deserialize)]
 #[serde(tag = "type")]
 pub enum AnthropicContentBlock {
     /** text content block */
@@ -59,13 +63,29 @@ pub enum AnthropicContentBlock {
         /** function input parameters */
         input: serde_json::Value,
     },
+    /** extended thinking (reasoning) block */
+    #[serde(rename = "thinking")]
+    Thinking {
+        /** the model's reasoning text */
+        thinking: String,
+        /** signature verifying the thinking block, needed to send it back upstream */
+        #[allow(dead_code)]
+        signature: String,
+    },
+    /** extended thinking block encrypted by the safety systems */
+    #[serde(rename = "redacted_thinking")]
+    RedactedThinking {
+        /** encrypted reasoning */
+        #[allow(dead_code)]
+        data: String,
+    },
 }
 
 ///
 /// Anthropic usage statistics for token consumption.
 ///
 /// Tracks input and output token counts for billing and monitoring.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct AnthropicUsage {
     /** number of tokens in the input prompt */
     #[serde(rename = "input_tokens")]
@@ -110,7 +130,6 @@ pub enum AnthropicStreamEvent {
     #[serde(rename = "message_start")]
     MessageStart {
         /** message metadata */
-        #[allow(dead_code)]
         message: serde_json::Value,
     },
     /** message delta with status updates */
@@ -118,6 +137,9 @@ pub enum AnthropicStreamEvent {
     MessageDelta {
         /** the message delta information */
         delta: MessageDelta,
+        /** cumulative token usage reported with the delta */
+        #[serde(default)]
+        usage: Option<AnthropicUsage>,
     },
     /** ping event for connection keep-alive */
     #[serde(rename = "ping")]
@@ -135,6 +157,8 @@ pub struct AnthropicDelta {
     /** partial JSON for tool call arguments */
     #[serde(rename = "partial_json")]
     pub partial_json: Option<String>,
+    /** incremental reasoning of a thinking block */
+    pub thinking: Option<String>,
 }
 
 ///
@@ -186,6 +210,9 @@ pub struct OpenAiResponse {
     pub choices: Vec<OpenAiChoice>,
     /** token usage statistics */
     pub usage: OpenAiUsage,
+    /** backend configuration fingerprint */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_fingerprint: Option<String>,
 }
 
 ///
@@ -218,6 +245,12 @@ pub struct OpenAiResponseMessage {
     /** tool calls made during generation */
     #[serde(rename = "tool_calls", skip_serializing_if = "Option::is_none")]
     pub tool_calls: Option<Vec<OpenAiToolCall>>,
+    /** function call for clients of the legacy `functions` API */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function_call: Option<OpenAiFunctionCall>,
+    /** extended thinking, when `converter.include_reasoning_in_response` is set */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning_content: Option<String>,
 }
 
 ///
@@ -281,6 +314,12 @@ pub struct OpenAiStreamChunk {
     pub model: String,
     /** streaming choices array */
     pub choices: Vec<OpenAiStreamChoice>,
+    /** token usage, only on the final chunk when the client asked for it */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<OpenAiUsage>,
+    /** backend configuration fingerprint */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_fingerprint: Option<String>,
 }
 
 ///
@@ -312,6 +351,12 @@ pub struct OpenAiStreamDelta {
     /** incremental tool call updates */
     #[serde(rename = "tool_calls", skip_serializing_if = "Option::is_none")]
     pub tool_calls: Option<Vec<OpenAiStreamToolCall>>,
+    /** incremental function call for clients of the legacy `functions` API */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function_call: Option<OpenAiStreamFunctionCall>,
+    /** incremental extended thinking, when reasoning is forwarded */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning_content: Option<String>,
 }
 
 ///
@@ -351,7 +396,8 @@ pub struct OpenAiStreamFunctionCall {
 /// Helper struct for tracking streaming tool calls.
 ///
 /// Maintains state for tool calls being built incrementally
-/// during streaming response generation.
+/// during streaming response generation. Parallel tool calls are kept in
+/// order; a call's position is its OpenAI `index`.
 #[derive(Debug)]
 pub struct StreamingToolCall {
     /** tool call unique identifier */
@@ -363,6 +409,28 @@ pub struct StreamingToolCall {
     pub arguments: String,
 }
 
+///
+/// State of one streamed response, kept across its stream events.
+#[derive(Debug, Default)]
+pub struct StreamingState {
+    /** tool calls streamed so far, in order */
+    pub tool_calls: Vec<StreamingToolCall>,
+    /** whether the response contains tool calls */
+    pub has_tool_calls: bool,
+    /** stop reason from the message delta */
+    pub stop_reason_from_delta: Option<String>,
+    /** whether a usage chunk is still to be sent (`stream_options.include_usage`) */
+    pub include_usage: bool,
+    /** prompt tokens from `message_start` */
+    pub input_tokens: u32,
+    /** completion tokens from the latest `message_delta` */
+    pub output_tokens: u32,
+    /** send tool calls as legacy `function_call` deltas */
+    pub legacy_functions: bool,
+    /** `system_fingerprint` of every chunk, see [`seed_fingerprint`] */
+    pub system_fingerprint: Option<String>,
+}
+
 ///
 /// Converter from Anthropic format to OpenAI format.
 ///
@@ -371,6 +439,8 @@ pub struct StreamingToolCall {
 pub struct AnthropicToOpenAiConverter {
     /** logging level for debug output */
     log_level: LogLevel,
+    /** forward thinking blocks as `reasoning_content` instead of dropping them */
+    include_reasoning: bool,
 }
 
 /* --- constants ------------------------------------------------------------------------------ */
@@ -387,6 +457,18 @@ const ASSISTANT_ROLE: &str = "assistant";
 /** Function tool call type */
 const FUNCTION_TOOL_TYPE: &str = "function";
 
+/** Finish reason of responses ending in tool calls */
+const TOOL_CALLS_FINISH_REASON: &str = "tool_calls";
+
+/** Finish reason of responses ending in a legacy function call */
+const FUNCTION_CALL_FINISH_REASON: &str = "function_call";
+
+/** Seconds during which the fingerprint of a seed and model stays the same */
+const FINGERPRINT_BUCKET_SECS: i64 = 3600;
+
+/** Hex digits of the hash in a seed fingerprint */
+const FINGERPRINT_HEX_LEN: usize = 10;
+
 /* --- start of code -------------------------------------------------------------------------- */
 
 impl AnthropicToOpenAiConverter {
@@ -399,7 +481,23 @@ impl AnthropicToOpenAiConverter {
     /// # Returns
     ///  * New converter instance
     pub fn new(log_level: LogLevel) -> Self {
-        Self { log_level }
+        Self { log_level, include_reasoning: false }
+    }
+
+    ///
+    /// Forward extended thinking to clients.
+    ///
+    /// Thinking blocks are dropped by default; with `include` they become the
+    /// `reasoning_content` field of the message, or of the stream deltas.
+    ///
+    /// # Arguments
+    ///  * `include` - whether to forward thinking blocks
+    ///
+    /// # Returns
+    ///  * Converter forwarding thinking blocks as configured
+    pub fn with_reasoning_content(mut self, include: bool) -> Self {
+        self.include_reasoning = include;
+        self
     }
 
     ///
@@ -414,14 +512,23 @@ impl AnthropicToOpenAiConverter {
     ///
     /// # Returns
     ///  * Converted OpenAI response
+    #[tracing::instrument(
+        skip(self, response),
+        fields(content_blocks = response.content.len(), stop_reason = ?response.stop_reason)
+    )]
     pub fn convert(&self, response: AnthropicResponse, model: &str) -> OpenAiResponse {
         let mut message = OpenAiResponseMessage {
             role: ASSISTANT_ROLE.to_string(),
             content: None,
             tool_calls: None,
+            function_call: None,
+            reasoning_content: None,
         };
 
         self.extract_text_content(&mut message, &response.content);
+        if self.include_reasoning {
+            self.extract_reasoning_content(&mut message, &response.content);
+        }
         self.extract_tool_calls(&mut message, &response.content);
 
         let finish_reason =
@@ -435,21 +542,76 @@ impl AnthropicToOpenAiConverter {
             model: model.to_string(),
             choices: vec![OpenAiChoice { index: 0, message, finish_reason }],
             usage,
+            system_fingerprint: None,
         }
     }
 
+    ///
+    /// Convert a batch of Anthropic responses to OpenAI format.
+    ///
+    /// # Arguments
+    ///  * `responses` - Anthropic responses, e.g. from [`parse_batch_jsonl`]
+    ///  * `model` - model identifier for the responses
+    ///
+    /// # Returns
+    ///  * Converted OpenAI responses in input order
+    pub fn convert_batch(
+        &self,
+        responses: impl Iterator<Item = AnthropicResponse>,
+        model: &str,
+    ) -> Vec<OpenAiResponse> {
+        responses.map(|response| self.convert(response, model)).collect()
+    }
+
+    ///
+    /// Convert a batch output file line by line without buffering it.
+    ///
+    /// Malformed lines are skipped with a warning, as in [`parse_batch_jsonl`].
+    ///
+    /// # Arguments
+    ///  * `reader` - buffered reader over the JSONL output file
+    ///  * `model` - model identifier for the responses
+    ///
+    /// # Returns
+    ///  * Stream of converted responses, or read errors
+    pub fn convert_batch_streaming<'a, R>(
+        &'a self,
+        reader: R,
+        model: &'a str,
+    ) -> impl Stream<Item = Result<OpenAiResponse>> + 'a
+    where
+        R: AsyncBufRead + Unpin + Send + 'a,
+    {
+        use tokio::io::AsyncBufReadExt;
+
+        let lines = futures::stream::unfold(
+            (reader.lines(), 0usize),
+            |(mut lines, line_number)| async move {
+                let line = lines.next_line().await.transpose()?;
+                Some(((line_number + 1, line), (lines, line_number + 1)))
+            },
+        );
+
+        lines.filter_map(move |(line_number, line)| match line {
+            Ok(line) => parse_batch_line(line_number, &line)
+                .map(|response| Ok(self.convert(response, model))),
+            Err(e) => Some(Err(ProxyError::Conversion(format!(
+                "Failed to read batch output line {}: {}",
+                line_number, e
+            )))),
+        })
+    }
+
     ///
     /// Convert Anthropic streaming event to OpenAI streaming chunk.
     ///
     /// Transforms streaming events into OpenAI-compatible chunks while maintaining
-    /// state for tool calls and completion status.
+    /// state for tool calls, token usage and completion status.
     ///
     /// # Arguments
     ///  * `event` - Anthropic streaming event to convert
     ///  * `model` - model identifier for the chunk
-    ///  * `current_tool_call` - mutable state for current tool call
-    ///  * `has_tool_calls` - mutable flag for tool call presence
-    ///  * `stop_reason_from_delta` - mutable stop reason from message delta
+    ///  * `state` - mutable state of the streamed response
     ///
     /// # Returns
     ///  * OpenAI streaming chunk if conversion produces output
@@ -458,30 +620,148 @@ impl AnthropicToOpenAiConverter {
         &self,
         event: &AnthropicStreamEvent,
         model: &str,
-        current_tool_call: &mut Option<StreamingToolCall>,
-        has_tool_calls: &mut bool,
-        stop_reason_from_delta: &mut Option<String>,
+        state: &mut StreamingState,
     ) -> Option<OpenAiStreamChunk> {
-        match event {
+        let mut chunk = match event {
             AnthropicStreamEvent::ContentBlockDelta { delta } => {
-                self.handle_content_delta(delta, model, current_tool_call)
+                self.handle_content_delta(delta, model, &mut state.tool_calls)
             }
-            AnthropicStreamEvent::ContentBlockStart { content_block } => {
-                self.handle_content_start(content_block, model, current_tool_call, has_tool_calls)
+            AnthropicStreamEvent::ContentBlockStart { content_block } => self.handle_content_start(
+                content_block,
+                model,
+                &mut state.tool_calls,
+                &mut state.has_tool_calls,
+            ),
+            AnthropicStreamEvent::ContentBlockStop => self.handle_content_stop(&state.tool_calls),
+            AnthropicStreamEvent::MessageStart { message } => {
+                self.handle_message_start(message, state)
             }
-            AnthropicStreamEvent::ContentBlockStop => self.handle_content_stop(current_tool_call),
-            AnthropicStreamEvent::MessageStart { .. } => self.handle_message_start(),
-            AnthropicStreamEvent::MessageDelta { delta } => {
-                self.handle_message_delta(delta, stop_reason_from_delta)
+            AnthropicStreamEvent::MessageDelta { delta, usage } => {
+                if let Some(output_tokens) = usage.as_ref().and_then(|usage| usage.output_tokens) {
+                    // Cumulative count, the latest delta has the total
+                    state.output_tokens = output_tokens;
+                }
+                self.handle_message_delta(delta, &mut state.stop_reason_from_delta)
             }
             AnthropicStreamEvent::Ping => None,
             AnthropicStreamEvent::MessageStop { stop_reason } => self.handle_message_stop(
                 stop_reason,
                 model,
-                current_tool_call,
-                has_tool_calls,
-                stop_reason_from_delta,
+                &mut state.tool_calls,
+                &state.has_tool_calls,
+                &mut state.stop_reason_from_delta,
             ),
+        };
+        if state.legacy_functions
+            && let Some(chunk) = chunk.as_mut()
+        {
+            self.apply_legacy_function_call_chunk(chunk);
+        }
+        if let Some(chunk) = chunk.as_mut() {
+            chunk.system_fingerprint = state.system_fingerprint.clone();
+        }
+        chunk
+    }
+
+    ///
+    /// Create the final usage chunk of a stream.
+    ///
+    /// Sent before `[DONE]` when the client set `stream_options.include_usage`;
+    /// like OpenAI's, the chunk has no choices. Returns the chunk only once per
+    /// stream.
+    ///
+    /// # Arguments
+    ///  * `model` - model identifier
+    ///  * `state` - state of the streamed response with the token counts
+    ///
+    /// # Returns
+    ///  * Usage chunk, or `None` when usage was not requested or already sent
+    pub fn create_usage_chunk(
+        &self,
+        model: &str,
+        state: &mut StreamingState,
+    ) -> Option<OpenAiStreamChunk> {
+        if !std::mem::take(&mut state.include_usage) {
+            return None;
+        }
+        Some(OpenAiStreamChunk {
+            id: self.generate_response_id(),
+            object: CHAT_COMPLETION_CHUNK_OBJECT.to_string(),
+            created: Utc::now().timestamp(),
+            model: model.to_string(),
+            choices: Vec::new(),
+            usage: Some(OpenAiUsage {
+                prompt_tokens: state.input_tokens,
+                completion_tokens: state.output_tokens,
+                total_tokens: state.input_tokens + state.output_tokens,
+            }),
+            system_fingerprint: state.system_fingerprint.clone(),
+        })
+    }
+
+    ///
+    /// Set the `system_fingerprint` of a response to a request with a `seed`.
+    ///
+    /// Anthropic has no seed parameter, so the fingerprint only tells clients
+    /// which seed and model produced a response, e.g. to invalidate caches.
+    ///
+    /// # Arguments
+    ///  * `response` - converted OpenAI response to update in place
+    ///  * `seed` - `seed` of the OpenAI request, if any
+    pub fn apply_seed(&self, response: &mut OpenAiResponse, seed: Option<u64>) {
+        if let Some(seed) = seed {
+            response.system_fingerprint =
+                Some(seed_fingerprint(seed, &response.model, response.created));
+        }
+    }
+
+    ///
+    /// Rewrite a response for clients of the legacy `functions` API.
+    ///
+    /// The first tool call moves to `message.function_call` and the
+    /// `tool_calls` finish reason becomes `function_call`. The legacy format
+    /// has no parallel calls, so further tool calls are dropped.
+    ///
+    /// # Arguments
+    ///  * `response` - converted OpenAI response to rewrite in place
+    pub fn apply_legacy_function_call(&self, response: &mut OpenAiResponse) {
+        for choice in &mut response.choices {
+            let Some(tool_calls) = choice.message.tool_calls.take() else {
+                continue;
+            };
+            if tool_calls.len() > 1 {
+                tracing::warn!(
+                    "Legacy function calling supports one call, dropping {} parallel tool call(s)",
+                    tool_calls.len() - 1
+                );
+            }
+            choice.message.function_call =
+                tool_calls.into_iter().next().map(|tool_call| tool_call.function);
+            if choice.finish_reason == TOOL_CALLS_FINISH_REASON {
+                choice.finish_reason = FUNCTION_CALL_FINISH_REASON.to_string();
+            }
+        }
+    }
+
+    ///
+    /// Rewrite a streaming chunk for clients of the legacy `functions` API.
+    ///
+    /// Deltas of the first tool call become `function_call` deltas; deltas
+    /// of further tool calls are dropped.
+    ///
+    /// # Arguments
+    ///  * `chunk` - converted streaming chunk to rewrite in place
+    fn apply_legacy_function_call_chunk(&self, chunk: &mut OpenAiStreamChunk) {
+        for choice in &mut chunk.choices {
+            if let Some(tool_calls) = choice.delta.tool_calls.take() {
+                choice.delta.function_call = tool_calls
+                    .into_iter()
+                    .find(|tool_call| tool_call.index == 0)
+                    .and_then(|tool_call| tool_call.function);
+            }
+            if choice.finish_reason.as_deref() == Some(TOOL_CALLS_FINISH_REASON) {
+                choice.finish_reason = Some(FUNCTION_CALL_FINISH_REASON.to_string());
+            }
         }
     }
 
@@ -512,6 +792,32 @@ impl AnthropicToOpenAiConverter {
         }
     }
 
+    ///
+    /// Extract extended thinking from Anthropic content blocks.
+    ///
+    /// Redacted thinking is encrypted and never forwarded.
+    ///
+    /// # Arguments
+    ///  * `message` - OpenAI message to populate with reasoning content
+    ///  * `content_blocks` - Anthropic content blocks to extract from
+    fn extract_reasoning_content(
+        &self,
+        message: &mut OpenAiResponseMessage,
+        content_blocks: &[AnthropicContentBlock],
+    ) {
+        let reasoning: Vec<&str> = content_blocks
+            .iter()
+            .filter_map(|block| match block {
+                AnthropicContentBlock::Thinking { thinking, .. } => Some(thinking.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        if !reasoning.is_empty() {
+            message.reasoning_content = Some(reasoning.join("\n\n"));
+        }
+    }
+
     ///
     /// Extract tool calls from Anthropic content blocks.
     ///
@@ -625,7 +931,7 @@ impl AnthropicToOpenAiConverter {
     /// # Arguments
     ///  * `delta` - content delta to process
     ///  * `model` - model identifier
-    ///  * `current_tool_call` - current tool call state
+    ///  * `tool_calls` - tool calls streamed so far
     ///
     /// # Returns
     ///  * OpenAI stream chunk if content should be output
@@ -633,14 +939,19 @@ impl AnthropicToOpenAiConverter {
         &self,
         delta: &AnthropicDelta,
         model: &str,
-        current_tool_call: &mut Option<StreamingToolCall>,
+        tool_calls: &mut [StreamingToolCall],
     ) -> Option<OpenAiStreamChunk> {
         if let Some(text) = &delta.text {
             self.create_text_chunk(text, model)
         } else if let Some(partial_json) = &delta.partial_json {
-            self.handle_tool_argument_delta(partial_json, model, current_tool_call)
+            self.handle_tool_argument_delta(partial_json, model, tool_calls)
         } else {
-            None
+            // Thinking deltas are forwarded only on request; signature deltas never
+            delta
+                .thinking
+                .as_ref()
+                .filter(|_| self.include_reasoning)
+                .map(|thinking| self.create_reasoning_chunk(thinking, model))
         }
     }
 
@@ -661,19 +972,59 @@ impl AnthropicToOpenAiConverter {
             model: model.to_string(),
             choices: vec![OpenAiStreamChoice {
                 index: 0,
-                delta: OpenAiStreamDelta { content: Some(text.to_string()), tool_calls: None },
+                delta: OpenAiStreamDelta {
+                    content: Some(text.to_string()),
+                    tool_calls: None,
+                    function_call: None,
+                    reasoning_content: None,
+                },
                 finish_reason: None,
             }],
+            usage: None,
+            system_fingerprint: None,
         })
     }
 
+    ///
+    /// Create an extended thinking streaming chunk.
+    ///
+    /// # Arguments
+    ///  * `thinking` - reasoning text of the delta
+    ///  * `model` - model identifier
+    ///
+    /// # Returns
+    ///  * OpenAI stream chunk with `reasoning_content`
+    fn create_reasoning_chunk(&self, thinking: &str, model: &str) -> OpenAiStreamChunk {
+        OpenAiStreamChunk {
+            id: self.generate_response_id(),
+            object: CHAT_COMPLETION_CHUNK_OBJECT.to_string(),
+            created: Utc::now().timestamp(),
+            model: model.to_string(),
+            choices: vec![OpenAiStreamChoice {
+                index: 0,
+                delta: OpenAiStreamDelta {
+                    content: None,
+                    tool_calls: None,
+                    function_call: None,
+                    reasoning_content: Some(thinking.to_string()),
+                },
+                finish_reason: None,
+            }],
+            usage: None,
+            system_fingerprint: None,
+        }
+    }
+
     ///
     /// Handle tool call argument delta for streaming.
     ///
+    /// Content blocks stream one after another, so the delta belongs to the
+    /// most recently started tool call.
+    ///
     /// # Arguments
     ///  * `partial_json` - partial JSON arguments
     ///  * `model` - model identifier
-    ///  * `current_tool_call` - current tool call state
+    ///  * `tool_calls` - tool calls streamed so far
     ///
     /// # Returns
     ///  * OpenAI stream chunk with tool call delta
@@ -681,16 +1032,17 @@ impl AnthropicToOpenAiConverter {
         &self,
         partial_json: &str,
         model: &str,
-        current_tool_call: &mut Option<StreamingToolCall>,
+        tool_calls: &mut [StreamingToolCall],
     ) -> Option<OpenAiStreamChunk> {
-        if let Some(tool_call) = current_tool_call.as_mut() {
+        let index = tool_calls.len().checked_sub(1)?;
+        if let Some(tool_call) = tool_calls.last_mut() {
             self.debug(&format!(
                 "[STREAM] Tool call arguments delta for {}: {}",
                 tool_call.name, partial_json
             ));
             tool_call.arguments.push_str(partial_json);
 
-            Some(self.create_tool_argument_chunk(partial_json, model))
+            Some(self.create_tool_argument_chunk(index as u32, partial_json, model))
         } else {
             None
         }
@@ -700,12 +1052,18 @@ impl AnthropicToOpenAiConverter {
     /// Create a tool call argument streaming chunk.
     ///
     /// # Arguments
+    ///  * `index` - index of the tool call in the response
     ///  * `partial_json` - partial JSON arguments
     ///  * `model` - model identifier
     ///
     /// # Returns
     ///  * OpenAI stream chunk with tool call arguments
-    fn create_tool_argument_chunk(&self, partial_json: &str, model: &str) -> OpenAiStreamChunk {
+    fn create_tool_argument_chunk(
+        &self,
+        index: u32,
+        partial_json: &str,
+        model: &str,
+    ) -> OpenAiStreamChunk {
         OpenAiStreamChunk {
             id: self.generate_response_id(),
             object: CHAT_COMPLETION_CHUNK_OBJECT.to_string(),
@@ -716,7 +1074,7 @@ impl AnthropicToOpenAiConverter {
                 delta: OpenAiStreamDelta {
                     content: None,
                     tool_calls: Some(vec![OpenAiStreamToolCall {
-                        index: 0,
+                        index,
                         id: None,
                         call_type: None,
                         function: Some(OpenAiStreamFunctionCall {
@@ -724,9 +1082,13 @@ impl AnthropicToOpenAiConverter {
                             arguments: Some(partial_json.to_string()),
                         }),
                     }]),
+                    function_call: None,
+                    reasoning_content: None,
                 },
                 finish_reason: None,
             }],
+            usage: None,
+            system_fingerprint: None,
         }
     }
 
@@ -736,7 +1098,7 @@ impl AnthropicToOpenAiConverter {
     /// # Arguments
     ///  * `content_block` - content block metadata
     ///  * `model` - model identifier
-    ///  * `current_tool_call` - current tool call state
+    ///  * `tool_calls` - tool calls streamed so far, the new call is appended
     ///  * `has_tool_calls` - tool call presence flag
     ///
     /// # Returns
@@ -745,20 +1107,21 @@ impl AnthropicToOpenAiConverter {
         &self,
         content_block: &AnthropicStreamContentBlock,
         model: &str,
-        current_tool_call: &mut Option<StreamingToolCall>,
+        tool_calls: &mut Vec<StreamingToolCall>,
         has_tool_calls: &mut bool,
     ) -> Option<OpenAiStreamChunk> {
         if content_block.block_type == "tool_use" {
             if let (Some(id), Some(name)) = (&content_block.id, &content_block.name) {
-                self.debug(&format!("[STREAM] Tool call started: {} (id: {})", name, id));
+                let index = tool_calls.len() as u32;
+                self.debug(&format!("[STREAM] Tool call {} started: {} (id: {})", index, name, id));
                 *has_tool_calls = true;
-                *current_tool_call = Some(StreamingToolCall {
+                tool_calls.push(StreamingToolCall {
                     id: id.clone(),
                     name: name.clone(),
                     arguments: String::new(),
                 });
 
-                Some(self.create_tool_start_chunk(id, name, model))
+                Some(self.create_tool_start_chunk(index, id, name, model))
             } else {
                 None
             }
@@ -771,13 +1134,20 @@ impl AnthropicToOpenAiConverter {
     /// Create a tool call start streaming chunk.
     ///
     /// # Arguments
+    ///  * `index` - index of the tool call in the response
     ///  * `id` - tool call identifier
     ///  * `name` - function name
     ///  * `model` - model identifier
     ///
     /// # Returns
     ///  * OpenAI stream chunk with tool call start
-    fn create_tool_start_chunk(&self, id: &str, name: &str, model: &str) -> OpenAiStreamChunk {
+    fn create_tool_start_chunk(
+        &self,
+        index: u32,
+        id: &str,
+        name: &str,
+        model: &str,
+    ) -> OpenAiStreamChunk {
         OpenAiStreamChunk {
             id: self.generate_response_id(),
             object: CHAT_COMPLETION_CHUNK_OBJECT.to_string(),
@@ -788,7 +1158,7 @@ impl AnthropicToOpenAiConverter {
                 delta: OpenAiStreamDelta {
                     content: None,
                     tool_calls: Some(vec![OpenAiStreamToolCall {
-                        index: 0,
+                        index,
                         id: Some(id.to_string()),
                         call_type: Some(FUNCTION_TOOL_TYPE.to_string()),
                         function: Some(OpenAiStreamFunctionCall {
@@ -796,9 +1166,13 @@ impl AnthropicToOpenAiConverter {
                             arguments: Some(String::new()),
                         }),
                     }]),
+                    function_call: None,
+                    reasoning_content: None,
                 },
                 finish_reason: None,
             }],
+            usage: None,
+            system_fingerprint: None,
         }
     }
 
@@ -806,15 +1180,12 @@ impl AnthropicToOpenAiConverter {
     /// Handle content block stop events for streaming.
     ///
     /// # Arguments
-    ///  * `current_tool_call` - current tool call state
+    ///  * `tool_calls` - tool calls streamed so far
     ///
     /// # Returns
     ///  * None (no output needed for content stop)
-    fn handle_content_stop(
-        &self,
-        current_tool_call: &Option<StreamingToolCall>,
-    ) -> Option<OpenAiStreamChunk> {
-        if let Some(tool_call) = current_tool_call {
+    fn handle_content_stop(&self, tool_calls: &[StreamingToolCall]) -> Option<OpenAiStreamChunk> {
+        if let Some(tool_call) = tool_calls.last() {
             self.debug(&format!("[STREAM] Tool call block stopped: {}", tool_call.name));
         }
         None
@@ -823,10 +1194,25 @@ impl AnthropicToOpenAiConverter {
     ///
     /// Handle message start events for streaming.
     ///
+    /// # Arguments
+    ///  * `message` - message metadata, with the prompt token usage
+    ///  * `state` - streamed response state to record the prompt tokens in
+    ///
     /// # Returns
     ///  * None (no output needed for message start)
-    fn handle_message_start(&self) -> Option<OpenAiStreamChunk> {
+    fn handle_message_start(
+        &self,
+        message: &serde_json::Value,
+        state: &mut StreamingState,
+    ) -> Option<OpenAiStreamChunk> {
         self.debug("[STREAM] Message start");
+        if let Some(input_tokens) = message
+            .get("usage")
+            .and_then(|usage| usage.get("input_tokens"))
+            .and_then(serde_json::Value::as_u64)
+        {
+            state.input_tokens = input_tokens as u32;
+        }
         None
     }
 
@@ -854,10 +1240,13 @@ impl AnthropicToOpenAiConverter {
     ///
     /// Handle message stop events for streaming.
     ///
+    /// All tool calls of the message have been streamed by now; they are
+    /// logged and cleared before the finish chunk is emitted.
+    ///
     /// # Arguments
     ///  * `stop_reason` - stop reason from message stop
     ///  * `model` - model identifier
-    ///  * `current_tool_call` - current tool call state
+    ///  * `tool_calls` - tool calls streamed in the message
     ///  * `has_tool_calls` - tool call presence flag
     ///  * `stop_reason_from_delta` - stop reason from message delta
     ///
@@ -867,13 +1256,13 @@ impl AnthropicToOpenAiConverter {
         &self,
         stop_reason: &Option<String>,
         model: &str,
-        current_tool_call: &mut Option<StreamingToolCall>,
+        tool_calls: &mut Vec<StreamingToolCall>,
         has_tool_calls: &bool,
         stop_reason_from_delta: &mut Option<String>,
     ) -> Option<OpenAiStreamChunk> {
         let effective_stop_reason = stop_reason_from_delta.as_deref().or(stop_reason.as_deref());
 
-        let finish_reason = if *has_tool_calls || current_tool_call.is_some() {
+        let finish_reason = if *has_tool_calls || !tool_calls.is_empty() {
             "tool_calls"
         } else {
             match effective_stop_reason {
@@ -891,10 +1280,10 @@ impl AnthropicToOpenAiConverter {
 
         *stop_reason_from_delta = None;
 
-        if let Some(tool_call) = current_tool_call.take() {
+        for (index, tool_call) in tool_calls.drain(..).enumerate() {
             self.debug(&format!(
-                "[STREAM] Completed tool call: {}({})",
-                tool_call.name, tool_call.arguments
+                "[STREAM] Completed tool call {}: {}({})",
+                index, tool_call.name, tool_call.arguments
             ));
         }
 
@@ -905,9 +1294,16 @@ impl AnthropicToOpenAiConverter {
             model: model.to_string(),
             choices: vec![OpenAiStreamChoice {
                 index: 0,
-                delta: OpenAiStreamDelta { content: None, tool_calls: None },
+                delta: OpenAiStreamDelta {
+                    content: None,
+                    tool_calls: None,
+                    function_call: None,
+                    reasoning_content: None,
+                },
                 finish_reason: Some(finish_reason.to_string()),
             }],
+            usage: None,
+            system_fingerprint: None,
         })
     }
 
@@ -922,3 +1318,390 @@ impl AnthropicToOpenAiConverter {
         }
     }
 }
+
+///
+/// Fingerprint of the responses to a seeded request.
+///
+/// A hash of the seed, the model and the hour of `created`, so requests with
+/// the same seed and model share a fingerprint for up to an hour.
+///
+/// # Arguments
+///  * `seed` - `seed` of the OpenAI request
+///  * `model` - model identifier of the response
+///  * `created` - creation timestamp of the response in seconds
+///
+/// # Returns
+///  * Fingerprint in OpenAI's `fp_<hex>` format
+pub fn seed_fingerprint(seed: u64, model: &str, created: i64) -> String {
+    let bucket = created.div_euclid(FINGERPRINT_BUCKET_SECS);
+    let digest = Sha256::digest(format!("{}:{}:{}", seed, model, bucket).as_bytes());
+    let hex: String = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("fp_{}", &hex[..FINGERPRINT_HEX_LEN])
+}
+
+///
+/// Parse an Anthropic batch output file (JSONL).
+///
+/// Each line is either a message response or a batch result entry
+/// (`{"custom_id": ..., "result": {"type": "succeeded", "message": {...}}}`).
+/// Malformed lines and non-succeeded results are skipped with a warning.
+///
+/// # Arguments
+///  * `jsonl` - contents of the batch output file
+///
+/// # Returns
+///  * Parsed responses in file order
+pub fn parse_batch_jsonl(jsonl: &str) -> Result<Vec<AnthropicResponse>> {
+    Ok(jsonl
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| parse_batch_line(index + 1, line))
+        .collect())
+}
+
+///
+/// Parse a single line of a batch output file.
+///
+/// # Arguments
+///  * `line_number` - 1-based line number for log messages
+///  * `line` - JSONL line
+///
+/// # Returns
+///  * Parsed response, or `None` for blank, malformed or failed entries
+fn parse_batch_line(line_number: usize, line: &str) -> Option<AnthropicResponse> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+
+    let mut value: serde_json::Value = match serde_json::from_str(line) {
+        Ok(value) => value,
+        Err(e) => {
+            tracing::warn!("Skipping malformed batch line {}: {}", line_number, e);
+            return None;
+        }
+    };
+
+    if let Some(result) = value.get_mut("result") {
+        let Some(message) = result.get_mut("message").map(serde_json::Value::take) else {
+            tracing::warn!(
+                "Skipping batch line {}: result type '{}' has no message",
+                line_number,
+                result.get("type").and_then(|t| t.as_str()).unwrap_or("unknown")
+            );
+            return None;
+        };
+        value = message;
+    }
+
+    match serde_json::from_value(value) {
+        Ok(response) => Some(response),
+        Err(e) => {
+            tracing::warn!("Skipping batch line {}: not an Anthropic response: {}", line_number, e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BATCH: &str = r#"{"custom_id":"a","result":{"type":"succeeded","message":{"content":[{"type":"text","text":"hello"}],"stop_reason":"end_turn","usage":{"input_tokens":3,"output_tokens":1}}}}
+not json
+{"custom_id":"b","result":{"type":"errored","error":{"type":"invalid_request"}}}
+
+{"content":[{"type":"text","text":"world"}],"stop_reason":"end_turn","usage":null}
+"#;
+
+    #[test]
+    fn test_parse_batch_jsonl_skips_malformed_lines() {
+        let responses = parse_batch_jsonl(BATCH).unwrap();
+        assert_eq!(responses.len(), 2);
+
+        let converter = AnthropicToOpenAiConverter::new(LogLevel::Info);
+        let converted = converter.convert_batch(responses.into_iter(), "claude");
+        assert_eq!(converted[0].choices[0].message.content.as_deref(), Some("hello"));
+        assert_eq!(converted[0].usage.total_tokens, 4);
+        assert_eq!(converted[1].choices[0].message.content.as_deref(), Some("world"));
+    }
+
+    #[tokio::test]
+    async fn test_convert_batch_streaming() {
+        let converter = AnthropicToOpenAiConverter::new(LogLevel::Info);
+        let stream = converter.convert_batch_streaming(BATCH.as_bytes(), "claude");
+        let converted: Vec<_> = stream.collect().await;
+
+        assert_eq!(converted.len(), 2);
+        assert!(converted.iter().all(|r| r.is_ok()));
+    }
+
+    #[test]
+    fn test_parallel_tool_calls() {
+        let response: AnthropicResponse = serde_json::from_str(
+            r#"{"content":[
+                {"type":"text","text":"Checking both cities."},
+                {"type":"tool_use","id":"toolu_1","name":"get_weather","input":{"city":"Paris"}},
+                {"type":"tool_use","id":"toolu_2","name":"get_weather","input":{"city":"Oslo"}}
+            ],"stop_reason":"tool_use","usage":null}"#,
+        )
+        .unwrap();
+        let converted = AnthropicToOpenAiConverter::new(LogLevel::Info).convert(response, "claude");
+
+        let choice = &converted.choices[0];
+        assert_eq!(choice.message.content.as_deref(), Some("Checking both cities."));
+        assert_eq!(choice.finish_reason, "tool_calls");
+        let tool_calls = choice.message.tool_calls.as_ref().unwrap();
+        assert_eq!(tool_calls.len(), 2);
+        assert_eq!(tool_calls[0].id, "toolu_1");
+        assert_eq!(tool_calls[0].function.arguments, r#"{"city":"Paris"}"#);
+        assert_eq!(tool_calls[1].id, "toolu_2");
+        assert_eq!(tool_calls[1].function.arguments, r#"{"city":"Oslo"}"#);
+    }
+
+    #[test]
+    fn test_thinking_blocks() {
+        let body = r#"{"content":[
+            {"type":"thinking","thinking":"The user wants 27 * 453. 27 * 453 = 12231.","signature":"EqQBCgIYAhIM1gbcDa9GJwZA2b3hGgxBdjrkzLoky3dl1pkiMOYds"},
+            {"type":"redacted_thinking","data":"EmwKAhgBEgy3va3pzix/LafPsn4aDFIT2Xlxh0L5L8rLVyIwxtE3rAFBa8cr3qpP"},
+            {"type":"text","text":"27 * 453 = 12,231"}
+        ],"stop_reason":"end_turn","usage":{"input_tokens":48,"output_tokens":112}}"#;
+
+        let converter = AnthropicToOpenAiConverter::new(LogLevel::Info);
+        let converted = converter.convert(serde_json::from_str(body).unwrap(), "claude");
+        let message = &converted.choices[0].message;
+        assert_eq!(message.content.as_deref(), Some("27 * 453 = 12,231"));
+        assert!(message.reasoning_content.is_none());
+        assert!(serde_json::to_value(message).unwrap().get("reasoning_content").is_none());
+
+        let converter = converter.with_reasoning_content(true);
+        let converted = converter.convert(serde_json::from_str(body).unwrap(), "claude");
+        let message = &converted.choices[0].message;
+        assert_eq!(message.content.as_deref(), Some("27 * 453 = 12,231"));
+        assert_eq!(
+            message.reasoning_content.as_deref(),
+            Some("The user wants 27 * 453. 27 * 453 = 12231.")
+        );
+        assert_eq!(converted.choices[0].finish_reason, "stop");
+    }
+
+    #[test]
+    fn test_stream_thinking_deltas() {
+        let events = [
+            r#"{"type":"content_block_start","index":0,"content_block":{"type":"thinking","thinking":""}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"thinking_delta","thinking":"Let me solve this "}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"thinking_delta","thinking":"step by step."}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"signature_delta","signature":"EqQBCgIYAhIM1gbcDa9GJwZA2b3h"}}"#,
+            r#"{"type":"content_block_stop","index":0}"#,
+            r#"{"type":"content_block_start","index":1,"content_block":{"type":"text","text":""}}"#,
+            r#"{"type":"content_block_delta","index":1,"delta":{"type":"text_delta","text":"12,231"}}"#,
+        ];
+        let deltas = |converter: &AnthropicToOpenAiConverter| {
+            let mut state = StreamingState::default();
+            events
+                .iter()
+                .filter_map(|event| {
+                    let event: AnthropicStreamEvent = serde_json::from_str(event).unwrap();
+                    converter.convert_stream_event(&event, "claude", &mut state)
+                })
+                .map(|chunk| serde_json::to_value(&chunk.choices[0].delta).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        let converter = AnthropicToOpenAiConverter::new(LogLevel::Info);
+        assert_eq!(deltas(&converter), [serde_json::json!({"content": "12,231"})]);
+
+        let converter = converter.with_reasoning_content(true);
+        assert_eq!(
+            deltas(&converter),
+            [
+                serde_json::json!({"reasoning_content": "Let me solve this "}),
+                serde_json::json!({"reasoning_content": "step by step."}),
+                serde_json::json!({"content": "12,231"}),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_seed_system_fingerprint() {
+        let converter = AnthropicToOpenAiConverter::new(LogLevel::Info);
+        let body =
+            r#"{"content":[{"type":"text","text":"Hi"}],"stop_reason":"end_turn","usage":null}"#;
+        let fingerprint = |seed| {
+            let mut response = converter.convert(serde_json::from_str(body).unwrap(), "claude");
+            converter.apply_seed(&mut response, seed);
+            response.system_fingerprint
+        };
+
+        let first = fingerprint(Some(42)).unwrap();
+        assert!(first.starts_with("fp_"));
+        assert_eq!(first.len(), 3 + FINGERPRINT_HEX_LEN);
+        assert_eq!(fingerprint(Some(42)).unwrap(), first);
+        assert_ne!(fingerprint(Some(43)).unwrap(), first);
+        assert_eq!(fingerprint(None), None);
+
+        // Stable within an hour, different for another model or hour
+        let created = 1_760_000_400;
+        assert_eq!(
+            seed_fingerprint(42, "claude", created),
+            seed_fingerprint(42, "claude", 1_760_000_500)
+        );
+        assert_ne!(seed_fingerprint(42, "claude", created), seed_fingerprint(42, "opus", created));
+        assert_ne!(
+            seed_fingerprint(42, "claude", created),
+            seed_fingerprint(42, "claude", created + 3600)
+        );
+    }
+
+    #[test]
+    fn test_stream_parallel_tool_calls() {
+        let converter = AnthropicToOpenAiConverter::new(LogLevel::Info);
+        let events = [
+            r#"{"type":"content_block_start","content_block":{"type":"tool_use","id":"toolu_1","name":"get_weather"}}"#,
+            r#"{"type":"content_block_delta","delta":{"type":"input_json_delta","partial_json":"{\"city\":\"Paris\"}"}}"#,
+            r#"{"type":"content_block_stop"}"#,
+            r#"{"type":"content_block_start","content_block":{"type":"tool_use","id":"toolu_2","name":"get_weather"}}"#,
+            r#"{"type":"content_block_delta","delta":{"type":"input_json_delta","partial_json":"{\"city\":\"Oslo\"}"}}"#,
+            r#"{"type":"content_block_stop"}"#,
+        ];
+        let mut state = StreamingState::default();
+
+        let mut indexes = Vec::new();
+        for event in events {
+            let event: AnthropicStreamEvent = serde_json::from_str(event).unwrap();
+            let chunk = converter.convert_stream_event(&event, "claude", &mut state);
+            if let Some(calls) = chunk.as_ref().and_then(|c| c.choices[0].delta.tool_calls.as_ref())
+            {
+                indexes.push(calls[0].index);
+            }
+        }
+        assert_eq!(indexes, [0, 0, 1, 1]);
+        assert_eq!(state.tool_calls.len(), 2);
+        assert_eq!(state.tool_calls[0].arguments, r#"{"city":"Paris"}"#);
+        assert_eq!(state.tool_calls[1].arguments, r#"{"city":"Oslo"}"#);
+
+        let stop: AnthropicStreamEvent =
+            serde_json::from_str(r#"{"type":"message_stop","stop_reason":"tool_use"}"#).unwrap();
+        let finish = converter.convert_stream_event(&stop, "claude", &mut state).unwrap();
+        assert_eq!(finish.choices[0].finish_reason.as_deref(), Some("tool_calls"));
+        assert!(state.tool_calls.is_empty());
+    }
+
+    #[test]
+    fn test_legacy_function_call() {
+        let converter = AnthropicToOpenAiConverter::new(LogLevel::Info);
+        let response: AnthropicResponse = serde_json::from_str(
+            r#"{"content":[
+                {"type":"tool_use","id":"toolu_1","name":"get_weather","input":{"city":"Paris"}},
+                {"type":"tool_use","id":"toolu_2","name":"get_weather","input":{"city":"Oslo"}}
+            ],"stop_reason":"tool_use","usage":null}"#,
+        )
+        .unwrap();
+        let mut converted = converter.convert(response, "claude");
+        converter.apply_legacy_function_call(&mut converted);
+
+        let json = serde_json::to_value(&converted).unwrap();
+        let choice = &json["choices"][0];
+        assert_eq!(choice["finish_reason"], "function_call");
+        assert!(choice["message"].get("tool_calls").is_none());
+        assert_eq!(
+            choice["message"]["function_call"],
+            serde_json::json!({"name": "get_weather", "arguments": r#"{"city":"Paris"}"#})
+        );
+
+        let events = [
+            r#"{"type":"content_block_start","content_block":{"type":"tool_use","id":"toolu_1","name":"get_weather"}}"#,
+            r#"{"type":"content_block_delta","delta":{"type":"input_json_delta","partial_json":"{}"}}"#,
+            r#"{"type":"message_stop","stop_reason":"tool_use"}"#,
+        ];
+        let mut state = StreamingState { legacy_functions: true, ..Default::default() };
+        let chunks: Vec<serde_json::Value> = events
+            .iter()
+            .filter_map(|event| {
+                let event: AnthropicStreamEvent = serde_json::from_str(event).unwrap();
+                converter.convert_stream_event(&event, "claude", &mut state)
+            })
+            .map(|chunk| serde_json::to_value(&chunk).unwrap())
+            .collect();
+        assert!(
+            chunks.iter().all(|chunk| chunk["choices"][0]["delta"].get("tool_calls").is_none())
+        );
+        assert_eq!(
+            chunks[0]["choices"][0]["delta"]["function_call"],
+            serde_json::json!({"name": "get_weather", "arguments": ""})
+        );
+        assert_eq!(chunks[1]["choices"][0]["delta"]["function_call"]["arguments"], "{}");
+        assert_eq!(chunks[2]["choices"][0]["finish_reason"], "function_call");
+    }
+
+    #[test]
+    fn test_stream_usage_chunk() {
+        let converter = AnthropicToOpenAiConverter::new(LogLevel::Info);
+        let events = [
+            r#"{"type":"message_start","message":{"usage":{"input_tokens":12,"output_tokens":1}}}"#,
+            r#"{"type":"content_block_delta","delta":{"type":"text_delta","text":"Hi"}}"#,
+            r#"{"type":"message_delta","delta":{"stop_reason":"end_turn"},"usage":{"output_tokens":5}}"#,
+            r#"{"type":"message_stop","stop_reason":null}"#,
+        ];
+        let mut state = StreamingState { include_usage: true, ..Default::default() };
+        for event in events {
+            let event: AnthropicStreamEvent = serde_json::from_str(event).unwrap();
+            converter.convert_stream_event(&event, "claude", &mut state);
+        }
+
+        let chunk = converter.create_usage_chunk("claude", &mut state).unwrap();
+        let json = serde_json::to_value(&chunk).unwrap();
+        assert_eq!(json["choices"], serde_json::json!([]));
+        assert_eq!(
+            json["usage"],
+            serde_json::json!({"prompt_tokens": 12, "completion_tokens": 5, "total_tokens": 17})
+        );
+        // Sent once, and never without include_usage
+        assert!(converter.create_usage_chunk("claude", &mut state).is_none());
+        let text = converter.create_text_chunk("Hi", "claude").unwrap();
+        assert!(serde_json::to_value(&text).unwrap().get("usage").is_none());
+    }
+
+    /// Writer collecting formatted log output for assertions.
+    #[derive(Clone, Default)]
+    struct CapturedLogs(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_convert_span_fields() {
+        use tracing_subscriber::fmt::format::FmtSpan;
+
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_max_level(tracing::Level::TRACE)
+            .with_span_events(FmtSpan::NEW)
+            .with_ansi(false)
+            .finish();
+
+        let response: AnthropicResponse = serde_json::from_str(
+            r#"{"content":[{"type":"text","text":"hi"}],"stop_reason":"end_turn","usage":null}"#,
+        )
+        .unwrap();
+        tracing::subscriber::with_default(subscriber, || {
+            AnthropicToOpenAiConverter::new(LogLevel::Info).convert(response, "claude");
+        });
+
+        let output = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        assert!(output.contains("convert{"), "{}", output);
+        assert!(output.contains("model=\"claude\""), "{}", output);
+        assert!(output.contains("content_blocks=1"), "{}", output);
+        assert!(output.contains("stop_reason=Some(\"end_turn\")"), "{}", output);
+    }
+}
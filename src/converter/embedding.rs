@@ -0,0 +1,375 @@
+//!
+//! OpenAI embeddings to Vertex AI text embedding converter.
+//!
+//! Converts OpenAI `POST /v1/embeddings` requests to the Vertex AI `predict`
+//! format of text embedding models such as `text-embedding-004`, and the
+//! predictions back to an OpenAI embedding list.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ProxyError, Result};
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// OpenAI embeddings request structure.
+#[derive(Debug, Deserialize)]
+pub struct OpenAiEmbeddingRequest {
+    /** model requested by the client, informational only */
+    pub model: Option<String>,
+    /** text, or batch of texts, to embed */
+    pub input: EmbeddingInput,
+    /** `float` (default) or `base64` */
+    #[serde(default)]
+    pub encoding_format: EncodingFormat,
+    /** number of dimensions of the returned embeddings */
+    pub dimensions: Option<u32>,
+}
+
+///
+/// Text input of an embeddings request.
+///
+/// Token arrays are not supported because Vertex AI embeds text only.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum EmbeddingInput {
+    /** single text */
+    Single(String),
+    /** batch of texts, embedded in order */
+    Batch(Vec<String>),
+}
+
+///
+/// Encoding of the returned embedding vectors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EncodingFormat {
+    /** JSON array of floats */
+    #[default]
+    Float,
+    /** base64 of little-endian 32-bit floats */
+    Base64,
+}
+
+///
+/// Vertex AI `predict` request for text embedding models.
+#[derive(Debug, Serialize)]
+pub struct VertexEmbeddingRequest {
+    /** one instance per input text */
+    pub instances: Vec<VertexEmbeddingInstance>,
+    /** prediction parameters, omitted when empty */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<VertexEmbeddingParameters>,
+}
+
+///
+/// Single text of a Vertex AI embedding request.
+#[derive(Debug, Serialize)]
+pub struct VertexEmbeddingInstance {
+    /** text to embed */
+    pub content: String,
+}
+
+///
+/// Parameters of a Vertex AI embedding request.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VertexEmbeddingParameters {
+    /** number of dimensions of the returned embeddings */
+    pub output_dimensionality: u32,
+}
+
+///
+/// Vertex AI `predict` response of text embedding models.
+#[derive(Debug, Deserialize)]
+pub struct VertexEmbeddingResponse {
+    /** one prediction per request instance, in order */
+    #[serde(default)]
+    pub predictions: Vec<VertexEmbeddingPrediction>,
+}
+
+///
+/// Single prediction of a Vertex AI embedding response.
+#[derive(Debug, Deserialize)]
+pub struct VertexEmbeddingPrediction {
+    /** embedding of the instance */
+    pub embeddings: VertexEmbedding,
+}
+
+///
+/// Embedding vector of one Vertex AI prediction.
+#[derive(Debug, Deserialize)]
+pub struct VertexEmbedding {
+    /** embedding vector */
+    pub values: Vec<f32>,
+    /** token statistics of the input */
+    pub statistics: Option<VertexEmbeddingStatistics>,
+}
+
+///
+/// Token statistics of one Vertex AI embedding.
+#[derive(Debug, Deserialize)]
+pub struct VertexEmbeddingStatistics {
+    /** tokens in the input text */
+    #[serde(default)]
+    pub token_count: f64,
+}
+
+///
+/// OpenAI embeddings response structure.
+#[derive(Debug, Serialize)]
+pub struct OpenAiEmbeddingResponse {
+    /** always `list` */
+    pub object: &'static str,
+    /** one embedding per input, in input order */
+    pub data: Vec<OpenAiEmbedding>,
+    /** Vertex AI model that produced the embeddings */
+    pub model: String,
+    /** token usage of the inputs */
+    pub usage: OpenAiEmbeddingUsage,
+}
+
+///
+/// Single embedding of an OpenAI embeddings response.
+#[derive(Debug, Serialize)]
+pub struct OpenAiEmbedding {
+    /** always `embedding` */
+    pub object: &'static str,
+    /** position of the input */
+    pub index: usize,
+    /** embedding vector in the requested encoding */
+    pub embedding: EmbeddingVector,
+}
+
+///
+/// Embedding vector in the requested encoding.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum EmbeddingVector {
+    /** JSON array of floats */
+    Float(Vec<f32>),
+    /** base64 of little-endian 32-bit floats */
+    Base64(String),
+}
+
+///
+/// Token usage of an OpenAI embeddings response.
+#[derive(Debug, Serialize)]
+pub struct OpenAiEmbeddingUsage {
+    /** tokens in all inputs */
+    pub prompt_tokens: u64,
+    /** same as `prompt_tokens`, embeddings generate no tokens */
+    pub total_tokens: u64,
+}
+
+///
+/// Converter between OpenAI embeddings and Vertex AI text embedding predictions.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmbeddingConverter;
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+impl EmbeddingInput {
+    ///
+    /// Input texts in order.
+    pub fn into_texts(self) -> Vec<String> {
+        match self {
+            EmbeddingInput::Single(text) => vec![text],
+            EmbeddingInput::Batch(texts) => texts,
+        }
+    }
+}
+
+impl EmbeddingConverter {
+    ///
+    /// Create a new embedding converter.
+    ///
+    /// # Returns
+    ///  * Embedding converter
+    pub fn new() -> Self {
+        Self
+    }
+
+    ///
+    /// Convert an OpenAI embeddings request to a Vertex AI `predict` request.
+    ///
+    /// # Arguments
+    ///  * `request` - OpenAI embeddings request
+    ///
+    /// # Returns
+    ///  * Vertex AI request with one instance per input text
+    ///  * `ProxyError::Conversion` when the input is empty
+    pub fn convert_request(
+        &self,
+        request: OpenAiEmbeddingRequest,
+    ) -> Result<VertexEmbeddingRequest> {
+        let texts = request.input.into_texts();
+        if texts.is_empty() {
+            return Err(ProxyError::Conversion("Embeddings input must not be empty".to_string()));
+        }
+
+        Ok(VertexEmbeddingRequest {
+            instances: texts
+                .into_iter()
+                .map(|content| VertexEmbeddingInstance { content })
+                .collect(),
+            parameters: request
+                .dimensions
+                .map(|output_dimensionality| VertexEmbeddingParameters { output_dimensionality }),
+        })
+    }
+
+    ///
+    /// Convert a Vertex AI embedding response to an OpenAI embeddings response.
+    ///
+    /// # Arguments
+    ///  * `response` - Vertex AI `predict` response
+    ///  * `model` - Vertex AI embedding model that served the request
+    ///  * `expected` - number of input texts sent
+    ///  * `encoding_format` - encoding requested by the client
+    ///
+    /// # Returns
+    ///  * OpenAI embeddings response
+    ///  * `ProxyError::Conversion` when the number of predictions does not match the inputs
+    pub fn convert_response(
+        &self,
+        response: VertexEmbeddingResponse,
+        model: &str,
+        expected: usize,
+        encoding_format: EncodingFormat,
+    ) -> Result<OpenAiEmbeddingResponse> {
+        if response.predictions.len() != expected {
+            return Err(ProxyError::Conversion(format!(
+                "Vertex AI returned {} embeddings for {} inputs",
+                response.predictions.len(),
+                expected
+            )));
+        }
+
+        let mut prompt_tokens = 0;
+        let data = response
+            .predictions
+            .into_iter()
+            .enumerate()
+            .map(|(index, prediction)| {
+                let embeddings = prediction.embeddings;
+                prompt_tokens +=
+                    embeddings.statistics.map(|s| s.token_count as u64).unwrap_or_default();
+                OpenAiEmbedding {
+                    object: "embedding",
+                    index,
+                    embedding: encode_vector(embeddings.values, encoding_format),
+                }
+            })
+            .collect();
+
+        Ok(OpenAiEmbeddingResponse {
+            object: "list",
+            data,
+            model: model.to_string(),
+            usage: OpenAiEmbeddingUsage { prompt_tokens, total_tokens: prompt_tokens },
+        })
+    }
+}
+
+///
+/// Encode an embedding vector in the requested format.
+fn encode_vector(values: Vec<f32>, encoding_format: EncodingFormat) -> EmbeddingVector {
+    match encoding_format {
+        EncodingFormat::Float => EmbeddingVector::Float(values),
+        EncodingFormat::Base64 => {
+            let bytes: Vec<u8> = values.iter().flat_map(|value| value.to_le_bytes()).collect();
+            EmbeddingVector::Base64(BASE64.encode(bytes))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Vertex AI response with one embedding per text, `[i, i + 0.5]` with `i + 1` tokens
+    fn vertex_response(count: usize) -> VertexEmbeddingResponse {
+        let predictions: Vec<_> = (0..count)
+            .map(|i| {
+                json!({"embeddings": {
+                    "values": [i as f32, i as f32 + 0.5],
+                    "statistics": {"token_count": i + 1, "truncated": false}
+                }})
+            })
+            .collect();
+        serde_json::from_value(json!({ "predictions": predictions })).unwrap()
+    }
+
+    #[test]
+    fn test_single_input_roundtrip() {
+        let converter = EmbeddingConverter::new();
+        let request: OpenAiEmbeddingRequest = serde_json::from_value(json!({
+            "model": "text-embedding-3-small", "input": "hello"
+        }))
+        .unwrap();
+        let format = request.encoding_format;
+
+        let vertex = converter.convert_request(request).unwrap();
+        assert_eq!(
+            serde_json::to_value(&vertex).unwrap(),
+            json!({"instances": [{"content": "hello"}]})
+        );
+
+        let response = converter
+            .convert_response(vertex_response(1), "text-embedding-004", 1, format)
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            json!({
+                "object": "list",
+                "data": [{"object": "embedding", "index": 0, "embedding": [0.0, 0.5]}],
+                "model": "text-embedding-004",
+                "usage": {"prompt_tokens": 1, "total_tokens": 1}
+            })
+        );
+    }
+
+    #[test]
+    fn test_batch_input_roundtrip() {
+        let converter = EmbeddingConverter::new();
+        let request: OpenAiEmbeddingRequest = serde_json::from_value(json!({
+            "input": ["a", "b", "c"], "encoding_format": "base64", "dimensions": 2
+        }))
+        .unwrap();
+        let format = request.encoding_format;
+
+        let vertex = converter.convert_request(request).unwrap();
+        assert_eq!(
+            serde_json::to_value(&vertex).unwrap(),
+            json!({
+                "instances": [{"content": "a"}, {"content": "b"}, {"content": "c"}],
+                "parameters": {"outputDimensionality": 2}
+            })
+        );
+
+        let response = converter
+            .convert_response(vertex_response(3), "text-embedding-004", 3, format)
+            .unwrap();
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["data"].as_array().unwrap().len(), 3);
+        assert_eq!(json["data"][2]["index"], 2);
+        let bytes = BASE64.decode(json["data"][2]["embedding"].as_str().unwrap()).unwrap();
+        assert_eq!(bytes, [2.0f32.to_le_bytes(), 2.5f32.to_le_bytes()].concat());
+        assert_eq!(json["usage"]["prompt_tokens"], 6);
+
+        assert!(converter.convert_response(vertex_response(2), "m", 3, format).is_err());
+        let empty: OpenAiEmbeddingRequest = serde_json::from_value(json!({"input": []})).unwrap();
+        assert!(converter.convert_request(empty).is_err());
+    }
+}
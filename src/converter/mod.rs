@@ -1,7 +1,8 @@
 //!
 //! Format conversion modules for OpenAI and Anthropic API compatibility.
 //!
-//! Handles bidirectional conversion between OpenAI and Anthropic/Vertex AI formats.
+//! Handles bidirectional conversion between OpenAI and Anthropic/Vertex AI formats,
+//! and between OpenAI and Ollama for local models.
 //! Each converter follows Single Responsibility Principle and focuses on a specific
 //! conversion direction.
 //!
@@ -13,9 +14,18 @@
 /* --- modules --------------------------------------------------------------------------------- */
 
 pub mod anthropic_to_openai;
+pub mod embedding;
+pub mod normalizer;
 pub mod openai_to_anthropic;
+pub mod openai_to_ollama;
+pub mod schema;
+pub mod token_counter;
 
 /* --- start of code -------------------------------------------------------------------------- */
 
-pub use anthropic_to_openai::AnthropicToOpenAiConverter;
-pub use openai_to_anthropic::OpenAiToAnthropicConverter;
+pub use anthropic_to_openai::{AnthropicToOpenAiConverter, parse_batch_jsonl};
+pub use embedding::EmbeddingConverter;
+pub use normalizer::ResponseNormalizer;
+pub use openai_to_anthropic::{OpenAiToAnthropicConverter, TargetFormat};
+pub use openai_to_ollama::OllamaConverter;
+pub use token_counter::ContextWindowChecker;
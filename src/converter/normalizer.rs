@@ -0,0 +1,137 @@
+//!
+//! Response normalization for clients with strict OpenAI response parsing.
+//!
+//! Some clients (Copilot proxies, Continue.dev) expect small variations of the
+//! OpenAI response format. `ResponseNormalizer` applies the adjustments
+//! configured in `[converter.response_normalization]` to complete responses
+//! after conversion, so the converters themselves stay format-faithful.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use super::anthropic_to_openai::{OpenAiChoice, OpenAiResponse, OpenAiResponseMessage};
+use crate::config::ResponseNormalizationConfig;
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// Applies configured adjustments to complete OpenAI responses.
+#[derive(Debug, Clone, Default)]
+pub struct ResponseNormalizer {
+    /** normalization options */
+    config: ResponseNormalizationConfig,
+}
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** `created` values above this are taken to be milliseconds (year 5138 in seconds) */
+const MAX_CREATED_SECONDS: i64 = 100_000_000_000;
+
+/** Role of the choice added to empty responses */
+const ASSISTANT_ROLE: &str = "assistant";
+
+/** Finish reason of the choice added to empty responses */
+const STOP_FINISH_REASON: &str = "stop";
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+impl ResponseNormalizer {
+    ///
+    /// Create a normalizer from configuration.
+    ///
+    /// # Arguments
+    ///  * `config` - `[converter.response_normalization]` options
+    ///
+    /// # Returns
+    ///  * New normalizer instance
+    pub fn new(config: &ResponseNormalizationConfig) -> Self {
+        Self { config: config.clone() }
+    }
+
+    ///
+    /// Apply the configured adjustments to a response.
+    ///
+    /// # Arguments
+    ///  * `response` - converted OpenAI response, modified in place
+    pub fn normalize(&self, response: &mut OpenAiResponse) {
+        if let Some(fingerprint) = &self.config.set_system_fingerprint {
+            response.system_fingerprint = Some(fingerprint.clone());
+        }
+        if let Some(object) = &self.config.force_object_type {
+            response.object = object.clone();
+        }
+        if self.config.ensure_choices_not_empty && response.choices.is_empty() {
+            response.choices.push(OpenAiChoice {
+                index: 0,
+                message: OpenAiResponseMessage {
+                    role: ASSISTANT_ROLE.to_string(),
+                    content: Some(String::new()),
+                    tool_calls: None,
+                    function_call: None,
+                    reasoning_content: None,
+                },
+                finish_reason: STOP_FINISH_REASON.to_string(),
+            });
+        }
+        if self.config.round_created_to_seconds && response.created > MAX_CREATED_SECONDS {
+            response.created /= 1000;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converter::anthropic_to_openai::OpenAiUsage;
+
+    fn response(created: i64) -> OpenAiResponse {
+        OpenAiResponse {
+            id: "chatcmpl-1".to_string(),
+            object: "chat.completion".to_string(),
+            created,
+            model: "claude".to_string(),
+            choices: Vec::new(),
+            usage: OpenAiUsage { prompt_tokens: 1, completion_tokens: 0, total_tokens: 1 },
+            system_fingerprint: None,
+        }
+    }
+
+    #[test]
+    fn test_default_leaves_response_unchanged() {
+        let mut normalized = response(1_706_000_000_123);
+        ResponseNormalizer::default().normalize(&mut normalized);
+
+        assert_eq!(normalized.object, "chat.completion");
+        assert_eq!(normalized.created, 1_706_000_000_123);
+        assert!(normalized.choices.is_empty());
+        assert!(normalized.system_fingerprint.is_none());
+    }
+
+    #[test]
+    fn test_all_adjustments() {
+        let normalizer = ResponseNormalizer::new(&ResponseNormalizationConfig {
+            set_system_fingerprint: Some("fp_modelmux".to_string()),
+            force_object_type: Some("chat.completion.v2".to_string()),
+            ensure_choices_not_empty: true,
+            round_created_to_seconds: true,
+        });
+
+        let mut normalized = response(1_706_000_000_123);
+        normalizer.normalize(&mut normalized);
+        assert_eq!(normalized.system_fingerprint.as_deref(), Some("fp_modelmux"));
+        assert_eq!(normalized.object, "chat.completion.v2");
+        assert_eq!(normalized.created, 1_706_000_000);
+        assert_eq!(normalized.choices.len(), 1);
+        assert_eq!(normalized.choices[0].message.content.as_deref(), Some(""));
+        assert_eq!(normalized.choices[0].finish_reason, "stop");
+
+        // Timestamps already in seconds are kept
+        let mut seconds = response(1_706_000_000);
+        normalizer.normalize(&mut seconds);
+        assert_eq!(seconds.created, 1_706_000_000);
+    }
+}
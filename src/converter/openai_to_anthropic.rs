@@ -15,7 +15,7 @@
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
-use crate::config::LogLevel;
+use crate::config::{LogLevel, ModelOverrides, PromptConfig, SystemPromptMode};
 use crate::error::{ProxyError, Result};
 
 /* --- helper functions ----------------------------------------------------------------------- */
@@ -37,6 +37,15 @@ fn skip_empty_tools(tools: &Option<Vec<AnthropicTool>>) -> bool {
     }
 }
 
+///
+/// Parameter schema of legacy functions defined without one.
+///
+/// # Returns
+///  * JSON schema of an object without properties
+fn empty_parameters_schema() -> serde_json::Value {
+    json!({ "type": "object", "properties": {} })
+}
+
 /* --- types ----------------------------------------------------------------------------------- */
 
 ///
@@ -54,12 +63,83 @@ pub struct OpenAiRequest {
     pub max_tokens: Option<u32>,
     /** sampling temperature for response generation */
     pub temperature: Option<f64>,
+    /** nucleus sampling probability mass */
+    pub top_p: Option<f64>,
+    /** sample only from the top K tokens (Anthropic extension) */
+    pub top_k: Option<u32>,
+    /** sequences that stop generation */
+    pub stop: Option<OpenAiStop>,
+    /** number of completions to generate, each from its own upstream request */
+    pub n: Option<u32>,
     /** whether to stream the response */
     pub stream: Option<bool>,
+    /** streaming options, e.g. a final usage chunk */
+    pub stream_options: Option<StreamOptions>,
     /** available tools for function calling */
     pub tools: Option<Vec<OpenAiTool>>,
     /** tool choice configuration */
     pub tool_choice: Option<OpenAiToolChoice>,
+    /** legacy function definitions, used when `tools` is absent */
+    pub functions: Option<Vec<OpenAiFunctionDef>>,
+    /** legacy function choice, used when `tool_choice` is absent */
+    pub function_call: Option<OpenAiFunctionCallChoice>,
+    /** requested output format, e.g. JSON */
+    pub response_format: Option<ResponseFormat>,
+    /** seed for deterministic sampling; Anthropic has none, so only logged and fingerprinted */
+    pub seed: Option<u64>,
+    /** comma-separated Anthropic beta features, e.g. `interleaved-thinking-2025-05-14` */
+    pub x_anthropic_beta: Option<String>,
+}
+
+///
+/// OpenAI `stream_options` of a streaming chat completion request.
+#[derive(Debug, Deserialize)]
+pub struct StreamOptions {
+    /** send a final chunk with the token usage before `[DONE]` */
+    #[serde(default)]
+    pub include_usage: bool,
+}
+
+///
+/// OpenAI `stop` parameter: a single sequence or a list of them.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum OpenAiStop {
+    /** single stop sequence */
+    Single(String),
+    /** several stop sequences */
+    Multiple(Vec<String>),
+}
+
+///
+/// OpenAI `response_format` of a chat completion request.
+///
+/// Anthropic has no equivalent field, so JSON formats are requested with an
+/// instruction appended to the system prompt.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseFormat {
+    /** free-form text, the default */
+    Text,
+    /** any valid JSON */
+    JsonObject,
+    /** JSON matching a schema */
+    JsonSchema {
+        /** schema the response must match */
+        json_schema: JsonSchemaFormat,
+    },
+}
+
+///
+/// Schema of a `json_schema` response format.
+#[derive(Debug, Deserialize)]
+pub struct JsonSchemaFormat {
+    /** name of the schema */
+    pub name: Option<String>,
+    /** what the response is for */
+    pub description: Option<String>,
+    /** JSON Schema of the response */
+    pub schema: Option<serde_json::Value>,
 }
 
 ///
@@ -78,6 +158,8 @@ pub struct OpenAiMessage {
     /** tool call ID for tool response messages */
     #[serde(rename = "tool_call_id")]
     pub tool_call_id: Option<String>,
+    /** legacy function call made by the assistant */
+    pub function_call: Option<OpenAiFunction>,
 }
 
 ///
@@ -109,6 +191,8 @@ pub struct OpenAiContentBlock {
     /** image URL reference for image blocks */
     #[serde(rename = "image_url")]
     pub image_url: Option<ImageUrl>,
+    /** inline file data for `image` and `document` blocks */
+    pub source: Option<Base64Source>,
 }
 
 ///
@@ -121,6 +205,19 @@ pub struct ImageUrl {
     pub url: String,
 }
 
+///
+/// Inline base64 file data of `image` and `document` content blocks.
+///
+/// Follows the Anthropic `source` format, so uploaded files pass the
+/// conversion unchanged.
+#[derive(Debug, Deserialize)]
+pub struct Base64Source {
+    /** MIME type of the file, e.g. `application/pdf` */
+    pub media_type: String,
+    /** base64-encoded file content */
+    pub data: String,
+}
+
 ///
 /// OpenAI tool call structure for function invocations.
 ///
@@ -214,6 +311,35 @@ pub struct OpenAiToolChoiceFunction {
     pub name: String,
 }
 
+///
+/// Legacy OpenAI function definition of the `functions` parameter.
+///
+/// Predates `tools`; converted to a function tool.
+#[derive(Debug, Deserialize)]
+pub struct OpenAiFunctionDef {
+    /** function name */
+    pub name: String,
+    /** function description */
+    #[serde(default)]
+    pub description: String,
+    /** JSON schema for function parameters */
+    #[serde(default = "empty_parameters_schema")]
+    pub parameters: serde_json::Value,
+}
+
+///
+/// Legacy OpenAI `function_call` parameter.
+///
+/// Predates `tool_choice`; converted to a tool choice.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum OpenAiFunctionCallChoice {
+    /** mode: "auto" or "none" */
+    Mode(String),
+    /** specific function to call */
+    Function(OpenAiToolChoiceFunction),
+}
+
 ///
 /// Anthropic chat completion request structure.
 ///
@@ -226,11 +352,23 @@ pub struct AnthropicRequest {
     pub anthropic_version: String,
     /** conversation messages in Anthropic format */
     pub messages: Vec<AnthropicMessage>,
+    /** system prompt joined from the OpenAI system messages */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
     /** maximum tokens to generate */
     #[serde(rename = "max_tokens")]
     pub max_tokens: u32,
     /** sampling temperature */
     pub temperature: f64,
+    /** nucleus sampling probability mass */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    /** sample only from the top K tokens */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_k: Option<u32>,
+    /** sequences that stop generation */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_sequences: Option<Vec<String>>,
     /** whether to stream the response */
     pub stream: bool,
     /** available tools in Anthropic format */
@@ -239,6 +377,21 @@ pub struct AnthropicRequest {
     /** tool choice configuration in Anthropic format */
     #[serde(rename = "tool_choice", skip_serializing_if = "Option::is_none")]
     pub tool_choice: Option<AnthropicToolChoice>,
+    /** sampling seed, for when the Anthropic API supports one; never set until then */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /** extra top-level fields merged into the request body when sent */
+    #[serde(skip)]
+    pub extra_fields: serde_json::Map<String, serde_json::Value>,
+    /** whether the client used the legacy `functions` API instead of `tools` */
+    #[serde(skip)]
+    pub used_legacy_functions: bool,
+    /** `seed` of the OpenAI request, for the response `system_fingerprint` */
+    #[serde(skip)]
+    pub requested_seed: Option<u64>,
+    /** Anthropic beta features, sent in the `anthropic-beta` header (Bedrock: in the body) */
+    #[serde(skip)]
+    pub anthropic_beta: Option<Vec<String>>,
 }
 
 ///
@@ -292,6 +445,12 @@ pub enum AnthropicContentBlock {
         /** image source information */
         source: ImageSource,
     },
+    /** document (PDF) content block */
+    #[serde(rename = "document")]
+    Document {
+        /** document source information */
+        source: ImageSource,
+    },
 }
 
 ///
@@ -309,16 +468,24 @@ pub enum AnthropicToolResultContent {
 }
 
 ///
-/// Image source information for Anthropic image blocks.
+/// Source information for Anthropic image and document blocks.
 ///
-/// Contains metadata about image resources.
+/// Either references a URL (`url` source type) or carries the file inline
+/// (`base64` source type).
 #[derive(Debug, Serialize)]
 pub struct ImageSource {
     /** source type identifier */
     #[serde(rename = "type")]
     pub source_type: String,
-    /** image URL */
-    pub url: String,
+    /** image URL of `url` sources */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /** MIME type of `base64` sources */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+    /** file content of `base64` sources */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
 }
 
 ///
@@ -354,6 +521,21 @@ pub enum AnthropicToolChoice {
     },
 }
 
+///
+/// Upstream API the converted request is sent to.
+///
+/// Vertex AI and AWS Bedrock both take Anthropic's Messages format but
+/// expect different `anthropic_version` values, and Bedrock selects
+/// streaming by endpoint instead of the `stream` field.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TargetFormat {
+    /** Vertex AI `rawPredict` / `streamRawPredict` */
+    #[default]
+    Vertex,
+    /** AWS Bedrock `invoke` / `invoke-with-response-stream` */
+    Bedrock,
+}
+
 ///
 /// Converter from OpenAI format to Anthropic format.
 ///
@@ -362,12 +544,17 @@ pub enum AnthropicToolChoice {
 pub struct OpenAiToAnthropicConverter {
     /** logging level for debug output */
     log_level: LogLevel,
+    /** system prompt enforced by the operator */
+    prompt: PromptConfig,
 }
 
 /* --- constants ------------------------------------------------------------------------------ */
 
-/** Anthropic API version to use for requests */
-const ANTHROPIC_VERSION: &str = "vertex-2023-10-16";
+/** Anthropic API version of requests to Vertex AI */
+const VERTEX_ANTHROPIC_VERSION: &str = "vertex-2023-10-16";
+
+/** Anthropic API version of requests to AWS Bedrock */
+const BEDROCK_ANTHROPIC_VERSION: &str = "bedrock-2023-05-31";
 
 /** Default maximum tokens if not specified */
 const DEFAULT_MAX_TOKENS: u32 = 8000;
@@ -375,6 +562,15 @@ const DEFAULT_MAX_TOKENS: u32 = 8000;
 /** Default temperature if not specified */
 const DEFAULT_TEMPERATURE: f64 = 0.9;
 
+/** System prompt instruction for JSON response formats */
+const JSON_RESPONSE_INSTRUCTION: &str = "You must respond with valid JSON only, no prose.";
+
+/** Tool call ID prefix of legacy function calls, which have no IDs */
+const LEGACY_FUNCTION_CALL_ID_PREFIX: &str = "call_legacy_";
+
+/** Request fields that extra fields may not override */
+pub const RESERVED_ANTHROPIC_FIELDS: &[&str] = &["max_tokens", "messages"];
+
 /* --- start of code -------------------------------------------------------------------------- */
 
 impl OpenAiToAnthropicConverter {
@@ -387,7 +583,23 @@ impl OpenAiToAnthropicConverter {
     /// # Returns
     ///  * New converter instance
     pub fn new(log_level: LogLevel) -> Self {
-        Self { log_level }
+        Self { log_level, prompt: PromptConfig::default() }
+    }
+
+    ///
+    /// Add a configured system prompt to every request.
+    ///
+    /// The template is combined with the client's system prompt as set by
+    /// `system_prompt_mode`; without a template the client's is kept as is.
+    ///
+    /// # Arguments
+    ///  * `prompt` - system prompt template and mode
+    ///
+    /// # Returns
+    ///  * Converter applying the system prompt template
+    pub fn with_system_prompt(mut self, prompt: PromptConfig) -> Self {
+        self.prompt = prompt;
+        self
     }
 
     ///
@@ -395,20 +607,39 @@ impl OpenAiToAnthropicConverter {
     ///
     /// Transforms the entire request structure including messages, tools, and
     /// configuration parameters. Handles system messages, tool calls, and
-    /// multimodal content appropriately.
+    /// multimodal content appropriately. `overrides` of the requested model
+    /// are applied to the converted request last.
     ///
     /// # Arguments
     ///  * `request` - OpenAI format request to convert
+    ///  * `target` - upstream API, selects the `anthropic_version`
+    ///  * `overrides` - `[models]` entry of the requested model, if any
     ///
     /// # Returns
     ///  * Converted Anthropic format request
     ///  * `ProxyError::Conversion` if conversion fails
-    pub fn convert(&self, request: OpenAiRequest) -> Result<AnthropicRequest> {
+    #[tracing::instrument(
+        skip(self, request),
+        fields(
+            message_count = request.messages.len(),
+            has_tools = request.tools.is_some(),
+            model = ?request.model
+        )
+    )]
+    pub fn convert(
+        &self,
+        mut request: OpenAiRequest,
+        target: TargetFormat,
+        overrides: Option<&ModelOverrides>,
+    ) -> Result<AnthropicRequest> {
         self.debug(&format!(
             "Converting {} message(s) from OpenAI to Anthropic format",
             request.messages.len()
         ));
 
+        let used_legacy_functions = request.tools.is_none() && request.functions.is_some();
+        self.convert_legacy_functions(&mut request);
+
         let mut anthropic_messages = Vec::new();
         let mut pending_tool_results = Vec::new();
         let mut last_assistant_message: Option<&'_ OpenAiMessage> = None;
@@ -428,20 +659,40 @@ impl OpenAiToAnthropicConverter {
             last_assistant_message,
         )?;
 
-        self.prepend_system_messages(&mut anthropic_messages, system_messages);
+        let user_system = self.join_system_messages(system_messages);
+        let mut system = self.apply_system_prompt(user_system, request.model.as_deref());
+        if let Some(instruction) = self.response_format_instruction(request.response_format) {
+            system = Some(match system {
+                Some(prompt) => format!("{}\n\n{}", prompt, instruction),
+                None => instruction,
+            });
+        }
 
         let tools = self.convert_tools(request.tools);
         let tool_choice = self.convert_tool_choice(request.tool_choice);
 
-        let anthropic_request = AnthropicRequest {
-            anthropic_version: ANTHROPIC_VERSION.to_string(),
+        let mut anthropic_request = AnthropicRequest {
+            anthropic_version: target.anthropic_version().to_string(),
             messages: anthropic_messages,
+            system,
             max_tokens: request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
             temperature: request.temperature.unwrap_or(DEFAULT_TEMPERATURE),
+            top_p: request.top_p,
+            top_k: request.top_k,
+            stop_sequences: request.stop.and_then(OpenAiStop::into_sequences),
             stream: request.stream.unwrap_or(false),
             tools,
             tool_choice,
+            // Vertex AI rejects unknown fields, so the seed is not sent upstream
+            seed: None,
+            extra_fields: serde_json::Map::new(),
+            used_legacy_functions,
+            requested_seed: request.seed,
+            anthropic_beta: request.x_anthropic_beta.as_deref().and_then(parse_anthropic_beta),
         };
+        if let Some(overrides) = overrides {
+            self.apply_model_overrides(&mut anthropic_request, overrides);
+        }
 
         self.debug(&format!(
             "Converted Anthropic request with {} messages",
@@ -451,6 +702,66 @@ impl OpenAiToAnthropicConverter {
         Ok(anthropic_request)
     }
 
+    ///
+    /// Rewrite the legacy function calling API as tools.
+    ///
+    /// `functions` and `function_call` fill in absent `tools` and
+    /// `tool_choice`. Assistant `function_call`s become tool calls and
+    /// `function` messages their results; legacy calls have no IDs, so each
+    /// result is paired with the latest call by a synthesized ID.
+    ///
+    /// # Arguments
+    ///  * `request` - OpenAI request to rewrite in place
+    fn convert_legacy_functions(&self, request: &mut OpenAiRequest) {
+        if request.tools.is_none()
+            && let Some(functions) = request.functions.take()
+        {
+            self.debug(&format!("Converting {} legacy function(s) to tools", functions.len()));
+            request.tools = Some(
+                functions
+                    .into_iter()
+                    .map(|function| OpenAiTool {
+                        tool_type: "function".to_string(),
+                        function: OpenAiToolFunction {
+                            name: function.name,
+                            description: function.description,
+                            parameters: function.parameters,
+                        },
+                    })
+                    .collect(),
+            );
+        }
+        if request.tool_choice.is_none()
+            && let Some(function_call) = request.function_call.take()
+        {
+            request.tool_choice = Some(match function_call {
+                OpenAiFunctionCallChoice::Mode(mode) => OpenAiToolChoice::String(mode),
+                OpenAiFunctionCallChoice::Function(function) => {
+                    OpenAiToolChoice::Object(OpenAiToolChoiceObject {
+                        choice_type: "function".to_string(),
+                        function: Some(function),
+                    })
+                }
+            });
+        }
+
+        let mut last_call_id = None;
+        for (index, msg) in request.messages.iter_mut().enumerate() {
+            if let Some(function) = msg.function_call.take() {
+                let id = format!("{}{}", LEGACY_FUNCTION_CALL_ID_PREFIX, index);
+                msg.tool_calls.get_or_insert_with(Vec::new).push(OpenAiToolCall {
+                    id: id.clone(),
+                    call_type: "function".to_string(),
+                    function,
+                });
+                last_call_id = Some(id);
+            } else if msg.role == "function" {
+                msg.role = "tool".to_string();
+                msg.tool_call_id = last_call_id.clone();
+            }
+        }
+    }
+
     ///
     /// Process all messages in the OpenAI request.
     ///
@@ -666,20 +977,131 @@ impl OpenAiToAnthropicConverter {
     }
 
     ///
-    /// Prepend system messages to the first user message.
+    /// Join system messages into the top-level Anthropic system prompt.
+    ///
+    /// # Arguments
+    ///  * `system_messages` - system messages in request order
+    ///
+    /// # Returns
+    ///  * System prompt, or `None` when the request has no system messages
+    fn join_system_messages(&self, system_messages: Vec<String>) -> Option<String> {
+        if system_messages.is_empty() {
+            return None;
+        }
+        self.debug(&format!("Using {} system message(s) as system prompt", system_messages.len()));
+        Some(system_messages.join("\n\n"))
+    }
+
+    ///
+    /// Combine the client's system prompt with the configured template.
+    ///
+    /// `{user_system}` in the template is replaced by the client's prompt,
+    /// which is then not added again; `{model}` and `{timestamp}` by the
+    /// requested model and the current UTC time.
     ///
     /// # Arguments
-    ///  * `anthropic_messages` - output Anthropic messages to modify
-    ///  * `system_messages` - system messages to prepend
-    fn prepend_system_messages(
+    ///  * `user_system` - system prompt of the client, if any
+    ///  * `model` - requested model, if any
+    ///
+    /// # Returns
+    ///  * System prompt to send upstream
+    fn apply_system_prompt(
         &self,
-        anthropic_messages: &mut [AnthropicMessage],
-        system_messages: Vec<String>,
-    ) {
-        if !system_messages.is_empty() && !anthropic_messages.is_empty() {
-            let system_text = system_messages.join("\n\n");
-            if let Some(first_user_msg) = anthropic_messages.iter_mut().find(|m| m.role == "user") {
-                self.prepend_system_text(first_user_msg, &system_text);
+        user_system: Option<String>,
+        model: Option<&str>,
+    ) -> Option<String> {
+        let Some(template) = &self.prompt.system_prompt else {
+            return user_system;
+        };
+        let mode = self.prompt.system_prompt_mode;
+        let user_system = user_system.filter(|_| mode != SystemPromptMode::Replace);
+        let embeds_user_system = template.contains("{user_system}");
+        let rendered = template
+            .replace("{user_system}", user_system.as_deref().unwrap_or_default())
+            .replace("{model}", model.unwrap_or_default())
+            .replace(
+                "{timestamp}",
+                &chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            );
+        self.debug(&format!("Applying the configured system prompt ({:?})", mode));
+
+        let system = match user_system.filter(|_| !embeds_user_system) {
+            Some(user_system) if mode == SystemPromptMode::Append => {
+                format!("{}\n\n{}", user_system, rendered)
+            }
+            Some(user_system) => format!("{}\n\n{}", rendered, user_system),
+            None => rendered,
+        };
+        let system = system.trim();
+        (!system.is_empty()).then(|| system.to_string())
+    }
+
+    ///
+    /// Apply the `[models]` overrides of the requested model.
+    ///
+    /// `max_tokens` and `temperature` overrides replace the request's values;
+    /// `system_prompt_append` is added to the end of the system prompt.
+    ///
+    /// # Arguments
+    ///  * `request` - converted request, updated in place
+    ///  * `overrides` - overrides of the requested model
+    fn apply_model_overrides(&self, request: &mut AnthropicRequest, overrides: &ModelOverrides) {
+        if let Some(max_tokens) = overrides.max_tokens_override {
+            self.debug(&format!(
+                "Overriding max_tokens {} with {}",
+                request.max_tokens, max_tokens
+            ));
+            request.max_tokens = max_tokens;
+        }
+        if let Some(temperature) = overrides.temperature_override {
+            self.debug(&format!(
+                "Overriding temperature {} with {}",
+                request.temperature, temperature
+            ));
+            request.temperature = temperature;
+        }
+        if let Some(append) = overrides.system_prompt_append.as_deref().map(str::trim)
+            && !append.is_empty()
+        {
+            request.system = Some(match request.system.take() {
+                Some(system) => format!("{}\n\n{}", system, append),
+                None => append.to_string(),
+            });
+        }
+    }
+
+    ///
+    /// Build the system prompt instruction for a JSON response format.
+    ///
+    /// # Arguments
+    ///  * `response_format` - requested output format, if any
+    ///
+    /// # Returns
+    ///  * Instruction to append to the system prompt, or `None` for text output
+    fn response_format_instruction(
+        &self,
+        response_format: Option<ResponseFormat>,
+    ) -> Option<String> {
+        match response_format? {
+            ResponseFormat::Text => None,
+            ResponseFormat::JsonObject => {
+                self.debug("Requesting JSON output in the system prompt");
+                Some(JSON_RESPONSE_INSTRUCTION.to_string())
+            }
+            ResponseFormat::JsonSchema { json_schema } => {
+                self.debug("Requesting JSON output matching a schema in the system prompt");
+                let mut instruction = JSON_RESPONSE_INSTRUCTION.to_string();
+                if let Some(description) = &json_schema.description {
+                    instruction.push_str(&format!(" The JSON is {}.", description));
+                }
+                if let Some(schema) = &json_schema.schema {
+                    instruction.push_str(&format!(
+                        " It must match the JSON Schema{}: {}",
+                        json_schema.name.map(|name| format!(" `{}`", name)).unwrap_or_default(),
+                        schema
+                    ));
+                }
+                Some(instruction)
             }
         }
     }
@@ -881,8 +1303,21 @@ impl OpenAiToAnthropicConverter {
                     block.text.as_ref().map(|t| AnthropicContentBlock::Text { text: t.clone() })
                 }
                 "image_url" => block.image_url.as_ref().map(|img| AnthropicContentBlock::Image {
-                    source: ImageSource { source_type: "url".to_string(), url: img.url.clone() },
+                    source: ImageSource {
+                        source_type: "url".to_string(),
+                        url: Some(img.url.clone()),
+                        media_type: None,
+                        data: None,
+                    },
                 }),
+                "image" => block
+                    .source
+                    .as_ref()
+                    .map(|source| AnthropicContentBlock::Image { source: source.into() }),
+                "document" => block
+                    .source
+                    .as_ref()
+                    .map(|source| AnthropicContentBlock::Document { source: source.into() }),
                 _ => None,
             })
             .collect()
@@ -930,27 +1365,6 @@ impl OpenAiToAnthropicConverter {
         Ok(())
     }
 
-    ///
-    /// Prepend system text to the first text block of a message.
-    ///
-    /// Either modifies the first existing text block or inserts a new
-    /// text block at the beginning with the system content.
-    ///
-    /// # Arguments
-    ///  * `msg` - message to prepend system text to
-    ///  * `system_text` - system text to prepend
-    fn prepend_system_text(&self, msg: &mut AnthropicMessage, system_text: &str) {
-        if let Some(first_text_block) =
-            msg.content.iter_mut().find(|c| matches!(c, AnthropicContentBlock::Text { .. }))
-        {
-            if let AnthropicContentBlock::Text { text } = first_text_block {
-                *text = format!("{}\n\n{}", system_text, text);
-            }
-        } else {
-            msg.content.insert(0, AnthropicContentBlock::Text { text: system_text.to_string() });
-        }
-    }
-
     ///
     /// Log debug message if trace logging is enabled.
     ///
@@ -962,3 +1376,413 @@ impl OpenAiToAnthropicConverter {
         }
     }
 }
+
+impl From<&Base64Source> for ImageSource {
+    fn from(source: &Base64Source) -> Self {
+        Self {
+            source_type: "base64".to_string(),
+            url: None,
+            media_type: Some(source.media_type.clone()),
+            data: Some(source.data.clone()),
+        }
+    }
+}
+
+impl OpenAiStop {
+    ///
+    /// Stop sequences for Anthropic's `stop_sequences`.
+    ///
+    /// Empty strings are dropped because Anthropic rejects them.
+    ///
+    /// # Returns
+    ///  * Non-empty sequences, or `None` when there are none
+    pub fn into_sequences(self) -> Option<Vec<String>> {
+        let sequences: Vec<String> = match self {
+            OpenAiStop::Single(sequence) => vec![sequence],
+            OpenAiStop::Multiple(sequences) => sequences,
+        }
+        .into_iter()
+        .filter(|sequence| !sequence.is_empty())
+        .collect();
+        (!sequences.is_empty()).then_some(sequences)
+    }
+}
+
+impl TargetFormat {
+    ///
+    /// `anthropic_version` expected by the upstream API.
+    pub fn anthropic_version(self) -> &'static str {
+        match self {
+            Self::Vertex => VERTEX_ANTHROPIC_VERSION,
+            Self::Bedrock => BEDROCK_ANTHROPIC_VERSION,
+        }
+    }
+}
+
+impl AnthropicRequest {
+    ///
+    /// Serialize the request body with extra fields merged in.
+    ///
+    /// Extra fields are added at the top level and replace typed fields with
+    /// the same name (e.g. `anthropic_version`). Bedrock request bodies carry
+    /// no `stream` field, which Bedrock rejects, and the beta features as
+    /// `anthropic_beta`, because Bedrock ignores the `anthropic-beta` header.
+    ///
+    /// # Returns
+    ///  * JSON request body
+    ///  * `ProxyError::Serialization` if serialization fails
+    pub fn to_request_body(&self) -> Result<serde_json::Value> {
+        let mut body = serde_json::to_value(self)?;
+        if let Some(obj) = body.as_object_mut() {
+            if self.anthropic_version == BEDROCK_ANTHROPIC_VERSION {
+                obj.remove("stream");
+                if let Some(betas) = &self.anthropic_beta {
+                    obj.insert("anthropic_beta".to_string(), serde_json::json!(betas));
+                }
+            }
+            for (key, value) in &self.extra_fields {
+                obj.insert(key.clone(), value.clone());
+            }
+        }
+        Ok(body)
+    }
+}
+
+///
+/// Parse a comma-separated list of Anthropic beta features.
+///
+/// # Arguments
+///  * `value` - `anthropic-beta` header value, e.g. `a-2025-01-01, b-2025-02-02`
+///
+/// # Returns
+///  * Trimmed feature names without duplicates, `None` when there are none
+pub fn parse_anthropic_beta(value: &str) -> Option<Vec<String>> {
+    let mut betas: Vec<String> = Vec::new();
+    for beta in value.split(',').map(str::trim).filter(|beta| !beta.is_empty()) {
+        if !betas.iter().any(|known| known == beta) {
+            betas.push(beta.to_string());
+        }
+    }
+    (!betas.is_empty()).then_some(betas)
+}
+
+///
+/// Check extra Anthropic fields for keys that may not be overridden.
+///
+/// # Arguments
+///  * `fields` - extra fields to check
+///
+/// # Returns
+///  * `Ok(())` if no reserved field is present
+///  * `ProxyError::Conversion` naming the conflicting fields
+pub fn check_extra_fields(fields: &serde_json::Map<String, serde_json::Value>) -> Result<()> {
+    let conflicts: Vec<&str> =
+        RESERVED_ANTHROPIC_FIELDS.iter().copied().filter(|key| fields.contains_key(*key)).collect();
+    if conflicts.is_empty() {
+        Ok(())
+    } else {
+        Err(ProxyError::Conversion(format!(
+            "Extra Anthropic fields cannot override required fields: {}",
+            conflicts.join(", ")
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn convert(request: serde_json::Value) -> AnthropicRequest {
+        OpenAiToAnthropicConverter::new(LogLevel::Error)
+            .convert(serde_json::from_value(request).unwrap(), TargetFormat::Vertex, None)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_seed_is_kept_but_not_sent_upstream() {
+        let request = convert(json!({"messages": [{"role": "user", "content": "Hi"}], "seed": 42}));
+        assert_eq!(request.requested_seed, Some(42));
+        assert!(request.to_request_body().unwrap().get("seed").is_none());
+    }
+
+    #[test]
+    fn test_bedrock_target_format() {
+        let request = OpenAiToAnthropicConverter::new(LogLevel::Error)
+            .convert(
+                serde_json::from_value(json!({
+                    "messages": [{"role": "user", "content": "Hi"}],
+                    "stream": true
+                }))
+                .unwrap(),
+                TargetFormat::Bedrock,
+                None,
+            )
+            .unwrap();
+        assert!(request.stream);
+
+        let body = request.to_request_body().unwrap();
+        assert_eq!(body["anthropic_version"], "bedrock-2023-05-31");
+        assert!(body.get("stream").is_none());
+
+        let vertex = convert(json!({"messages": [{"role": "user", "content": "Hi"}]}));
+        let body = vertex.to_request_body().unwrap();
+        assert_eq!(body["anthropic_version"], "vertex-2023-10-16");
+        assert_eq!(body["stream"], false);
+    }
+
+    #[test]
+    fn test_anthropic_beta() {
+        assert_eq!(parse_anthropic_beta(" , "), None);
+        assert_eq!(
+            parse_anthropic_beta("a-2025-01-01, b-2025-02-02,a-2025-01-01"),
+            Some(vec!["a-2025-01-01".to_string(), "b-2025-02-02".to_string()])
+        );
+
+        // Vertex AI gets the betas in the header only
+        let vertex = convert(json!({
+            "messages": [{"role": "user", "content": "Hi"}],
+            "x_anthropic_beta": "interleaved-thinking-2025-05-14"
+        }));
+        assert_eq!(
+            vertex.anthropic_beta,
+            Some(vec!["interleaved-thinking-2025-05-14".to_string()])
+        );
+        assert!(vertex.to_request_body().unwrap().get("anthropic_beta").is_none());
+
+        // Bedrock ignores the header and reads them from the body
+        let bedrock = OpenAiToAnthropicConverter::new(LogLevel::Error)
+            .convert(
+                serde_json::from_value(json!({
+                    "messages": [{"role": "user", "content": "Hi"}],
+                    "x_anthropic_beta": "interleaved-thinking-2025-05-14"
+                }))
+                .unwrap(),
+                TargetFormat::Bedrock,
+                None,
+            )
+            .unwrap();
+        let body = bedrock.to_request_body().unwrap();
+        assert_eq!(body["anthropic_beta"], json!(["interleaved-thinking-2025-05-14"]));
+    }
+
+    #[test]
+    fn test_system_messages_become_system_prompt() {
+        let request = convert(json!({
+            "messages": [
+                {"role": "system", "content": "Be brief."},
+                {"role": "user", "content": "Hi"},
+                {"role": "system", "content": "Answer in English."}
+            ]
+        }));
+        assert_eq!(request.system.as_deref(), Some("Be brief.\n\nAnswer in English."));
+        assert_eq!(request.messages.len(), 1);
+        assert!(matches!(
+            &request.messages[0].content[..],
+            [AnthropicContentBlock::Text { text }] if text == "Hi"
+        ));
+
+        let no_system = convert(json!({"messages": [{"role": "user", "content": "Hi"}]}));
+        assert_eq!(no_system.system, None);
+    }
+
+    #[test]
+    fn test_configured_system_prompt_modes() {
+        let convert_with = |mode: SystemPromptMode, template: &str, request: serde_json::Value| {
+            let prompt = PromptConfig {
+                system_prompt: Some(template.to_string()),
+                system_prompt_mode: mode,
+            };
+            OpenAiToAnthropicConverter::new(LogLevel::Error)
+                .with_system_prompt(prompt)
+                .convert(serde_json::from_value(request).unwrap(), TargetFormat::Vertex, None)
+                .unwrap()
+                .system
+        };
+        let with_system = json!({
+            "model": "claude",
+            "messages": [
+                {"role": "system", "content": "Be brief."},
+                {"role": "user", "content": "Hi"}
+            ]
+        });
+        let without_system = json!({"messages": [{"role": "user", "content": "Hi"}]});
+
+        let prepended = convert_with(SystemPromptMode::Prepend, "Be kind.", with_system.clone());
+        assert_eq!(prepended.as_deref(), Some("Be kind.\n\nBe brief."));
+        let appended = convert_with(SystemPromptMode::Append, "Be kind.", with_system.clone());
+        assert_eq!(appended.as_deref(), Some("Be brief.\n\nBe kind."));
+        let replaced = convert_with(SystemPromptMode::Replace, "Be kind.", with_system.clone());
+        assert_eq!(replaced.as_deref(), Some("Be kind."));
+
+        // Without a client system prompt the template is used alone in every mode
+        for mode in [SystemPromptMode::Prepend, SystemPromptMode::Append, SystemPromptMode::Replace]
+        {
+            let system = convert_with(mode, "Be kind. {user_system}", without_system.clone());
+            assert_eq!(system.as_deref(), Some("Be kind."));
+        }
+
+        // Placeholders are substituted; an embedded client prompt is not added again
+        let embedded =
+            convert_with(SystemPromptMode::Prepend, "[{user_system}] for {model}", with_system);
+        assert_eq!(embedded.as_deref(), Some("[Be brief.] for claude"));
+        let timestamped =
+            convert_with(SystemPromptMode::Append, "Now: {timestamp}", without_system).unwrap();
+        let timestamp = timestamped.strip_prefix("Now: ").unwrap();
+        assert!(chrono::DateTime::parse_from_rfc3339(timestamp).is_ok(), "{}", timestamp);
+    }
+
+    #[test]
+    fn test_system_prompt_serialized_at_top_level() {
+        let request = convert(json!({
+            "messages": [
+                {"role": "system", "content": "You are a helpful assistant."},
+                {"role": "user", "content": "Hello"}
+            ]
+        }));
+        let body = request.to_request_body().unwrap();
+        assert_eq!(body["system"], "You are a helpful assistant.");
+        assert_eq!(body["messages"][0]["content"], json!([{"type": "text", "text": "Hello"}]));
+
+        let body = convert(json!({"messages": [{"role": "user", "content": "Hello"}]}))
+            .to_request_body()
+            .unwrap();
+        assert!(body.get("system").is_none());
+    }
+
+    #[test]
+    fn test_json_object_response_format() {
+        let request = convert(json!({
+            "messages": [
+                {"role": "system", "content": "Be brief."},
+                {"role": "user", "content": "List three colors"}
+            ],
+            "response_format": {"type": "json_object"}
+        }));
+        assert_eq!(
+            request.system.as_deref(),
+            Some("Be brief.\n\nYou must respond with valid JSON only, no prose.")
+        );
+
+        let request = convert(json!({
+            "messages": [{"role": "user", "content": "Hi"}],
+            "response_format": {"type": "json_object"}
+        }));
+        assert_eq!(request.system.as_deref(), Some(JSON_RESPONSE_INSTRUCTION));
+
+        let request = convert(json!({
+            "messages": [{"role": "user", "content": "Hi"}],
+            "response_format": {"type": "text"}
+        }));
+        assert_eq!(request.system, None);
+    }
+
+    #[test]
+    fn test_json_schema_response_format() {
+        let request = convert(json!({
+            "messages": [{"role": "user", "content": "Weather in Paris?"}],
+            "response_format": {
+                "type": "json_schema",
+                "json_schema": {
+                    "name": "weather",
+                    "schema": {
+                        "type": "object",
+                        "properties": {"temp": {"type": "number"}},
+                        "required": ["temp"]
+                    }
+                }
+            }
+        }));
+        let system = request.system.unwrap();
+        assert!(system.starts_with(JSON_RESPONSE_INSTRUCTION), "{}", system);
+        assert!(
+            system.ends_with(
+                r#"JSON Schema `weather`: {"properties":{"temp":{"type":"number"}},"required":["temp"],"type":"object"}"#
+            ),
+            "{}",
+            system
+        );
+    }
+
+    #[test]
+    fn test_sampling_parameters() {
+        let request = convert(json!({
+            "messages": [{"role": "user", "content": "Hi"}],
+            "top_p": 0.9,
+            "top_k": 40
+        }));
+        assert_eq!(request.top_p, Some(0.9));
+        assert_eq!(request.top_k, Some(40));
+        let body = request.to_request_body().unwrap();
+        assert_eq!(body["top_p"], 0.9);
+        assert_eq!(body["top_k"], 40);
+
+        let body = convert(json!({"messages": [{"role": "user", "content": "Hi"}]}))
+            .to_request_body()
+            .unwrap();
+        assert!(body.get("top_p").is_none());
+        assert!(body.get("top_k").is_none());
+        assert!(body.get("stop_sequences").is_none());
+    }
+
+    #[test]
+    fn test_stop_sequences() {
+        let messages = json!([{"role": "user", "content": "Count"}]);
+        let request = convert(json!({"messages": messages, "stop": "END"}));
+        assert_eq!(request.stop_sequences, Some(vec!["END".to_string()]));
+
+        let request = convert(json!({"messages": messages, "stop": ["\n\n", "", "Observation:"]}));
+        assert_eq!(
+            request.stop_sequences,
+            Some(vec!["\n\n".to_string(), "Observation:".to_string()])
+        );
+        assert_eq!(request.to_request_body().unwrap()["stop_sequences"][1], "Observation:");
+
+        assert!(convert(json!({"messages": messages, "stop": []})).stop_sequences.is_none());
+        assert!(convert(json!({"messages": messages, "stop": ""})).stop_sequences.is_none());
+    }
+
+    #[test]
+    fn test_legacy_functions_become_tools() {
+        let request = convert(json!({
+            "messages": [
+                {"role": "user", "content": "Weather in Oslo?"},
+                {
+                    "role": "assistant",
+                    "content": null,
+                    "function_call": {"name": "get_weather", "arguments": "{\"city\":\"Oslo\"}"}
+                },
+                {"role": "function", "name": "get_weather", "content": "Sunny"}
+            ],
+            "functions": [{"name": "get_weather", "parameters": {"type": "object"}}],
+            "function_call": {"name": "get_weather"}
+        }));
+        assert!(request.used_legacy_functions);
+
+        let body = request.to_request_body().unwrap();
+        assert_eq!(body["tools"][0]["name"], "get_weather");
+        assert_eq!(body["tools"][0]["input_schema"], json!({"type": "object"}));
+        assert_eq!(body["tool_choice"], json!({"type": "tool", "name": "get_weather"}));
+        let tool_use = &body["messages"][1]["content"][0];
+        assert_eq!(tool_use["type"], "tool_use");
+        assert_eq!(tool_use["input"], json!({"city": "Oslo"}));
+        let tool_result = &body["messages"][2]["content"][0];
+        assert_eq!(tool_result["type"], "tool_result");
+        assert_eq!(tool_result["tool_use_id"], tool_use["id"]);
+
+        // tools take precedence; functions are then ignored
+        let request = convert(json!({
+            "messages": [{"role": "user", "content": "Hi"}],
+            "tools": [{"type": "function", "function": {
+                "name": "search", "description": "Search", "parameters": {"type": "object"}
+            }}],
+            "functions": [{"name": "get_weather"}],
+            "function_call": "auto"
+        }));
+        assert!(!request.used_legacy_functions);
+        let tools = request.tools.unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "search");
+        assert!(matches!(request.tool_choice, Some(AnthropicToolChoice::Auto)));
+    }
+}
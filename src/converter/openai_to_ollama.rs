@@ -0,0 +1,682 @@
+//!
+//! OpenAI to Ollama format converter for local models.
+//!
+//! Converts OpenAI chat completion requests to the Ollama `/api/chat` format
+//! and Ollama responses back to OpenAI chat completions. Streamed Ollama
+//! responses are newline-delimited JSON, one [OllamaResponse] per line, each
+//! converted to an OpenAI stream chunk.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::anthropic_to_openai::{
+    OpenAiChoice, OpenAiFunctionCall, OpenAiResponse, OpenAiResponseMessage, OpenAiStreamChoice,
+    OpenAiStreamChunk, OpenAiStreamDelta, OpenAiStreamFunctionCall, OpenAiStreamToolCall,
+    OpenAiToolCall, OpenAiUsage,
+};
+use super::openai_to_anthropic::{
+    OpenAiContent, OpenAiFunction, OpenAiMessage, OpenAiRequest, OpenAiStop, ResponseFormat,
+};
+use crate::error::{ProxyError, Result};
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// Ollama `/api/chat` request.
+#[derive(Debug, Serialize)]
+pub struct OllamaRequest {
+    /** Ollama model name, e.g. `llama3.1` */
+    pub model: String,
+    /** conversation messages */
+    pub messages: Vec<OllamaMessage>,
+    /** stream the response as newline-delimited JSON */
+    pub stream: bool,
+    /** sampling options */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<OllamaOptions>,
+    /** `"json"` or a JSON schema the response must match */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<Value>,
+    /** available tools for function calling */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<OllamaTool>>,
+}
+
+///
+/// Ollama chat message, in requests and responses.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OllamaMessage {
+    /** message role: system, user, assistant or tool */
+    pub role: String,
+    /** text content */
+    #[serde(default)]
+    pub content: String,
+    /** base64-encoded images, without `data:` prefix */
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub images: Option<Vec<String>>,
+    /** tool calls made by the assistant */
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<OllamaToolCall>>,
+}
+
+///
+/// Tool call of an Ollama assistant message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaToolCall {
+    /** called function */
+    pub function: OllamaFunctionCall,
+}
+
+///
+/// Function called by an Ollama tool call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaFunctionCall {
+    /** function name */
+    pub name: String,
+    /** function arguments as a JSON object, not a string */
+    #[serde(default)]
+    pub arguments: Value,
+}
+
+///
+/// Tool definition of an Ollama request.
+#[derive(Debug, Serialize)]
+pub struct OllamaTool {
+    /** tool type, always "function" */
+    #[serde(rename = "type")]
+    pub tool_type: String,
+    /** function definition */
+    pub function: OllamaToolFunction,
+}
+
+///
+/// Function definition of an Ollama tool.
+#[derive(Debug, Serialize)]
+pub struct OllamaToolFunction {
+    /** function name */
+    pub name: String,
+    /** function description */
+    pub description: String,
+    /** JSON schema for function parameters */
+    pub parameters: Value,
+}
+
+///
+/// Sampling options of an Ollama request.
+#[derive(Debug, Default, Serialize)]
+pub struct OllamaOptions {
+    /** sampling temperature */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    /** nucleus sampling probability mass */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    /** sample only from the top K tokens */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_k: Option<u32>,
+    /** maximum number of tokens to generate (OpenAI `max_tokens`) */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_predict: Option<u32>,
+    /** sequences that stop generation */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    /** seed for deterministic sampling */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+///
+/// Ollama `/api/chat` response, or one line of a streamed response.
+///
+/// Streamed lines carry a part of the message each; the last line has
+/// `done: true`, the stop reason and the token counts.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OllamaResponse {
+    /** generated message, or the part of it in this line */
+    pub message: Option<OllamaMessage>,
+    /** whether generation finished */
+    #[serde(default)]
+    pub done: bool,
+    /** why generation finished: stop, length, ... */
+    pub done_reason: Option<String>,
+    /** number of tokens in the prompt */
+    pub prompt_eval_count: Option<u32>,
+    /** number of generated tokens */
+    pub eval_count: Option<u32>,
+}
+
+///
+/// Per-stream state of converting Ollama lines to OpenAI chunks.
+#[derive(Debug)]
+pub struct OllamaStreamState {
+    /** response identifier shared by all chunks */
+    pub id: String,
+    /** creation timestamp shared by all chunks */
+    pub created: i64,
+    /** tool calls streamed so far, the OpenAI `index` of the next one */
+    pub tool_calls: u32,
+    /** whether a tool call was streamed, for the finish reason */
+    pub has_tool_calls: bool,
+}
+
+///
+/// Converter between OpenAI chat completions and the Ollama chat API.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OllamaConverter;
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+impl OllamaStreamState {
+    ///
+    /// State of a new stream.
+    pub fn new() -> Self {
+        Self {
+            id: generate_response_id(),
+            created: Utc::now().timestamp(),
+            tool_calls: 0,
+            has_tool_calls: false,
+        }
+    }
+}
+
+impl Default for OllamaStreamState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OllamaConverter {
+    ///
+    /// Create a new Ollama converter.
+    ///
+    /// # Returns
+    ///  * Ollama converter
+    pub fn new() -> Self {
+        Self
+    }
+
+    ///
+    /// Convert an OpenAI chat completion request to an Ollama chat request.
+    ///
+    /// The request is always sent to the configured model; `max_tokens`
+    /// becomes `num_predict` and JSON response formats become `format`.
+    ///
+    /// # Arguments
+    ///  * `request` - OpenAI chat completion request
+    ///  * `model` - Ollama model to send the request to
+    ///
+    /// # Returns
+    ///  * Ollama chat request
+    ///  * `ProxyError::Conversion` for content Ollama cannot take, e.g. image URLs
+    pub fn convert_request(&self, request: OpenAiRequest, model: &str) -> Result<OllamaRequest> {
+        let messages =
+            request.messages.into_iter().map(Self::convert_message).collect::<Result<Vec<_>>>()?;
+
+        let tools = match (request.tools, request.functions) {
+            (Some(tools), _) => Some(
+                tools
+                    .into_iter()
+                    .map(|tool| OllamaTool {
+                        tool_type: "function".to_string(),
+                        function: OllamaToolFunction {
+                            name: tool.function.name,
+                            description: tool.function.description,
+                            parameters: tool.function.parameters,
+                        },
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            (None, Some(functions)) => Some(
+                functions
+                    .into_iter()
+                    .map(|function| OllamaTool {
+                        tool_type: "function".to_string(),
+                        function: OllamaToolFunction {
+                            name: function.name,
+                            description: function.description,
+                            parameters: function.parameters,
+                        },
+                    })
+                    .collect(),
+            ),
+            (None, None) => None,
+        };
+
+        let format = match request.response_format {
+            Some(ResponseFormat::JsonObject) => Some(Value::String("json".to_string())),
+            Some(ResponseFormat::JsonSchema { json_schema }) => {
+                Some(json_schema.schema.unwrap_or_else(|| Value::String("json".to_string())))
+            }
+            Some(ResponseFormat::Text) | None => None,
+        };
+
+        let options = OllamaOptions {
+            temperature: request.temperature,
+            top_p: request.top_p,
+            top_k: request.top_k,
+            num_predict: request.max_tokens,
+            stop: request.stop.map(|stop| match stop {
+                OpenAiStop::Single(sequence) => vec![sequence],
+                OpenAiStop::Multiple(sequences) => sequences,
+            }),
+            seed: request.seed,
+        };
+        let has_options = options.temperature.is_some()
+            || options.top_p.is_some()
+            || options.top_k.is_some()
+            || options.num_predict.is_some()
+            || options.stop.is_some()
+            || options.seed.is_some();
+
+        Ok(OllamaRequest {
+            model: model.to_string(),
+            messages,
+            stream: request.stream.unwrap_or(false),
+            options: has_options.then_some(options),
+            format,
+            tools: tools.filter(|tools| !tools.is_empty()),
+        })
+    }
+
+    ///
+    /// Convert a complete Ollama chat response to an OpenAI chat completion.
+    ///
+    /// # Arguments
+    ///  * `response` - Ollama response with `done: true`
+    ///  * `model` - model reported to the client
+    ///
+    /// # Returns
+    ///  * OpenAI chat completion response
+    pub fn convert_response(&self, response: OllamaResponse, model: &str) -> OpenAiResponse {
+        let usage = Self::usage(&response);
+        let message = response.message.unwrap_or_default();
+        let tool_calls: Vec<OpenAiToolCall> = message
+            .tool_calls
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .map(|(index, call)| OpenAiToolCall {
+                id: tool_call_id(index),
+                call_type: "function".to_string(),
+                function: OpenAiFunctionCall {
+                    name: call.function.name,
+                    arguments: arguments_string(&call.function.arguments),
+                },
+            })
+            .collect();
+        let finish_reason = finish_reason(response.done_reason.as_deref(), !tool_calls.is_empty());
+
+        OpenAiResponse {
+            id: generate_response_id(),
+            object: "chat.completion".to_string(),
+            created: Utc::now().timestamp(),
+            model: model.to_string(),
+            choices: vec![OpenAiChoice {
+                index: 0,
+                message: OpenAiResponseMessage {
+                    role: "assistant".to_string(),
+                    content: (!message.content.is_empty() || tool_calls.is_empty())
+                        .then_some(message.content),
+                    tool_calls: (!tool_calls.is_empty()).then_some(tool_calls),
+                    function_call: None,
+                    reasoning_content: None,
+                },
+                finish_reason,
+            }],
+            usage,
+            system_fingerprint: None,
+        }
+    }
+
+    ///
+    /// Convert one line of a streamed Ollama response to an OpenAI stream chunk.
+    ///
+    /// # Arguments
+    ///  * `line` - parsed NDJSON line
+    ///  * `model` - model reported to the client
+    ///  * `state` - state of the stream
+    ///
+    /// # Returns
+    ///  * Stream chunk, or `None` for a line without content that does not end the stream
+    pub fn convert_chunk(
+        &self,
+        line: &OllamaResponse,
+        model: &str,
+        state: &mut OllamaStreamState,
+    ) -> Option<OpenAiStreamChunk> {
+        let message = line.message.as_ref();
+        let content = message.map(|m| m.content.clone()).filter(|content| !content.is_empty());
+        let tool_calls: Vec<OpenAiStreamToolCall> = message
+            .and_then(|m| m.tool_calls.as_ref())
+            .into_iter()
+            .flatten()
+            .map(|call| {
+                let index = state.tool_calls;
+                state.tool_calls += 1;
+                OpenAiStreamToolCall {
+                    index,
+                    id: Some(tool_call_id(index as usize)),
+                    call_type: Some("function".to_string()),
+                    function: Some(OpenAiStreamFunctionCall {
+                        name: Some(call.function.name.clone()),
+                        arguments: Some(arguments_string(&call.function.arguments)),
+                    }),
+                }
+            })
+            .collect();
+        state.has_tool_calls |= !tool_calls.is_empty();
+
+        if content.is_none() && tool_calls.is_empty() && !line.done {
+            return None;
+        }
+        let finish_reason =
+            line.done.then(|| finish_reason(line.done_reason.as_deref(), state.has_tool_calls));
+
+        Some(OpenAiStreamChunk {
+            id: state.id.clone(),
+            object: "chat.completion.chunk".to_string(),
+            created: state.created,
+            model: model.to_string(),
+            choices: vec![OpenAiStreamChoice {
+                index: 0,
+                delta: OpenAiStreamDelta {
+                    content,
+                    tool_calls: (!tool_calls.is_empty()).then_some(tool_calls),
+                    function_call: None,
+                    reasoning_content: None,
+                },
+                finish_reason,
+            }],
+            usage: None,
+            system_fingerprint: None,
+        })
+    }
+
+    ///
+    /// Usage chunk sent after the last line when the client asked for it.
+    ///
+    /// # Arguments
+    ///  * `line` - last NDJSON line, with the token counts
+    ///  * `model` - model reported to the client
+    ///  * `state` - state of the stream
+    ///
+    /// # Returns
+    ///  * Stream chunk without choices and with the token usage
+    pub fn usage_chunk(
+        &self,
+        line: &OllamaResponse,
+        model: &str,
+        state: &OllamaStreamState,
+    ) -> OpenAiStreamChunk {
+        OpenAiStreamChunk {
+            id: state.id.clone(),
+            object: "chat.completion.chunk".to_string(),
+            created: state.created,
+            model: model.to_string(),
+            choices: Vec::new(),
+            usage: Some(Self::usage(line)),
+            system_fingerprint: None,
+        }
+    }
+
+    ///
+    /// Convert one OpenAI message to an Ollama message.
+    ///
+    /// Text blocks are joined with newlines; images must be inline
+    /// (`data:` URLs or base64 `source` blocks), Ollama cannot fetch URLs.
+    fn convert_message(message: OpenAiMessage) -> Result<OllamaMessage> {
+        let mut text = Vec::new();
+        let mut images = Vec::new();
+        match message.content {
+            Some(OpenAiContent::String(content)) => text.push(content),
+            Some(OpenAiContent::Array(blocks)) => {
+                for block in blocks {
+                    if let Some(block_text) = block.text {
+                        text.push(block_text);
+                    } else if let Some(image_url) = block.image_url {
+                        let data = image_url
+                            .url
+                            .strip_prefix("data:")
+                            .and_then(|rest| rest.split_once(";base64,"))
+                            .map(|(_, data)| data.to_string())
+                            .ok_or_else(|| {
+                                ProxyError::Conversion(
+                                    "Ollama only accepts inline base64 (data:) images".to_string(),
+                                )
+                            })?;
+                        images.push(data);
+                    } else if let Some(source) = block.source {
+                        if !source.media_type.starts_with("image/") {
+                            return Err(ProxyError::Conversion(format!(
+                                "Ollama does not accept {} content",
+                                source.media_type
+                            )));
+                        }
+                        images.push(source.data);
+                    }
+                }
+            }
+            None => {}
+        }
+
+        let calls = message
+            .tool_calls
+            .into_iter()
+            .flatten()
+            .map(|call| call.function)
+            .chain(message.function_call)
+            .map(|function: OpenAiFunction| OllamaToolCall {
+                function: OllamaFunctionCall {
+                    name: function.name,
+                    arguments: arguments_value(function.arguments),
+                },
+            })
+            .collect::<Vec<_>>();
+
+        let role = match message.role.as_str() {
+            "function" => "tool".to_string(),
+            "developer" => "system".to_string(),
+            _ => message.role,
+        };
+        Ok(OllamaMessage {
+            role,
+            content: text.join("\n"),
+            images: (!images.is_empty()).then_some(images),
+            tool_calls: (!calls.is_empty()).then_some(calls),
+        })
+    }
+
+    ///
+    /// Token usage of a complete response or of the last NDJSON line.
+    fn usage(response: &OllamaResponse) -> OpenAiUsage {
+        let prompt_tokens = response.prompt_eval_count.unwrap_or(0);
+        let completion_tokens = response.eval_count.unwrap_or(0);
+        OpenAiUsage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+        }
+    }
+}
+
+///
+/// OpenAI finish reason of an Ollama `done_reason`.
+fn finish_reason(done_reason: Option<&str>, has_tool_calls: bool) -> String {
+    match done_reason {
+        _ if has_tool_calls => "tool_calls",
+        Some("length") => "length",
+        _ => "stop",
+    }
+    .to_string()
+}
+
+///
+/// Tool call arguments as the JSON object Ollama expects.
+///
+/// OpenAI clients send the arguments as a JSON string; anything that does
+/// not parse is passed as it is.
+fn arguments_value(arguments: Value) -> Value {
+    match arguments {
+        Value::String(text) => serde_json::from_str(&text).unwrap_or(Value::String(text)),
+        other => other,
+    }
+}
+
+///
+/// Tool call arguments as the JSON string OpenAI clients expect.
+fn arguments_string(arguments: &Value) -> String {
+    match arguments {
+        Value::String(text) => text.clone(),
+        Value::Null => "{}".to_string(),
+        other => other.to_string(),
+    }
+}
+
+///
+/// Identifier of a tool call; Ollama does not assign any.
+fn tool_call_id(index: usize) -> String {
+    format!("call_{}_{}", Utc::now().timestamp_millis(), index)
+}
+
+///
+/// Generate a unique response identifier.
+fn generate_response_id() -> String {
+    format!("chatcmpl-{}", Utc::now().timestamp_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn request(json: Value) -> OpenAiRequest {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_convert_request() {
+        let converter = OllamaConverter::new();
+        let ollama = converter
+            .convert_request(
+                request(json!({
+                    "model": "gpt-4o",
+                    "messages": [
+                        {"role": "system", "content": "Be brief."},
+                        {"role": "user", "content": [
+                            {"type": "text", "text": "What is this?"},
+                            {"type": "image_url", "image_url": {"url": "data:image/png;base64,iVBOR"}}
+                        ]},
+                        {"role": "assistant", "content": null, "tool_calls": [{
+                            "id": "call_1", "type": "function",
+                            "function": {"name": "lookup", "arguments": "{\"q\":\"png\"}"}
+                        }]},
+                        {"role": "tool", "tool_call_id": "call_1", "content": "an image"}
+                    ],
+                    "max_tokens": 64,
+                    "temperature": 0.2,
+                    "stop": "END",
+                    "stream": true,
+                    "response_format": {"type": "json_object"},
+                    "tools": [{"type": "function", "function": {
+                        "name": "lookup", "description": "Look up", "parameters": {"type": "object"}
+                    }}]
+                })),
+                "llama3.1",
+            )
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&ollama).unwrap(),
+            json!({
+                "model": "llama3.1",
+                "messages": [
+                    {"role": "system", "content": "Be brief."},
+                    {"role": "user", "content": "What is this?", "images": ["iVBOR"]},
+                    {"role": "assistant", "content": "", "tool_calls": [
+                        {"function": {"name": "lookup", "arguments": {"q": "png"}}}
+                    ]},
+                    {"role": "tool", "content": "an image"}
+                ],
+                "stream": true,
+                "options": {"temperature": 0.2, "num_predict": 64, "stop": ["END"]},
+                "format": "json",
+                "tools": [{"type": "function", "function": {
+                    "name": "lookup", "description": "Look up", "parameters": {"type": "object"}
+                }}]
+            })
+        );
+
+        let remote_image = request(json!({"messages": [{"role": "user", "content": [
+            {"type": "image_url", "image_url": {"url": "https://example.com/cat.png"}}
+        ]}]}));
+        assert!(converter.convert_request(remote_image, "llama3.1").is_err());
+    }
+
+    #[test]
+    fn test_convert_response() {
+        let response: OllamaResponse = serde_json::from_value(json!({
+            "model": "llama3.1",
+            "message": {"role": "assistant", "content": "", "tool_calls": [
+                {"function": {"name": "lookup", "arguments": {"q": "png"}}}
+            ]},
+            "done": true,
+            "done_reason": "stop",
+            "prompt_eval_count": 12,
+            "eval_count": 5
+        }))
+        .unwrap();
+
+        let openai = OllamaConverter::new().convert_response(response, "llama3.1");
+        let json = serde_json::to_value(&openai).unwrap();
+        assert_eq!(json["object"], "chat.completion");
+        assert_eq!(json["choices"][0]["finish_reason"], "tool_calls");
+        assert!(json["choices"][0]["message"]["content"].is_null());
+        let call = &json["choices"][0]["message"]["tool_calls"][0];
+        assert_eq!(call["function"]["name"], "lookup");
+        assert_eq!(call["function"]["arguments"], "{\"q\":\"png\"}");
+        assert_eq!(
+            json["usage"],
+            json!({"prompt_tokens": 12, "completion_tokens": 5, "total_tokens": 17})
+        );
+    }
+
+    #[test]
+    fn test_convert_stream_lines() {
+        let converter = OllamaConverter::new();
+        let mut state = OllamaStreamState::new();
+        let lines: Vec<OllamaResponse> = [
+            json!({"message": {"role": "assistant", "content": "Hel"}, "done": false}),
+            json!({"message": {"role": "assistant", "content": ""}, "done": false}),
+            json!({"message": {"role": "assistant", "content": "lo"}, "done": false}),
+            json!({"message": {"role": "assistant", "content": ""}, "done": true,
+                   "done_reason": "length", "prompt_eval_count": 3, "eval_count": 2}),
+        ]
+        .into_iter()
+        .map(|line| serde_json::from_value(line).unwrap())
+        .collect();
+
+        let chunks: Vec<Value> = lines
+            .iter()
+            .filter_map(|line| converter.convert_chunk(line, "llama3.1", &mut state))
+            .map(|chunk| serde_json::to_value(chunk).unwrap())
+            .collect();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0]["choices"][0]["delta"]["content"], "Hel");
+        assert_eq!(chunks[1]["choices"][0]["delta"]["content"], "lo");
+        assert_eq!(chunks[2]["choices"][0]["finish_reason"], "length");
+        assert!(chunks.iter().all(|chunk| chunk["id"] == chunks[0]["id"]));
+
+        let usage = converter.usage_chunk(&lines[3], "llama3.1", &state);
+        assert!(usage.choices.is_empty());
+        assert_eq!(usage.usage.unwrap().total_tokens, 5);
+    }
+}
@@ -0,0 +1,276 @@
+//!
+//! JSON Schema of the accepted chat completion request format.
+//!
+//! Served by `GET /v1/chat/completions/schema` so tools that build requests
+//! programmatically can discover which OpenAI fields modelmux understands and
+//! how each one is mapped to the Anthropic request. The schema mirrors
+//! `OpenAiRequest`; fields modelmux does not read are listed only when clients
+//! commonly send them, marked `deprecated` and described as ignored.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use serde_json::{Value, json};
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** JSON Schema dialect of the generated schema */
+const SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+///
+/// Build the JSON Schema of `POST /v1/chat/completions` request bodies.
+///
+/// Extension points that are not part of the OpenAI format carry
+/// `"x-modelmux-extension": true`.
+///
+/// # Returns
+///  * JSON Schema document
+pub fn chat_completion_request_schema() -> Value {
+    json!({
+        "$schema": SCHEMA_DIALECT,
+        "title": "OpenAiRequest",
+        "description": "OpenAI chat completion request accepted by modelmux and converted to an Anthropic Messages request.",
+        "type": "object",
+        "required": ["messages"],
+        "properties": {
+            "model": {
+                "type": ["string", "null"],
+                "description": "Model to use. Matched against the default model and the [[vertex.models]] names; unknown or missing names use the default model."
+            },
+            "messages": {
+                "type": "array",
+                "description": "Conversation so far. System messages become the Anthropic `system` prompt, tool messages become `tool_result` blocks of a user message.",
+                "items": { "$ref": "#/$defs/message" }
+            },
+            "max_tokens": {
+                "type": ["integer", "null"],
+                "description": "Maximum tokens to generate. Anthropic requires this field, so a default is used when it is missing."
+            },
+            "temperature": {
+                "type": ["number", "null"],
+                "description": "Sampling temperature, passed through to Anthropic."
+            },
+            "stream": {
+                "type": ["boolean", "null"],
+                "description": "Stream the response as server-sent events. The Accept header and streaming.mode can override it."
+            },
+            "tools": {
+                "type": ["array", "null"],
+                "description": "Functions the model may call. Each function becomes an Anthropic tool with `parameters` as its `input_schema`.",
+                "items": { "$ref": "#/$defs/tool" }
+            },
+            "tool_choice": {
+                "description": "`\"auto\"` maps to Anthropic `auto`, a named function forces that tool, `\"none\"` and other values are omitted.",
+                "anyOf": [
+                    { "type": "string", "enum": ["auto", "none", "required"] },
+                    {
+                        "type": "object",
+                        "required": ["type"],
+                        "properties": {
+                            "type": { "type": "string", "const": "function" },
+                            "function": {
+                                "type": "object",
+                                "required": ["name"],
+                                "properties": { "name": { "type": "string" } }
+                            }
+                        }
+                    },
+                    { "type": "null" }
+                ]
+            },
+            "functions": {
+                "type": ["array", "null"],
+                "deprecated": true,
+                "description": "Legacy OpenAI function definitions, converted to `tools` when `tools` is absent. Responses then carry `message.function_call` instead of `tool_calls`.",
+                "items": {
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": {
+                        "name": { "type": "string" },
+                        "description": { "type": "string" },
+                        "parameters": { "type": "object" }
+                    }
+                }
+            },
+            "function_call": {
+                "deprecated": true,
+                "description": "Legacy OpenAI function selection, converted to `tool_choice` when `tool_choice` is absent.",
+                "anyOf": [
+                    { "type": "string", "enum": ["auto", "none"] },
+                    {
+                        "type": "object",
+                        "required": ["name"],
+                        "properties": { "name": { "type": "string" } }
+                    },
+                    { "type": "null" }
+                ]
+            },
+            "seed": {
+                "type": ["integer", "null"],
+                "minimum": 0,
+                "description": "Seed for reproducibility testing. Anthropic has no seed parameter, so it is only logged and turned into the response `system_fingerprint`."
+            },
+            "x_anthropic_beta": {
+                "type": ["string", "null"],
+                "x-modelmux-extension": true,
+                "description": "Comma-separated Anthropic beta features (e.g. `interleaved-thinking-2025-05-14`), sent upstream in the `anthropic-beta` header. Merged with the `anthropic-beta` / `X-Anthropic-Beta` request headers."
+            }
+        },
+        "x-modelmux-headers": {
+            "x-extra-anthropic": {
+                "type": "string",
+                "x-modelmux-extension": true,
+                "description": "JSON object of extra top-level Anthropic request fields, merged over converter.extra_anthropic_fields. Only honoured when converter.allow_client_extra_fields is enabled; max_tokens and messages cannot be set."
+            },
+            "X-Anthropic-Beta": {
+                "type": "string",
+                "x-modelmux-extension": true,
+                "description": "Comma-separated Anthropic beta features forwarded upstream as `anthropic-beta`; `anthropic-beta` itself is accepted too."
+            },
+            "Idempotency-Key": {
+                "type": "string",
+                "x-modelmux-extension": true,
+                "description": "Deduplicates retried streaming requests: a repeated key joins or replays the first request's stream instead of calling the model again."
+            }
+        },
+        "$defs": {
+            "message": {
+                "type": "object",
+                "required": ["role"],
+                "properties": {
+                    "role": {
+                        "type": "string",
+                        "enum": ["system", "user", "assistant", "tool", "function"],
+                        "description": "Message author. Legacy `function` messages answer the preceding `function_call`."
+                    },
+                    "content": {
+                        "description": "Text, or content blocks for multimodal user messages.",
+                        "anyOf": [
+                            { "type": "string" },
+                            { "type": "array", "items": { "$ref": "#/$defs/content_block" } },
+                            { "type": "null" }
+                        ]
+                    },
+                    "tool_calls": {
+                        "type": ["array", "null"],
+                        "description": "Tool calls made by an assistant message, converted to Anthropic `tool_use` blocks.",
+                        "items": {
+                            "type": "object",
+                            "required": ["id", "type", "function"],
+                            "properties": {
+                                "id": { "type": "string" },
+                                "type": { "type": "string", "const": "function" },
+                                "function": {
+                                    "type": "object",
+                                    "required": ["name", "arguments"],
+                                    "properties": {
+                                        "name": { "type": "string" },
+                                        "arguments": {
+                                            "description": "Arguments as a JSON string or object."
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    "tool_call_id": {
+                        "type": ["string", "null"],
+                        "description": "Tool call answered by a tool message, used as the Anthropic `tool_use_id`."
+                    },
+                    "function_call": {
+                        "type": ["object", "null"],
+                        "deprecated": true,
+                        "description": "Legacy function call made by an assistant message, converted to a tool call.",
+                        "required": ["name", "arguments"],
+                        "properties": {
+                            "name": { "type": "string" },
+                            "arguments": { "description": "Arguments as a JSON string or object." }
+                        }
+                    }
+                }
+            },
+            "content_block": {
+                "type": "object",
+                "required": ["type"],
+                "properties": {
+                    "type": { "type": "string", "enum": ["text", "image_url", "image", "document"] },
+                    "text": { "type": ["string", "null"], "description": "Text of a text block." },
+                    "source": {
+                        "type": ["object", "null"],
+                        "x-modelmux-extension": true,
+                        "description": "Base64 file of an Anthropic-style image or document (PDF) block, as added by POST /v1/files/upload-and-chat.",
+                        "required": ["type", "media_type", "data"],
+                        "properties": {
+                            "type": { "type": "string", "const": "base64" },
+                            "media_type": { "type": "string" },
+                            "data": { "type": "string" }
+                        }
+                    },
+                    "image_url": {
+                        "type": ["object", "null"],
+                        "description": "Image of an image_url block, converted to an Anthropic image block.",
+                        "required": ["url"],
+                        "properties": { "url": { "type": "string" } }
+                    }
+                }
+            },
+            "tool": {
+                "type": "object",
+                "required": ["type", "function"],
+                "properties": {
+                    "type": { "type": "string", "const": "function" },
+                    "function": {
+                        "type": "object",
+                        "required": ["name", "description", "parameters"],
+                        "properties": {
+                            "name": { "type": "string" },
+                            "description": { "type": "string" },
+                            "parameters": {
+                                "type": "object",
+                                "description": "JSON Schema of the arguments, sent as the Anthropic tool `input_schema`."
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converter::openai_to_anthropic::OpenAiRequest;
+
+    #[test]
+    fn test_schema_covers_request_fields() {
+        let schema = chat_completion_request_schema();
+        let properties = schema["properties"].as_object().unwrap();
+
+        // A request using every field the schema documents as supported must parse
+        let request = json!({
+            "model": "claude-sonnet",
+            "messages": [{"role": "user", "content": [{"type": "text", "text": "Hi"}]}],
+            "max_tokens": 16,
+            "temperature": 0.0,
+            "stream": false,
+            "tools": [{"type": "function", "function": {
+                "name": "f", "description": "d", "parameters": {"type": "object"}}}],
+            "tool_choice": "auto"
+        });
+        for field in request.as_object().unwrap().keys() {
+            assert!(properties.contains_key(field), "schema misses {}", field);
+        }
+        assert!(serde_json::from_value::<OpenAiRequest>(request).is_ok());
+
+        assert_eq!(schema["properties"]["functions"]["deprecated"], true);
+        assert_eq!(schema["properties"]["function_call"]["deprecated"], true);
+        assert_eq!(schema["x-modelmux-headers"]["x-extra-anthropic"]["x-modelmux-extension"], true);
+    }
+}
@@ -0,0 +1,269 @@
+//!
+//! Prompt token estimates for the context window check.
+//!
+//! Vertex AI rejects prompts longer than the model's context window with a
+//! 400 whose message does not say what went wrong. `ContextWindowChecker`
+//! estimates the prompt size of a converted request before it is sent, so
+//! the proxy can answer with an OpenAI `context_length_exceeded` error
+//! instead.
+//!
+//! The default estimate counts 4 characters per token. Builds with the
+//! `tiktoken` feature count the tokens of the `cl100k_base` BPE encoding,
+//! which is closer to the Claude tokenizer for code and non-English text.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use super::openai_to_anthropic::{
+    AnthropicContentBlock, AnthropicRequest, AnthropicToolResultContent,
+};
+use crate::error::{ProxyError, Result};
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// Estimates the prompt tokens of Anthropic requests.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ContextWindowChecker;
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** Message prefix of the error returned for prompts above the limit */
+pub const CONTEXT_WINDOW_EXCEEDED: &str = "Context window exceeded";
+
+/** Characters counted as one token by the fast estimate */
+#[cfg(not(feature = "tiktoken"))]
+const CHARS_PER_TOKEN: usize = 4;
+
+/** Tokens counted for an image or document block (a ~1.15 megapixel image) */
+const MEDIA_BLOCK_TOKENS: u32 = 1_600;
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+impl ContextWindowChecker {
+    ///
+    /// Estimate the prompt tokens of a request.
+    ///
+    /// Counts the system prompt, the text, tool calls and tool results of
+    /// all messages and the tool definitions. Images and documents count as
+    /// a fixed 1,600 tokens each.
+    ///
+    /// # Arguments
+    ///  * `request` - converted request
+    ///
+    /// # Returns
+    ///  * Estimated prompt tokens
+    pub fn estimate_tokens(request: &AnthropicRequest) -> u32 {
+        let system = request.system.as_deref().map_or(0, count_text_tokens);
+        let messages = request
+            .messages
+            .iter()
+            .flat_map(|message| &message.content)
+            .fold(0u32, |total, block| total.saturating_add(count_block_tokens(block)));
+        let tools = request.tools.iter().flatten().fold(0u32, |total, tool| {
+            total
+                .saturating_add(count_text_tokens(&tool.name))
+                .saturating_add(count_text_tokens(&tool.description))
+                .saturating_add(count_text_tokens(&tool.input_schema.to_string()))
+        });
+        system.saturating_add(messages).saturating_add(tools)
+    }
+
+    ///
+    /// Check that a request fits the context window.
+    ///
+    /// # Arguments
+    ///  * `request` - converted request
+    ///  * `limit` - context window of the target model in tokens
+    ///
+    /// # Returns
+    ///  * Estimated prompt tokens
+    ///  * `ProxyError::Conversion` ("Context window exceeded: ...") when the
+    ///    estimate is above `limit`
+    pub fn check(request: &AnthropicRequest, limit: u32) -> Result<u32> {
+        let tokens = Self::estimate_tokens(request);
+        if tokens > limit {
+            return Err(ProxyError::Conversion(format!(
+                "{}: estimated {} tokens > limit {}",
+                CONTEXT_WINDOW_EXCEEDED, tokens, limit
+            )));
+        }
+        Ok(tokens)
+    }
+
+    ///
+    /// Whether an error was returned by [`check`](Self::check).
+    pub fn is_exceeded_error(error: &ProxyError) -> bool {
+        matches!(error, ProxyError::Conversion(message) if message.starts_with(CONTEXT_WINDOW_EXCEEDED))
+    }
+}
+
+///
+/// Estimate the tokens of one content block.
+fn count_block_tokens(block: &AnthropicContentBlock) -> u32 {
+    match block {
+        AnthropicContentBlock::Text { text } => count_text_tokens(text),
+        AnthropicContentBlock::ToolUse { id: _, name, input } => {
+            count_text_tokens(name).saturating_add(count_text_tokens(&input.to_string()))
+        }
+        AnthropicContentBlock::ToolResult { content, .. } => match content {
+            AnthropicToolResultContent::String(text) => count_text_tokens(text),
+            AnthropicToolResultContent::Array(parts) => parts.iter().fold(0u32, |total, part| {
+                let tokens = match part.get("text").and_then(serde_json::Value::as_str) {
+                    Some(text) => count_text_tokens(text),
+                    None => count_text_tokens(&part.to_string()),
+                };
+                total.saturating_add(tokens)
+            }),
+        },
+        AnthropicContentBlock::Image { .. } | AnthropicContentBlock::Document { .. } => {
+            MEDIA_BLOCK_TOKENS
+        }
+    }
+}
+
+///
+/// Count the tokens of a text with the `cl100k_base` encoding.
+#[cfg(feature = "tiktoken")]
+fn count_text_tokens(text: &str) -> u32 {
+    let tokens = tiktoken_rs::cl100k_base_singleton().encode_ordinary(text).len();
+    u32::try_from(tokens).unwrap_or(u32::MAX)
+}
+
+///
+/// Estimate the tokens of a text at [`CHARS_PER_TOKEN`] characters per token.
+#[cfg(not(feature = "tiktoken"))]
+fn count_text_tokens(text: &str) -> u32 {
+    let tokens = text.chars().count().div_ceil(CHARS_PER_TOKEN);
+    u32::try_from(tokens).unwrap_or(u32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::converter::openai_to_anthropic::AnthropicMessage;
+
+    fn request(messages: Vec<AnthropicMessage>) -> AnthropicRequest {
+        AnthropicRequest {
+            anthropic_version: "vertex-2023-10-16".to_string(),
+            messages,
+            system: None,
+            max_tokens: 1024,
+            temperature: 1.0,
+            top_p: None,
+            top_k: None,
+            stop_sequences: None,
+            stream: false,
+            tools: None,
+            tool_choice: None,
+            seed: None,
+            extra_fields: serde_json::Map::new(),
+            used_legacy_functions: false,
+            requested_seed: None,
+            anthropic_beta: None,
+        }
+    }
+
+    fn message(role: &str, content: Vec<AnthropicContentBlock>) -> AnthropicMessage {
+        AnthropicMessage { role: role.to_string(), content }
+    }
+
+    fn text(text: &str) -> AnthropicContentBlock {
+        AnthropicContentBlock::Text { text: text.to_string() }
+    }
+
+    #[test]
+    fn test_empty_request_has_no_tokens() {
+        assert_eq!(ContextWindowChecker::estimate_tokens(&request(Vec::new())), 0);
+        assert_eq!(ContextWindowChecker::check(&request(Vec::new()), 0).unwrap(), 0);
+
+        let empty = request(vec![message("user", Vec::new()), message("user", vec![text("")])]);
+        assert_eq!(ContextWindowChecker::estimate_tokens(&empty), 0);
+    }
+
+    #[test]
+    fn test_tool_results_only() {
+        let result = |content| AnthropicContentBlock::ToolResult {
+            tool_use_id: "toolu_1".to_string(),
+            content,
+        };
+        let string_result = request(vec![message(
+            "user",
+            vec![result(AnthropicToolResultContent::String("72°F and sunny".repeat(100)))],
+        )]);
+        let array_result = request(vec![message(
+            "user",
+            vec![result(AnthropicToolResultContent::Array(vec![
+                json!({"type": "text", "text": "72°F and sunny".repeat(100)}),
+            ]))],
+        )]);
+
+        let tokens = ContextWindowChecker::estimate_tokens(&string_result);
+        assert!(tokens > 100);
+        assert_eq!(ContextWindowChecker::estimate_tokens(&array_result), tokens);
+    }
+
+    #[test]
+    #[cfg(not(feature = "tiktoken"))]
+    fn test_fast_estimate_counts_all_parts() {
+        use crate::converter::openai_to_anthropic::{AnthropicTool, ImageSource};
+
+        let mut counted = request(vec![
+            message("user", vec![text("abcdefgh"), text("abcde")]),
+            message(
+                "assistant",
+                vec![AnthropicContentBlock::ToolUse {
+                    id: "toolu_1".to_string(),
+                    name: "calc".to_string(),
+                    input: json!({}),
+                }],
+            ),
+            message(
+                "user",
+                vec![AnthropicContentBlock::Image {
+                    source: ImageSource {
+                        source_type: "base64".to_string(),
+                        url: None,
+                        media_type: Some("image/png".to_string()),
+                        data: Some("iVBORw0KGgo".repeat(10_000)),
+                    },
+                }],
+            ),
+        ]);
+        counted.system = Some("abcd".to_string());
+        counted.tools = Some(vec![AnthropicTool {
+            name: "calc".to_string(),
+            description: "abcd".to_string(),
+            input_schema: json!({}),
+        }]);
+
+        // system 1, text 2 + 2, tool use 1 + 1, image 1600, tool 1 + 1 + 1
+        assert_eq!(ContextWindowChecker::estimate_tokens(&counted), 1_610);
+    }
+
+    #[test]
+    fn test_limit_exceeded() {
+        let long = request(vec![message("user", vec![text(&"word ".repeat(2_000))])]);
+        let tokens = ContextWindowChecker::estimate_tokens(&long);
+
+        assert_eq!(ContextWindowChecker::check(&long, tokens).unwrap(), tokens);
+        let error = ContextWindowChecker::check(&long, 100).unwrap_err();
+        assert!(ContextWindowChecker::is_exceeded_error(&error));
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "Conversion error: Context window exceeded: estimated {} tokens > limit 100",
+                tokens
+            )
+        );
+        assert!(!ContextWindowChecker::is_exceeded_error(&ProxyError::Conversion(
+            "Invalid request format".to_string()
+        )));
+    }
+}
@@ -4,6 +4,10 @@
 //! Defines all error types used throughout the application using thiserror
 //! for ergonomic error handling. Follows Rust best practices for error design.
 //!
+//! `Config` and `Http` errors can carry the underlying error that caused them,
+//! exposed through `std::error::Error::source()` and appended to the message
+//! as `"... caused by: ..."`, so error chains survive without `anyhow`.
+//!
 //! Authors:
 //!   Jaro <yarenty@gmail.com>
 //!
@@ -11,6 +15,8 @@
 
 /* --- uses ------------------------------------------------------------------------------------ */
 
+use std::error::Error as StdError;
+
 use thiserror::Error;
 
 /* --- types ----------------------------------------------------------------------------------- */
@@ -22,14 +28,14 @@ use thiserror::Error;
 /// Uses thiserror for automatic Display and Error trait implementations.
 #[derive(Error, Debug)]
 pub enum ProxyError {
-    #[error("Configuration error: {0}")]
-    Config(String),
+    #[error("Configuration error: {0}{cause}", cause = caused_by(.1))]
+    Config(String, #[source] Option<BoxError>),
 
     #[error("Authentication error: {0}")]
     Auth(String),
 
-    #[error("HTTP error: {0}")]
-    Http(String),
+    #[error("HTTP error: {0}{cause}", cause = caused_by(.1))]
+    Http(String, #[source] Option<BoxError>),
 
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
@@ -39,9 +45,111 @@ pub enum ProxyError {
 
     #[error("Conversion error: {0}")]
     Conversion(String),
+
+    #[error("HTTP error: {message}")]
+    RateLimit { message: String, retry_after_secs: Option<u64> },
+
+    #[error("Upstream {phase} timeout")]
+    Timeout { phase: &'static str },
+
+    #[error("Server busy: request waited {waited_ms}ms in the queue")]
+    QueueTimeout { waited_ms: u64, retry_after_secs: u64 },
+
+    #[error("Unsupported media type: {0}")]
+    UnsupportedMediaType(String),
+
+    #[error("Upload exceeds the limit of {limit} bytes")]
+    UploadTooLarge { limit: usize },
+
+    #[error("API key '{key}' is not allowed to use model '{model}'")]
+    ModelNotAllowed { key: String, model: String },
 }
 
+/// Boxed underlying error kept as the source of a `ProxyError`
+pub type BoxError = Box<dyn StdError + Send + Sync>;
+
 /* --- start of code -------------------------------------------------------------------------- */
 
 /// Result type alias for cleaner error handling throughout the application
 pub type Result<T> = std::result::Result<T, ProxyError>;
+
+impl ProxyError {
+    ///
+    /// Create an HTTP error caused by another error.
+    ///
+    /// # Arguments
+    ///  * `message` - description of what failed
+    ///  * `source` - underlying error
+    pub fn http_with_source(
+        message: impl Into<String>,
+        source: impl Into<BoxError>,
+    ) -> Self {
+        Self::Http(message.into(), Some(source.into()))
+    }
+
+    ///
+    /// Create a configuration error caused by another error.
+    ///
+    /// # Arguments
+    ///  * `message` - description of what failed
+    ///  * `source` - underlying error
+    pub fn config_with_source(
+        message: impl Into<String>,
+        source: impl Into<BoxError>,
+    ) -> Self {
+        Self::Config(message.into(), Some(source.into()))
+    }
+}
+
+///
+/// Format an error and all of its sources.
+///
+/// Sources whose message is already part of the text (because an error's
+/// `Display` includes its cause) are not repeated.
+///
+/// # Arguments
+///  * `error` - outermost error
+///
+/// # Returns
+///  * `"error caused by: source caused by: ..."`
+pub fn error_chain(error: &(dyn StdError + 'static)) -> String {
+    let mut chain = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        let message = cause.to_string();
+        if !chain.contains(&message) {
+            chain.push_str(" caused by: ");
+            chain.push_str(&message);
+        }
+        source = cause.source();
+    }
+    chain
+}
+
+///
+/// Display suffix for an optional source error.
+fn caused_by(source: &Option<BoxError>) -> String {
+    source.as_ref().map(|source| format!(" caused by: {}", source)).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_chain() {
+        let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+        let error = ProxyError::config_with_source("Failed to read config.toml", io_error);
+
+        assert_eq!(
+            error.to_string(),
+            "Configuration error: Failed to read config.toml caused by: no such file"
+        );
+        assert_eq!(error.source().unwrap().to_string(), "no such file");
+        assert_eq!(error_chain(&error), error.to_string());
+
+        let plain = ProxyError::Http("Upstream failed".to_string(), None);
+        assert_eq!(plain.to_string(), "HTTP error: Upstream failed");
+        assert!(plain.source().is_none());
+    }
+}
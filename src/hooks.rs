@@ -0,0 +1,134 @@
+//!
+//! Request and response hooks for library users.
+//!
+//! Applications embedding modelmux through [`create_app_with_hooks`] can
+//! register [`RequestHook`]s to inspect or rewrite chat completions without
+//! forking the crate, e.g. to redact PII, attribute costs or route by model.
+//! Hooks run in registration order:
+//!
+//! - `on_request` - after the OpenAI request is parsed, before it is converted
+//!   to the Anthropic format; for streaming and non-streaming requests
+//! - `on_response` - on the complete OpenAI response of a non-streaming
+//!   request, before it is stored in the semantic cache and returned
+//!
+//! An error from a hook fails the request with that error.
+//!
+//! [`create_app_with_hooks`]: crate::create_app_with_hooks
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use crate::converter::anthropic_to_openai::OpenAiResponse;
+use crate::converter::openai_to_anthropic::{OpenAiContent, OpenAiRequest};
+use crate::error::Result;
+
+/// Attribute macro for implementing [`RequestHook`] without depending on `async-trait`.
+pub use async_trait::async_trait;
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// Hook intercepting chat completion requests and responses.
+///
+/// Object-safe, so hooks of different types can be registered together as
+/// `Arc<dyn RequestHook>`. Implement it with [`macro@async_trait`].
+#[async_trait]
+pub trait RequestHook: Send + Sync {
+    ///
+    /// Inspect or rewrite an incoming request.
+    ///
+    /// # Arguments
+    ///  * `req` - parsed OpenAI request, before conversion
+    ///
+    /// # Returns
+    ///  * `Ok(())` to continue, or the error to fail the request with
+    async fn on_request(&self, req: &mut OpenAiRequest) -> Result<()>;
+
+    ///
+    /// Inspect or rewrite a complete non-streaming response.
+    ///
+    /// # Arguments
+    ///  * `resp` - converted OpenAI response
+    ///
+    /// # Returns
+    ///  * `Ok(())` to continue, or the error to fail the request with
+    async fn on_response(&self, resp: &mut OpenAiResponse) -> Result<()>;
+}
+
+///
+/// Example hook logging the size of requests and responses at debug level.
+#[derive(Debug, Default, Clone, Copy)]
+#[allow(dead_code)]
+pub struct LoggingHook;
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+#[async_trait]
+impl RequestHook for LoggingHook {
+    async fn on_request(&self, req: &mut OpenAiRequest) -> Result<()> {
+        tracing::debug!(
+            "Hook: request with {} message(s), {} text characters",
+            req.messages.len(),
+            req.messages.iter().filter_map(|m| m.content.as_ref()).map(text_len).sum::<usize>()
+        );
+        Ok(())
+    }
+
+    async fn on_response(&self, resp: &mut OpenAiResponse) -> Result<()> {
+        let content_len: usize = resp
+            .choices
+            .iter()
+            .filter_map(|choice| choice.message.content.as_ref())
+            .map(|content| content.chars().count())
+            .sum();
+        tracing::debug!(
+            "Hook: response with {} choice(s), {} text characters, {} completion tokens",
+            resp.choices.len(),
+            content_len,
+            resp.usage.completion_tokens
+        );
+        Ok(())
+    }
+}
+
+///
+/// Number of text characters in message content.
+#[allow(dead_code)]
+fn text_len(content: &OpenAiContent) -> usize {
+    match content {
+        OpenAiContent::String(text) => text.chars().count(),
+        OpenAiContent::Array(blocks) => {
+            blocks.iter().filter_map(|block| block.text.as_ref()).map(|t| t.chars().count()).sum()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_logging_hook_counts_text() {
+        let mut request: OpenAiRequest = serde_json::from_value(serde_json::json!({
+            "messages": [
+                {"role": "system", "content": "Be brief"},
+                {"role": "user", "content": [
+                    {"type": "text", "text": "Héllo"},
+                    {"type": "image_url", "image_url": {"url": "https://example.com/a.png"}}
+                ]}
+            ]
+        }))
+        .unwrap();
+        let lengths: Vec<usize> =
+            request.messages.iter().filter_map(|m| m.content.as_ref()).map(text_len).collect();
+        assert_eq!(lengths, [8, 5]);
+
+        let hook: std::sync::Arc<dyn RequestHook> = std::sync::Arc::new(LoggingHook);
+        assert!(hook.on_request(&mut request).await.is_ok());
+        assert_eq!(request.messages.len(), 2);
+    }
+}
@@ -30,19 +30,30 @@
 //! - [`config`] - Configuration management and environment variable handling
 //! - [`provider`] - LLM backend abstraction ([`LlmProviderBackend`]); Vertex and OpenAI-compatible (stub)
 //! - [`auth`] - Request auth (GCP OAuth2 or Bearer token)
+//! - [`cache`] - Semantic similarity response cache
+//! - [`usage`] - Token usage aggregation for `GET /v1/usage`
 //! - [`server`] - HTTP server setup and route handlers
 //! - [`converter`] - Format conversion between OpenAI and Anthropic formats
 //! - [`error`] - Error types and handling
+//! - [`hooks`] - Request/response hooks for embedding applications ([`hooks::RequestHook`])
+//! - [`logging`] - Log subscriber setup and secret redaction for JSON logs
+//! - [`telemetry`] - OpenTelemetry trace export (`telemetry` feature)
 
 pub mod auth;
+pub mod bench;
+pub mod cache;
 pub mod config;
 pub mod converter;
 pub mod error;
+pub mod hooks;
+pub mod logging;
 pub mod provider;
 pub mod server;
+pub mod telemetry;
+pub mod usage;
 
 // Re-export commonly used types
-pub use config::Config;
+pub use config::{Config, PartialConfig, Severity, ValidationIssue};
 pub use error::ProxyError;
 
 /// Creates a new ModelMux application with the given configuration.
@@ -79,19 +90,157 @@ pub use error::ProxyError;
 /// }
 /// ```
 pub async fn create_app(config: Config) -> Result<axum::Router, ProxyError> {
+    create_app_with_hooks(config, Vec::new()).await
+}
+
+/// Creates a ModelMux application that runs the given hooks on chat completions.
+///
+/// Each hook sees every parsed chat completion request before conversion,
+/// and every complete non-streaming response, in the order given. See
+/// [`hooks`] for when exactly they run.
+///
+/// # Arguments
+///
+/// * `config` - Application configuration
+/// * `hooks` - Hooks to register, e.g. [`hooks::LoggingHook`]
+///
+/// # Returns
+///
+/// Returns an Axum Router that can be served directly.
+///
+/// # Errors
+///
+/// Returns a `ProxyError` if authentication setup fails or other
+/// initialization issues occur.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::sync::Arc;
+///
+/// use modelmux::hooks::{LoggingHook, RequestHook};
+/// use modelmux::{Config, create_app_with_hooks};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let config = Config::load()?;
+///     let hooks: Vec<Arc<dyn RequestHook>> = vec![Arc::new(LoggingHook)];
+///     let app = create_app_with_hooks(config, hooks).await?;
+///
+///     let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await?;
+///     axum::serve(listener, app).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn create_app_with_hooks(
+    config: Config,
+    hooks: Vec<std::sync::Arc<dyn hooks::RequestHook>>,
+) -> Result<axum::Router, ProxyError> {
     use axum::Router;
     use axum::routing::{get, post};
     use std::sync::Arc;
-    use tower_http::cors::CorsLayer;
     use tower_http::trace::TraceLayer;
 
-    let app_state = Arc::new(server::AppState::new(config).await?);
+    let mut app_state = server::AppState::new(config).await?;
+    app_state.hooks = hooks;
+    let app_state = Arc::new(app_state);
+    server::jobs::start_workers(&app_state);
+    server::queue::start_dispatcher(&app_state);
+    server::deep_health::start_deep_health_check(&app_state);
 
-    Ok(Router::new()
-        .route("/v1/chat/completions", post(server::chat_completions))
+    let upload_limit = server::uploads::body_limit(&app_state.config.read().await.upload);
+    let body_limit = server::limits::body_limit(&app_state.config.read().await.limits);
+    let batch_limit = server::limits::batch_body_limit(&app_state.config.read().await.limits);
+    let cors = server::cors::cors_layer(&app_state.config.read().await.cors);
+    let audit = server::audit::AuditLogLayer::new(&app_state.config.read().await.audit_log)?;
+    let admin_routes = Router::new()
+        .route("/v1/usage", get(server::usage))
+        .route("/admin/ab-test/stats", get(server::ab_test_stats))
+        .route("/admin/config", get(server::admin_config::admin_config))
+        .route("/admin/config/sources", get(server::admin_config::admin_config_sources))
+        .route_layer(axum::middleware::from_fn_with_state(
+            app_state.clone(),
+            server::require_admin_secret,
+        ));
+
+    let router = Router::new()
+        .route("/v1/chat/completions", post(server::chat_completions).layer(body_limit))
+        .route("/v1/chat/completions/schema", get(server::chat_completion_schema))
+        .route(
+            "/v1/chat/completions/async",
+            post(server::jobs::create_async_completion).layer(body_limit),
+        )
+        .route("/v1/jobs/{job_id}", get(server::jobs::job_status))
+        .route("/v1/jobs/{job_id}/result", get(server::jobs::job_result))
+        .route(
+            "/v1/files/upload-and-chat",
+            post(server::uploads::upload_and_chat).layer(upload_limit),
+        )
+        .route("/v1/embeddings", post(server::embeddings::embeddings).layer(body_limit))
+        .route(
+            "/v1/batches/convert",
+            post(server::batches::convert_batch_output).layer(batch_limit),
+        )
         .route("/v1/models", get(server::models))
         .route("/health", get(server::health))
-        .layer(CorsLayer::permissive())
+        .route("/health/connections", get(server::connections::connection_stats))
+        .route("/metrics", get(server::metrics::prometheus_metrics));
+    // Admin routes keep their own admin secret check. Installed even without a
+    // proxy key so a configuration reload can turn the check on.
+    let auth = axum::middleware::from_fn_with_state(
+        app_state.clone(),
+        server::auth_middleware::auth_middleware,
+    );
+
+    Ok(router
+        .layer(auth)
+        .layer(audit)
+        .merge(admin_routes)
+        .layer(cors)
         .layer(TraceLayer::new_for_http())
         .with_state(app_state))
 }
+
+/// Creates a ModelMux application from a base configuration and an overlay.
+///
+/// Applies `overlay` on top of `base_config` (see [`Config::merge`]) and
+/// builds the application with the result, so embedders can serve e.g.
+/// per-tenant model selections without reloading configuration files.
+///
+/// # Arguments
+///
+/// * `base_config` - Shared base configuration
+/// * `overlay` - Sections and keys to change for this application
+///
+/// # Returns
+///
+/// Returns an Axum Router that can be served directly.
+///
+/// # Errors
+///
+/// Returns a `ProxyError` if the overlay does not match the configuration
+/// schema or the application cannot be created.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use modelmux::{Config, PartialConfig, create_app_with_overlay};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let base = Config::load()?;
+///     let overlay = PartialConfig::from_toml("[vertex]\nmodel = \"claude-opus-4@20250514\"\n")?;
+///     let app = create_app_with_overlay(base, overlay).await?;
+///
+///     let listener = tokio::net::TcpListener::bind("0.0.0.0:3001").await?;
+///     axum::serve(listener, app).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn create_app_with_overlay(
+    mut base_config: Config,
+    overlay: PartialConfig,
+) -> Result<axum::Router, ProxyError> {
+    base_config.merge(overlay)?;
+    create_app(base_config).await
+}
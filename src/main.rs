@@ -75,7 +75,7 @@
 //! # Interactive configuration setup
 //! modelmux config init
 //!
-//! # Validate configuration
+//! # Validate configuration (exit code 0 = valid, 1 = errors, 2 = warnings only)
 //! modelmux config validate
 //!
 //! # Show current configuration
@@ -83,6 +83,10 @@
 //!
 //! # Edit configuration
 //! modelmux config edit
+//!
+//! # Back up and restore configuration
+//! modelmux config backup
+//! modelmux config restore --file <backup>
 //! ```
 //!
 //! ## API Usage
@@ -115,11 +119,13 @@ use std::sync::Arc;
 
 use axum::Router;
 use axum::routing::{get, post};
-use tower_http::cors::CorsLayer;
 use tower_http::trace::TraceLayer;
 use tracing::info;
 
-use crate::config::{Config, cli::ConfigCli};
+use crate::config::{
+    Config, cli::ConfigCli, cli::DiffOptions, cli::ExportOptions, cli::InitOptions,
+    cli::MigrateOptions, cli::SchemaOptions, cli::ShowOptions, cli::ValidateOptions,
+};
 use crate::error::Result;
 use crate::provider::LlmProviderBackend;
 use crate::server::AppState;
@@ -127,11 +133,17 @@ use crate::server::AppState;
 /* --- modules --------------------------------------------------------------------------------- */
 
 mod auth;
+mod bench;
+mod cache;
 mod config;
 mod converter;
 mod error;
+mod hooks;
+mod logging;
 mod provider;
 mod server;
+mod telemetry;
+mod usage;
 
 /* --- constants ------------------------------------------------------------------------------ */
 
@@ -152,14 +164,6 @@ const VERSION: &str = env!("CARGO_PKG_VERSION");
 ///  * `ProxyError` if initialization or server startup fails
 #[tokio::main]
 async fn main() {
-    // Load .env file for legacy environment variable support (before any config loading)
-    if let Err(e) = dotenvy::dotenv() {
-        // Only log at debug level - .env is optional
-        if std::path::Path::new(".env").exists() {
-            eprintln!("Warning: Could not load .env file: {}", e);
-        }
-    }
-
     // Handle CLI arguments before config loading
     if let Some(exit_code) = handle_cli_args().await {
         std::process::exit(exit_code);
@@ -180,12 +184,15 @@ async fn run() -> Result<()> {
     let config = initialize_config()?;
     // Keep `_log_guard` alive until the end of `run()` so the rolling
     // file writer can flush on shutdown.
-    let _log_guard = initialize_logging(&config);
+    let (_log_guard, telemetry_guard) = initialize_logging(&config);
 
     let app_state = create_app_state(config.clone()).await?;
-    let app = create_router(app_state);
+    let app = create_router(app_state.clone()).await?;
 
-    start_server(&config, app).await
+    let result = start_server(&config, app).await;
+    app_state.shutdown();
+    telemetry_guard.shutdown();
+    result
 }
 
 ///
@@ -194,7 +201,22 @@ async fn run() -> Result<()> {
 /// This ensures these commands work even without proper configuration.
 /// Returns Some(exit_code) if the program should exit, None to continue.
 async fn handle_cli_args() -> Option<i32> {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+
+    // `--config <path>` before the command wins over MODELMUX_CONFIG_FILE
+    match take_config_flag(&mut args) {
+        Ok(Some(path)) => {
+            // SAFETY: nothing else has started reading or writing the environment yet;
+            // configuration and logging are initialised after argument handling
+            unsafe { env::set_var(crate::config::loader::CONFIG_FILE_ENV, path) };
+        }
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            eprintln!("Usage: modelmux --config <path> [COMMAND]");
+            return Some(1);
+        }
+    }
 
     if args.len() < 2 {
         return None; // No arguments, proceed with normal startup
@@ -223,6 +245,33 @@ async fn handle_cli_args() -> Option<i32> {
             let exit_code = run_logs(follow);
             Some(exit_code)
         }
+        "keygen" => {
+            let exit_code = run_keygen(&args[2..]);
+            Some(exit_code)
+        }
+        "test-connection" => Some(ConfigCli::test_connection().await),
+        "bench" => Some(run_bench(&args[2..]).await),
+        "provider" => match args.get(2).map(String::as_str) {
+            Some("list") => Some(ConfigCli::list_providers()),
+            _ => {
+                eprintln!("Usage: modelmux provider list");
+                Some(1)
+            }
+        },
+        "completion" => match args.get(2).map(String::as_str).map(ConfigCli::completion) {
+            Some(Ok(script)) => {
+                print!("{}", script);
+                Some(0)
+            }
+            Some(Err(e)) => {
+                eprintln!("Error: {}", e);
+                Some(1)
+            }
+            None => {
+                eprintln!("Usage: modelmux completion <bash|zsh|fish|powershell>");
+                Some(1)
+            }
+        },
         _ => {
             // Unknown command or option - show error and help
             if args[1].starts_with('-') {
@@ -238,10 +287,16 @@ async fn handle_cli_args() -> Option<i32> {
                 eprintln!("  doctor    - Run configuration health check");
                 eprintln!("  validate  - Validate configuration");
                 eprintln!("  logs      - Show log file location and recent entries");
+                eprintln!("  keygen    - Generate a client API key");
+                eprintln!("  completion - Print a shell completion script");
+                eprintln!("  test-connection - Check auth and send a ping request upstream");
+                eprintln!("  bench     - Measure end-to-end request latency");
+                eprintln!("  provider  - List configured providers and their resolved URLs");
                 eprintln!();
                 eprintln!("Available options:");
                 eprintln!("  --version, -V  - Show version");
                 eprintln!("  --help, -h     - Show help");
+                eprintln!("  --config <path> - Use a custom config file");
                 eprintln!();
                 eprintln!("Run 'modelmux --help' for more information.");
                 Some(1)
@@ -250,6 +305,37 @@ async fn handle_cli_args() -> Option<i32> {
     }
 }
 
+///
+/// Remove a leading `--config <path>` or `--config=<path>` from the arguments.
+///
+/// # Arguments
+///  * `args` - command line arguments, including the program name
+///
+/// # Returns
+///  * `Ok(Some(path))` when the flag was given, `Ok(None)` otherwise
+///  * Error message when the path is missing
+fn take_config_flag(args: &mut Vec<String>) -> std::result::Result<Option<String>, String> {
+    let Some(flag) = args.get(1) else {
+        return Ok(None);
+    };
+    let path = if flag == "--config" {
+        let path = args.get(2).filter(|path| !path.starts_with('-')).cloned();
+        let path = path.ok_or_else(|| "--config needs a file path".to_string())?;
+        args.drain(1..3);
+        path
+    } else if let Some(path) = flag.strip_prefix("--config=") {
+        let path = path.to_string();
+        args.remove(1);
+        path
+    } else {
+        return Ok(None);
+    };
+    if path.is_empty() {
+        return Err("--config needs a file path".to_string());
+    }
+    Ok(Some(path))
+}
+
 ///
 /// Handle config subcommands.
 async fn handle_config_command(args: &[String]) -> Option<i32> {
@@ -261,10 +347,166 @@ async fn handle_config_command(args: &[String]) -> Option<i32> {
     }
 
     let result = match args[0].as_str() {
-        "init" => ConfigCli::init(),
-        "show" => ConfigCli::show(),
-        "validate" => ConfigCli::validate(),
+        "init" => {
+            let mut options = InitOptions::default();
+            for flag in &args[1..] {
+                match flag.as_str() {
+                    "--no-backup" => options.no_backup = true,
+                    "--non-interactive" => options.non_interactive = true,
+                    "--dry-run" => options.dry_run = true,
+                    _ => {
+                        eprintln!(
+                            "Usage: modelmux config init [--no-backup] [--non-interactive] \
+                             [--dry-run]"
+                        );
+                        return Some(1);
+                    }
+                }
+            }
+            ConfigCli::init(options)
+        }
+        "show" => {
+            let usage = "Usage: modelmux config show [--format text|json|toml|yaml] \
+                         [--include-sensitive --confirm-show-sensitive]";
+            let mut options = ShowOptions::default();
+            let mut confirmed = false;
+            let mut flags = args[1..].iter();
+            while let Some(flag) = flags.next() {
+                match flag.as_str() {
+                    "--format" => match flags.next().map(|format| format.parse()) {
+                        Some(Ok(format)) => options.format = format,
+                        Some(Err(e)) => {
+                            eprintln!("Error: {}", e);
+                            return Some(1);
+                        }
+                        None => {
+                            eprintln!("{}", usage);
+                            return Some(1);
+                        }
+                    },
+                    "--include-sensitive" => options.include_sensitive = true,
+                    "--confirm-show-sensitive" => confirmed = true,
+                    _ => {
+                        eprintln!("{}", usage);
+                        return Some(1);
+                    }
+                }
+            }
+            if options.include_sensitive && !confirmed {
+                eprintln!(
+                    "Error: --include-sensitive prints secrets; add --confirm-show-sensitive to \
+                     proceed"
+                );
+                return Some(1);
+            }
+            ConfigCli::show(options)
+        }
+        "validate" => {
+            let mut options = ValidateOptions::default();
+            for flag in &args[1..] {
+                match flag.as_str() {
+                    "--exit-on-warnings" => options.exit_on_warnings = true,
+                    "--quiet" | "-q" => options.quiet = true,
+                    "--json" => options.json = true,
+                    "--check-size" => options.check_size = true,
+                    _ => {
+                        eprintln!(
+                            "Usage: modelmux config validate [--exit-on-warnings] [--quiet] \
+                             [--json] [--check-size]"
+                        );
+                        return Some(1);
+                    }
+                }
+            }
+            return Some(ConfigCli::validate(options));
+        }
+        "export" => {
+            let usage = "Usage: modelmux config export [--format env|json] [--redact-secrets]";
+            let mut options = ExportOptions::default();
+            let mut flags = args[1..].iter();
+            while let Some(flag) = flags.next() {
+                match flag.as_str() {
+                    "--format" => match flags.next().map(|format| format.parse()) {
+                        Some(Ok(format)) => options.format = format,
+                        Some(Err(e)) => {
+                            eprintln!("Error: {}", e);
+                            return Some(1);
+                        }
+                        None => {
+                            eprintln!("{}", usage);
+                            return Some(1);
+                        }
+                    },
+                    "--redact-secrets" => options.redact_secrets = true,
+                    _ => {
+                        eprintln!("{}", usage);
+                        return Some(1);
+                    }
+                }
+            }
+            ConfigCli::export(options)
+        }
+        "diff" => {
+            let usage = "Usage: modelmux config diff [--format text|json]";
+            let mut options = DiffOptions::default();
+            let mut flags = args[1..].iter();
+            while let Some(flag) = flags.next() {
+                match flag.as_str() {
+                    "--format" => match flags.next().map(|format| format.parse()) {
+                        Some(Ok(format)) => options.format = format,
+                        Some(Err(e)) => {
+                            eprintln!("Error: {}", e);
+                            return Some(1);
+                        }
+                        None => {
+                            eprintln!("{}", usage);
+                            return Some(1);
+                        }
+                    },
+                    _ => {
+                        eprintln!("{}", usage);
+                        return Some(1);
+                    }
+                }
+            }
+            ConfigCli::diff(options)
+        }
+        "schema" => match args[1..].iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+            [] => ConfigCli::schema(SchemaOptions::default()),
+            ["--output", path] => {
+                ConfigCli::schema(SchemaOptions { output: Some(std::path::PathBuf::from(path)) })
+            }
+            _ => {
+                eprintln!("Usage: modelmux config schema [--output <file>]");
+                return Some(1);
+            }
+        },
         "edit" => ConfigCli::edit(),
+        "backup" => ConfigCli::backup(),
+        "restore" => match args[1..].iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+            [] => ConfigCli::restore(None),
+            ["--file", path] => ConfigCli::restore(Some(std::path::Path::new(path))),
+            _ => {
+                eprintln!("Usage: modelmux config restore [--file <path>]");
+                return Some(1);
+            }
+        },
+        "migrate" => {
+            let mut options = MigrateOptions::default();
+            for flag in &args[1..] {
+                match flag.as_str() {
+                    "--dry-run" => options.dry_run = true,
+                    _ => {
+                        eprintln!("Usage: modelmux config migrate [--dry-run]");
+                        return Some(1);
+                    }
+                }
+            }
+            return Some(ConfigCli::migrate(options).unwrap_or_else(|e| {
+                eprintln!("Error: {}", e);
+                1
+            }));
+        }
         "--help" | "-h" => {
             print_config_help();
             return Some(0);
@@ -298,14 +540,38 @@ fn print_config_help() {
     println!("    init        Interactive configuration setup");
     println!("    show        Display current configuration");
     println!("    validate    Validate configuration");
+    println!("    export      Print configuration as MODELMUX_* env vars or JSON");
+    println!("    diff        List settings that differ from the defaults");
+    println!("    schema      Print the JSON Schema of the config file (--output <file>)");
     println!("    edit        Edit configuration file in default editor");
+    println!("    backup      Save a timestamped copy of the configuration file");
+    println!("    restore     List backups, or restore one with --file <path>");
+    println!("    migrate     Convert legacy GCP_SERVICE_ACCOUNT_KEY / LLM_URL env vars to config");
     println!("    help        Show this help message");
     println!();
     println!("EXAMPLES:");
     println!("    modelmux config init        # Set up configuration interactively");
     println!("    modelmux config show        # Show current configuration");
+    println!("    modelmux config show --format json  # Print config as JSON, TOML or YAML");
     println!("    modelmux config validate    # Check configuration validity");
+    println!("    modelmux config validate --json --exit-on-warnings");
+    println!("                                # CI check: exit 0 clean, 1 errors, 2 warnings");
+    println!("    modelmux config validate --check-size");
+    println!("                                # Warn if the running server saw larger bodies");
+    println!("    modelmux config export --redact-secrets > modelmux.env");
+    println!("    modelmux config diff        # Show settings changed from the defaults");
+    println!("                                # Env vars for a container, secrets hidden");
+    println!("    modelmux config schema --output config.schema.json");
+    println!("                                # Write the schema for editor completion");
     println!("    modelmux config edit        # Open config file in editor");
+    println!("    modelmux config backup      # Back up config (keeps the newest 10)");
+    println!("    modelmux config restore     # List available backups");
+    println!("    modelmux config restore --file ~/.config/modelmux/config.<time>.toml.bak");
+    println!("    modelmux config init --no-backup   # Overwrite config without a backup");
+    println!("    modelmux config init --non-interactive --dry-run");
+    println!("                                # Print config built from MODELMUX_SETUP_* vars");
+    println!("    modelmux config migrate --dry-run");
+    println!("                                # Print config converted from legacy env vars");
 }
 
 ///
@@ -315,16 +581,27 @@ fn print_help() {
     println!("High-performance proxy server converting OpenAI API requests to Vertex AI format");
     println!();
     println!("USAGE:");
-    println!("    modelmux [COMMAND] [OPTIONS]");
+    println!("    modelmux [--config <path>] [COMMAND] [OPTIONS]");
     println!();
     println!("COMMANDS:");
     println!("    config              Configuration management (init, show, validate, edit)");
     println!("    doctor              Check configuration and system health (legacy)");
     println!("    validate            Validate configuration and exit (legacy)");
+    println!("    logs                Show log file location and recent entries");
+    println!("    keygen              Generate a client API key and its SHA-256 hash");
+    println!("                        [--name <name>] [--allowed-models <m1,m2>]");
+    println!("    completion <shell>  Print a tab-completion script (bash, zsh, fish, powershell)");
+    println!("    test-connection     Fetch an access token and send a 1-token ping upstream");
+    println!("    bench               Measure latency of the running proxy (or --direct upstream)");
+    println!("                        [--requests N] [--concurrency C] [--model M]");
+    println!("                        [--output table|json] [--api-key K] [--dry-run]");
+    println!("    provider list       Show configured providers, resolved URLs and auth type");
     println!();
     println!("OPTIONS:");
     println!("    -h, --help          Print help information");
     println!("    -V, --version       Print version information");
+    println!("    --config <path>     Load this config file instead of the user and system ones");
+    println!("                        (also MODELMUX_CONFIG_FILE; the flag wins)");
     println!();
     println!("CONFIGURATION:");
     println!("    ModelMux uses a modern configuration system with multiple sources:");
@@ -346,7 +623,7 @@ fn print_help() {
     println!();
     println!("  Provider / model configuration:");
     println!(
-        "    LLM_PROVIDER               Backend: vertex (default) or openai_compatible (future)"
+        "    LLM_PROVIDER               Backend: vertex (default), openai_compatible, bedrock, azure_openai or ollama"
     );
     println!();
     println!("    Either set a single override (ignores provider-specific fields):");
@@ -370,6 +647,10 @@ fn print_help() {
     println!("    modelmux validate           Validate and exit");
     println!("    modelmux logs               Show log directory and recent entries");
     println!("    modelmux logs -f            Follow (tail) the latest log file");
+    println!("    modelmux keygen --name ci   Generate a key and add it to auth.api_keys");
+    println!("    modelmux test-connection    Check credentials and reach the model");
+    println!("    modelmux bench --requests 100 --concurrency 10   Benchmark the running proxy");
+    println!("    source <(modelmux completion bash)   Enable tab completion in bash");
     println!();
     println!("For more information, visit: https://github.com/yarenty/modelmux");
 }
@@ -421,8 +702,13 @@ fn run_doctor() -> i32 {
             // Run validation checks
             println!("Running validation checks:");
             match config.validate() {
-                Ok(_) => {
-                    println!("✓ No validation issues found");
+                Ok(warnings) => {
+                    if warnings.is_empty() {
+                        println!("✓ No validation issues found");
+                    }
+                    for warning in &warnings {
+                        println!("{}", ConfigCli::issue_line(warning));
+                    }
                     println!();
                     println!("[SUCCESS] Configuration looks good! You're ready to run ModelMux.");
                     println!();
@@ -468,9 +754,11 @@ fn run_doctor() -> i32 {
 
                     0
                 }
-                Err(e) => {
+                Err(_) => {
                     println!("✗ Configuration validation failed:");
-                    println!("{}", e);
+                    for issue in config.validate_with_issues() {
+                        println!("{}", ConfigCli::issue_line(&issue));
+                    }
                     println!();
                     println!(
                         "[ERROR] Configuration has errors. Please fix them before running ModelMux."
@@ -501,13 +789,18 @@ fn run_validate() -> i32 {
 
     match Config::load() {
         Ok(config) => match config.validate() {
-            Ok(_) => {
+            Ok(warnings) => {
+                for warning in &warnings {
+                    println!("{}", ConfigCli::issue_line(warning));
+                }
                 println!("✅ Configuration is valid");
                 0
             }
-            Err(e) => {
+            Err(_) => {
                 println!("❌ Configuration validation failed:");
-                println!("{}", e);
+                for issue in config.validate_with_issues() {
+                    println!("{}", ConfigCli::issue_line(&issue));
+                }
                 1
             }
         },
@@ -519,6 +812,170 @@ fn run_validate() -> i32 {
     }
 }
 
+///
+/// Generate a client API key, print it with its SHA-256 hash, and optionally register it.
+///
+/// Usage:
+///   modelmux keygen                                   — print a new key and its hash
+///   modelmux keygen --name ci --allowed-models a,b    — also append to `auth.api_keys`
+///
+/// Returns exit code 0 on success, 1 on invalid arguments or failure.
+fn run_keygen(args: &[String]) -> i32 {
+    let mut name: Option<String> = None;
+    let mut allowed_models: Vec<String> = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--name" => match iter.next() {
+                Some(value) if !value.trim().is_empty() => name = Some(value.trim().to_string()),
+                _ => {
+                    eprintln!("Error: --name requires a value");
+                    return 1;
+                }
+            },
+            "--allowed-models" => match iter.next() {
+                Some(value) => {
+                    allowed_models = value
+                        .split(',')
+                        .map(|m| m.trim().to_string())
+                        .filter(|m| !m.is_empty())
+                        .collect();
+                }
+                None => {
+                    eprintln!("Error: --allowed-models requires a comma-separated list");
+                    return 1;
+                }
+            },
+            other => {
+                eprintln!("Error: Unknown keygen option: {}", other);
+                eprintln!("Usage: modelmux keygen [--name <name>] [--allowed-models <m1,m2>]");
+                return 1;
+            }
+        }
+    }
+
+    let api_key = match crate::auth::generate_api_key() {
+        Ok(key) => key,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            return 1;
+        }
+    };
+    let key_hash = crate::auth::hash_api_key(&api_key);
+
+    println!("🔑 API key:  {}", api_key);
+    println!("⚠️  Save this key - it will not be shown again");
+    println!();
+    println!("SHA-256:     {}", key_hash);
+
+    let Some(name) = name else {
+        println!();
+        println!("Add it to your config under [[auth.api_keys]] with name and key_hash,");
+        println!("or rerun with --name to append it automatically.");
+        return 0;
+    };
+
+    let entry = crate::config::ApiKeyEntry { name, key_hash, allowed_models };
+    match ConfigCli::append_api_key(&entry) {
+        Ok(path) => {
+            println!();
+            println!("✅ Added API key '{}' to {}", entry.name, path.display());
+            0
+        }
+        Err(e) => {
+            eprintln!();
+            eprintln!("❌ {}", e);
+            1
+        }
+    }
+}
+
+///
+/// Benchmark end-to-end latency and print the results.
+///
+/// Usage:
+///   modelmux bench [--requests N] [--concurrency C] [--model M] [--direct]
+///                  [--api-key K] [--output table|json] [--dry-run]
+///
+/// Returns exit code 0 on success, 1 on invalid arguments or failure.
+async fn run_bench(args: &[String]) -> i32 {
+    const USAGE: &str = "Usage: modelmux bench [--requests N] [--concurrency C] [--model M] \
+                         [--direct] [--api-key K] [--output table|json] [--dry-run]";
+    let mut options = crate::bench::BenchOptions::default();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--requests" | "-n" => match iter.next().map(|value| value.parse()) {
+                Some(Ok(requests)) if requests > 0 => options.requests = requests,
+                _ => {
+                    eprintln!("Error: --requests requires a positive number");
+                    return 1;
+                }
+            },
+            "--concurrency" | "-c" => match iter.next().map(|value| value.parse()) {
+                Some(Ok(concurrency)) if concurrency > 0 => options.concurrency = concurrency,
+                _ => {
+                    eprintln!("Error: --concurrency requires a positive number");
+                    return 1;
+                }
+            },
+            "--model" => match iter.next() {
+                Some(model) => options.model = Some(model.clone()),
+                None => {
+                    eprintln!("Error: --model requires a value");
+                    return 1;
+                }
+            },
+            "--api-key" => match iter.next() {
+                Some(key) => options.api_key = Some(key.clone()),
+                None => {
+                    eprintln!("Error: --api-key requires a value");
+                    return 1;
+                }
+            },
+            "--output" => match iter.next().map(|output| output.parse()) {
+                Some(Ok(output)) => options.output = output,
+                Some(Err(e)) => {
+                    eprintln!("Error: {}", e);
+                    return 1;
+                }
+                None => {
+                    eprintln!("Error: --output requires a value (table or json)");
+                    return 1;
+                }
+            },
+            "--direct" => options.direct = true,
+            "--dry-run" => options.dry_run = true,
+            other => {
+                eprintln!("Error: Unknown bench option: {}", other);
+                eprintln!("{}", USAGE);
+                return 1;
+            }
+        }
+    }
+
+    let report = match crate::bench::run(&options).await {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            return 1;
+        }
+    };
+    match options.output {
+        crate::bench::BenchOutput::Table => print!("{}", crate::bench::render_table(&report)),
+        crate::bench::BenchOutput::Json => match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                return 1;
+            }
+        },
+    }
+    0
+}
+
 ///
 /// Show log file location, list recent log files, and optionally tail the latest one.
 ///
@@ -583,7 +1040,7 @@ fn run_logs(follow: bool) -> i32 {
     let print_tail = |path: &std::path::Path, lines: usize| {
         if let Ok(file) = fs::File::open(path) {
             let reader = BufReader::new(file);
-            let all: Vec<String> = reader.lines().filter_map(|l| l.ok()).collect();
+            let all: Vec<String> = reader.lines().map_while(|l| l.ok()).collect();
             let start = all.len().saturating_sub(lines);
             for line in &all[start..] {
                 println!("{}", line);
@@ -657,11 +1114,24 @@ fn initialize_config() -> Result<Config> {
 /// The last 30 rotated files are kept (~ the last month) so logs never grow
 /// without bound. The returned guard must be kept alive for the lifetime of
 /// the process so the file appender can flush on shutdown.
-fn initialize_logging(config: &Config) -> Option<tracing_appender::non_blocking::WorkerGuard> {
+///
+/// With `telemetry.enabled` spans are also exported to the OpenTelemetry
+/// collector; the returned telemetry guard flushes them on shutdown.
+fn initialize_logging(
+    config: &Config,
+) -> (Option<tracing_appender::non_blocking::WorkerGuard>, telemetry::TelemetryGuard) {
     use tracing_appender::rolling::{RollingFileAppender, Rotation};
     use tracing_subscriber::fmt::writer::MakeWriterExt;
 
     let level = config.server.log_level.to_tracing_level();
+    let format = config.server.log_format;
+    let (telemetry_layer, telemetry_guard) = match telemetry::layer(&config.telemetry) {
+        Ok(telemetry) => telemetry,
+        Err(e) => {
+            eprintln!("[modelmux] trace export disabled: {}", e);
+            (None, telemetry::TelemetryGuard::default())
+        }
+    };
 
     let appender = crate::config::paths::user_log_dir().and_then(|dir| {
         RollingFileAppender::builder()
@@ -669,25 +1139,27 @@ fn initialize_logging(config: &Config) -> Option<tracing_appender::non_blocking:
             .filename_prefix("modelmux.log")
             .max_log_files(30)
             .build(&dir)
-            .map_err(|e| crate::error::ProxyError::Config(format!("log appender: {}", e)))
+            .map_err(|e| crate::error::ProxyError::config_with_source("log appender", e))
     });
 
-    match appender {
+    let log_guard = match appender {
         Ok(appender) => {
             let (non_blocking, guard) = tracing_appender::non_blocking(appender);
-            tracing_subscriber::fmt()
-                .with_max_level(level)
-                .with_target(false)
-                .with_writer(non_blocking.and(std::io::stdout))
-                .init();
+            logging::init_subscriber(
+                format,
+                level,
+                non_blocking.and(std::io::stdout),
+                telemetry_layer,
+            );
             Some(guard)
         }
         Err(e) => {
             eprintln!("[modelmux] file logging disabled: {}", e);
-            tracing_subscriber::fmt().with_max_level(level).with_target(false).init();
+            logging::init_subscriber(format, level, std::io::stdout, telemetry_layer);
             None
         }
-    }
+    };
+    (log_guard, telemetry_guard)
 }
 
 ///
@@ -704,6 +1176,10 @@ fn initialize_logging(config: &Config) -> Option<tracing_appender::non_blocking:
 ///  * `ProxyError` if state initialization fails
 async fn create_app_state(config: Config) -> Result<Arc<AppState>> {
     let app_state = Arc::new(AppState::new(config.clone()).await?);
+    server::jobs::start_workers(&app_state);
+    server::queue::start_dispatcher(&app_state);
+    server::deep_health::start_deep_health_check(&app_state);
+    server::start_config_reloader(&app_state);
     Ok(app_state)
 }
 
@@ -718,14 +1194,59 @@ async fn create_app_state(config: Config) -> Result<Arc<AppState>> {
 ///
 /// # Returns
 ///  * Configured Axum router ready for serving
-fn create_router(app_state: Arc<AppState>) -> Router {
-    Router::new()
-        .route("/v1/chat/completions", post(server::chat_completions))
+///  * `ProxyError::Config` if the audit log cannot be opened
+async fn create_router(app_state: Arc<AppState>) -> Result<Router> {
+    let upload_limit = server::uploads::body_limit(&app_state.config.read().await.upload);
+    let body_limit = server::limits::body_limit(&app_state.config.read().await.limits);
+    let batch_limit = server::limits::batch_body_limit(&app_state.config.read().await.limits);
+    let cors = server::cors::cors_layer(&app_state.config.read().await.cors);
+    let audit = server::audit::AuditLogLayer::new(&app_state.config.read().await.audit_log)?;
+    let admin_routes = Router::new()
+        .route("/v1/usage", get(server::usage))
+        .route("/admin/ab-test/stats", get(server::ab_test_stats))
+        .route("/admin/config", get(server::admin_config::admin_config))
+        .route("/admin/config/sources", get(server::admin_config::admin_config_sources))
+        .route_layer(axum::middleware::from_fn_with_state(
+            app_state.clone(),
+            server::require_admin_secret,
+        ));
+
+    let router = Router::new()
+        .route("/v1/chat/completions", post(server::chat_completions).layer(body_limit))
+        .route("/v1/chat/completions/schema", get(server::chat_completion_schema))
+        .route(
+            "/v1/chat/completions/async",
+            post(server::jobs::create_async_completion).layer(body_limit),
+        )
+        .route("/v1/jobs/{job_id}", get(server::jobs::job_status))
+        .route("/v1/jobs/{job_id}/result", get(server::jobs::job_result))
+        .route(
+            "/v1/files/upload-and-chat",
+            post(server::uploads::upload_and_chat).layer(upload_limit),
+        )
+        .route("/v1/embeddings", post(server::embeddings::embeddings).layer(body_limit))
+        .route(
+            "/v1/batches/convert",
+            post(server::batches::convert_batch_output).layer(batch_limit),
+        )
         .route("/v1/models", get(server::models))
         .route("/health", get(server::health))
-        .layer(CorsLayer::permissive())
+        .route("/health/connections", get(server::connections::connection_stats))
+        .route("/metrics", get(server::metrics::prometheus_metrics));
+    // Admin routes keep their own admin secret check. Installed even without a
+    // proxy key so a configuration reload can turn the check on.
+    let auth = axum::middleware::from_fn_with_state(
+        app_state.clone(),
+        server::auth_middleware::auth_middleware,
+    );
+
+    Ok(router
+        .layer(auth)
+        .layer(audit)
+        .merge(admin_routes)
+        .layer(cors)
         .layer(TraceLayer::new_for_http())
-        .with_state(app_state)
+        .with_state(app_state))
 }
 
 ///
@@ -772,17 +1293,20 @@ async fn start_server(config: &Config, app: Router) -> Result<()> {
                 config.server.port,
                 config.server.port
             );
-            crate::error::ProxyError::Http(suggestions)
+            crate::error::ProxyError::Http(suggestions, None)
         } else {
-            crate::error::ProxyError::Http(format!(
-                "{}\n\n\
+            crate::error::ProxyError::Http(
+                format!(
+                    "{}\n\n\
                     To fix this:\n\
                     • Check if the port is valid (1-65535)\n\
                     • Ensure you have permission to bind to the port\n\
                     • Try a different port: export PORT=3001\n\n\
                     Run 'modelmux doctor' for more help.",
-                error_msg
-            ))
+                    error_msg
+                ),
+                None,
+            )
         }
     })?;
 
@@ -790,7 +1314,7 @@ async fn start_server(config: &Config, app: Router) -> Result<()> {
 
     axum::serve(listener, app)
         .await
-        .map_err(|e| crate::error::ProxyError::Http(format!("Server error: {}", e)))?;
+        .map_err(|e| crate::error::ProxyError::http_with_source("Server error", e))?;
 
     Ok(())
 }
@@ -804,18 +1328,20 @@ async fn start_server(config: &Config, app: Router) -> Result<()> {
 /// # Arguments
 ///  * `config` - application configuration
 fn log_startup_info(config: &Config) {
-    info!("ModelMux v{} running on port {}", VERSION, config.server.port);
+    info!(version = VERSION, port = config.server.port, "ModelMux running");
     info!("Proxy supports tool/function calling for file creation and editing");
-    info!("OpenAI-compatible endpoint: http://localhost:{}/v1", config.server.port);
+    info!(
+        endpoint = %format_args!("http://localhost:{}/v1", config.server.port),
+        "OpenAI-compatible endpoint"
+    );
 
     if matches!(
         config.server.log_level,
         crate::config::LogLevel::Trace | crate::config::LogLevel::Debug
     ) {
         info!(
-            "[TRACE] Trace logging is ENABLED (LOG_LEVEL={:?}) - tool calls and interactions will \
-       be logged",
-            config.server.log_level
+            log_level = ?config.server.log_level,
+            "[TRACE] Trace logging is ENABLED - tool calls and interactions will be logged"
         );
     }
 }
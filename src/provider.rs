@@ -3,7 +3,10 @@
 //!
 //! Each provider implements [LlmProviderBackend]. Config is driven by `LLM_PROVIDER`;
 //! only the matching provider is loaded (Vertex: full URL or VERTEX_* structure;
-//! others: provider-specific vars, with stubs ready for future implementation).
+//! OpenAI-compatible: `OPENAI_API_BASE` and `OPENAI_API_KEY`; Bedrock: `AWS_REGION`,
+//! AWS credentials and `BEDROCK_MODEL_ID`; Azure OpenAI: `AZURE_OPENAI_ENDPOINT`,
+//! `AZURE_OPENAI_API_KEY` and `AZURE_OPENAI_DEPLOYMENT`; Ollama: `OLLAMA_BASE_URL` and
+//! `OLLAMA_MODEL`).
 //!
 //! Authors:
 //!   Jaro <yarenty@gmail.com>
@@ -12,9 +15,17 @@
 
 use std::env;
 
-use crate::config::{ServiceAccountKey, VertexConfig};
+use crate::config::{AzureOpenAiConfig, OpenAiCompatibleConfig, ServiceAccountKey, VertexConfig, VertexRegionConfig};
+use crate::converter::openai_to_anthropic::TargetFormat;
 use crate::error::{ProxyError, Result};
 
+pub mod azure;
+pub mod bedrock;
+pub mod ollama;
+pub use azure::AzureOpenAiProvider;
+pub use bedrock::BedrockProvider;
+pub use ollama::OllamaProvider;
+
 /* --- auth strategy --------------------------------------------------------------------------- */
 
 ///
@@ -27,8 +38,30 @@ pub enum AuthStrategy {
     /// Google Cloud OAuth2 with service account (Vertex AI).
     GcpOAuth2(Box<ServiceAccountKey>),
     /// Static Bearer token (e.g. from OPENAI_API_KEY, MISTRAL_API_KEY).
-    #[allow(dead_code)]
     BearerToken(String),
+    /// Google Cloud OAuth2 token from the metadata server (GKE / Cloud Run Workload Identity).
+    WorkloadIdentity {
+        /// Audience sent with the token request; empty for the default service account token
+        audience: String,
+        /// Token endpoint; `None` for the metadata server default
+        token_url: Option<String>,
+    },
+    /// Google Cloud OAuth2 with Application Default Credentials (`gcloud auth application-default login`).
+    ApplicationDefault {
+        /// ADC file; `None` for `GOOGLE_APPLICATION_CREDENTIALS` or the gcloud default location
+        credentials_file: Option<String>,
+    },
+    /// AWS Signature Version 4 with static credentials (AWS Bedrock).
+    AwsSigV4 {
+        /// AWS region of the signing scope, e.g. `us-east-1`
+        region: String,
+        /// `AWS_ACCESS_KEY_ID`
+        access_key: String,
+        /// `AWS_SECRET_ACCESS_KEY`
+        secret_key: String,
+        /// `AWS_SESSION_TOKEN` of temporary credentials
+        session_token: Option<String>,
+    },
 }
 
 /* --- provider trait -------------------------------------------------------------------------- */
@@ -61,6 +94,37 @@ pub trait LlmProviderBackend: std::fmt::Debug + Send + Sync {
 
 /* --- vertex provider ------------------------------------------------------------------------- */
 
+/** Vertex fields built into the resource URL, with the env var each falls back to */
+const VERTEX_FIELDS: [(&str, &str); 5] = [
+    ("region", "VERTEX_REGION"),
+    ("project", "VERTEX_PROJECT"),
+    ("location", "VERTEX_LOCATION"),
+    ("publisher", "VERTEX_PUBLISHER"),
+    ("model", "VERTEX_MODEL_ID"),
+];
+
+/** Host of the global Vertex AI endpoint; regional hosts add a `<region>-` prefix */
+const VERTEX_HOST_SUFFIX: &str = "aiplatform.googleapis.com";
+
+/** Publisher of the Vertex AI text embedding models */
+const EMBEDDING_PUBLISHER: &str = "google";
+
+/** Output token limits of Claude models by model ID prefix, most specific first */
+const MODEL_MAX_OUTPUT_TOKENS: [(&str, u32); 9] = [
+    ("claude-opus-4-5", 64_000),
+    ("claude-opus-4", 32_000),
+    ("claude-sonnet-4", 64_000),
+    ("claude-haiku-4-5", 64_000),
+    ("claude-3-7-sonnet", 64_000),
+    ("claude-3-5-sonnet", 8_192),
+    ("claude-3-5-haiku", 8_192),
+    ("claude-3-opus", 4_096),
+    ("claude-3-haiku", 4_096),
+];
+
+/** Context window of Claude models in tokens */
+const CLAUDE_CONTEXT_WINDOW_TOKENS: u32 = 200_000;
+
 /// Parsed host from a Vertex AI resource URL.
 /// Used only when region is absent and we need to preserve a non-standard host.
 #[derive(Debug)]
@@ -75,6 +139,8 @@ pub struct VertexProvider {
     pub predict_resource_url: String,
     pub display_model: String,
     pub auth: AuthStrategy,
+    /** most tokens the model can generate, `None` for unknown models */
+    pub max_output_tokens: Option<u32>,
 }
 
 impl VertexProvider {
@@ -88,8 +154,9 @@ impl VertexProvider {
         let service_account_key = Self::load_service_account_key()?;
         let (predict_resource_url, display_model) = Self::resolve_predict_url_and_model(None)?;
         let auth = AuthStrategy::GcpOAuth2(Box::new(service_account_key));
+        let max_output_tokens = Self::max_output_tokens_for_url(&predict_resource_url);
 
-        Ok(Self { predict_resource_url, display_model, auth })
+        Ok(Self { predict_resource_url, display_model, auth, max_output_tokens })
     }
 
     ///
@@ -105,16 +172,123 @@ impl VertexProvider {
     ///
     /// Load Vertex provider from config file and/or environment.
     ///
-    /// Config file values take precedence. Falls back to env vars (including from .env).
+    /// A `url` (config `vertex.url`, then `LLM_URL`) is used as the full resource URL.
+    /// Otherwise the URL is built from `region`/`project`/`location`/`publisher`/`model`,
+    /// each taken from the config file and falling back to its `VERTEX_*` env var
+    /// (including from .env). The resulting URL must be an `https://` Vertex AI
+    /// (`aiplatform.googleapis.com`) URL.
     pub fn from_config_or_env_with_key(
         service_account_key: ServiceAccountKey,
         vertex_config: Option<&VertexConfig>,
+    ) -> Result<Self> {
+        Self::from_config_or_env_with_auth(
+            AuthStrategy::GcpOAuth2(Box::new(service_account_key)),
+            vertex_config,
+        )
+    }
+
+    ///
+    /// Load Vertex provider from config file and/or environment with the given auth strategy.
+    ///
+    /// Same URL resolution as [`from_config_or_env_with_key`](Self::from_config_or_env_with_key);
+    /// used for Workload Identity, where there is no service account key.
+    pub fn from_config_or_env_with_auth(
+        auth: AuthStrategy,
+        vertex_config: Option<&VertexConfig>,
     ) -> Result<Self> {
         let (predict_resource_url, display_model) =
             Self::resolve_predict_url_and_model(vertex_config)?;
-        let auth = AuthStrategy::GcpOAuth2(Box::new(service_account_key));
+        let max_output_tokens = Self::max_output_tokens_for_url(&predict_resource_url);
+
+        Ok(Self { predict_resource_url, display_model, auth, max_output_tokens })
+    }
+
+    ///
+    /// Load the failover Vertex provider from `[failover_vertex]`.
+    ///
+    /// A `url` is used as the full resource URL. Otherwise the URL is built
+    /// from the failover fields; fields it leaves unset are taken from the
+    /// primary `[vertex]` section (so e.g. only `region` and `location` need
+    /// to differ), then from the `VERTEX_*` env vars. `LLM_URL` is ignored
+    /// because it names the primary endpoint.
+    ///
+    /// # Arguments
+    ///  * `auth` - auth strategy, shared with the primary provider
+    ///  * `failover` - `[failover_vertex]` section
+    ///  * `primary` - primary `[vertex]` section, if any
+    ///
+    /// # Returns
+    ///  * Failover provider
+    ///  * `ProxyError::Config` when neither a URL nor the Vertex fields are set
+    pub fn from_failover_config(
+        auth: AuthStrategy,
+        failover: &VertexConfig,
+        primary: Option<&VertexConfig>,
+    ) -> Result<Self> {
+        Self::from_override_config(auth, failover, primary, "[failover_vertex]")
+    }
+
+    ///
+    /// Load a Vertex provider from a section overriding the primary `[vertex]` one.
+    ///
+    /// Used for `[failover_vertex]` and `[model_routes.<name>]`: a `url` is
+    /// used as the full resource URL, otherwise fields left unset are
+    /// inherited from `primary`, then from the `VERTEX_*` env vars.
+    ///
+    /// # Arguments
+    ///  * `auth` - auth strategy of the provider
+    ///  * `overrides` - section with the fields that differ from the primary
+    ///  * `primary` - primary `[vertex]` section, if any
+    ///  * `section` - name of the section, for error messages
+    ///
+    /// # Returns
+    ///  * Provider
+    ///  * `ProxyError::Config` when neither a URL nor the Vertex fields are set
+    pub fn from_override_config(
+        auth: AuthStrategy,
+        overrides: &VertexConfig,
+        primary: Option<&VertexConfig>,
+        section: &str,
+    ) -> Result<Self> {
+        let inherit = |field: &Option<String>,
+                       primary_field: fn(&VertexConfig) -> &Option<String>| {
+            field.clone().or_else(|| primary.and_then(|p| primary_field(p).clone()))
+        };
+        let merged = VertexConfig {
+            region: inherit(&overrides.region, |p| &p.region),
+            project: inherit(&overrides.project, |p| &p.project),
+            location: inherit(&overrides.location, |p| &p.location),
+            publisher: inherit(&overrides.publisher, |p| &p.publisher),
+            model: inherit(&overrides.model, |p| &p.model),
+            ..overrides.clone()
+        };
 
-        Ok(Self { predict_resource_url, display_model, auth })
+        let (predict_resource_url, display_model) =
+            match merged.url.as_deref().map(str::trim).filter(|url| !url.is_empty()) {
+                Some(url) => {
+                    let resource_url = Self::strip_predict_method_suffix(url);
+                    Self::validate_resource_url(&resource_url)?;
+                    (resource_url, Self::get_model_display_name_from_config_or_env(&merged)?)
+                }
+                None => {
+                    let (resource_url, model_id) = Self::build_vertex_resource_url(Some(&merged))?
+                        .ok_or_else(|| {
+                            ProxyError::Config(
+                                format!(
+                                    "Vertex URL of {} not configured: set url or \
+                                     region/project/location/publisher/model",
+                                    section
+                                ),
+                                None,
+                            )
+                        })?;
+                    Self::validate_resource_url(&resource_url)?;
+                    (resource_url, Self::get_model_display_name_vertex(&model_id))
+                }
+            };
+        let max_output_tokens = Self::max_output_tokens_for_url(&predict_resource_url);
+
+        Ok(Self { predict_resource_url, display_model, auth, max_output_tokens })
     }
 
     #[allow(dead_code)]
@@ -122,6 +296,8 @@ impl VertexProvider {
         crate::config::Config::load_service_account_key_standalone()
     }
 
+    // Nested `if let` blocks are intentional here (see CHANGELOG 1.3.1).
+    #[allow(clippy::collapsible_if)]
     fn resolve_predict_url_and_model(vertex_config: Option<&VertexConfig>) -> Result<(String, String)> {
         // 1. Config file URL override — highest priority
         if let Some(cfg) = vertex_config {
@@ -130,6 +306,7 @@ impl VertexProvider {
                 if !trimmed.is_empty() {
                     tracing::debug!("Using config file url override: {}", trimmed);
                     let resource_url = Self::strip_predict_method_suffix(trimmed);
+                    Self::validate_resource_url(&resource_url)?;
                     let display = Self::get_model_display_name_from_config_or_env(cfg)?;
                     return Ok((resource_url, display));
                 }
@@ -142,26 +319,17 @@ impl VertexProvider {
             if !trimmed.is_empty() {
                 tracing::debug!("Using LLM_URL env var: {}", trimmed);
                 let resource_url = Self::strip_predict_method_suffix(&trimmed);
+                Self::validate_resource_url(&resource_url)?;
                 let display = Self::get_model_display_name_override()?;
                 return Ok((resource_url, display));
             }
         }
 
-        // 3. Config file vertex fields
-        if let Some(cfg) = vertex_config {
-            if Self::has_vertex_config(cfg) {
-                tracing::debug!("Using config file vertex fields");
-                let resource_url = Self::build_vertex_resource_url_from_config(cfg)?;
-                let display = Self::get_model_display_name_from_config_or_env(cfg)?;
-                return Ok((resource_url, display));
-            }
-        }
-
-        // 4. Environment variables
-        if Self::has_vertex_vars() {
-            tracing::debug!("Using VERTEX_* environment variables");
-            let resource_url = Self::build_vertex_resource_url()?;
-            let display = Self::get_model_display_name_vertex()?;
+        // 3. Vertex fields: config file first, VERTEX_* env vars for missing ones
+        if let Some((resource_url, model_id)) = Self::build_vertex_resource_url(vertex_config)? {
+            tracing::debug!("Using vertex fields from config file and/or environment");
+            Self::validate_resource_url(&resource_url)?;
+            let display = Self::get_model_display_name_vertex(&model_id);
             return Ok((resource_url, display));
         }
 
@@ -179,6 +347,7 @@ impl VertexProvider {
              Or environment variables:\n\
                LLM_URL=<full-url>  OR  VERTEX_REGION, VERTEX_PROJECT, VERTEX_LOCATION, VERTEX_PUBLISHER, VERTEX_MODEL_ID"
                 .to_string(),
+            None,
         ))
     }
 
@@ -213,68 +382,89 @@ impl VertexProvider {
         )
     }
 
-    fn has_vertex_config(cfg: &VertexConfig) -> bool {
-        let project = cfg.project.as_deref().unwrap_or("").trim();
-        let region = cfg.region.as_deref().unwrap_or("").trim();
-        let location = cfg.location.as_deref().unwrap_or("").trim();
-        let publisher = cfg.publisher.as_deref().unwrap_or("").trim();
-        let model = cfg.model.as_deref().unwrap_or("").trim();
-        !project.is_empty()
-            && !region.is_empty()
-            && !location.is_empty()
-            && !publisher.is_empty()
-            && !model.is_empty()
-    }
-
-    fn has_vertex_vars() -> bool {
-        env::var("VERTEX_REGION").is_ok()
-            && env::var("VERTEX_PROJECT").is_ok()
-            && env::var("VERTEX_LOCATION").is_ok()
-            && env::var("VERTEX_PUBLISHER").is_ok()
-            && env::var("VERTEX_MODEL_ID").is_ok()
-    }
-
-    fn build_vertex_resource_url_from_config(cfg: &VertexConfig) -> Result<String> {
-        let region = cfg
-            .region
-            .as_ref()
-            .map(|s| s.trim().to_string())
-            .ok_or_else(|| ProxyError::Config("vertex.region is required".to_string()))?;
-        let project = cfg
-            .project
-            .as_ref()
-            .map(|s| s.trim().to_string())
-            .ok_or_else(|| ProxyError::Config("vertex.project is required".to_string()))?;
-        let location = cfg
-            .location
-            .as_ref()
-            .map(|s| s.trim().to_string())
-            .ok_or_else(|| ProxyError::Config("vertex.location is required".to_string()))?;
-        let publisher = cfg
-            .publisher
-            .as_ref()
-            .map(|s| s.trim().to_string())
-            .ok_or_else(|| ProxyError::Config("vertex.publisher is required".to_string()))?;
-        let model_id = cfg
-            .model
-            .as_ref()
-            .map(|s| s.trim().to_string())
-            .ok_or_else(|| ProxyError::Config("vertex.model is required".to_string()))?;
-        Ok(Self::build_resource_url(&region, &project, &location, &publisher, &model_id))
-    }
-
-    fn build_vertex_resource_url() -> Result<String> {
-        let region = env::var("VERTEX_REGION")
-            .map_err(|_| ProxyError::Config("VERTEX_REGION is required.".to_string()))?;
-        let project = env::var("VERTEX_PROJECT")
-            .map_err(|_| ProxyError::Config("VERTEX_PROJECT is required.".to_string()))?;
-        let location = env::var("VERTEX_LOCATION")
-            .map_err(|_| ProxyError::Config("VERTEX_LOCATION is required.".to_string()))?;
-        let publisher = env::var("VERTEX_PUBLISHER")
-            .map_err(|_| ProxyError::Config("VERTEX_PUBLISHER is required.".to_string()))?;
-        let model_id = env::var("VERTEX_MODEL_ID")
-            .map_err(|_| ProxyError::Config("VERTEX_MODEL_ID is required.".to_string()))?;
-        Ok(Self::build_resource_url(region.trim(), project.trim(), location.trim(), publisher.trim(), model_id.trim()))
+    ///
+    /// Build the resource URL from individual Vertex fields.
+    ///
+    /// Each field is taken from the config file when set and non-empty, otherwise
+    /// from its environment variable.
+    ///
+    /// # Arguments
+    ///  * `cfg` - `[vertex]` config section, if any
+    ///
+    /// # Returns
+    ///  * `Some((resource_url, model_id))` when every field is set
+    ///  * `None` when no field is set anywhere
+    ///  * `ProxyError::Config` naming the missing fields when only some are set
+    fn build_vertex_resource_url(cfg: Option<&VertexConfig>) -> Result<Option<(String, String)>> {
+        let config_values = [
+            cfg.and_then(|c| c.region.as_deref()),
+            cfg.and_then(|c| c.project.as_deref()),
+            cfg.and_then(|c| c.location.as_deref()),
+            cfg.and_then(|c| c.publisher.as_deref()),
+            cfg.and_then(|c| c.model.as_deref()),
+        ];
+        let values: [Option<String>; 5] = std::array::from_fn(|i| {
+            Self::resolve_vertex_field(config_values[i], VERTEX_FIELDS[i].1)
+        });
+
+        if values.iter().all(Option::is_none) {
+            return Ok(None);
+        }
+        let [Some(region), Some(project), Some(location), Some(publisher), Some(model_id)] =
+            &values
+        else {
+            let missing: Vec<String> = VERTEX_FIELDS
+                .iter()
+                .zip(&values)
+                .filter(|(_, value)| value.is_none())
+                .map(|((field, env_var), _)| format!("vertex.{} ({})", field, env_var))
+                .collect();
+            return Err(ProxyError::Config(
+                format!("Incomplete Vertex configuration, missing: {}", missing.join(", ")),
+                None,
+            ));
+        };
+
+        let resource_url = Self::build_resource_url(region, project, location, publisher, model_id);
+        Ok(Some((resource_url, model_id.clone())))
+    }
+
+    ///
+    /// Resolve one Vertex field from the config file, falling back to the environment.
+    fn resolve_vertex_field(config_value: Option<&str>, env_var: &str) -> Option<String> {
+        config_value
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+            .or_else(|| env::var(env_var).ok().map(|value| value.trim().to_string()))
+            .filter(|value| !value.is_empty())
+    }
+
+    ///
+    /// Check that a resource URL points at Vertex AI over HTTPS.
+    ///
+    /// # Arguments
+    ///  * `url` - resource URL without the predict method suffix
+    ///
+    /// # Returns
+    ///  * `ProxyError::Config` if the URL is not `https://[<region>-]aiplatform.googleapis.com/...`
+    fn validate_resource_url(url: &str) -> Result<()> {
+        let host = url
+            .strip_prefix("https://")
+            .map(|rest| rest.split('/').next().unwrap_or(""))
+            .unwrap_or("");
+        let is_vertex_host =
+            host == VERTEX_HOST_SUFFIX || host.ends_with(&format!("-{}", VERTEX_HOST_SUFFIX));
+        if !is_vertex_host {
+            return Err(ProxyError::Config(
+                format!(
+                    "Vertex URL must be an https:// URL on {}, got: {}",
+                    VERTEX_HOST_SUFFIX, url
+                ),
+                None,
+            ));
+        }
+        Ok(())
     }
 
     fn get_model_display_name_override() -> Result<String> {
@@ -293,28 +483,24 @@ impl VertexProvider {
                 return Ok(display);
             }
         }
-        Err(ProxyError::Config("With LLM_URL set LLM_MODEL or LLM_MODEL_DISPLAY_NAME.".to_string()))
+        Err(ProxyError::Config(
+            "With LLM_URL set LLM_MODEL or LLM_MODEL_DISPLAY_NAME.".to_string(),
+            None,
+        ))
     }
 
-    fn get_model_display_name_vertex() -> Result<String> {
+    fn get_model_display_name_vertex(model_id: &str) -> String {
         if let Ok(name) = env::var("LLM_MODEL_DISPLAY_NAME")
-            && !name.trim().is_empty() {
-                return Ok(name.trim().to_string());
-            }
+            && !name.trim().is_empty()
+        {
+            return name.trim().to_string();
+        }
         if let Ok(name) = env::var("LLM_MODEL")
-            && !name.trim().is_empty() {
-                return Ok(name.trim().to_string());
-            }
-        if let Ok(id) = env::var("VERTEX_MODEL_ID") {
-            let display = id.trim().split('@').next().unwrap_or(id.trim()).to_string();
-            if !display.is_empty() {
-                return Ok(display);
-            }
+            && !name.trim().is_empty()
+        {
+            return name.trim().to_string();
         }
-        Err(ProxyError::Config(
-            "Set LLM_MODEL, LLM_MODEL_DISPLAY_NAME, or VERTEX_MODEL_ID for display name."
-                .to_string(),
-        ))
+        model_id.split('@').next().unwrap_or(model_id).to_string()
     }
 
     fn get_model_display_name_from_config_or_env(cfg: &VertexConfig) -> Result<String> {
@@ -343,6 +529,7 @@ impl VertexProvider {
         Err(ProxyError::Config(
             "Set vertex.model in config or LLM_MODEL / LLM_MODEL_DISPLAY_NAME for display name."
                 .to_string(),
+            None,
         ))
     }
 }
@@ -367,6 +554,63 @@ impl LlmProviderBackend for VertexProvider {
 }
 
 impl VertexProvider {
+    /// Output token limit of a Claude model.
+    ///
+    /// `model_id` is the Vertex AI model ID, e.g. `claude-3-5-sonnet-v2@20241022`.
+    /// Returns `None` for models without a known limit.
+    pub fn max_output_tokens_for_model(model_id: &str) -> Option<u32> {
+        MODEL_MAX_OUTPUT_TOKENS
+            .iter()
+            .find(|(prefix, _)| model_id.starts_with(prefix))
+            .map(|(_, limit)| *limit)
+    }
+
+    /// Output token limit of the model a resource or request URL points to.
+    pub fn max_output_tokens_for_url(url: &str) -> Option<u32> {
+        let model_id = url.rsplit("/models/").next()?.split(':').next()?;
+        Self::max_output_tokens_for_model(model_id)
+    }
+
+    /// Context window of a Claude model in tokens.
+    ///
+    /// Returns `None` for models without a known context window.
+    pub fn context_window_for_model(model_id: &str) -> Option<u32> {
+        model_id.starts_with("claude-").then_some(CLAUDE_CONTEXT_WINDOW_TOKENS)
+    }
+
+    /// Context window of the model a resource or request URL points to.
+    pub fn context_window_for_url(url: &str) -> Option<u32> {
+        let model_id = url.rsplit("/models/").next()?.split(':').next()?;
+        Self::context_window_for_model(model_id)
+    }
+
+    /// Build the Vertex AI `predict` URL of a text embedding model.
+    ///
+    /// Keeps the host, project and location of the default model and swaps
+    /// its publisher and model for `google/<model>`.
+    pub fn build_embedding_url(&self, model: &str) -> String {
+        let url = &self.predict_resource_url;
+        let base = url.find("/publishers/").map_or(url.as_str(), |i| &url[..i]);
+        format!("{}/publishers/{}/models/{}:predict", base, EMBEDDING_PUBLISHER, model)
+    }
+
+    /// Vertex AI model ID of the default model, e.g. `claude-3-5-sonnet@20241022`.
+    pub fn model_id(&self) -> Option<&str> {
+        let (_, model_id) = self.predict_resource_url.rsplit_once("/models/")?;
+        Some(model_id).filter(|id| !id.is_empty())
+    }
+
+    /// Build the request URL of another model from the same publisher.
+    ///
+    /// Keeps the host, project, location and publisher of the default model
+    /// and swaps only the model ID.
+    pub fn build_url_for_model_id(&self, model_id: &str, is_streaming: bool) -> String {
+        let url = &self.predict_resource_url;
+        let base = url.rfind("/models/").map_or(url.as_str(), |i| &url[..i]);
+        let method = if is_streaming { "streamRawPredict" } else { "rawPredict" };
+        format!("{}/models/{}:{}", base, model_id, method)
+    }
+
     /// Build a Vertex AI request URL for a named model entry from `[[vertex.models]]`.
     ///
     /// Resolution order for each field (project, location, publisher, host):
@@ -398,10 +642,10 @@ impl VertexProvider {
         }
 
         // Resolve each field: entry override → parent config field.
-        let region    = entry.region.as_deref().or_else(|| cfg.region.as_deref()).unwrap_or("").trim();
-        let project   = entry.project.as_deref().or_else(|| cfg.project.as_deref()).unwrap_or("").trim();
-        let location  = entry.location.as_deref().or_else(|| cfg.location.as_deref()).unwrap_or("").trim();
-        let publisher = entry.publisher.as_deref().or_else(|| cfg.publisher.as_deref()).unwrap_or("").trim();
+        let region    = entry.region.as_deref().or(cfg.region.as_deref()).unwrap_or("").trim();
+        let project   = entry.project.as_deref().or(cfg.project.as_deref()).unwrap_or("").trim();
+        let location  = entry.location.as_deref().or(cfg.location.as_deref()).unwrap_or("").trim();
+        let publisher = entry.publisher.as_deref().or(cfg.publisher.as_deref()).unwrap_or("").trim();
         let model_id  = entry.model.trim();
 
         if project.is_empty() || location.is_empty() || publisher.is_empty() || model_id.is_empty() {
@@ -451,47 +695,118 @@ impl VertexProvider {
     }
 }
 
-/* --- openai-compatible provider (stub) ------------------------------------------------------- */
+/* --- openai-compatible provider ------------------------------------------------------------ */
+
+/** Chat completions path appended to base URLs that already end in `/v1` */
+const CHAT_COMPLETIONS_PATH: &str = "/chat/completions";
+
+/** Chat completions path appended to base URLs without an API version */
+const VERSIONED_CHAT_COMPLETIONS_PATH: &str = "/v1/chat/completions";
+
+/** Model used for requests without `model` when none is configured */
+const DEFAULT_OPENAI_MODEL: &str = "gpt-4o-mini";
+
+/** `LLM_PROVIDER` values served by [OpenAiCompatibleProvider] */
+const OPENAI_COMPATIBLE_IDS: [&str; 4] = ["openai_compatible", "openai", "mistral", "cloudflare"];
 
 ///
-/// OpenAI-compatible providers (Mistral, Cloudflare, custom /v1/chat/completions endpoints).
+/// OpenAI-compatible providers (OpenAI, Mistral, Cloudflare, vLLM, custom
+/// `/v1/chat/completions` endpoints).
 ///
-/// Template for future implementation: base URL + path + Bearer token.
+/// The upstream speaks the OpenAI format natively, so requests are forwarded
+/// unchanged with a static Bearer token.
 #[derive(Debug, Clone)]
 pub struct OpenAiCompatibleProvider {
-    _base_url: String,
-    _chat_path: String,
-    _display_model: String,
+    base_url: String,
+    chat_path: String,
+    display_model: String,
     auth: AuthStrategy,
 }
 
 impl OpenAiCompatibleProvider {
     ///
-    /// Build from explicit values (for when from_env is implemented).
-    #[allow(dead_code)]
+    /// Build from explicit values.
+    ///
+    /// # Arguments
+    ///  * `base_url` - upstream base URL, e.g. `https://api.openai.com/v1`
+    ///  * `chat_path` - path of the chat completions endpoint below the base URL
+    ///  * `display_model` - model used for requests that do not name one
+    ///  * `auth` - authentication of upstream requests
     pub fn new(
         base_url: String,
         chat_path: String,
         display_model: String,
         auth: AuthStrategy,
     ) -> Self {
-        Self { _base_url: base_url, _chat_path: chat_path, _display_model: display_model, auth }
+        Self { base_url, chat_path, display_model, auth }
     }
 
     ///
-    /// Load from env. Currently returns an error (not yet implemented).
+    /// Load from environment only.
+    #[allow(dead_code)]
     pub fn from_env() -> Result<Self> {
-        let _ = env::var("OPENAI_BASE_URL").map_err(|_| {
+        Self::from_config_or_env(None)
+    }
+
+    ///
+    /// Load from the `[openai_compatible]` config section and/or environment.
+    ///
+    /// The base URL is taken from `base_url`, then `OPENAI_API_BASE`, then
+    /// `OPENAI_BASE_URL`; the model from `model`, then `OPENAI_MODEL`. The API
+    /// key is read from `OPENAI_API_KEY`.
+    ///
+    /// # Arguments
+    ///  * `config` - `[openai_compatible]` section, if configured
+    ///
+    /// # Returns
+    ///  * Provider forwarding to `<base_url>/v1/chat/completions`
+    ///  * `ProxyError::Config` when the base URL or API key is missing or invalid
+    pub fn from_config_or_env(config: Option<&OpenAiCompatibleConfig>) -> Result<Self> {
+        let non_empty = |value: Option<String>| value.filter(|v| !v.trim().is_empty());
+        let base_url = non_empty(config.and_then(|c| c.base_url.clone()))
+            .or_else(|| non_empty(env::var("OPENAI_API_BASE").ok()))
+            .or_else(|| non_empty(env::var("OPENAI_BASE_URL").ok()))
+            .ok_or_else(|| {
+                ProxyError::Config(
+                    "openai_compatible provider needs a base URL. \
+                     Set [openai_compatible] base_url or OPENAI_API_BASE."
+                        .to_string(),
+                    None,
+                )
+            })?;
+        if !base_url.starts_with("http://") && !base_url.starts_with("https://") {
+            return Err(ProxyError::Config(
+                format!(
+                    "openai_compatible base URL must start with http:// or https://: {}",
+                    base_url
+                ),
+                None,
+            ));
+        }
+        let api_key = non_empty(env::var("OPENAI_API_KEY").ok()).ok_or_else(|| {
             ProxyError::Config(
-                "openai_compatible provider not yet implemented. \
-                 Set OPENAI_BASE_URL, OPENAI_CHAT_PATH, model and API key when supported."
-                    .to_string(),
+                "openai_compatible provider needs an API key. Set OPENAI_API_KEY.".to_string(),
+                None,
             )
         })?;
-        Err(ProxyError::Config(
-            "LLM_PROVIDER=openai_compatible is not yet implemented. Use vertex for now."
-                .to_string(),
-        ))
+        let display_model = non_empty(config.and_then(|c| c.model.clone()))
+            .or_else(|| non_empty(env::var("OPENAI_MODEL").ok()))
+            .unwrap_or_else(|| DEFAULT_OPENAI_MODEL.to_string());
+
+        let chat_path = Self::chat_path_for(&base_url).to_string();
+        Ok(Self::new(base_url, chat_path, display_model, AuthStrategy::BearerToken(api_key)))
+    }
+
+    ///
+    /// Path of the chat completions endpoint below a base URL.
+    ///
+    /// Base URLs are accepted with or without the `/v1` API version suffix.
+    fn chat_path_for(base_url: &str) -> &'static str {
+        if base_url.trim_end_matches('/').ends_with("/v1") {
+            CHAT_COMPLETIONS_PATH
+        } else {
+            VERSIONED_CHAT_COMPLETIONS_PATH
+        }
     }
 }
 
@@ -501,12 +816,13 @@ impl LlmProviderBackend for OpenAiCompatibleProvider {
     }
 
     fn build_request_url(&self, is_streaming: bool) -> String {
+        // Streaming is selected by the `stream` field of the body, not the URL
         let _ = is_streaming;
-        format!("{}{}", self._base_url.trim_end_matches('/'), self._chat_path)
+        format!("{}{}", self.base_url.trim_end_matches('/'), self.chat_path)
     }
 
     fn display_model_name(&self) -> &str {
-        &self._display_model
+        &self.display_model
     }
 
     fn auth_strategy(&self) -> &AuthStrategy {
@@ -524,33 +840,45 @@ impl LlmProviderBackend for OpenAiCompatibleProvider {
 pub enum LlmProviderConfig {
     Vertex(VertexProvider),
     OpenAiCompatible(OpenAiCompatibleProvider),
+    Bedrock(BedrockProvider),
+    AzureOpenAi(AzureOpenAiProvider),
+    Ollama(OllamaProvider),
 }
 
 impl LlmProviderConfig {
     ///
     /// Load the provider config from environment based on `LLM_PROVIDER`.
     ///
-    /// Defaults to `vertex` when unset. Supported: `vertex`, `openai_compatible` (stub).
+    /// Defaults to `vertex` when unset. Supported: `vertex`, `openai_compatible`, `bedrock`,
+    /// `azure_openai`, `ollama`.
     #[allow(dead_code)]
     pub fn from_env() -> Result<Self> {
         let id = env::var("LLM_PROVIDER").unwrap_or_else(|_| "vertex".to_string());
         let id = id.trim().to_lowercase();
         match id.as_str() {
             "vertex" => VertexProvider::from_env().map(Self::Vertex),
-            "openai_compatible" | "openai" | "mistral" | "cloudflare" => {
+            id if OPENAI_COMPATIBLE_IDS.contains(&id) => {
                 OpenAiCompatibleProvider::from_env().map(Self::OpenAiCompatible)
             }
-            _ => Err(ProxyError::Config(format!(
-                "Unknown LLM_PROVIDER: '{}'. Supported: vertex, openai_compatible",
-                id
-            ))),
+            "bedrock" => BedrockProvider::from_env().map(Self::Bedrock),
+            "azure_openai" => AzureOpenAiProvider::from_env().map(Self::AzureOpenAi),
+            "ollama" => OllamaProvider::from_env().map(Self::Ollama),
+            _ => Err(ProxyError::Config(
+                format!(
+                    "Unknown LLM_PROVIDER: '{}'. Supported: vertex, openai_compatible, bedrock, \
+                     azure_openai, ollama",
+                    id
+                ),
+                None,
+            )),
         }
     }
 
     ///
     /// Load the provider config with provided service account key (to avoid circular dependency).
     ///
-    /// Defaults to `vertex` when unset. Supported: `vertex`, `openai_compatible` (stub).
+    /// Defaults to `vertex` when unset. Supported: `vertex`, `openai_compatible`, `bedrock`,
+    /// `azure_openai`, `ollama`.
     #[allow(dead_code)] // Public API, used when loading without config file
     pub fn from_env_with_key(service_account_key: ServiceAccountKey) -> Result<Self> {
         Self::from_config_or_env_with_key(service_account_key, None)
@@ -563,22 +891,108 @@ impl LlmProviderConfig {
     pub fn from_config_or_env_with_key(
         service_account_key: ServiceAccountKey,
         vertex_config: Option<&VertexConfig>,
+    ) -> Result<Self> {
+        Self::from_config_or_env_with_auth(
+            AuthStrategy::GcpOAuth2(Box::new(service_account_key)),
+            vertex_config,
+        )
+    }
+
+    ///
+    /// Load the provider config from config file and/or environment with the
+    /// given Vertex AI auth strategy.
+    ///
+    /// Config file values take precedence. Falls back to env vars (including from .env).
+    pub fn from_config_or_env_with_auth(
+        auth: AuthStrategy,
+        vertex_config: Option<&VertexConfig>,
     ) -> Result<Self> {
         let id = env::var("LLM_PROVIDER").unwrap_or_else(|_| "vertex".to_string());
         let id = id.trim().to_lowercase();
         match id.as_str() {
-            "vertex" => VertexProvider::from_config_or_env_with_key(
-                service_account_key,
-                vertex_config,
-            )
-            .map(Self::Vertex),
-            "openai_compatible" | "openai" | "mistral" | "cloudflare" => {
+            "vertex" => {
+                VertexProvider::from_config_or_env_with_auth(auth, vertex_config).map(Self::Vertex)
+            }
+            id if OPENAI_COMPATIBLE_IDS.contains(&id) => {
                 OpenAiCompatibleProvider::from_env().map(Self::OpenAiCompatible)
             }
-            _ => Err(ProxyError::Config(format!(
-                "Unknown LLM_PROVIDER: '{}'. Supported: vertex, openai_compatible",
-                id
-            ))),
+            "bedrock" => BedrockProvider::from_env().map(Self::Bedrock),
+            "azure_openai" => AzureOpenAiProvider::from_env().map(Self::AzureOpenAi),
+            "ollama" => OllamaProvider::from_env().map(Self::Ollama),
+            _ => Err(ProxyError::Config(
+                format!(
+                    "Unknown LLM_PROVIDER: '{}'. Supported: vertex, openai_compatible, bedrock, \
+                     azure_openai, ollama",
+                    id
+                ),
+                None,
+            )),
+        }
+    }
+
+    ///
+    /// Whether the OpenAI-compatible provider is selected.
+    ///
+    /// `LLM_PROVIDER` decides when set; otherwise a configured
+    /// `[openai_compatible] base_url` selects it over Vertex AI.
+    ///
+    /// # Arguments
+    ///  * `config` - `[openai_compatible]` section, if configured
+    pub fn selects_openai_compatible(config: Option<&OpenAiCompatibleConfig>) -> bool {
+        match env::var("LLM_PROVIDER") {
+            Ok(id) => OPENAI_COMPATIBLE_IDS.contains(&id.trim().to_lowercase().as_str()),
+            Err(_) => config.is_some_and(|c| c.base_url.is_some()),
+        }
+    }
+
+    ///
+    /// Whether the AWS Bedrock provider is selected (`LLM_PROVIDER=bedrock`).
+    pub fn selects_bedrock() -> bool {
+        env::var("LLM_PROVIDER").is_ok_and(|id| id.trim().eq_ignore_ascii_case("bedrock"))
+    }
+
+    ///
+    /// Whether the Ollama provider is selected (`LLM_PROVIDER=ollama`).
+    pub fn selects_ollama() -> bool {
+        env::var("LLM_PROVIDER").is_ok_and(|id| id.trim().eq_ignore_ascii_case("ollama"))
+    }
+
+    ///
+    /// Whether the Azure OpenAI provider is selected.
+    ///
+    /// `LLM_PROVIDER=azure_openai` decides when set; otherwise a configured
+    /// `[azure_openai] endpoint` selects it over Vertex AI.
+    ///
+    /// # Arguments
+    ///  * `config` - `[azure_openai]` section, if configured
+    pub fn selects_azure_openai(config: Option<&AzureOpenAiConfig>) -> bool {
+        match env::var("LLM_PROVIDER") {
+            Ok(id) => id.trim().eq_ignore_ascii_case("azure_openai"),
+            Err(_) => config.is_some_and(|c| c.endpoint.is_some()),
+        }
+    }
+
+    ///
+    /// API key sent in the `api-key` header instead of `Authorization`.
+    ///
+    /// # Returns
+    ///  * The Azure OpenAI API key, `None` for the other providers
+    pub fn api_key_header(&self) -> Option<&str> {
+        match self {
+            Self::AzureOpenAi(p) => Some(p.api_key()),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Request format of the upstream Anthropic API.
+    pub fn target_format(&self) -> TargetFormat {
+        match self {
+            Self::Bedrock(_) => TargetFormat::Bedrock,
+            Self::Vertex(_)
+            | Self::OpenAiCompatible(_)
+            | Self::AzureOpenAi(_)
+            | Self::Ollama(_) => TargetFormat::Vertex,
         }
     }
 }
@@ -588,6 +1002,9 @@ impl LlmProviderBackend for LlmProviderConfig {
         match self {
             Self::Vertex(p) => p.id(),
             Self::OpenAiCompatible(p) => p.id(),
+            Self::Bedrock(p) => p.id(),
+            Self::AzureOpenAi(p) => p.id(),
+            Self::Ollama(p) => p.id(),
         }
     }
 
@@ -595,6 +1012,9 @@ impl LlmProviderBackend for LlmProviderConfig {
         match self {
             Self::Vertex(p) => p.build_request_url(is_streaming),
             Self::OpenAiCompatible(p) => p.build_request_url(is_streaming),
+            Self::Bedrock(p) => p.build_request_url(is_streaming),
+            Self::AzureOpenAi(p) => p.build_request_url(is_streaming),
+            Self::Ollama(p) => p.build_request_url(is_streaming),
         }
     }
 
@@ -602,6 +1022,9 @@ impl LlmProviderBackend for LlmProviderConfig {
         match self {
             Self::Vertex(p) => p.display_model_name(),
             Self::OpenAiCompatible(p) => p.display_model_name(),
+            Self::Bedrock(p) => p.display_model_name(),
+            Self::AzureOpenAi(p) => p.display_model_name(),
+            Self::Ollama(p) => p.display_model_name(),
         }
     }
 
@@ -609,6 +1032,369 @@ impl LlmProviderBackend for LlmProviderConfig {
         match self {
             Self::Vertex(p) => p.auth_strategy(),
             Self::OpenAiCompatible(p) => p.auth_strategy(),
+            Self::Bedrock(p) => p.auth_strategy(),
+            Self::AzureOpenAi(p) => p.auth_strategy(),
+            Self::Ollama(p) => p.auth_strategy(),
+        }
+    }
+}
+
+/* --- service discovery ----------------------------------------------------------------------- */
+
+///
+/// Source of upstream endpoints registered in a service catalog.
+///
+/// Implementations return base URLs (`scheme://host:port`) of healthy
+/// instances. An empty list means "unknown" and keeps the current endpoints.
+#[async_trait::async_trait]
+pub trait ServiceDiscovery: Send + Sync {
+    ///
+    /// Resolve the healthy endpoints currently registered for a service.
+    ///
+    /// # Arguments
+    ///  * `service_name` - service name in the catalog
+    ///
+    /// # Returns
+    ///  * Base URLs of healthy instances (empty on lookup failure)
+    async fn resolve_endpoints(&self, service_name: &str) -> Vec<String>;
+}
+
+/** Scheme of endpoints discovered in Consul; the upstream credentials must not go out in plaintext */
+const DEFAULT_ENDPOINT_SCHEME: &str = "https";
+
+///
+/// Service discovery backed by the Consul health API.
+///
+/// Queries `/v1/health/service/{name}?passing=true` and returns one endpoint
+/// per passing instance.
+#[derive(Debug, Clone)]
+pub struct ConsulServiceDiscovery {
+    /** Consul agent base URL */
+    url: String,
+    /** scheme of the discovered endpoints, `https` unless configured otherwise */
+    endpoint_scheme: String,
+    /** HTTP client for catalog requests */
+    client: reqwest::Client,
+}
+
+impl ConsulServiceDiscovery {
+    ///
+    /// Create a Consul discovery client.
+    ///
+    /// # Arguments
+    ///  * `url` - Consul agent base URL (e.g. `http://localhost:8500`)
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into().trim_end_matches('/').to_string(),
+            endpoint_scheme: DEFAULT_ENDPOINT_SCHEME.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    ///
+    /// Set the scheme of the discovered endpoints.
+    ///
+    /// # Arguments
+    ///  * `scheme` - `https` or `http`; requests carry the upstream credentials
+    pub fn with_endpoint_scheme(mut self, scheme: impl Into<String>) -> Self {
+        self.endpoint_scheme = scheme.into();
+        self
+    }
+
+    ///
+    /// Extract endpoints from a Consul health API response.
+    ///
+    /// Uses `Service.Address` and falls back to `Node.Address` when the service
+    /// was registered without an address.
+    ///
+    /// # Arguments
+    ///  * `entries` - JSON array returned by `/v1/health/service/{name}`
+    ///  * `scheme` - scheme of the endpoints, e.g. `https`
+    ///
+    /// # Returns
+    ///  * Sorted, de-duplicated `scheme://host:port` endpoints
+    pub fn parse_health_entries(entries: &serde_json::Value, scheme: &str) -> Vec<String> {
+        let mut endpoints: Vec<String> = entries
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let service = entry.get("Service")?;
+                let port = service.get("Port")?.as_u64()?;
+                let address = service
+                    .get("Address")
+                    .and_then(|a| a.as_str())
+                    .filter(|a| !a.is_empty())
+                    .or_else(|| entry.get("Node")?.get("Address")?.as_str())?;
+                Some(format!("{}://{}:{}", scheme, address, port))
+            })
+            .collect();
+        endpoints.sort();
+        endpoints.dedup();
+        endpoints
+    }
+}
+
+#[async_trait::async_trait]
+impl ServiceDiscovery for ConsulServiceDiscovery {
+    async fn resolve_endpoints(&self, service_name: &str) -> Vec<String> {
+        let url = format!("{}/v1/health/service/{}?passing=true", self.url, service_name);
+        let response = match self.client.get(&url).send().await {
+            Ok(response) => response,
+            Err(e) => {
+                tracing::warn!("Consul lookup for '{}' failed: {}", service_name, e);
+                return Vec::new();
+            }
+        };
+
+        if !response.status().is_success() {
+            tracing::warn!("Consul lookup for '{}' returned {}", service_name, response.status());
+            return Vec::new();
+        }
+
+        match response.json::<serde_json::Value>().await {
+            Ok(entries) => Self::parse_health_entries(&entries, &self.endpoint_scheme),
+            Err(e) => {
+                tracing::warn!("Invalid Consul response for '{}': {}", service_name, e);
+                Vec::new()
+            }
         }
     }
 }
+
+///
+/// Round-robin load balancer over upstream endpoints.
+///
+/// The endpoint list is swapped atomically on refresh. Requests already sent
+/// keep their own copy of the endpoint, so removed endpoints are drained: they
+/// receive no new requests while in-flight requests run to completion.
+///
+/// With `[[vertex.regions]]` configured, each request is also assigned a
+/// region by weighted random selection.
+#[derive(Debug, Default)]
+pub struct VertexLoadBalancer {
+    /** current endpoints (base URLs) */
+    endpoints: std::sync::RwLock<std::sync::Arc<Vec<String>>>,
+    /** round-robin cursor */
+    next: std::sync::atomic::AtomicUsize,
+    /** regions requests are spread over (empty: keep the configured region) */
+    regions: Vec<VertexRegionConfig>,
+}
+
+impl VertexLoadBalancer {
+    ///
+    /// Create a load balancer with an initial endpoint list.
+    ///
+    /// # Arguments
+    ///  * `endpoints` - initial base URLs (may be empty)
+    #[allow(dead_code)]
+    pub fn new(endpoints: Vec<String>) -> Self {
+        Self {
+            endpoints: std::sync::RwLock::new(std::sync::Arc::new(endpoints)),
+            next: std::sync::atomic::AtomicUsize::new(0),
+            regions: Vec::new(),
+        }
+    }
+
+    ///
+    /// Set the regions requests are spread over.
+    ///
+    /// # Arguments
+    ///  * `regions` - weighted regions, see `VertexConfig::effective_regions`
+    ///
+    /// # Returns
+    ///  * Load balancer routing to the given regions
+    pub fn with_regions(mut self, regions: Vec<VertexRegionConfig>) -> Self {
+        self.regions = regions;
+        self
+    }
+
+    ///
+    /// Pick a region by weighted random selection.
+    ///
+    /// # Returns
+    ///  * Selected region, or `None` when no region has a positive weight
+    pub fn select_region(&self) -> Option<&VertexRegionConfig> {
+        let total: u64 = self.regions.iter().map(|r| u64::from(r.weight)).sum();
+        if total == 0 {
+            return None;
+        }
+
+        use rand::Rng;
+        let mut pick = rand::rng().random_range(0..total);
+        self.regions.iter().find(|region| {
+            let weight = u64::from(region.weight);
+            if pick < weight {
+                return true;
+            }
+            pick -= weight;
+            false
+        })
+    }
+
+    ///
+    /// Build the request URL for a region.
+    ///
+    /// Replaces the project, location and (with `model_override`) model of a
+    /// Vertex resource URL, and the region of `*-aiplatform.googleapis.com`
+    /// hosts. Other hosts, such as proxies, are kept.
+    ///
+    /// # Arguments
+    ///  * `url` - request URL built from the configuration
+    ///  * `region` - region to send the request to
+    ///
+    /// # Returns
+    ///  * Rewritten URL, or `url` unchanged if it cannot be parsed
+    pub fn build_request_url(url: &str, region: &VertexRegionConfig) -> String {
+        let Ok(mut parsed) = reqwest::Url::parse(url) else {
+            return url.to_string();
+        };
+
+        let is_vertex_host = parsed.host_str().is_some_and(|host| {
+            host == VERTEX_HOST_SUFFIX || host.ends_with(&format!("-{}", VERTEX_HOST_SUFFIX))
+        });
+        if is_vertex_host
+            && parsed.set_host(Some(&VertexProvider::vertex_host(&region.region))).is_err()
+        {
+            return url.to_string();
+        }
+
+        let mut segments: Vec<String> =
+            parsed.path_segments().into_iter().flatten().map(str::to_string).collect();
+        for i in 1..segments.len() {
+            match segments[i - 1].as_str() {
+                "projects" if !region.project.is_empty() => segments[i] = region.project.clone(),
+                "locations" => segments[i] = region.region.clone(),
+                "models" => {
+                    if let Some(model) = &region.model_override {
+                        let method = segments[i].find(':').map(|at| segments[i][at..].to_string());
+                        segments[i] = format!("{}{}", model, method.unwrap_or_default());
+                    }
+                }
+                _ => {}
+            }
+        }
+        parsed.set_path(&segments.join("/"));
+        parsed.to_string()
+    }
+
+    ///
+    /// Region a Vertex request URL is sent to.
+    ///
+    /// # Arguments
+    ///  * `url` - upstream request URL
+    ///
+    /// # Returns
+    ///  * Value of the `locations/<region>` path segment, if any
+    pub fn region_of_url(url: &reqwest::Url) -> Option<String> {
+        let mut segments = url.path_segments()?;
+        segments.find(|segment| *segment == "locations")?;
+        segments.next().filter(|region| !region.is_empty()).map(str::to_string)
+    }
+
+    ///
+    /// Current endpoint list.
+    pub fn endpoints(&self) -> std::sync::Arc<Vec<String>> {
+        self.endpoints.read().map(|e| e.clone()).unwrap_or_default()
+    }
+
+    ///
+    /// Pick the next endpoint in round-robin order.
+    ///
+    /// # Returns
+    ///  * Base URL, or `None` when no endpoints are known
+    pub fn select(&self) -> Option<String> {
+        let endpoints = self.endpoints();
+        if endpoints.is_empty() {
+            return None;
+        }
+        let index = self.next.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        endpoints.get(index % endpoints.len()).cloned()
+    }
+
+    ///
+    /// Replace the endpoint list.
+    ///
+    /// An empty list is ignored so a failed lookup never removes every endpoint.
+    ///
+    /// # Arguments
+    ///  * `endpoints` - newly discovered base URLs
+    pub fn update(&self, endpoints: Vec<String>) {
+        if endpoints.is_empty() {
+            return;
+        }
+
+        let current = self.endpoints();
+        if *current == endpoints {
+            return;
+        }
+
+        for added in endpoints.iter().filter(|e| !current.contains(e)) {
+            tracing::info!(endpoint = %added, "Service discovery: added endpoint");
+        }
+        for removed in current.iter().filter(|e| !endpoints.contains(e)) {
+            tracing::info!(endpoint = %removed, "Service discovery: draining endpoint");
+        }
+
+        if let Ok(mut guard) = self.endpoints.write() {
+            *guard = std::sync::Arc::new(endpoints);
+        }
+    }
+
+    ///
+    /// Route a request URL to the given endpoint.
+    ///
+    /// Replaces scheme, host and port of `url` with `endpoint`, keeping the path
+    /// and query.
+    ///
+    /// # Arguments
+    ///  * `url` - full upstream request URL
+    ///  * `endpoint` - base URL to send the request to
+    ///
+    /// # Returns
+    ///  * Rewritten URL, or `url` unchanged if it cannot be parsed
+    pub fn route_url(url: &str, endpoint: &str) -> String {
+        let Ok(parsed) = reqwest::Url::parse(url) else {
+            return url.to_string();
+        };
+        let mut routed = format!("{}{}", endpoint.trim_end_matches('/'), parsed.path());
+        if let Some(query) = parsed.query() {
+            routed.push('?');
+            routed.push_str(query);
+        }
+        routed
+    }
+
+    ///
+    /// Start a background task that refreshes endpoints periodically.
+    ///
+    /// # Arguments
+    ///  * `discovery` - service discovery backend
+    ///  * `service_name` - service name to resolve
+    ///  * `interval` - time between refreshes
+    ///
+    /// # Returns
+    ///  * Handle of the spawned refresh task
+    pub fn spawn_refresh(
+        self: std::sync::Arc<Self>,
+        discovery: std::sync::Arc<dyn ServiceDiscovery>,
+        service_name: String,
+        interval: std::time::Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let endpoints = discovery.resolve_endpoints(&service_name).await;
+                if endpoints.is_empty() {
+                    tracing::warn!(
+                        "Service discovery returned no healthy endpoints for '{}'; keeping {} current endpoint(s)",
+                        service_name,
+                        self.endpoints().len()
+                    );
+                }
+                self.update(endpoints);
+            }
+        })
+    }
+}
@@ -0,0 +1,206 @@
+//!
+//! Azure OpenAI Service provider.
+//!
+//! Selected with `LLM_PROVIDER=azure_openai`. Azure speaks the OpenAI chat
+//! completions format, so requests are forwarded unchanged like for
+//! OpenAI-compatible upstreams, but to a deployment-scoped URL
+//! (`{endpoint}/openai/deployments/{deployment}/chat/completions?api-version=…`)
+//! and with the API key in the `api-key` header instead of `Authorization`.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::env;
+
+use super::{AuthStrategy, LlmProviderBackend};
+use crate::config::AzureOpenAiConfig;
+use crate::error::{ProxyError, Result};
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// Azure OpenAI Service provider.
+#[derive(Debug, Clone)]
+pub struct AzureOpenAiProvider {
+    /** resource endpoint, e.g. `https://my-resource.openai.azure.com` */
+    endpoint: String,
+    /** deployment name, also used as the display model */
+    deployment: String,
+    /** `api-version` query parameter */
+    api_version: String,
+    /** API key, as `BearerToken` */
+    auth: AuthStrategy,
+}
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** `api-version` used when none is configured */
+const DEFAULT_API_VERSION: &str = "2024-10-21";
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+impl AzureOpenAiProvider {
+    ///
+    /// Build from explicit values.
+    ///
+    /// # Arguments
+    ///  * `endpoint` - resource endpoint without trailing path
+    ///  * `deployment` - deployment name
+    ///  * `api_version` - `api-version` query parameter
+    ///  * `api_key` - API key sent in the `api-key` header
+    pub fn new(endpoint: String, deployment: String, api_version: String, api_key: String) -> Self {
+        Self { endpoint, deployment, api_version, auth: AuthStrategy::BearerToken(api_key) }
+    }
+
+    ///
+    /// Load from environment only.
+    pub fn from_env() -> Result<Self> {
+        Self::from_config_or_env(None)
+    }
+
+    ///
+    /// Load from the `[azure_openai]` config section and/or environment.
+    ///
+    /// Each field is taken from the config section, then from
+    /// `AZURE_OPENAI_ENDPOINT`, `AZURE_OPENAI_DEPLOYMENT` and
+    /// `AZURE_OPENAI_API_VERSION`. The API key is only read from
+    /// `AZURE_OPENAI_API_KEY`.
+    ///
+    /// # Arguments
+    ///  * `config` - `[azure_openai]` section, if configured
+    ///
+    /// # Returns
+    ///  * Provider for the configured deployment
+    ///  * `ProxyError::Config` when a required value is missing or the endpoint is invalid
+    pub fn from_config_or_env(config: Option<&AzureOpenAiConfig>) -> Result<Self> {
+        let resolve = |value: Option<&String>, name: &str| {
+            value.filter(|v| !v.trim().is_empty()).map(|v| v.trim().to_string()).or_else(|| {
+                env::var(name).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+            })
+        };
+        let required = |value: Option<String>, name: &str| {
+            value.ok_or_else(|| {
+                ProxyError::Config(format!("azure_openai provider needs {}", name), None)
+            })
+        };
+
+        let endpoint = required(
+            resolve(config.and_then(|c| c.endpoint.as_ref()), "AZURE_OPENAI_ENDPOINT"),
+            "AZURE_OPENAI_ENDPOINT",
+        )?;
+        if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+            return Err(ProxyError::Config(
+                format!("azure_openai endpoint must start with http:// or https://: {}", endpoint),
+                None,
+            ));
+        }
+        let deployment = required(
+            resolve(config.and_then(|c| c.deployment.as_ref()), "AZURE_OPENAI_DEPLOYMENT"),
+            "AZURE_OPENAI_DEPLOYMENT",
+        )?;
+        let api_key = required(resolve(None, "AZURE_OPENAI_API_KEY"), "AZURE_OPENAI_API_KEY")?;
+        let api_version =
+            resolve(config.and_then(|c| c.api_version.as_ref()), "AZURE_OPENAI_API_VERSION")
+                .unwrap_or_else(|| DEFAULT_API_VERSION.to_string());
+
+        Ok(Self::new(endpoint, deployment, api_version, api_key))
+    }
+
+    ///
+    /// API key sent in the `api-key` header.
+    pub fn api_key(&self) -> &str {
+        match &self.auth {
+            AuthStrategy::BearerToken(key) => key,
+            _ => "",
+        }
+    }
+}
+
+impl LlmProviderBackend for AzureOpenAiProvider {
+    fn id(&self) -> &'static str {
+        "azure_openai"
+    }
+
+    fn build_request_url(&self, is_streaming: bool) -> String {
+        // Streaming is selected by the `stream` field of the body, not the URL
+        let _ = is_streaming;
+        format!(
+            "{}/openai/deployments/{}/chat/completions?api-version={}",
+            self.endpoint.trim_end_matches('/'),
+            self.deployment,
+            self.api_version
+        )
+    }
+
+    fn display_model_name(&self) -> &str {
+        &self.deployment
+    }
+
+    fn auth_strategy(&self) -> &AuthStrategy {
+        &self.auth
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_request_url() {
+        let azure = AzureOpenAiProvider::new(
+            "https://my-resource.openai.azure.com/".to_string(),
+            "gpt-4o".to_string(),
+            "2024-06-01".to_string(),
+            "secret".to_string(),
+        );
+        let expected = "https://my-resource.openai.azure.com/openai/deployments/gpt-4o/\
+                        chat/completions?api-version=2024-06-01";
+        assert_eq!(azure.build_request_url(false), expected);
+        assert_eq!(azure.build_request_url(true), expected);
+        assert_eq!(azure.display_model_name(), "gpt-4o");
+        assert_eq!(azure.api_key(), "secret");
+    }
+
+    #[test]
+    fn test_from_config_or_env() {
+        let config = AzureOpenAiConfig {
+            endpoint: Some("https://my-resource.openai.azure.com".to_string()),
+            deployment: Some("prod-gpt4o".to_string()),
+            api_version: None,
+        };
+        temp_env::with_vars(
+            [
+                ("AZURE_OPENAI_ENDPOINT", Some("https://ignored.openai.azure.com")),
+                ("AZURE_OPENAI_DEPLOYMENT", None),
+                ("AZURE_OPENAI_API_VERSION", None),
+                ("AZURE_OPENAI_API_KEY", Some("secret")),
+            ],
+            || {
+                let azure = AzureOpenAiProvider::from_config_or_env(Some(&config)).unwrap();
+                assert_eq!(
+                    azure.build_request_url(false),
+                    format!(
+                        "https://my-resource.openai.azure.com/openai/deployments/prod-gpt4o/\
+                         chat/completions?api-version={}",
+                        DEFAULT_API_VERSION
+                    )
+                );
+            },
+        );
+        temp_env::with_vars(
+            [
+                ("AZURE_OPENAI_ENDPOINT", Some("https://my-resource.openai.azure.com")),
+                ("AZURE_OPENAI_DEPLOYMENT", Some("gpt-4o")),
+                ("AZURE_OPENAI_API_KEY", None::<&str>),
+            ],
+            || {
+                let err = AzureOpenAiProvider::from_env().unwrap_err();
+                assert!(err.to_string().contains("AZURE_OPENAI_API_KEY"));
+            },
+        );
+    }
+}
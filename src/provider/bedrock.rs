@@ -0,0 +1,326 @@
+//!
+//! AWS Bedrock provider for Anthropic Claude models.
+//!
+//! Selected with `LLM_PROVIDER=bedrock`. Requests use Anthropic's native
+//! Messages format (with Bedrock's `anthropic_version`, see
+//! [`TargetFormat::Bedrock`](crate::converter::openai_to_anthropic::TargetFormat))
+//! and go to `InvokeModel` / `InvokeModelWithResponseStream`, signed with
+//! AWS SigV4.
+//!
+//! Streamed responses arrive in the binary AWS event stream encoding, one
+//! Anthropic streaming event per `chunk` message. [`into_sse_response`]
+//! re-encodes them as the `data:` lines the Vertex AI streaming path reads.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::env;
+
+use aws_smithy_eventstream::frame::{DecodedFrame, MessageFrameDecoder};
+use aws_smithy_types::event_stream::Message;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use bytes::{Bytes, BytesMut};
+use futures::StreamExt;
+use serde_json::{Value, json};
+
+use super::{AuthStrategy, LlmProviderBackend};
+use crate::error::{ProxyError, Result};
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// AWS Bedrock runtime provider.
+#[derive(Debug, Clone)]
+pub struct BedrockProvider {
+    /** runtime endpoint, e.g. `https://bedrock-runtime.us-east-1.amazonaws.com` */
+    endpoint: String,
+    /** Bedrock model or inference profile ID, e.g. `anthropic.claude-3-5-sonnet-20241022-v2:0` */
+    model_id: String,
+    /** SigV4 credentials */
+    auth: AuthStrategy,
+}
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** Content type of Bedrock streaming responses */
+const EVENT_STREAM_CONTENT_TYPE: &str = "application/vnd.amazon.eventstream";
+
+/** Event stream header naming the message type (`event` or `exception`) */
+const MESSAGE_TYPE_HEADER: &str = ":message-type";
+
+/** Event stream header naming the exception of `exception` messages */
+const EXCEPTION_TYPE_HEADER: &str = ":exception-type";
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+impl BedrockProvider {
+    ///
+    /// Build from explicit values.
+    ///
+    /// # Arguments
+    ///  * `endpoint` - runtime endpoint without trailing path
+    ///  * `model_id` - Bedrock model or inference profile ID
+    ///  * `auth` - `AwsSigV4` auth of the requests
+    pub fn new(endpoint: String, model_id: String, auth: AuthStrategy) -> Self {
+        Self { endpoint, model_id, auth }
+    }
+
+    ///
+    /// Load from environment.
+    ///
+    /// Reads `AWS_REGION`, `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`, the
+    /// optional `AWS_SESSION_TOKEN` of temporary credentials, and the model
+    /// from `BEDROCK_MODEL_ID`.
+    ///
+    /// # Returns
+    ///  * Provider for the regional Bedrock runtime endpoint
+    ///  * `ProxyError::Config` when a required variable is missing
+    pub fn from_env() -> Result<Self> {
+        let required = |name: &str| {
+            non_empty_env(name)
+                .ok_or_else(|| ProxyError::Config(format!("bedrock provider needs {}", name), None))
+        };
+        let region = required("AWS_REGION")?;
+        let access_key = required("AWS_ACCESS_KEY_ID")?;
+        let secret_key = required("AWS_SECRET_ACCESS_KEY")?;
+        let session_token = non_empty_env("AWS_SESSION_TOKEN");
+        let model_id = required("BEDROCK_MODEL_ID")?;
+
+        let endpoint = format!("https://bedrock-runtime.{}.amazonaws.com", region);
+        let auth = AuthStrategy::AwsSigV4 { region, access_key, secret_key, session_token };
+        Ok(Self::new(endpoint, model_id, auth))
+    }
+
+    ///
+    /// Model ID as a URL path segment.
+    ///
+    /// Model IDs contain `:` (version) and inference profile ARNs `/`, both
+    /// of which must be percent-encoded in the path.
+    fn encoded_model_id(&self) -> String {
+        self.model_id.replace(':', "%3A").replace('/', "%2F")
+    }
+}
+
+impl LlmProviderBackend for BedrockProvider {
+    fn id(&self) -> &'static str {
+        "bedrock"
+    }
+
+    fn build_request_url(&self, is_streaming: bool) -> String {
+        let method = if is_streaming { "invoke-with-response-stream" } else { "invoke" };
+        format!(
+            "{}/model/{}/{}",
+            self.endpoint.trim_end_matches('/'),
+            self.encoded_model_id(),
+            method
+        )
+    }
+
+    fn display_model_name(&self) -> &str {
+        &self.model_id
+    }
+
+    fn auth_strategy(&self) -> &AuthStrategy {
+        &self.auth
+    }
+}
+
+///
+/// Whether a response body is in the AWS event stream encoding.
+pub fn is_event_stream(response: &reqwest::Response) -> bool {
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with(EVENT_STREAM_CONTENT_TYPE))
+}
+
+///
+/// Re-encode a Bedrock event stream response as Server-Sent Events.
+///
+/// Each `chunk` message carries one base64-encoded Anthropic streaming
+/// event, which becomes a `data:` line. Exception messages (e.g. throttling
+/// in the middle of a stream) become Anthropic `error` events.
+///
+/// # Arguments
+///  * `response` - successful `invoke-with-response-stream` response
+///
+/// # Returns
+///  * Response with the same status whose body is the SSE stream
+pub fn into_sse_response(response: reqwest::Response) -> reqwest::Response {
+    let status = response.status();
+    let events = response.bytes_stream().scan(
+        (MessageFrameDecoder::new(), BytesMut::new()),
+        |(decoder, buffer), chunk| {
+            let events = chunk.map_err(ProxyError::from).and_then(|chunk| {
+                buffer.extend_from_slice(&chunk);
+                decode_events(decoder, buffer)
+            });
+            futures::future::ready(Some(events))
+        },
+    );
+
+    let mut sse = axum::http::Response::new(reqwest::Body::wrap_stream(events));
+    *sse.status_mut() = status;
+    sse.headers_mut().insert(
+        reqwest::header::CONTENT_TYPE,
+        reqwest::header::HeaderValue::from_static("text/event-stream"),
+    );
+    reqwest::Response::from(sse)
+}
+
+///
+/// Decode all complete event stream messages in a buffer into SSE lines.
+///
+/// # Arguments
+///  * `decoder` - frame decoder, keeps the prelude of a partly received message
+///  * `buffer` - received bytes; decoded messages are removed
+///
+/// # Returns
+///  * SSE text of the decoded events, empty when no message is complete yet
+///  * `ProxyError::Conversion` for malformed frames or chunk payloads
+fn decode_events(decoder: &mut MessageFrameDecoder, buffer: &mut BytesMut) -> Result<Bytes> {
+    let mut sse = String::new();
+    loop {
+        let frame = decoder.decode_frame(&mut *buffer).map_err(|e| {
+            ProxyError::Conversion(format!("Invalid Bedrock event stream frame: {}", e))
+        })?;
+        let DecodedFrame::Complete(message) = frame else {
+            break;
+        };
+        let event = message_to_event(&message)?;
+        sse.push_str("data: ");
+        sse.push_str(&event.to_string());
+        sse.push_str("\n\n");
+    }
+    Ok(Bytes::from(sse))
+}
+
+///
+/// Extract the Anthropic streaming event of an event stream message.
+fn message_to_event(message: &Message) -> Result<Value> {
+    let header = |name: &str| {
+        message
+            .headers()
+            .iter()
+            .find(|header| header.name().as_str() == name)
+            .and_then(|header| header.value().as_string().ok())
+            .map(|value| value.as_str().to_string())
+    };
+    let payload: Value = serde_json::from_slice(message.payload())?;
+
+    if header(MESSAGE_TYPE_HEADER).as_deref() == Some("exception") {
+        let error_type = header(EXCEPTION_TYPE_HEADER).unwrap_or_else(|| "exception".to_string());
+        let message = payload.get("message").and_then(Value::as_str).unwrap_or_default();
+        return Ok(json!({"type": "error", "error": {"type": error_type, "message": message}}));
+    }
+
+    let encoded = payload.get("bytes").and_then(Value::as_str).ok_or_else(|| {
+        ProxyError::Conversion("Bedrock stream chunk without 'bytes' payload".to_string())
+    })?;
+    let decoded = STANDARD.decode(encoded).map_err(|e| {
+        ProxyError::Conversion(format!("Invalid base64 in Bedrock stream chunk: {}", e))
+    })?;
+    Ok(serde_json::from_slice(&decoded)?)
+}
+
+///
+/// Value of an environment variable, `None` when unset or blank.
+fn non_empty_env(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_smithy_eventstream::frame::write_message_to;
+    use aws_smithy_types::event_stream::{Header, HeaderValue};
+
+    use super::*;
+
+    fn provider(model_id: &str) -> BedrockProvider {
+        BedrockProvider::new(
+            "https://bedrock-runtime.us-east-1.amazonaws.com".to_string(),
+            model_id.to_string(),
+            AuthStrategy::BearerToken("unused".to_string()),
+        )
+    }
+
+    fn frame(
+        message_type: &'static str,
+        extra: Option<(&'static str, &'static str)>,
+        payload: Value,
+    ) -> Vec<u8> {
+        let mut message = Message::new(payload.to_string().into_bytes())
+            .add_header(Header::new(MESSAGE_TYPE_HEADER, HeaderValue::String(message_type.into())));
+        if let Some((name, value)) = extra {
+            message = message.add_header(Header::new(name, HeaderValue::String(value.into())));
+        }
+        let mut bytes = Vec::new();
+        write_message_to(&message, &mut bytes).unwrap();
+        bytes
+    }
+
+    fn chunk(event: Value) -> Vec<u8> {
+        let encoded = STANDARD.encode(event.to_string());
+        frame("event", Some((":event-type", "chunk")), json!({"bytes": encoded}))
+    }
+
+    #[test]
+    fn test_build_request_url() {
+        let bedrock = provider("anthropic.claude-3-5-sonnet-20241022-v2:0");
+        assert_eq!(
+            bedrock.build_request_url(false),
+            "https://bedrock-runtime.us-east-1.amazonaws.com/model/\
+             anthropic.claude-3-5-sonnet-20241022-v2%3A0/invoke"
+        );
+        assert_eq!(
+            bedrock.build_request_url(true),
+            "https://bedrock-runtime.us-east-1.amazonaws.com/model/\
+             anthropic.claude-3-5-sonnet-20241022-v2%3A0/invoke-with-response-stream"
+        );
+        assert_eq!(bedrock.display_model_name(), "anthropic.claude-3-5-sonnet-20241022-v2:0");
+        assert_eq!(bedrock.id(), "bedrock");
+    }
+
+    #[test]
+    fn test_decode_events_across_chunks() {
+        let start = json!({"type": "message_start", "message": {"id": "msg_1"}});
+        let delta = json!({"type": "content_block_delta", "delta": {"text": "Hi"}});
+        let mut bytes = chunk(start.clone());
+        bytes.extend(chunk(delta.clone()));
+
+        // Split inside the second frame's prelude
+        let split = bytes.len() - chunk(delta.clone()).len() + 5;
+        let mut decoder = MessageFrameDecoder::new();
+        let mut buffer = BytesMut::from(&bytes[..split]);
+        let first = decode_events(&mut decoder, &mut buffer).unwrap();
+        assert_eq!(first, format!("data: {}\n\n", start));
+
+        buffer.extend_from_slice(&bytes[split..]);
+        let second = decode_events(&mut decoder, &mut buffer).unwrap();
+        assert_eq!(second, format!("data: {}\n\n", delta));
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_exception_becomes_error_event() {
+        let bytes = frame(
+            "exception",
+            Some((EXCEPTION_TYPE_HEADER, "throttlingException")),
+            json!({"message": "Too many requests"}),
+        );
+        let mut buffer = BytesMut::from(&bytes[..]);
+        let sse = decode_events(&mut MessageFrameDecoder::new(), &mut buffer).unwrap();
+
+        let sse = std::str::from_utf8(&sse).unwrap();
+        let event: Value = serde_json::from_str(sse.strip_prefix("data: ").unwrap()).unwrap();
+        assert_eq!(event["type"], "error");
+        assert_eq!(event["error"]["type"], "throttlingException");
+        assert_eq!(event["error"]["message"], "Too many requests");
+    }
+}
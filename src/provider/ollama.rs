@@ -0,0 +1,144 @@
+//!
+//! Ollama provider for local models.
+//!
+//! Selected with `LLM_PROVIDER=ollama`. Ollama has its own chat format, so
+//! requests go through the OpenAI → Ollama converter
+//! ([`OllamaConverter`](crate::converter::OllamaConverter)) and are sent to
+//! `{base_url}/api/chat`; streamed responses come back as newline-delimited
+//! JSON instead of SSE. A local Ollama needs no credentials.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::env;
+
+use super::{AuthStrategy, LlmProviderBackend};
+use crate::error::{ProxyError, Result};
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// Ollama provider.
+#[derive(Debug, Clone)]
+pub struct OllamaProvider {
+    /** Ollama server URL, e.g. `http://localhost:11434` */
+    base_url: String,
+    /** Ollama model every request is sent to, e.g. `llama3.1` */
+    model: String,
+    /** empty Bearer token, Ollama does not authenticate */
+    auth: AuthStrategy,
+}
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** Ollama server used when `OLLAMA_BASE_URL` is not set */
+const DEFAULT_BASE_URL: &str = "http://localhost:11434";
+
+/** Chat endpoint below the base URL */
+const CHAT_PATH: &str = "/api/chat";
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+impl OllamaProvider {
+    ///
+    /// Build from explicit values.
+    ///
+    /// # Arguments
+    ///  * `base_url` - Ollama server URL without trailing path
+    ///  * `model` - Ollama model name, e.g. `llama3.1:8b`
+    pub fn new(base_url: String, model: String) -> Self {
+        Self { base_url, model, auth: AuthStrategy::BearerToken(String::new()) }
+    }
+
+    ///
+    /// Load from environment.
+    ///
+    /// The server URL is read from `OLLAMA_BASE_URL` (default
+    /// `http://localhost:11434`), the model from `OLLAMA_MODEL`.
+    ///
+    /// # Returns
+    ///  * Provider for the configured model
+    ///  * `ProxyError::Config` when `OLLAMA_MODEL` is missing or the URL is invalid
+    pub fn from_env() -> Result<Self> {
+        let read = |name: &str| {
+            env::var(name).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+        };
+
+        let base_url = read("OLLAMA_BASE_URL").unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+        if !base_url.starts_with("http://") && !base_url.starts_with("https://") {
+            return Err(ProxyError::Config(
+                format!("OLLAMA_BASE_URL must start with http:// or https://: {}", base_url),
+                None,
+            ));
+        }
+        let model = read("OLLAMA_MODEL").ok_or_else(|| {
+            ProxyError::Config("ollama provider needs OLLAMA_MODEL".to_string(), None)
+        })?;
+
+        Ok(Self::new(base_url, model))
+    }
+}
+
+impl LlmProviderBackend for OllamaProvider {
+    fn id(&self) -> &'static str {
+        "ollama"
+    }
+
+    fn build_request_url(&self, is_streaming: bool) -> String {
+        // Streaming is selected by the `stream` field of the body, not the URL
+        let _ = is_streaming;
+        format!("{}{}", self.base_url.trim_end_matches('/'), CHAT_PATH)
+    }
+
+    fn display_model_name(&self) -> &str {
+        &self.model
+    }
+
+    fn auth_strategy(&self) -> &AuthStrategy {
+        &self.auth
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_request_url() {
+        let ollama =
+            OllamaProvider::new("http://gpu-box:11434/".to_string(), "llama3.1".to_string());
+        assert_eq!(ollama.build_request_url(false), "http://gpu-box:11434/api/chat");
+        assert_eq!(ollama.build_request_url(true), "http://gpu-box:11434/api/chat");
+        assert_eq!(ollama.display_model_name(), "llama3.1");
+        assert!(
+            matches!(ollama.auth_strategy(), AuthStrategy::BearerToken(token) if token.is_empty())
+        );
+    }
+
+    #[test]
+    fn test_from_env() {
+        temp_env::with_vars(
+            [("OLLAMA_BASE_URL", None::<&str>), ("OLLAMA_MODEL", Some("qwen2.5-coder"))],
+            || {
+                let ollama = OllamaProvider::from_env().unwrap();
+                assert_eq!(ollama.build_request_url(true), "http://localhost:11434/api/chat");
+                assert_eq!(ollama.display_model_name(), "qwen2.5-coder");
+            },
+        );
+        temp_env::with_vars([("OLLAMA_MODEL", None::<&str>)], || {
+            let err = OllamaProvider::from_env().unwrap_err();
+            assert!(err.to_string().contains("OLLAMA_MODEL"));
+        });
+        temp_env::with_vars(
+            [("OLLAMA_BASE_URL", Some("localhost:11434")), ("OLLAMA_MODEL", Some("llama3.1"))],
+            || {
+                let err = OllamaProvider::from_env().unwrap_err();
+                assert!(err.to_string().contains("OLLAMA_BASE_URL"));
+            },
+        );
+    }
+}
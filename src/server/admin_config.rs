@@ -0,0 +1,304 @@
+//!
+//! Live configuration inspection for operators.
+//!
+//! `GET /admin/config` returns the configuration the server is running with,
+//! as JSON or, with `Accept: application/toml`, as TOML. Secrets are replaced
+//! with `<redacted>`. `GET /admin/config/sources` lists the configuration
+//! files and environment variables that fed into it, highest priority first.
+//!
+//! Both endpoints sit behind the admin secret and are only served when
+//! `admin.enable_config_endpoint` is set.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::http::header::{ACCEPT, CONTENT_TYPE};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+
+use super::{AppState, create_error_response, json_response};
+use crate::config::{Config, loader, paths};
+use crate::error::{ProxyError, Result};
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// Query parameters of `GET /admin/config`.
+#[derive(Debug, Deserialize)]
+pub struct ConfigQuery {
+    /** top-level section to return, e.g. `server` (default: whole configuration) */
+    pub section: Option<String>,
+}
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** Content type of TOML responses */
+const CONTENT_TYPE_TOML: &str = "application/toml";
+
+/** Prefix of environment variables read by the configuration loader */
+const CONFIG_ENV_PREFIX: &str = "MODELMUX_";
+
+/** Environment variables read for the LLM provider when `[vertex]` is not configured */
+const PROVIDER_ENV_PREFIXES: &[&str] = &["LLM_", "VERTEX_", "OPENAI_"];
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+///
+/// Handle `GET /admin/config`.
+///
+/// # Arguments
+///  * `state` - shared application state with the running configuration
+///  * `headers` - request headers; `Accept: application/toml` selects TOML
+///  * `query` - optional `section` to return
+///
+/// # Returns
+///  * Redacted configuration with its `loaded_at` time, `400` for an unknown
+///    section, or `403` when `admin.enable_config_endpoint` is off
+pub async fn admin_config(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<ConfigQuery>,
+) -> Response {
+    let mut config = state.config.read().await.clone();
+    if let Some(response) = disabled_response(&config) {
+        return response;
+    }
+
+    config.redact_sensitive();
+    let loaded_at = state.config_loaded_at.read().await.to_rfc3339();
+    let section = query.section.as_deref();
+
+    let wants_toml = headers
+        .get(ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|accept| accept.contains("toml"));
+    let rendered = if wants_toml {
+        render_toml(&config, section).map(|body| {
+            let body = format!("# loaded_at = \"{}\"\n{}", loaded_at, body);
+            ([(CONTENT_TYPE, CONTENT_TYPE_TOML)], body).into_response()
+        })
+    } else {
+        render_json(&config, section).map(|config| {
+            json_response(StatusCode::OK, json!({ "loaded_at": loaded_at, "config": config }))
+        })
+    };
+
+    rendered.unwrap_or_else(|e| create_error_response(&e))
+}
+
+///
+/// Handle `GET /admin/config/sources`.
+///
+/// # Arguments
+///  * `state` - shared application state
+///
+/// # Returns
+///  * Configuration sources ordered by priority (`1` = highest), or `403`
+///    when `admin.enable_config_endpoint` is off
+pub async fn admin_config_sources(State(state): State<Arc<AppState>>) -> Response {
+    if let Some(response) = disabled_response(&*state.config.read().await) {
+        return response;
+    }
+
+    let env_names: Vec<String> = std::env::vars().map(|(name, _)| name).collect();
+    let dotenv = std::env::current_dir().map(|dir| dir.join(loader::dotenv_file())).ok();
+    // A custom config file replaces the user and system ones
+    let config_files = match loader::custom_config_file() {
+        Ok(Some(path)) => vec![path],
+        _ => paths::config_file_paths(),
+    };
+    let body = json!({
+      "loaded_at": state.config_loaded_at.read().await.to_rfc3339(),
+      "sources": config_sources(&env_names, &config_files),
+      "dotenv": {
+        "path": dotenv.as_ref().map(|path| path.display().to_string()),
+        "found": dotenv.is_some_and(|path| path.exists()),
+        "note": "variables from the .env file are loaded with the configuration unless already set"
+      }
+    });
+    json_response(StatusCode::OK, body)
+}
+
+///
+/// List configuration sources from highest to lowest priority.
+///
+/// Only the names of environment variables are reported, never their values.
+///
+/// # Arguments
+///  * `env_names` - names of the set environment variables
+///  * `config_files` - config file paths in precedence order
+///
+/// # Returns
+///  * One JSON object per source with `priority`, `type` and `active`
+fn config_sources(env_names: &[String], config_files: &[std::path::PathBuf]) -> Vec<Value> {
+    let matching = |prefixes: &[&str]| {
+        let mut names: Vec<&str> = env_names
+            .iter()
+            .map(String::as_str)
+            .filter(|name| prefixes.iter().any(|prefix| name.starts_with(prefix)))
+            .collect();
+        names.sort_unstable();
+        names
+    };
+
+    let mut sources = Vec::new();
+    let config_env = matching(&[CONFIG_ENV_PREFIX]);
+    sources.push(json!({
+      "type": "environment",
+      "description": "MODELMUX_* environment variables",
+      "active": !config_env.is_empty(),
+      "variables": config_env
+    }));
+    for path in config_files {
+        sources.push(json!({
+          "type": "file",
+          "path": path.display().to_string(),
+          "active": path.exists()
+        }));
+    }
+    let provider_env = matching(PROVIDER_ENV_PREFIXES);
+    sources.push(json!({
+      "type": "provider_environment",
+      "description": "LLM provider variables, used when [vertex] is not configured",
+      "active": !provider_env.is_empty(),
+      "variables": provider_env
+    }));
+    sources.push(json!({ "type": "defaults", "active": true }));
+
+    for (priority, source) in sources.iter_mut().enumerate() {
+        source["priority"] = json!(priority + 1);
+    }
+    sources
+}
+
+///
+/// Serialise the configuration, or one section of it, as JSON.
+///
+/// # Returns
+///  * JSON object keyed by section, or `ProxyError::Config` for an unknown section
+fn render_json(config: &Config, section: Option<&str>) -> Result<Value> {
+    let mut sections: Map<String, Value> = serde_json::from_value(serde_json::to_value(config)?)?;
+    match section {
+        None => Ok(Value::Object(sections)),
+        Some(name) => {
+            let value = sections.remove(name).ok_or_else(|| unknown_section(name))?;
+            Ok(json!({ name: value }))
+        }
+    }
+}
+
+///
+/// Serialise the configuration, or one section of it, as TOML.
+///
+/// # Returns
+///  * TOML document, or `ProxyError::Config` for an unknown section
+fn render_toml(config: &Config, section: Option<&str>) -> Result<String> {
+    let toml_error = |e: toml::ser::Error| {
+        ProxyError::config_with_source("Failed to serialise configuration as TOML", e)
+    };
+    let mut table = toml::Table::try_from(config).map_err(toml_error)?;
+    if let Some(name) = section {
+        let value = table.remove(name).ok_or_else(|| unknown_section(name))?;
+        table = toml::Table::from_iter([(name.to_string(), value)]);
+    }
+    toml::to_string_pretty(&table).map_err(toml_error)
+}
+
+///
+/// Error for a `section` the configuration does not contain.
+fn unknown_section(name: &str) -> ProxyError {
+    ProxyError::Config(format!("Unknown or unset configuration section '{}'", name), None)
+}
+
+///
+/// Response for requests while `admin.enable_config_endpoint` is off.
+fn disabled_response(config: &Config) -> Option<Response> {
+    if config.admin.enable_config_endpoint {
+        return None;
+    }
+    let error = json!({
+      "error": {
+        "message": "Config endpoint is disabled: set [admin] enable_config_endpoint = true \
+                    or MODELMUX_ADMIN_ENABLE_CONFIG_ENDPOINT",
+        "type": "permission_error",
+        "code": 403
+      }
+    });
+    Some(json_response(StatusCode::FORBIDDEN, error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ApiKeyEntry, REDACTED};
+
+    #[test]
+    fn test_render_redacted_sections() {
+        let mut config = Config::default();
+        config.auth.service_account_json = Some("{\"private_key\": \"secret\"}".to_string());
+        config.auth.api_keys.push(ApiKeyEntry {
+            name: "ci".to_string(),
+            key_hash: "a".repeat(64),
+            allowed_models: Vec::new(),
+        });
+        config.redact_sensitive();
+
+        let json = render_json(&config, None).unwrap();
+        assert_eq!(json["auth"]["service_account_json"], REDACTED);
+        assert_eq!(json["auth"]["api_keys"][0]["key_hash"], REDACTED);
+        assert!(json["server"]["port"].is_number());
+
+        let server = render_json(&config, Some("server")).unwrap();
+        assert_eq!(server.as_object().unwrap().len(), 1);
+        assert_eq!(server["server"]["port"], json["server"]["port"]);
+
+        let toml = render_toml(&config, Some("streaming")).unwrap();
+        assert!(toml.starts_with("[streaming]"));
+        assert!(!render_toml(&config, None).unwrap().contains("secret"));
+
+        assert!(matches!(render_json(&config, Some("nope")), Err(ProxyError::Config(..))));
+        assert!(render_toml(&config, Some("nope")).is_err());
+    }
+
+    #[test]
+    fn test_config_sources_priorities() {
+        let env_names: Vec<String> =
+            ["PATH", "MODELMUX_SERVER_PORT", "VERTEX_PROJECT", "MODELMUX_ADMIN_SECRET"]
+                .iter()
+                .map(|name| name.to_string())
+                .collect();
+        let files = vec![std::path::PathBuf::from("/nonexistent/modelmux/config.toml")];
+        let sources = config_sources(&env_names, &files);
+
+        assert_eq!(sources.len(), 4);
+        assert_eq!(sources[0]["priority"], 1);
+        assert_eq!(
+            sources[0]["variables"],
+            json!(["MODELMUX_ADMIN_SECRET", "MODELMUX_SERVER_PORT"])
+        );
+        assert_eq!(sources[1]["type"], "file");
+        assert_eq!(sources[1]["active"], false);
+        assert_eq!(sources[2]["variables"], json!(["VERTEX_PROJECT"]));
+        assert_eq!(sources[3]["type"], "defaults");
+        assert_eq!(sources[3]["priority"], 4);
+    }
+
+    #[test]
+    fn test_disabled_by_default() {
+        let mut config = Config::default();
+        let response = disabled_response(&config).unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        config.admin.enable_config_endpoint = true;
+        assert!(disabled_response(&config).is_none());
+    }
+}
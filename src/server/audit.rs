@@ -0,0 +1,599 @@
+//!
+//! Audit log of API requests.
+//!
+//! `AuditLogLayer` records every `POST` to the API as one JSON line: arrival
+//! time, request ID, path, status, model and messages of the request, and
+//! with `audit_log.include_response_body` the response body (the SSE text of
+//! streamed responses). Bodies are captured while the handler reads them and
+//! while the response is sent, so neither is buffered up front; the record is
+//! handed to a background writer task over a bounded channel and dropped with
+//! a warning when the writer falls behind, never delaying the request.
+//!
+//! The file is rotated by size: at `max_size_mb` the current file becomes
+//! `<name>.1`, older files shift up and at most `max_files` files are kept.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use axum::body::Body;
+use axum::http::{HeaderMap, Method, Request, Response, header};
+use bytes::Bytes;
+use chrono::{SecondsFormat, Utc};
+use futures::TryStreamExt;
+use futures::future::BoxFuture;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tower::{Layer, Service};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+use super::REQUEST_ID_HEADER;
+use crate::config::{AuditLogConfig, paths};
+use crate::error::{ProxyError, Result};
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// Layer recording API requests in the audit log.
+///
+/// A disabled layer passes requests through untouched.
+#[derive(Clone)]
+pub struct AuditLogLayer {
+    /** writer channel and recording options (None when disabled) */
+    sink: Option<AuditSink>,
+}
+
+///
+/// Service of [`AuditLogLayer`].
+#[derive(Clone)]
+pub struct AuditLogService<S> {
+    /** wrapped service */
+    inner: S,
+    /** writer channel and recording options (None when disabled) */
+    sink: Option<AuditSink>,
+}
+
+///
+/// Destination and options of audit records.
+#[derive(Clone)]
+struct AuditSink {
+    /** records waiting for the writer task */
+    sender: mpsc::Sender<AuditRecord>,
+    /** record response bodies */
+    include_response_body: bool,
+    /** replace message content with `[REDACTED]` */
+    redact_user_content: bool,
+}
+
+///
+/// One line of the audit log.
+#[derive(Debug, Serialize)]
+struct AuditRecord {
+    /** arrival time of the request (RFC 3339, UTC) */
+    timestamp: String,
+    /** `X-Request-ID` of the response, or else of the request */
+    request_id: Option<String>,
+    /** request method */
+    method: String,
+    /** request path */
+    path: String,
+    /** response status code */
+    status: u16,
+    /** requested model */
+    model: Option<String>,
+    /** number of messages in the request */
+    message_count: Option<usize>,
+    /** messages of the request */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    messages: Option<Value>,
+    /** response body: JSON when it parses, text otherwise */
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_body: Option<Value>,
+}
+
+///
+/// Record waiting for the end of its response body.
+///
+/// Sent when dropped, i.e. when the body is finished or the client went away.
+struct PendingRecord {
+    /** record without the response body */
+    record: Option<AuditRecord>,
+    /** response body sent so far */
+    body: Vec<u8>,
+    /** destination of the record */
+    sink: AuditSink,
+}
+
+///
+/// Size-rotated audit log file.
+pub struct AuditFileWriter {
+    /** directory of the audit files */
+    directory: PathBuf,
+    /** name of the current file; rotated files get `.1`, `.2`, ... appended */
+    file_name: String,
+    /** size at which the current file is rotated */
+    max_bytes: u64,
+    /** files kept, the current one included */
+    max_files: u32,
+    /** bytes in the current file */
+    written: u64,
+    /** appender of the current file */
+    appender: RollingFileAppender,
+}
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** Records waiting for the writer before new ones are dropped */
+const AUDIT_CHANNEL_CAPACITY: usize = 1024;
+
+/** Bytes of a request or response body captured for one record; the rest is not recorded */
+const MAX_CAPTURED_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+/** File name of the audit log when `audit_log.path` is empty */
+const DEFAULT_AUDIT_FILE: &str = "audit.log";
+
+/** Replacement of redacted message content */
+const REDACTED_CONTENT: &str = "[REDACTED]";
+
+/** Bytes per configured megabyte */
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+impl AuditLogLayer {
+    ///
+    /// Create the layer and start its writer task.
+    ///
+    /// Must be called within a Tokio runtime when the audit log is enabled.
+    ///
+    /// # Arguments
+    ///  * `config` - audit log section
+    ///
+    /// # Returns
+    ///  * Layer recording requests, or passing them through when disabled
+    ///  * `ProxyError::Config` if the audit file cannot be opened
+    pub fn new(config: &AuditLogConfig) -> Result<Self> {
+        if !config.enabled {
+            return Ok(Self { sink: None });
+        }
+
+        let path = audit_log_path(config)?;
+        let writer = AuditFileWriter::new(
+            &path,
+            config.max_size_mb.saturating_mul(BYTES_PER_MB),
+            config.max_files.max(1),
+        )?;
+        let (sender, receiver) = mpsc::channel(AUDIT_CHANNEL_CAPACITY);
+        tokio::task::spawn_blocking(move || write_records(writer, receiver));
+        tracing::info!(path = %path.display(), "Audit log enabled");
+
+        Ok(Self {
+            sink: Some(AuditSink {
+                sender,
+                include_response_body: config.include_response_body,
+                redact_user_content: config.redact_user_content,
+            }),
+        })
+    }
+}
+
+impl<S> Layer<S> for AuditLogLayer {
+    type Service = AuditLogService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuditLogService { inner, sink: self.sink.clone() }
+    }
+}
+
+impl<S> Service<Request<Body>> for AuditLogService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>>,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, std::result::Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let Some(sink) = self.sink.clone().filter(|_| request.method() == Method::POST) else {
+            return Box::pin(self.inner.call(request));
+        };
+
+        let timestamp = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+        let method = request.method().to_string();
+        let path = request.uri().path().to_string();
+        let request_id = header_request_id(request.headers());
+        // Multipart uploads carry file contents, not messages
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        let request = if is_json(request.headers()) {
+            let capture = captured.clone();
+            request.map(|body| {
+                Body::from_stream(
+                    body.into_data_stream().inspect_ok(move |chunk| append(&capture, chunk)),
+                )
+            })
+        } else {
+            request
+        };
+
+        let future = self.inner.call(request);
+        Box::pin(async move {
+            let response = future.await?;
+            let request_body = captured.lock().map(|mut body| std::mem::take(&mut *body));
+            let record = AuditRecord::new(
+                timestamp,
+                header_request_id(response.headers()).or(request_id),
+                method,
+                path,
+                response.status().as_u16(),
+                &request_body.unwrap_or_default(),
+                sink.redact_user_content,
+            );
+            if !sink.include_response_body {
+                sink.send(record);
+                return Ok(response);
+            }
+
+            let mut pending = PendingRecord { record: Some(record), body: Vec::new(), sink };
+            Ok(response.map(|body| {
+                Body::from_stream(
+                    body.into_data_stream().inspect_ok(move |chunk| pending.append(chunk)),
+                )
+            }))
+        })
+    }
+}
+
+impl AuditSink {
+    ///
+    /// Hand a record to the writer task without waiting.
+    fn send(&self, record: AuditRecord) {
+        if let Err(e) = self.sender.try_send(record) {
+            tracing::warn!("Audit record dropped: {}", e);
+        }
+    }
+}
+
+impl AuditRecord {
+    ///
+    /// Build the record of a request.
+    ///
+    /// # Arguments
+    ///  * `timestamp` - arrival time of the request
+    ///  * `request_id` - ID of the request, if known
+    ///  * `method` - request method
+    ///  * `path` - request path
+    ///  * `status` - response status code
+    ///  * `request_body` - captured request body; model and messages are read
+    ///    from it when it is a JSON object
+    ///  * `redact` - replace message content with `[REDACTED]`
+    fn new(
+        timestamp: String,
+        request_id: Option<String>,
+        method: String,
+        path: String,
+        status: u16,
+        request_body: &[u8],
+        redact: bool,
+    ) -> Self {
+        let body: Value = serde_json::from_slice(request_body).unwrap_or(Value::Null);
+        let mut messages = body.get("messages").filter(|m| m.is_array()).cloned();
+        if redact && let Some(Value::Array(items)) = &mut messages {
+            items.iter_mut().for_each(redact_content);
+        }
+
+        Self {
+            timestamp,
+            request_id,
+            method,
+            path,
+            status,
+            model: body.get("model").and_then(Value::as_str).map(str::to_string),
+            message_count: messages.as_ref().and_then(Value::as_array).map(Vec::len),
+            messages,
+            response_body: None,
+        }
+    }
+}
+
+impl PendingRecord {
+    ///
+    /// Capture a chunk of the response body.
+    fn append(&mut self, chunk: &Bytes) {
+        let room = MAX_CAPTURED_BODY_BYTES.saturating_sub(self.body.len());
+        self.body.extend_from_slice(&chunk[..chunk.len().min(room)]);
+    }
+}
+
+impl Drop for PendingRecord {
+    fn drop(&mut self) {
+        let Some(mut record) = self.record.take() else {
+            return;
+        };
+
+        let body = match serde_json::from_slice::<Value>(&self.body) {
+            Ok(mut body) => {
+                if self.sink.redact_user_content
+                    && let Some(choices) = body.get_mut("choices").and_then(Value::as_array_mut)
+                {
+                    for choice in choices {
+                        if let Some(message) = choice.get_mut("message") {
+                            redact_content(message);
+                        }
+                    }
+                }
+                body
+            }
+            Err(_) if self.sink.redact_user_content => Value::String(REDACTED_CONTENT.to_string()),
+            Err(_) => Value::String(String::from_utf8_lossy(&self.body).into_owned()),
+        };
+        record.response_body = Some(body);
+        self.sink.send(record);
+    }
+}
+
+impl AuditFileWriter {
+    ///
+    /// Open an audit file for appending.
+    ///
+    /// # Arguments
+    ///  * `path` - audit file; its directory is created if missing
+    ///  * `max_bytes` - size at which the file is rotated
+    ///  * `max_files` - files kept, the current one included
+    ///
+    /// # Returns
+    ///  * Writer appending to the file
+    ///  * `ProxyError::Config` if the file cannot be opened
+    pub fn new(path: &Path, max_bytes: u64, max_files: u32) -> Result<Self> {
+        let file_name =
+            path.file_name().map(|name| name.to_string_lossy().into_owned()).ok_or_else(|| {
+                ProxyError::Config(
+                    format!("Audit log path '{}' does not name a file", path.display()),
+                    None,
+                )
+            })?;
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let appender = open_appender(&directory, &file_name)?;
+        let written = fs::metadata(path).map(|metadata| metadata.len()).unwrap_or(0);
+
+        Ok(Self { directory, file_name, max_bytes, max_files, written, appender })
+    }
+
+    ///
+    /// Append one line, rotating the file first if the line does not fit.
+    ///
+    /// # Arguments
+    ///  * `line` - line without the trailing newline
+    pub fn write_line(&mut self, line: &[u8]) -> io::Result<()> {
+        let len = line.len() as u64 + 1;
+        if self.written > 0 && self.written + len > self.max_bytes {
+            self.rotate()?;
+        }
+
+        let mut buf = Vec::with_capacity(line.len() + 1);
+        buf.extend_from_slice(line);
+        buf.push(b'\n');
+        self.appender.write_all(&buf)?;
+        self.written += len;
+        Ok(())
+    }
+
+    ///
+    /// Shift the rotated files up by one, dropping the oldest, and start a new file.
+    fn rotate(&mut self) -> io::Result<()> {
+        let rotated = |index: u32| self.directory.join(format!("{}.{}", self.file_name, index));
+        let current = self.directory.join(&self.file_name);
+
+        if self.max_files > 1 {
+            for index in (1..self.max_files - 1).rev() {
+                let from = rotated(index);
+                if from.exists() {
+                    fs::rename(&from, rotated(index + 1))?;
+                }
+            }
+            fs::rename(&current, rotated(1))?;
+        } else {
+            fs::remove_file(&current)?;
+        }
+
+        self.appender =
+            open_appender(&self.directory, &self.file_name).map_err(io::Error::other)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+///
+/// Write records to the audit file until every sender is gone.
+///
+/// Runs on a blocking task so file I/O stays off the async workers.
+fn write_records(mut writer: AuditFileWriter, mut receiver: mpsc::Receiver<AuditRecord>) {
+    while let Some(record) = receiver.blocking_recv() {
+        let result = serde_json::to_vec(&record)
+            .map_err(io::Error::other)
+            .and_then(|line| writer.write_line(&line));
+        if let Err(e) = result {
+            tracing::error!(error = %e, "Failed to write audit record");
+        }
+    }
+}
+
+///
+/// Appender of one audit file, created with its directory if missing.
+fn open_appender(directory: &Path, file_name: &str) -> Result<RollingFileAppender> {
+    RollingFileAppender::builder()
+        .rotation(Rotation::NEVER)
+        .filename_prefix(file_name)
+        .build(directory)
+        .map_err(|e| ProxyError::config_with_source("Failed to open audit log", e))
+}
+
+///
+/// Audit file of the configuration: `audit_log.path`, or `audit.log` in the log directory.
+fn audit_log_path(config: &AuditLogConfig) -> Result<PathBuf> {
+    if config.path.trim().is_empty() {
+        Ok(paths::user_log_dir()?.join(DEFAULT_AUDIT_FILE))
+    } else {
+        paths::expand_path(config.path.trim())
+    }
+}
+
+///
+/// Replace the content of a chat message with `[REDACTED]`.
+fn redact_content(message: &mut Value) {
+    if let Some(content) = message.get_mut("content").filter(|content| !content.is_null()) {
+        *content = Value::String(REDACTED_CONTENT.to_string());
+    }
+}
+
+///
+/// Append a chunk of the request body to the capture buffer, up to the capture limit.
+fn append(buffer: &Mutex<Vec<u8>>, chunk: &Bytes) {
+    if let Ok(mut buffer) = buffer.lock() {
+        let room = MAX_CAPTURED_BODY_BYTES.saturating_sub(buffer.len());
+        buffer.extend_from_slice(&chunk[..chunk.len().min(room)]);
+    }
+}
+
+///
+/// Whether a request carries a JSON body.
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"))
+}
+
+///
+/// `X-Request-ID` header value, if present.
+fn header_request_id(headers: &HeaderMap) -> Option<String> {
+    headers.get(REQUEST_ID_HEADER)?.to_str().ok().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_audit_file_writer_rotates_by_size() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("audit.log");
+        let mut writer = AuditFileWriter::new(&path, 100, 3).unwrap();
+
+        for index in 0..10 {
+            writer.write_line(format!("{{\"line\":{:030}}}", index).as_bytes()).unwrap();
+        }
+
+        let read = |name: &str| fs::read_to_string(dir.path().join(name)).unwrap();
+        let current = read("audit.log");
+        assert!(current.len() <= 100);
+        assert!(current.ends_with(&format!("{{\"line\":{:030}}}\n", 9)));
+        assert!(read("audit.log.1").contains(&format!("{:030}", 7)));
+        assert!(read("audit.log.2").contains(&format!("{:030}", 4)));
+        assert!(!dir.path().join("audit.log.3").exists());
+    }
+
+    #[test]
+    fn test_audit_record_redacts_user_content() {
+        let body = serde_json::json!({
+            "model": "claude-sonnet",
+            "messages": [
+                {"role": "system", "content": "You are helpful"},
+                {"role": "user", "content": [{"type": "text", "text": "my card is 4111"}]}
+            ]
+        });
+        let body = serde_json::to_vec(&body).unwrap();
+
+        let record = |redact| {
+            let record = AuditRecord::new(
+                "2026-01-01T00:00:00.000Z".to_string(),
+                Some("req-1".to_string()),
+                "POST".to_string(),
+                "/v1/chat/completions".to_string(),
+                200,
+                &body,
+                redact,
+            );
+            serde_json::to_string(&record).unwrap()
+        };
+
+        let redacted = record(true);
+        assert!(!redacted.contains("4111"));
+        assert!(!redacted.contains("You are helpful"));
+        assert_eq!(redacted.matches(REDACTED_CONTENT).count(), 2);
+        assert!(redacted.contains("\"model\":\"claude-sonnet\""));
+        assert!(redacted.contains("\"message_count\":2"));
+
+        assert!(record(false).contains("my card is 4111"));
+    }
+
+    #[tokio::test]
+    async fn test_audit_layer_records_request_and_response() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("logs").join("audit.log");
+        let config = AuditLogConfig {
+            enabled: true,
+            path: path.display().to_string(),
+            include_response_body: true,
+            redact_user_content: true,
+            ..Default::default()
+        };
+        let layer = AuditLogLayer::new(&config).unwrap();
+        let service = layer.layer(tower::service_fn(|request: Request<Body>| async move {
+            let body = axum::body::to_bytes(request.into_body(), usize::MAX).await.unwrap();
+            let request: Value = serde_json::from_slice(&body).unwrap();
+            let reply = serde_json::json!({
+                "model": request["model"],
+                "choices": [{"message": {"role": "assistant", "content": "secret reply"}}]
+            });
+            let response = Response::builder()
+                .header(REQUEST_ID_HEADER, "req-42")
+                .body(Body::from(reply.to_string()))
+                .unwrap();
+            Ok::<_, std::convert::Infallible>(response)
+        }));
+
+        let request = Request::post("/v1/chat/completions")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{"model":"m1","messages":[{"role":"user","content":"hi there"}]}"#))
+            .unwrap();
+        let response = service.oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("secret reply"));
+        drop(layer);
+
+        let mut line = String::new();
+        for _ in 0..100 {
+            line = fs::read_to_string(&path).unwrap_or_default();
+            if !line.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        let record: Value = serde_json::from_str(line.trim()).unwrap();
+        assert_eq!(record["request_id"], "req-42");
+        assert_eq!(record["model"], "m1");
+        assert_eq!(record["status"], 200);
+        assert_eq!(record["messages"][0]["content"], REDACTED_CONTENT);
+        assert_eq!(record["response_body"]["choices"][0]["message"]["content"], REDACTED_CONTENT);
+        assert!(!line.contains("hi there") && !line.contains("secret reply"));
+    }
+}
@@ -0,0 +1,190 @@
+//!
+//! Bearer token guard of the proxy's own API.
+//!
+//! When `auth.proxy_api_key` is set or `[[auth.api_keys]]` lists client keys,
+//! every `/v1/*` request must carry `Authorization: Bearer <key>`; requests
+//! without it, or with a key matching neither, get `401`. `/health` and
+//! `/metrics` stay open for probes and scrapers, and the admin endpoints keep
+//! their own `X-Admin-Secret` check.
+//!
+//! Client keys are stored as SHA-256 hashes (see `modelmux keygen`) and
+//! compared in constant time. A client key with `allowed_models` may only use
+//! those models: the chat completion, async job and embeddings handlers check
+//! the model with [`check_model_access`] and answer others with `403`.
+//!
+//! The keys are read from the running configuration on every request, so a
+//! configuration reload rotates them without a restart.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::sync::Arc;
+
+use axum::extract::{Request, State};
+use axum::http::HeaderValue;
+use axum::http::header::{AUTHORIZATION, WWW_AUTHENTICATE};
+use axum::middleware::Next;
+use axum::response::Response;
+use subtle::ConstantTimeEq;
+
+use super::{AppState, create_error_response};
+use crate::auth::hash_api_key;
+use crate::config::{ApiKeyEntry, Config};
+use crate::error::{ProxyError, Result};
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** Path prefix of the guarded endpoints */
+const GUARDED_PATH_PREFIX: &str = "/v1/";
+
+/** Scheme prefix of the `Authorization` header */
+const BEARER_PREFIX: &str = "Bearer ";
+
+tokio::task_local! {
+    /** client key of the request being handled, when it is restricted to some models */
+    static RESTRICTED_KEY: ApiKeyEntry;
+}
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+///
+/// Require the proxy API key or a client API key on `/v1/*` requests.
+///
+/// # Arguments
+///  * `state` - shared application state with the auth configuration
+///  * `request` - incoming HTTP request
+///  * `next` - next handler in the chain
+///
+/// # Returns
+///  * Response of the next handler, or `401` with `WWW-Authenticate: Bearer`
+///    when the key is missing or wrong
+pub async fn auth_middleware(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !request.uri().path().starts_with(GUARDED_PATH_PREFIX) {
+        return next.run(request).await;
+    }
+    // Cloned so no read lock is held while the handler runs
+    let (proxy_api_key, api_keys) = {
+        let config = state.config.read().await;
+        (config.auth.proxy_api_key.clone(), config.auth.api_keys.clone())
+    };
+    if proxy_api_key.is_none() && api_keys.is_empty() {
+        return next.run(request).await;
+    }
+
+    let provided = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix(BEARER_PREFIX));
+    let Some(provided) = provided else {
+        return unauthorized("Missing API key: send Authorization: Bearer <key>");
+    };
+
+    let provided_hash = hash_api_key(provided.trim());
+    if proxy_api_key
+        .is_some_and(|expected| key_hash_matches(&provided_hash, &hash_api_key(&expected)))
+    {
+        return next.run(request).await;
+    }
+    match api_keys.into_iter().find(|entry| key_hash_matches(&provided_hash, &entry.key_hash)) {
+        Some(entry) if entry.allowed_models.is_empty() => next.run(request).await,
+        Some(entry) => RESTRICTED_KEY.scope(entry, next.run(request)).await,
+        None => unauthorized("Invalid API key"),
+    }
+}
+
+///
+/// Check that the client key of the current request may use a model.
+///
+/// Keys without `allowed_models`, the proxy API key and unauthenticated
+/// requests may use any model. A key's `allowed_models` may name the model as
+/// requested or, for aliases, the model it stands for.
+///
+/// # Arguments
+///  * `config` - running configuration, for the default model and aliases
+///  * `model` - requested model, `None` for the default model
+///
+/// # Returns
+///  * `Ok(())` when the model may be used
+///  * `ProxyError::ModelNotAllowed` otherwise
+pub fn check_model_access(config: &Config, model: Option<&str>) -> Result<()> {
+    RESTRICTED_KEY
+        .try_with(|key| {
+            let requested = model.unwrap_or_else(|| config.llm_model());
+            let resolved = config.resolve_model_alias(requested);
+            if key.allowed_models.iter().any(|allowed| allowed == requested || allowed == resolved)
+            {
+                Ok(())
+            } else {
+                Err(ProxyError::ModelNotAllowed {
+                    key: key.name.clone(),
+                    model: requested.to_string(),
+                })
+            }
+        })
+        .unwrap_or(Ok(()))
+}
+
+///
+/// Compare the hash of a provided API key with a stored hash in constant time.
+///
+/// Stored hashes are lowercase hex; uppercase ones are accepted too.
+fn key_hash_matches(provided_hash: &str, stored_hash: &str) -> bool {
+    provided_hash.as_bytes().ct_eq(stored_hash.to_ascii_lowercase().as_bytes()).into()
+}
+
+///
+/// `401` response asking for a bearer token.
+fn unauthorized(message: &str) -> Response {
+    let mut response = create_error_response(&ProxyError::Auth(message.to_string()));
+    response.headers_mut().insert(WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_hash_matches() {
+        let hash = hash_api_key("secret-key");
+        assert!(key_hash_matches(&hash_api_key("secret-key"), &hash));
+        assert!(key_hash_matches(&hash_api_key("secret-key"), &hash.to_ascii_uppercase()));
+        assert!(!key_hash_matches(&hash_api_key("secret-kex"), &hash));
+        assert!(!key_hash_matches(&hash_api_key(""), &hash));
+        assert!(!key_hash_matches(&hash_api_key("secret-key"), ""));
+    }
+
+    #[tokio::test]
+    async fn test_check_model_access() {
+        let config = Config {
+            model_aliases: [("sonnet".to_string(), "claude-sonnet-4@1".to_string())].into(),
+            ..Default::default()
+        };
+        // Without a restricted key every model is allowed
+        assert!(check_model_access(&config, Some("anything")).is_ok());
+
+        let key = ApiKeyEntry {
+            name: "ci".to_string(),
+            key_hash: hash_api_key("key"),
+            allowed_models: vec!["claude-sonnet-4@1".to_string()],
+        };
+        RESTRICTED_KEY
+            .scope(key, async {
+                assert!(check_model_access(&config, Some("claude-sonnet-4@1")).is_ok());
+                assert!(check_model_access(&config, Some("sonnet")).is_ok());
+                let error = check_model_access(&config, Some("opus")).unwrap_err();
+                assert!(matches!(error, ProxyError::ModelNotAllowed { .. }), "{}", error);
+                assert!(check_model_access(&config, None).is_err());
+            })
+            .await;
+    }
+}
@@ -0,0 +1,190 @@
+//!
+//! Anthropic batch output conversion.
+//!
+//! `POST /v1/batches/convert` takes the output file of a completed Anthropic
+//! message batch (JSONL, one result per line) and returns it as OpenAI chat
+//! completions, one JSON object per line. Malformed lines and results without
+//! a message are skipped with a warning.
+//!
+//! Files up to `limits.max_request_body_bytes` with a known length are read
+//! and converted at once. Larger files, and files sent without a
+//! `Content-Length`, are converted line by line as they arrive, so they are
+//! never fully buffered. Files above `limits.max_batch_bytes` get `413`, or
+//! end the response early when sent without a `Content-Length`, as does any
+//! other read error while streaming.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::extract::{Query, State};
+use axum::http::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use futures::TryStreamExt;
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tokio_stream::StreamExt;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::io::StreamReader;
+
+use super::{AppState, create_error_response};
+use crate::converter::anthropic_to_openai::OpenAiResponse;
+use crate::converter::parse_batch_jsonl;
+use crate::error::{ProxyError, Result};
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// Query parameters of `POST /v1/batches/convert`.
+#[derive(Debug, Deserialize)]
+pub struct BatchConvertQuery {
+    /** model reported in the converted responses, defaults to the configured model */
+    pub model: Option<String>,
+}
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** Content type of the converted file */
+const JSONL_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/** Converted lines buffered between the conversion task and the response body */
+const LINE_CHANNEL_BUFFER: usize = 64;
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+///
+/// Handle `POST /v1/batches/convert`.
+///
+/// # Arguments
+///  * `state` - shared application state
+///  * `query` - optional model override
+///  * `headers` - request headers, for the body length
+///  * `body` - Anthropic batch output file
+///
+/// # Returns
+///  * OpenAI chat completions as JSONL, or an OpenAI-style error response
+pub async fn convert_batch_output(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<BatchConvertQuery>,
+    headers: HeaderMap,
+    body: Body,
+) -> Response {
+    let (model, max_buffered_bytes) = {
+        let config = state.config.read().await;
+        let model = query.model.unwrap_or_else(|| config.llm_model().to_string());
+        (model, config.limits.max_request_body_bytes)
+    };
+
+    let content_length = headers
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+
+    match content_length {
+        Some(length) if length <= max_buffered_bytes => {
+            match convert_buffered(&state, body, &model, max_buffered_bytes).await {
+                Ok(jsonl) => jsonl_response(Body::from(jsonl)),
+                Err(e) => create_error_response(&e),
+            }
+        }
+        _ => jsonl_response(convert_streaming(state, body, model)),
+    }
+}
+
+///
+/// Read the whole batch output file and convert it.
+///
+/// # Arguments
+///  * `state` - shared application state
+///  * `body` - Anthropic batch output file
+///  * `model` - model reported in the converted responses
+///  * `limit` - most bytes read from the body
+///
+/// # Returns
+///  * Converted file as JSONL
+///  * `ProxyError` if the body cannot be read or is not UTF-8
+async fn convert_buffered(
+    state: &AppState,
+    body: Body,
+    model: &str,
+    limit: usize,
+) -> Result<Vec<u8>> {
+    let bytes = axum::body::to_bytes(body, limit)
+        .await
+        .map_err(|e| ProxyError::http_with_source("Failed to read batch output file", e))?;
+    let jsonl = std::str::from_utf8(&bytes)
+        .map_err(|e| ProxyError::Conversion(format!("Batch output file is not UTF-8: {}", e)))?;
+
+    let responses = parse_batch_jsonl(jsonl)?;
+    let mut out = Vec::new();
+    for response in state.anthropic_to_openai.convert_batch(responses.into_iter(), model) {
+        out.extend(jsonl_line(&response)?);
+    }
+    Ok(out)
+}
+
+///
+/// Convert the batch output file line by line as it arrives.
+///
+/// # Arguments
+///  * `state` - shared application state
+///  * `body` - Anthropic batch output file
+///  * `model` - model reported in the converted responses
+///
+/// # Returns
+///  * Response body yielding one converted line at a time
+fn convert_streaming(state: Arc<AppState>, body: Body, model: String) -> Body {
+    let (lines, rx) = mpsc::channel::<std::io::Result<Vec<u8>>>(LINE_CHANNEL_BUFFER);
+
+    tokio::spawn(async move {
+        let reader = StreamReader::new(body.into_data_stream().map_err(std::io::Error::other));
+        let converted = state.anthropic_to_openai.convert_batch_streaming(reader, &model);
+        tokio::pin!(converted);
+
+        while let Some(response) = converted.next().await {
+            let line = response.and_then(|response| jsonl_line(&response));
+            if let Err(e) = &line {
+                tracing::warn!("Stopping batch output conversion: {}", e);
+            }
+            let failed = line.is_err();
+            if lines.send(line.map_err(std::io::Error::other)).await.is_err() || failed {
+                break;
+            }
+        }
+    });
+
+    Body::from_stream(ReceiverStream::new(rx))
+}
+
+///
+/// Serialise a converted response as one JSONL line.
+///
+/// # Arguments
+///  * `response` - converted OpenAI response
+///
+/// # Returns
+///  * JSON bytes followed by a newline
+fn jsonl_line(response: &OpenAiResponse) -> Result<Vec<u8>> {
+    let mut line = serde_json::to_vec(response)?;
+    line.push(b'\n');
+    Ok(line)
+}
+
+///
+/// Build a `200 OK` response carrying a JSONL body.
+///
+/// # Arguments
+///  * `body` - converted file
+///
+/// # Returns
+///  * Response with the JSONL content type
+fn jsonl_response(body: Body) -> Response {
+    (StatusCode::OK, [(CONTENT_TYPE, JSONL_CONTENT_TYPE)], body).into_response()
+}
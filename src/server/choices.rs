@@ -0,0 +1,405 @@
+//!
+//! Multiple completions (`n > 1`) of one chat completion request.
+//!
+//! Anthropic models return one completion per request, so a request for `n`
+//! completions is fanned out to `n` concurrent upstream requests. Their
+//! responses are merged into one response with `n` choices and summed token
+//! usage; streams are multiplexed into one SSE stream whose chunks carry the
+//! `index` of their choice.
+//!
+//! A failed upstream request fails the whole request, unless
+//! `limits.n_partial_allowed` is set: the completions that succeeded are then
+//! returned, numbered from 0.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::future::Future;
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use axum::response::Response;
+use axum::response::sse::Event;
+use tokio::sync::mpsc;
+use tokio_stream::StreamExt;
+use tokio_stream::wrappers::ReceiverStream;
+
+use super::{
+    AppState, ProviderStream, ResponseOptions, ResponseTokens, STREAMING_CHANNEL_BUFFER,
+    handle_collected_streaming_response, in_request_scope, json_response, log_anthropic_response,
+    log_openai_response, merge_choice_streams, read_anthropic_response, record_response_usage,
+    request_vertex, run_response_hooks, split_sse_lines, sse_response, stream_accumulator,
+};
+use crate::converter::anthropic_to_openai::OpenAiResponse;
+use crate::converter::openai_to_anthropic::AnthropicRequest;
+use crate::error::{ProxyError, Result};
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+///
+/// Answer a non-streaming request for several completions with one JSON response.
+///
+/// With forced streaming (`request.stream` set for a client that asked for
+/// JSON) the streamed choices are collected instead; their usage is already
+/// recorded by the streams.
+///
+/// # Arguments
+///  * `state` - application state
+///  * `request` - converted request, sent once per completion
+///  * `requested_model` - model requested by the client
+///  * `n` - number of completions
+///  * `options` - model name, `functions` API and `seed` of the request
+///
+/// # Returns
+///  * OpenAI format JSON response with one choice per completion
+///  * `ProxyError` of the first failed upstream request
+pub(super) async fn completion_response(
+    state: &Arc<AppState>,
+    request: AnthropicRequest,
+    requested_model: Option<String>,
+    n: u32,
+    options: ResponseOptions,
+) -> Result<Response> {
+    let streamed = request.stream;
+    let mut openai_response =
+        complete_choices(state, request, requested_model, n, &options).await?;
+    if streamed {
+        return Ok(json_response(StatusCode::OK, openai_response));
+    }
+
+    run_response_hooks(state, &mut openai_response).await?;
+    if options.legacy_functions {
+        state.anthropic_to_openai.apply_legacy_function_call(&mut openai_response);
+    }
+    log_openai_response(state, &openai_response);
+    record_response_usage(state, &openai_response);
+
+    let tokens = ResponseTokens::from(&openai_response.usage);
+    let mut response = json_response(StatusCode::OK, openai_response);
+    response.extensions_mut().insert(tokens);
+    Ok(response)
+}
+
+///
+/// Run the upstream requests of several completions and merge their choices.
+///
+/// Response hooks, legacy `function_call` conversion and usage recording are
+/// left to the caller, which applies them to the merged response once.
+///
+/// # Arguments
+///  * `state` - application state
+///  * `request` - converted request, sent once per completion
+///  * `requested_model` - model requested by the client
+///  * `n` - number of completions
+///  * `options` - model name, `functions` API and `seed` of the request
+///
+/// # Returns
+///  * Response with one choice per completion and the summed usage
+///  * `ProxyError` of the first failed upstream request
+pub(super) async fn complete_choices(
+    state: &Arc<AppState>,
+    request: AnthropicRequest,
+    requested_model: Option<String>,
+    n: u32,
+    options: &ResponseOptions,
+) -> Result<OpenAiResponse> {
+    let partial_allowed = state.config.read().await.limits.n_partial_allowed;
+    let request = Arc::new(request);
+    let responses = fan_out(n, partial_allowed, |_| {
+        complete_choice(state.clone(), request.clone(), requested_model.clone(), options.clone())
+    })
+    .await?;
+
+    merge_choices(responses)
+        .ok_or_else(|| ProxyError::Http("No completion was returned".to_string(), None))
+}
+
+///
+/// Stream several completions as the choices of one SSE stream.
+///
+/// The upstream requests are all started before the response begins, so a
+/// failing one is still answered with an error status.
+///
+/// # Arguments
+///  * `state` - application state
+///  * `request` - converted request, sent once per completion
+///  * `requested_model` - model requested by the client
+///  * `n` - number of completions
+///  * `options` - model name, `functions` API and `seed` of the request
+///  * `include_usage` - whether to send a final usage chunk
+///
+/// # Returns
+///  * Server-sent events response multiplexing the streamed choices
+///  * `ProxyError` of the first failed upstream request
+pub(super) async fn stream_choices(
+    state: Arc<AppState>,
+    request: AnthropicRequest,
+    requested_model: Option<String>,
+    n: u32,
+    options: ResponseOptions,
+    include_usage: bool,
+) -> Result<Response> {
+    let partial_allowed = state.config.read().await.limits.n_partial_allowed;
+    let request = Arc::new(request);
+    let upstreams = fan_out(n, partial_allowed, |_| {
+        let state = state.clone();
+        let request = request.clone();
+        let requested_model = requested_model.clone();
+        async move { request_vertex(&state, &request, requested_model.as_deref()).await }
+    })
+    .await?;
+
+    let mut streams = Vec::with_capacity(upstreams.len());
+    for upstream in upstreams {
+        let stream_state = options.stream_state(include_usage);
+        let sse = handle_collected_streaming_response(
+            upstream,
+            state.clone(),
+            options.model.clone(),
+            stream_state,
+        )
+        .await?;
+        streams.push(sse_data_stream(sse));
+    }
+
+    let mut merged = merge_choice_streams(streams);
+    let (tx, rx) = mpsc::channel(STREAMING_CHANNEL_BUFFER);
+    tokio::spawn(async move {
+        while let Some(data) = merged.next().await {
+            if tx.send(Ok(Event::default().data(data))).await.is_err() {
+                return;
+            }
+        }
+    });
+    Ok(sse_response(rx))
+}
+
+///
+/// Run one completion of a request for several.
+///
+/// # Arguments
+///  * `state` - application state
+///  * `request` - converted request
+///  * `requested_model` - model requested by the client
+///  * `options` - model name, `functions` API and `seed` of the request
+///
+/// # Returns
+///  * Converted response with a single choice
+///  * `ProxyError` if the upstream request or its stream fails
+async fn complete_choice(
+    state: Arc<AppState>,
+    request: Arc<AnthropicRequest>,
+    requested_model: Option<String>,
+    options: ResponseOptions,
+) -> Result<OpenAiResponse> {
+    let response = request_vertex(&state, &request, requested_model.as_deref()).await?;
+
+    if request.stream {
+        let stream_state = options.stream_state(true);
+        let sse =
+            handle_collected_streaming_response(response, state, options.model, stream_state)
+                .await?;
+        return stream_accumulator::collect_sse_body(sse.into_body())
+            .await?
+            .map_err(|error| ProxyError::Http(format!("Upstream stream failed: {}", error), None));
+    }
+
+    let anthropic_response = read_anthropic_response(&state, response).await?;
+    log_anthropic_response(&state, &anthropic_response);
+    let mut openai_response = state.anthropic_to_openai.convert(anthropic_response, &options.model);
+    state.anthropic_to_openai.apply_seed(&mut openai_response, options.seed);
+    state.response_normalizer.normalize(&mut openai_response);
+    Ok(openai_response)
+}
+
+///
+/// Run `n` requests concurrently.
+///
+/// Without `partial_allowed` the first error is returned at once and the
+/// remaining requests are aborted.
+///
+/// # Arguments
+///  * `n` - number of requests
+///  * `partial_allowed` - keep the successful results when some requests fail
+///  * `request` - builds the request with the given index
+///
+/// # Returns
+///  * Results of the successful requests, in index order
+///  * `ProxyError` of the first failed request, or of the last one when all failed
+async fn fan_out<T, F, Fut>(n: u32, partial_allowed: bool, request: F) -> Result<Vec<T>>
+where
+    T: Send + 'static,
+    F: Fn(usize) -> Fut,
+    Fut: Future<Output = Result<T>> + Send + 'static,
+{
+    let mut tasks = tokio::task::JoinSet::new();
+    for index in 0..n as usize {
+        let future = in_request_scope(request(index));
+        tasks.spawn(async move { (index, future.await) });
+    }
+
+    let mut results = Vec::with_capacity(n as usize);
+    let mut last_error = None;
+    while let Some(joined) = tasks.join_next().await {
+        let (index, result) = joined
+            .map_err(|e| ProxyError::http_with_source("Completion request task failed", e))?;
+        match result {
+            Ok(value) => results.push((index, value)),
+            Err(e) if partial_allowed => {
+                tracing::warn!(choice = index, error = %e, "Completion request failed");
+                last_error = Some(e);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    if let (true, Some(e)) = (results.is_empty(), last_error) {
+        return Err(e);
+    }
+    results.sort_by_key(|(index, _)| *index);
+    Ok(results.into_iter().map(|(_, value)| value).collect())
+}
+
+///
+/// Merge single-choice responses into one response.
+///
+/// # Arguments
+///  * `responses` - responses in choice order
+///
+/// # Returns
+///  * The first response with the choices of all, numbered from 0, and the
+///    summed usage; `None` without responses
+fn merge_choices(responses: Vec<OpenAiResponse>) -> Option<OpenAiResponse> {
+    let mut responses = responses.into_iter();
+    let mut merged = responses.next()?;
+    for response in responses {
+        merged.usage.prompt_tokens += response.usage.prompt_tokens;
+        merged.usage.completion_tokens += response.usage.completion_tokens;
+        merged.usage.total_tokens += response.usage.total_tokens;
+        merged.choices.extend(response.choices);
+    }
+    for (index, choice) in merged.choices.iter_mut().enumerate() {
+        choice.index = index as u32;
+    }
+    Some(merged)
+}
+
+///
+/// Stream the `data:` payloads of an SSE response.
+///
+/// # Arguments
+///  * `response` - SSE response of `handle_collected_streaming_response`
+///
+/// # Returns
+///  * Data payloads; comments and `retry:` lines are skipped
+pub(super) fn sse_data_stream(response: Response) -> ProviderStream {
+    let (tx, rx) = mpsc::channel(STREAMING_CHANNEL_BUFFER);
+    tokio::spawn(async move {
+        let mut body = response.into_body().into_data_stream();
+        let mut buffer = Vec::new();
+        while let Some(Ok(bytes)) = body.next().await {
+            buffer.extend_from_slice(&bytes);
+            let lines = match split_sse_lines(&mut buffer) {
+                Ok(lines) => lines,
+                Err(e) => {
+                    tracing::error!(error = %e, "Choice stream processing error");
+                    return;
+                }
+            };
+            for data in lines.iter().filter_map(|line| line.strip_prefix("data:")) {
+                if tx.send(data.trim_start().to_string()).await.is_err() {
+                    return;
+                }
+            }
+        }
+    });
+    Box::pin(ReceiverStream::new(rx))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converter::anthropic_to_openai::{OpenAiChoice, OpenAiResponseMessage, OpenAiUsage};
+
+    fn response(text: &str, prompt_tokens: u32, completion_tokens: u32) -> OpenAiResponse {
+        OpenAiResponse {
+            id: format!("chatcmpl-{}", text),
+            object: "chat.completion".to_string(),
+            created: 0,
+            model: "claude".to_string(),
+            choices: vec![OpenAiChoice {
+                index: 0,
+                message: OpenAiResponseMessage {
+                    role: "assistant".to_string(),
+                    content: Some(text.to_string()),
+                    tool_calls: None,
+                    function_call: None,
+                    reasoning_content: None,
+                },
+                finish_reason: "stop".to_string(),
+            }],
+            usage: OpenAiUsage {
+                prompt_tokens,
+                completion_tokens,
+                total_tokens: prompt_tokens + completion_tokens,
+            },
+            system_fingerprint: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_choices_numbers_choices_and_sums_usage() {
+        let merged =
+            merge_choices(vec![response("a", 10, 2), response("b", 10, 3), response("c", 10, 4)])
+                .unwrap();
+
+        assert_eq!(merged.id, "chatcmpl-a");
+        let texts: Vec<_> =
+            merged.choices.iter().map(|choice| choice.message.content.as_deref()).collect();
+        assert_eq!(texts, [Some("a"), Some("b"), Some("c")]);
+        let indices: Vec<_> = merged.choices.iter().map(|choice| choice.index).collect();
+        assert_eq!(indices, [0, 1, 2]);
+        assert_eq!(merged.usage.prompt_tokens, 30);
+        assert_eq!(merged.usage.completion_tokens, 9);
+        assert_eq!(merged.usage.total_tokens, 39);
+        assert!(merge_choices(Vec::new()).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fan_out_fails_fast_unless_partial_allowed() {
+        let request = |index: usize| async move {
+            if index == 1 {
+                Err(ProxyError::Http("upstream failed".to_string(), None))
+            } else {
+                Ok(index)
+            }
+        };
+
+        let error = fan_out(3, false, request).await.unwrap_err();
+        assert!(error.to_string().contains("upstream failed"));
+        assert_eq!(fan_out(3, true, request).await.unwrap(), [0, 2]);
+        assert_eq!(fan_out(3, false, |index| async move { Ok(index) }).await.unwrap(), [0, 1, 2]);
+
+        let all_failed = fan_out(2, true, |_| async {
+            Err::<usize, _>(ProxyError::Http("down".to_string(), None))
+        });
+        assert!(all_failed.await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sse_data_stream_keeps_characters_split_across_chunks() {
+        let text = "data: {\"content\":\"naïve\"}\n\n: keep-alive\ndata: [DONE]\n\n".as_bytes();
+        // Split inside the two bytes of 'ï'
+        let split = text.iter().position(|&byte| byte > 0x7f).unwrap() + 1;
+        let chunks =
+            vec![Ok::<_, std::io::Error>(text[..split].to_vec()), Ok(text[split..].to_vec())];
+        let response = Response::new(axum::body::Body::from_stream(tokio_stream::iter(chunks)));
+
+        let data: Vec<String> = sse_data_stream(response).collect().await;
+        assert_eq!(data, ["{\"content\":\"naïve\"}", "[DONE]"]);
+    }
+}
@@ -0,0 +1,328 @@
+//!
+//! Circuit breaker for upstream Vertex AI requests.
+//!
+//! While Vertex AI keeps failing (5xx responses, connection errors, timeouts)
+//! every request would still run its full set of retries against it. The
+//! breaker tracks request outcomes over a rolling window and, once the failure
+//! rate reaches `server.circuit_breaker_threshold`, opens: requests fail fast
+//! with `503` without reaching the upstream. After
+//! `server.circuit_breaker_cooldown_secs` a single probe request is let
+//! through (half-open); its success closes the circuit, its failure opens it
+//! again.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::ServerConfig;
+use crate::error::{ProxyError, Result};
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// State of the circuit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /** requests pass, outcomes are tracked */
+    Closed,
+    /** requests are rejected until the cooldown has passed */
+    Open,
+    /** one probe request is let through to test the upstream */
+    HalfOpen,
+}
+
+///
+/// Circuit breaker shared by all upstream requests.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    /** whether the breaker is active; when off every request passes */
+    enabled: bool,
+    /** failure rate in the window that opens the circuit */
+    threshold: f64,
+    /** length of the rolling window */
+    window: Duration,
+    /** time the circuit stays open before a probe is let through */
+    cooldown: Duration,
+    /** outcomes needed in the window before the failure rate is acted on */
+    min_requests: usize,
+    /** mutable state */
+    inner: Mutex<CircuitInner>,
+}
+
+///
+/// Mutable state of a circuit breaker.
+#[derive(Debug)]
+struct CircuitInner {
+    /** current state */
+    state: CircuitState,
+    /** outcomes in the rolling window, `true` for failures, oldest first */
+    outcomes: VecDeque<(Instant, bool)>,
+    /** when the circuit last opened */
+    opened_at: Option<Instant>,
+    /** when the current half-open probe was let through */
+    probe_started_at: Option<Instant>,
+}
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+impl CircuitState {
+    ///
+    /// Name reported in the health endpoint.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CircuitState::Closed => "closed",
+            CircuitState::Open => "open",
+            CircuitState::HalfOpen => "half_open",
+        }
+    }
+}
+
+impl CircuitBreaker {
+    ///
+    /// Create a closed circuit breaker.
+    ///
+    /// # Arguments
+    ///  * `config` - server configuration with the `circuit_breaker_*` settings
+    ///
+    /// # Returns
+    ///  * Circuit breaker in the closed state
+    pub fn new(config: &ServerConfig) -> Self {
+        Self {
+            enabled: config.circuit_breaker_enabled,
+            threshold: config.circuit_breaker_threshold,
+            window: Duration::from_secs(config.circuit_breaker_window_secs),
+            cooldown: Duration::from_secs(config.circuit_breaker_cooldown_secs),
+            min_requests: config.circuit_breaker_min_requests as usize,
+            inner: Mutex::new(CircuitInner {
+                state: CircuitState::Closed,
+                outcomes: VecDeque::new(),
+                opened_at: None,
+                probe_started_at: None,
+            }),
+        }
+    }
+
+    ///
+    /// Current state of the circuit.
+    pub fn state(&self) -> CircuitState {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner()).state
+    }
+
+    ///
+    /// Ask to send a request upstream.
+    ///
+    /// An open circuit whose cooldown has passed turns half-open and admits
+    /// this request as the probe. A probe that never reports back (e.g. its
+    /// client disconnected) is replaced after another cooldown.
+    ///
+    /// # Returns
+    ///  * `Ok(())` when the request may be sent
+    ///  * `ProxyError::Http` ("Circuit open", mapped to `503`) otherwise
+    pub fn try_acquire(&self) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        match inner.state {
+            CircuitState::Closed => Ok(()),
+            CircuitState::Open => {
+                let opened_at = inner.opened_at.unwrap_or(now);
+                let remaining = self.cooldown.saturating_sub(now.duration_since(opened_at));
+                if !remaining.is_zero() {
+                    return Err(circuit_open_error(remaining));
+                }
+                tracing::info!("Circuit breaker half-open, sending a probe request to Vertex AI");
+                inner.state = CircuitState::HalfOpen;
+                inner.probe_started_at = Some(now);
+                Ok(())
+            }
+            CircuitState::HalfOpen => {
+                let probe_stale = inner
+                    .probe_started_at
+                    .is_none_or(|started| now.duration_since(started) >= self.cooldown);
+                if !probe_stale {
+                    return Err(circuit_open_error(self.cooldown));
+                }
+                inner.probe_started_at = Some(now);
+                Ok(())
+            }
+        }
+    }
+
+    ///
+    /// Record the outcome of a request admitted by [`try_acquire`](Self::try_acquire).
+    ///
+    /// # Arguments
+    ///  * `result` - final result of the request, after retries
+    pub fn record<T>(&self, result: &Result<T>) {
+        self.record_outcome(result.as_ref().is_err_and(is_upstream_failure));
+    }
+
+    ///
+    /// Record whether a request admitted by [`try_acquire`](Self::try_acquire)
+    /// failed upstream, for callers that relay upstream errors without a
+    /// `ProxyError`.
+    ///
+    /// # Arguments
+    ///  * `failed` - whether the upstream failed (unreachable, timeout or `5xx`)
+    pub fn record_outcome(&self, failed: bool) {
+        if !self.enabled {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        match inner.state {
+            CircuitState::HalfOpen if failed => {
+                tracing::warn!("Circuit breaker probe failed, opening the circuit again");
+                inner.open(now);
+            }
+            CircuitState::HalfOpen => {
+                tracing::info!("Circuit breaker probe succeeded, closing the circuit");
+                inner.state = CircuitState::Closed;
+                inner.outcomes.clear();
+                inner.opened_at = None;
+                inner.probe_started_at = None;
+            }
+            CircuitState::Closed => {
+                inner.outcomes.push_back((now, failed));
+                while inner
+                    .outcomes
+                    .front()
+                    .is_some_and(|(at, _)| now.duration_since(*at) > self.window)
+                {
+                    inner.outcomes.pop_front();
+                }
+
+                let total = inner.outcomes.len();
+                let failures = inner.outcomes.iter().filter(|(_, failed)| *failed).count();
+                if total >= self.min_requests.max(1)
+                    && failures as f64 / total as f64 >= self.threshold
+                {
+                    tracing::warn!(
+                        "Circuit breaker opened: {}/{} Vertex AI requests failed in the last {}s",
+                        failures,
+                        total,
+                        self.window.as_secs()
+                    );
+                    inner.open(now);
+                }
+            }
+            // Requests started before the circuit opened
+            CircuitState::Open => {}
+        }
+    }
+}
+
+impl CircuitInner {
+    ///
+    /// Open the circuit, starting the cooldown.
+    fn open(&mut self, now: Instant) {
+        self.state = CircuitState::Open;
+        self.opened_at = Some(now);
+        self.probe_started_at = None;
+        self.outcomes.clear();
+    }
+}
+
+///
+/// Whether an error means the upstream itself is failing.
+///
+/// Server errors, connection errors and timeouts count; client errors and
+/// quota limits show a responsive upstream and do not.
+pub fn is_upstream_failure(error: &ProxyError) -> bool {
+    match error {
+        ProxyError::Http(msg, _) => msg.contains("temporarily unavailable"),
+        ProxyError::Request(_) | ProxyError::Timeout { .. } => true,
+        _ => false,
+    }
+}
+
+///
+/// Error returned while the circuit is open.
+fn circuit_open_error(retry_in: Duration) -> ProxyError {
+    ProxyError::Http(
+        format!(
+            "Circuit open: Vertex AI is temporarily unavailable, retry in {}s",
+            retry_in.as_secs().max(1)
+        ),
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker(cooldown_secs: u64) -> CircuitBreaker {
+        CircuitBreaker::new(&ServerConfig {
+            circuit_breaker_threshold: 0.5,
+            circuit_breaker_window_secs: 60,
+            circuit_breaker_cooldown_secs: cooldown_secs,
+            circuit_breaker_min_requests: 4,
+            ..Default::default()
+        })
+    }
+
+    fn unavailable() -> Result<()> {
+        Err(ProxyError::Http("Vertex AI service is temporarily unavailable.".to_string(), None))
+    }
+
+    #[test]
+    fn test_opens_at_failure_threshold() {
+        let breaker = breaker(30);
+        breaker.record(&Ok(()));
+        breaker.record(&unavailable());
+        breaker.record(&Err::<(), _>(ProxyError::Auth("denied".to_string())));
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        breaker.record(&unavailable());
+        assert_eq!(breaker.state(), CircuitState::Open);
+        let error = breaker.try_acquire().unwrap_err();
+        assert!(error.to_string().contains("Circuit open"));
+        let response = crate::server::create_error_response(&error);
+        assert_eq!(response.status(), axum::http::StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn test_half_open_probe() {
+        let breaker = breaker(0);
+        for _ in 0..4 {
+            breaker.record(&unavailable());
+        }
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        // Cooldown over: one probe, which fails and opens the circuit again
+        breaker.try_acquire().unwrap();
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        breaker.record(&unavailable());
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        breaker.try_acquire().unwrap();
+        breaker.record(&Ok(()));
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        breaker.try_acquire().unwrap();
+    }
+
+    #[test]
+    fn test_disabled_never_opens() {
+        let breaker = CircuitBreaker::new(&ServerConfig {
+            circuit_breaker_enabled: false,
+            ..Default::default()
+        });
+        for _ in 0..100 {
+            breaker.record(&unavailable());
+        }
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.try_acquire().is_ok());
+    }
+}
@@ -0,0 +1,271 @@
+//!
+//! Upstream connection pool metrics.
+//!
+//! `reqwest` does not expose its connection pool, so pool usage is estimated
+//! from the outgoing requests themselves, per upstream host:
+//!
+//! * a request is *active* from the moment it is sent until the upstream
+//!   response headers arrive;
+//! * the pool grows whenever more requests are active at once than ever
+//!   before, and such a request is counted as opening a new connection;
+//!   every other request is counted as reusing an idle pooled connection;
+//! * upstreams that report `X-Upstream-Connect-Time` (some Vertex AI
+//!   instances do) only send it on freshly opened connections, so a request
+//!   carrying it is always counted as a new connection.
+//!
+//! The time until response headers arrive is recorded in a fixed-bucket
+//! histogram, from which `connection_wait_ms_p99` is read. All hot-path
+//! updates are lock-free atomic operations.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::Response;
+use dashmap::DashMap;
+use serde_json::{Value, json};
+
+use super::{AppState, json_response};
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// Connection statistics for all upstream hosts.
+#[derive(Debug, Default)]
+pub struct ConnectionPoolMetrics {
+    /** statistics keyed by upstream host */
+    hosts: DashMap<String, Arc<ConnectionStats>>,
+}
+
+///
+/// Connection statistics for one upstream host.
+#[derive(Debug, Default)]
+pub struct ConnectionStats {
+    /** requests waiting for upstream response headers */
+    pub active_connections: AtomicU64,
+    /** estimated pool size (highest number of concurrently active requests) */
+    pub pool_size: AtomicU64,
+    /** requests that opened a new connection */
+    pub total_connections_created: AtomicU64,
+    /** requests that reused a pooled connection */
+    pub total_connections_reused: AtomicU64,
+    /** wait-time histogram, one counter per entry of `WAIT_BUCKETS_MS` plus overflow */
+    wait_buckets: [AtomicU64; WAIT_BUCKETS_MS.len() + 1],
+}
+
+///
+/// Request in flight to an upstream host.
+///
+/// Dropping the guard without calling [`finish`](Self::finish) (e.g. when the
+/// request fails) only releases the active slot.
+pub struct InFlightRequest {
+    /** statistics of the request's host */
+    stats: Arc<ConnectionStats>,
+    /** whether the pool had to grow for this request */
+    grew_pool: bool,
+    /** when the request was sent */
+    started: Instant,
+}
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** Response header some upstreams set when a new connection was opened */
+pub const UPSTREAM_CONNECT_TIME_HEADER: &str = "X-Upstream-Connect-Time";
+
+/** Upper bounds of the wait-time histogram buckets, in milliseconds */
+const WAIT_BUCKETS_MS: [u64; 14] =
+    [1, 2, 5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000, 10000, 30000];
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+impl ConnectionPoolMetrics {
+    ///
+    /// Register a request about to be sent.
+    ///
+    /// # Arguments
+    ///  * `url` - request URL; statistics are kept per host
+    ///
+    /// # Returns
+    ///  * Guard to finish once the response headers arrive
+    pub fn begin(&self, url: &str) -> InFlightRequest {
+        let stats = self.hosts.entry(host_of(url)).or_default().clone();
+        let active = stats.active_connections.fetch_add(1, Ordering::Relaxed) + 1;
+        let grew_pool = stats.pool_size.fetch_max(active, Ordering::Relaxed) < active;
+
+        InFlightRequest { stats, grew_pool, started: Instant::now() }
+    }
+
+    ///
+    /// Snapshot the statistics of all hosts as JSON.
+    ///
+    /// # Returns
+    ///  * Object keyed by upstream host
+    pub fn to_json(&self) -> Value {
+        let hosts: serde_json::Map<String, Value> = self
+            .hosts
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().to_json()))
+            .collect();
+        Value::Object(hosts)
+    }
+}
+
+impl ConnectionStats {
+    ///
+    /// Pooled connections not currently serving a request.
+    pub fn idle_connections(&self) -> u64 {
+        let pool_size = self.pool_size.load(Ordering::Relaxed);
+        pool_size.saturating_sub(self.active_connections.load(Ordering::Relaxed))
+    }
+
+    ///
+    /// 99th percentile of the time until response headers arrived.
+    ///
+    /// # Returns
+    ///  * Upper bound of the bucket holding the 99th percentile, in milliseconds
+    ///    (0 before any request finished)
+    pub fn connection_wait_ms_p99(&self) -> u64 {
+        let counts: Vec<u64> =
+            self.wait_buckets.iter().map(|bucket| bucket.load(Ordering::Relaxed)).collect();
+        let total: u64 = counts.iter().sum();
+        if total == 0 {
+            return 0;
+        }
+
+        let target = total.saturating_mul(99).div_ceil(100);
+        let mut seen = 0;
+        for (index, count) in counts.iter().enumerate() {
+            seen += count;
+            if seen >= target {
+                return WAIT_BUCKETS_MS.get(index).copied().unwrap_or(u64::MAX);
+            }
+        }
+        u64::MAX
+    }
+
+    ///
+    /// Record the wait time of a finished request.
+    fn record_wait(&self, wait: Duration) {
+        let wait_ms = u64::try_from(wait.as_millis()).unwrap_or(u64::MAX);
+        let index = WAIT_BUCKETS_MS.iter().position(|&bound| wait_ms <= bound);
+        self.wait_buckets[index.unwrap_or(WAIT_BUCKETS_MS.len())].fetch_add(1, Ordering::Relaxed);
+    }
+
+    ///
+    /// Snapshot the statistics as JSON.
+    fn to_json(&self) -> Value {
+        json!({
+            "active_connections": self.active_connections.load(Ordering::Relaxed),
+            "idle_connections": self.idle_connections(),
+            "total_connections_created": self.total_connections_created.load(Ordering::Relaxed),
+            "total_connections_reused": self.total_connections_reused.load(Ordering::Relaxed),
+            "connection_wait_ms_p99": self.connection_wait_ms_p99()
+        })
+    }
+}
+
+impl InFlightRequest {
+    ///
+    /// Record a request whose response headers arrived.
+    ///
+    /// # Arguments
+    ///  * `headers` - upstream response headers
+    pub fn finish(self, headers: &HeaderMap) {
+        self.stats.record_wait(self.started.elapsed());
+        if self.grew_pool || headers.contains_key(UPSTREAM_CONNECT_TIME_HEADER) {
+            self.stats.total_connections_created.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.stats.total_connections_reused.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Drop for InFlightRequest {
+    fn drop(&mut self) {
+        self.stats.active_connections.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+///
+/// Extract the host (with port, if any) of a URL.
+fn host_of(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| {
+            let host = url.host_str()?.to_string();
+            Some(match url.port() {
+                Some(port) => format!("{}:{}", host, port),
+                None => host,
+            })
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+///
+/// Upstream connection pool statistics endpoint.
+///
+/// # Arguments
+///  * `state` - application state
+///
+/// # Returns
+///  * JSON object with per-host connection statistics
+pub async fn connection_stats(State(state): State<Arc<AppState>>) -> Response {
+    json_response(StatusCode::OK, json!({ "hosts": state.metrics.connection_pool.to_json() }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_growth_and_reuse() {
+        let metrics = ConnectionPoolMetrics::default();
+        let url = "https://us-east5-aiplatform.googleapis.com/v1/projects/p";
+
+        let first = metrics.begin(url);
+        let second = metrics.begin(url);
+        first.finish(&HeaderMap::new());
+        second.finish(&HeaderMap::new());
+
+        // Pool holds two connections now, so a third sequential request reuses one
+        metrics.begin(url).finish(&HeaderMap::new());
+
+        let mut fresh = HeaderMap::new();
+        fresh.insert(UPSTREAM_CONNECT_TIME_HEADER, "12".parse().unwrap());
+        metrics.begin(url).finish(&fresh);
+
+        // A failed request only releases its slot
+        drop(metrics.begin(url));
+
+        let stats = metrics.to_json();
+        let host = &stats["us-east5-aiplatform.googleapis.com"];
+        assert_eq!(host["active_connections"], 0);
+        assert_eq!(host["idle_connections"], 2);
+        assert_eq!(host["total_connections_created"], 3);
+        assert_eq!(host["total_connections_reused"], 1);
+    }
+
+    #[test]
+    fn test_connection_wait_p99() {
+        let stats = ConnectionStats::default();
+        assert_eq!(stats.connection_wait_ms_p99(), 0);
+
+        for _ in 0..99 {
+            stats.record_wait(Duration::from_millis(3));
+        }
+        stats.record_wait(Duration::from_millis(800));
+        assert_eq!(stats.connection_wait_ms_p99(), 5);
+
+        stats.record_wait(Duration::from_secs(60));
+        assert_eq!(stats.connection_wait_ms_p99(), 1000);
+    }
+}
@@ -0,0 +1,80 @@
+//!
+//! CORS policy of the HTTP API.
+//!
+//! Builds the `CorsLayer` from the `[cors]` section. Without configured
+//! origins (or with `"*"`) the API stays open to any origin, as in earlier
+//! releases; otherwise only the listed origins get CORS headers, so browsers
+//! block responses to pages served from anywhere else.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::time::Duration;
+
+use axum::http::{HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer};
+
+use crate::config::CorsConfig;
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+///
+/// CORS layer of the API routes.
+///
+/// Empty method and header lists allow what the preflight request asks for.
+/// Entries that are not valid origins, methods or header names are skipped
+/// (configuration validation reports them).
+///
+/// # Arguments
+///  * `config` - CORS section
+///
+/// # Returns
+///  * `CorsLayer::permissive()` when any origin is allowed, a restricted layer otherwise
+pub fn cors_layer(config: &CorsConfig) -> CorsLayer {
+    if allows_any_origin(config) {
+        return CorsLayer::permissive();
+    }
+
+    let origins: Vec<HeaderValue> =
+        config.allowed_origins.iter().filter_map(|origin| origin.parse().ok()).collect();
+    let methods = if config.allowed_methods.is_empty() {
+        AllowMethods::mirror_request()
+    } else {
+        AllowMethods::list(
+            config
+                .allowed_methods
+                .iter()
+                .filter_map(|method| Method::from_bytes(method.trim().as_bytes()).ok()),
+        )
+    };
+    let headers = if config.allowed_headers.is_empty() {
+        AllowHeaders::mirror_request()
+    } else {
+        AllowHeaders::list(
+            config
+                .allowed_headers
+                .iter()
+                .filter_map(|header| HeaderName::from_bytes(header.trim().as_bytes()).ok()),
+        )
+    };
+
+    let mut layer = CorsLayer::new()
+        .allow_origin(AllowOrigin::list(origins))
+        .allow_methods(methods)
+        .allow_headers(headers)
+        .allow_credentials(config.allow_credentials);
+    if config.max_age_secs > 0 {
+        layer = layer.max_age(Duration::from_secs(config.max_age_secs));
+    }
+    layer
+}
+
+///
+/// Whether the configuration leaves the API open to any origin.
+fn allows_any_origin(config: &CorsConfig) -> bool {
+    config.allowed_origins.is_empty() || config.allowed_origins.iter().any(|origin| origin == "*")
+}
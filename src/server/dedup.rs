@@ -0,0 +1,219 @@
+//!
+//! Coalescing of identical concurrent chat completions.
+//!
+//! Under load the same request often arrives several times at once, e.g. a
+//! user hitting refresh. With `streaming.dedup_enabled` the first request for
+//! a key goes upstream; identical requests arriving within
+//! `streaming.dedup_window_ms` subscribe to it and receive a copy of its
+//! upstream response instead of sending their own. The key is the response
+//! cache key of the converted request, so it covers the model, the body and
+//! the Anthropic beta features.
+//!
+//! Only non-streaming requests are coalesced: every streaming client needs
+//! its own SSE stream. When the first request fails, the waiting requests
+//! are sent upstream on their own, so each gets its own error or retry.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+use tokio::sync::broadcast;
+
+use crate::converter::anthropic_to_openai::AnthropicResponse;
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// Upstream response shared with the requests that joined it.
+pub type DedupResult = Arc<AnthropicResponse>;
+
+///
+/// Requests currently going upstream, keyed by dedup key.
+#[derive(Debug, Default)]
+pub struct RequestDeduplicator {
+    /** fan-out of each request that identical requests may still join */
+    in_flight: Arc<DashMap<u64, Arc<broadcast::Sender<DedupResult>>>>,
+}
+
+///
+/// Outcome of registering a request with the deduplicator.
+pub enum Dedup {
+    /// First request with this key; send it upstream and share the response
+    Leader(DedupLeader),
+    /// An identical request is in flight; wait for its response
+    Follower(broadcast::Receiver<DedupResult>),
+}
+
+///
+/// Handle of the request that goes upstream for a key.
+///
+/// Dropping it without [`complete`](Self::complete) releases the waiting
+/// requests without a response.
+pub struct DedupLeader {
+    /** dedup key */
+    key: u64,
+    /** fan-out to the joined requests */
+    sender: Arc<broadcast::Sender<DedupResult>>,
+    /** map the key is removed from when the request finishes */
+    in_flight: Arc<DashMap<u64, Arc<broadcast::Sender<DedupResult>>>>,
+}
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+impl RequestDeduplicator {
+    ///
+    /// Register a request, or join an identical one in flight.
+    ///
+    /// Runs atomically per key, so of two concurrent identical requests
+    /// exactly one goes upstream. The key stops accepting new requests after
+    /// `window`, even while the first request is still running.
+    ///
+    /// # Arguments
+    ///  * `key` - dedup key (see [`dedup_key`](Self::dedup_key))
+    ///  * `window` - time in which identical requests join this one
+    ///
+    /// # Returns
+    ///  * `Follower` when an identical request is in flight, otherwise `Leader`
+    pub fn begin(&self, key: u64, window: Duration) -> Dedup {
+        match self.in_flight.entry(key) {
+            Entry::Occupied(entry) => Dedup::Follower(entry.get().subscribe()),
+            Entry::Vacant(entry) => {
+                let (sender, _) = broadcast::channel(1);
+                let sender = Arc::new(sender);
+                entry.insert(sender.clone());
+
+                let in_flight = self.in_flight.clone();
+                let expiring = Arc::downgrade(&sender);
+                tokio::spawn(async move {
+                    tokio::time::sleep(window).await;
+                    remove_key(&in_flight, key, &expiring);
+                });
+                Dedup::Leader(DedupLeader { key, sender, in_flight: self.in_flight.clone() })
+            }
+        }
+    }
+
+    ///
+    /// Dedup key of a request.
+    ///
+    /// # Arguments
+    ///  * `cache_key` - hex-encoded response cache key of the request
+    ///
+    /// # Returns
+    ///  * First 64 bits of the cache key, or `None` for a malformed key
+    pub fn dedup_key(cache_key: &str) -> Option<u64> {
+        u64::from_str_radix(cache_key.get(..16)?, 16).ok()
+    }
+
+    ///
+    /// Number of keys that identical requests can currently join.
+    pub fn len(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    ///
+    /// Whether no request can currently be joined.
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.in_flight.is_empty()
+    }
+}
+
+impl DedupLeader {
+    ///
+    /// Share the upstream response with the joined requests.
+    ///
+    /// # Arguments
+    ///  * `response` - upstream response of the leading request
+    pub fn complete(self, response: &AnthropicResponse) {
+        // Late arrivals start their own request instead of missing the broadcast
+        remove_key(&self.in_flight, self.key, &Arc::downgrade(&self.sender));
+        let _ = self.sender.send(Arc::new(response.clone()));
+    }
+}
+
+impl Drop for DedupLeader {
+    fn drop(&mut self) {
+        remove_key(&self.in_flight, self.key, &Arc::downgrade(&self.sender));
+    }
+}
+
+///
+/// Remove `key` if it still belongs to `sender`.
+///
+/// A key that expired may have been taken over by a newer request, which
+/// must stay joinable.
+fn remove_key(
+    in_flight: &DashMap<u64, Arc<broadcast::Sender<DedupResult>>>,
+    key: u64,
+    sender: &Weak<broadcast::Sender<DedupResult>>,
+) {
+    in_flight.remove_if(&key, |_, current| std::ptr::eq(Arc::as_ptr(current), sender.as_ptr()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converter::anthropic_to_openai::AnthropicContentBlock;
+
+    fn response(text: &str) -> AnthropicResponse {
+        serde_json::from_value(serde_json::json!({
+            "id": "msg_1", "type": "message", "role": "assistant", "model": "m",
+            "content": [{"type": "text", "text": text}],
+            "stop_reason": "end_turn", "usage": {"input_tokens": 1, "output_tokens": 1}
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_followers_receive_leader_response() {
+        let dedup = RequestDeduplicator::default();
+        let window = Duration::from_secs(5);
+        let Dedup::Leader(leader) = dedup.begin(1, window) else {
+            panic!("first request must lead");
+        };
+        let Dedup::Follower(mut follower) = dedup.begin(1, window) else {
+            panic!("identical request must follow");
+        };
+        assert!(matches!(dedup.begin(2, window), Dedup::Leader(_)));
+
+        leader.complete(&response("shared"));
+        let shared = follower.recv().await.unwrap();
+        assert!(
+            matches!(&shared.content[0], AnthropicContentBlock::Text { text } if text == "shared")
+        );
+        // The key is released once the response is shared
+        assert!(matches!(dedup.begin(1, window), Dedup::Leader(_)));
+    }
+
+    #[tokio::test]
+    async fn test_failed_leader_releases_followers() {
+        let dedup = RequestDeduplicator::default();
+        let leader = dedup.begin(1, Duration::from_secs(5));
+        let Dedup::Follower(mut follower) = dedup.begin(1, Duration::from_secs(5)) else {
+            panic!("identical request must follow");
+        };
+        drop(leader);
+        assert!(follower.recv().await.is_err());
+        assert!(dedup.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_key_expires_after_window() {
+        let dedup = RequestDeduplicator::default();
+        let _leader = dedup.begin(1, Duration::from_millis(10));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(dedup.is_empty());
+        assert!(matches!(dedup.begin(1, Duration::from_millis(10)), Dedup::Leader(_)));
+        assert_eq!(RequestDeduplicator::dedup_key("00000000000000ffabc"), Some(255));
+        assert_eq!(RequestDeduplicator::dedup_key("short"), None);
+    }
+}
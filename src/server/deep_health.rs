@@ -0,0 +1,245 @@
+//!
+//! Deep health check of the Vertex AI upstream.
+//!
+//! `/health` normally only reports in-process metrics, so a proxy whose
+//! credentials expired or whose upstream is down still looks healthy. With
+//! `health.deep_check = true` (and a build with the `deep-health` feature) a
+//! background task sends a one-token request to the default Vertex AI model
+//! every `health.deep_check_interval_secs`. The latest result is reported
+//! under `vertex_ai` in `/health`, which answers `503` while Vertex AI is
+//! unreachable.
+//!
+//! The check uses its own HTTP client with a 5 second timeout, so it neither
+//! waits behind nor holds connections of regular requests.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use reqwest::Client;
+use serde_json::{Value, json};
+
+use super::AppState;
+use crate::converter::openai_to_anthropic::TargetFormat;
+use crate::error::{ProxyError, Result};
+use crate::provider::LlmProviderConfig;
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// Outcome of the last deep health check.
+#[derive(Debug, Clone)]
+pub struct DeepHealthStatus {
+    /** `Ok` when Vertex AI answered, otherwise why it could not be reached */
+    pub result: std::result::Result<(), String>,
+    /** when the check finished */
+    pub checked_at: chrono::DateTime<chrono::Utc>,
+    /** time from sending the check request to its response */
+    pub latency_ms: u64,
+}
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** Timeout of a check request, connection included */
+const DEEP_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+impl DeepHealthStatus {
+    ///
+    /// Whether Vertex AI answered the last check.
+    pub fn is_reachable(&self) -> bool {
+        self.result.is_ok()
+    }
+
+    ///
+    /// Status as reported under `vertex_ai` in `/health`.
+    pub fn to_json(&self) -> Value {
+        let mut json = json!({
+            "reachable": self.is_reachable(),
+            "last_checked": self.checked_at.to_rfc3339(),
+            "latency_ms": self.latency_ms
+        });
+        if let Err(error) = &self.result {
+            json["error"] = Value::String(error.clone());
+        }
+        json
+    }
+}
+
+///
+/// Start the background task running deep health checks.
+///
+/// The task reads `[health]` before every check, so enabling, disabling or
+/// changing the interval takes effect after a configuration reload.
+///
+/// # Arguments
+///  * `state` - shared application state receiving the check results
+#[cfg(feature = "deep-health")]
+pub fn start_deep_health_check(state: &Arc<AppState>) {
+    let client = match create_client() {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!("Deep health check disabled: {}", e);
+            return;
+        }
+    };
+    let state = state.clone();
+    tokio::spawn(async move {
+        loop {
+            let (enabled, interval_secs) = {
+                let config = state.config.read().await;
+                (config.health.deep_check, config.health.deep_check_interval_secs.max(1))
+            };
+            let status = if enabled { check_vertex_ai(&state, &client).await } else { None };
+            if let Some(Err(error)) = status.as_ref().map(|s| &s.result) {
+                tracing::warn!("Deep health check: Vertex AI unreachable: {}", error);
+            }
+            if let Ok(mut last) = state.deep_health.lock() {
+                *last = status;
+            }
+            tokio::time::sleep(Duration::from_secs(interval_secs)).await;
+        }
+    });
+}
+
+///
+/// Deep health checks need a build with the `deep-health` feature.
+#[cfg(not(feature = "deep-health"))]
+pub fn start_deep_health_check(_state: &Arc<AppState>) {}
+
+///
+/// Create the HTTP client of the check requests.
+///
+/// # Returns
+///  * Client with a 5 second timeout
+///  * `ProxyError::Http` if client creation fails
+#[cfg_attr(not(feature = "deep-health"), allow(dead_code))]
+fn create_client() -> Result<Client> {
+    Client::builder()
+        .timeout(DEEP_CHECK_TIMEOUT)
+        .build()
+        .map_err(|e| ProxyError::http_with_source("Failed to create deep health check client", e))
+}
+
+///
+/// Send a one-token request to the default Vertex AI model.
+///
+/// Any successful response counts as reachable, and so does `429`: Vertex AI
+/// answered, only the quota is used up.
+///
+/// # Arguments
+///  * `state` - application state with the configuration and credentials
+///  * `client` - HTTP client of the check requests
+///
+/// # Returns
+///  * Check outcome, or `None` when the LLM provider is not Vertex AI
+#[cfg_attr(not(feature = "deep-health"), allow(dead_code))]
+async fn check_vertex_ai(state: &Arc<AppState>, client: &Client) -> Option<DeepHealthStatus> {
+    let url = {
+        let config = state.config.read().await;
+        let Some(LlmProviderConfig::Vertex(_)) = config.llm_provider.as_ref() else {
+            return None;
+        };
+        config.build_predict_url(false)
+    };
+    let body = json!({
+        "anthropic_version": TargetFormat::Vertex.anthropic_version(),
+        "messages": [{"role": "user", "content": "ping"}],
+        "max_tokens": 1
+    });
+
+    let started = Instant::now();
+    let result = match super::get_authorization_header(state.clone()).await {
+        Ok(authorization) => {
+            let response =
+                client.post(&url).header("Authorization", authorization).json(&body).send().await;
+            match response {
+                Ok(response)
+                    if response.status().is_success()
+                        || response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS =>
+                {
+                    Ok(())
+                }
+                Ok(response) => Err(format!("HTTP {}", response.status())),
+                Err(e) if e.is_timeout() => {
+                    Err(format!("no response within {}s", DEEP_CHECK_TIMEOUT.as_secs()))
+                }
+                Err(e) => Err(crate::error::error_chain(&e)),
+            }
+        }
+        Err(e) => Err(crate::error::error_chain(&e)),
+    };
+    Some(DeepHealthStatus {
+        result,
+        checked_at: chrono::Utc::now(),
+        latency_ms: u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Json;
+    use axum::http::StatusCode;
+    use axum::routing::post;
+
+    use super::*;
+    use crate::config::Config;
+    use crate::provider::{AuthStrategy, VertexProvider};
+
+    /// Application state for a Vertex AI model served at `url`.
+    async fn vertex_state(url: String) -> Arc<AppState> {
+        let vertex = VertexProvider {
+            predict_resource_url: url,
+            display_model: "claude".to_string(),
+            auth: AuthStrategy::BearerToken("token".to_string()),
+            max_output_tokens: None,
+        };
+        let config =
+            Config { llm_provider: Some(LlmProviderConfig::Vertex(vertex)), ..Default::default() };
+        Arc::new(AppState::new(config).await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_check_vertex_ai() {
+        async fn raw_predict(Json(body): Json<Value>) -> (StatusCode, Json<Value>) {
+            assert_eq!(body["max_tokens"], 1);
+            (StatusCode::OK, Json(json!({"type": "message"})))
+        }
+        async fn broken(Json(_): Json<Value>) -> StatusCode {
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+        let upstream = axum::Router::new()
+            .route("/models/ok:rawPredict", post(raw_predict))
+            .route("/models/broken:rawPredict", post(broken));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, upstream).await.unwrap() });
+        let client = create_client().unwrap();
+
+        let state = vertex_state(format!("http://{}/models/ok", addr)).await;
+        let status = check_vertex_ai(&state, &client).await.unwrap();
+        assert!(status.is_reachable());
+        assert_eq!(status.to_json()["reachable"], true);
+        assert!(status.to_json().get("error").is_none());
+
+        let state = vertex_state(format!("http://{}/models/broken", addr)).await;
+        let status = check_vertex_ai(&state, &client).await.unwrap();
+        assert!(!status.is_reachable());
+        assert_eq!(status.to_json()["error"], "HTTP 500 Internal Server Error");
+
+        *state.deep_health.lock().unwrap() = Some(status);
+        let response = super::super::health(axum::extract::State(state)).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["status"], "unavailable");
+        assert_eq!(body["vertex_ai"]["reachable"], false);
+    }
+}
@@ -0,0 +1,117 @@
+//!
+//! OpenAI-compatible embeddings endpoint.
+//!
+//! `POST /v1/embeddings` embeds text with a Vertex AI text embedding model
+//! (`vertex.embedding_model`, default `text-embedding-004`) of the configured
+//! project and location, so retrieval pipelines can use the same proxy as
+//! chat completions.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::Response;
+
+use super::auth_middleware::check_model_access;
+use super::{
+    AUTHORIZATION_HEADER, AppState, create_error_response, get_authorization_header, json_response,
+    upstream_request_error, validate_vertex_response,
+};
+use crate::converter::embedding::{
+    OpenAiEmbeddingRequest, OpenAiEmbeddingResponse, VertexEmbeddingResponse,
+};
+use crate::error::{ProxyError, Result};
+use crate::provider::VertexLoadBalancer;
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+///
+/// Handle `POST /v1/embeddings`.
+///
+/// # Arguments
+///  * `state` - shared application state
+///  * `request` - OpenAI embeddings request
+///
+/// # Returns
+///  * OpenAI embeddings response, or an OpenAI-style error response
+pub async fn embeddings(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<OpenAiEmbeddingRequest>,
+) -> Response {
+    state.metrics.total_requests.fetch_add(1, Ordering::Relaxed);
+
+    match create_embeddings(state.clone(), request).await {
+        Ok(response) => {
+            state.metrics.successful_requests.fetch_add(1, Ordering::Relaxed);
+            json_response(StatusCode::OK, response)
+        }
+        Err(e) => {
+            state.metrics.failed_requests.fetch_add(1, Ordering::Relaxed);
+            create_error_response(&e)
+        }
+    }
+}
+
+///
+/// Embed the request inputs with the Vertex AI embedding model.
+///
+/// # Arguments
+///  * `state` - shared application state
+///  * `request` - OpenAI embeddings request
+///
+/// # Returns
+///  * OpenAI embeddings response
+///  * `ProxyError::Config` when the LLM provider is not Vertex AI
+#[tracing::instrument(
+    skip_all,
+    fields(model = %state.embedding_model, requested_model = ?request.model, inputs = tracing::field::Empty)
+)]
+async fn create_embeddings(
+    state: Arc<AppState>,
+    request: OpenAiEmbeddingRequest,
+) -> Result<OpenAiEmbeddingResponse> {
+    let mut url = {
+        let config = state.config.read().await;
+        check_model_access(&config, Some(&state.embedding_model))?;
+        config.build_embedding_url().ok_or_else(|| {
+            ProxyError::Config("Embeddings require the Vertex AI provider".to_string(), None)
+        })?
+    };
+    if let Some(endpoint) = state.load_balancer.as_ref().and_then(|lb| lb.select()) {
+        url = VertexLoadBalancer::route_url(&url, &endpoint);
+    }
+
+    let encoding_format = request.encoding_format;
+    let vertex_request = state.embedding_converter.convert_request(request)?;
+    let inputs = vertex_request.instances.len();
+    tracing::Span::current().record("inputs", inputs);
+    tracing::debug!("Sending {} embedding input(s) to Vertex AI: {}", inputs, url);
+
+    let auth_header = get_authorization_header(state.clone()).await?;
+    let response = state
+        .http_client
+        .post(&url)
+        .header(AUTHORIZATION_HEADER, auth_header)
+        .json(&vertex_request)
+        .send()
+        .await
+        .map_err(|e| upstream_request_error(&url, e))?;
+    let vertex_response: VertexEmbeddingResponse =
+        validate_vertex_response(response).await?.json().await?;
+
+    state.embedding_converter.convert_response(
+        vertex_response,
+        &state.embedding_model,
+        inputs,
+        encoding_format,
+    )
+}
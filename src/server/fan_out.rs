@@ -0,0 +1,159 @@
+//!
+//! Streaming fan-out of one chat completion to several models.
+//!
+//! A streaming request for one of `streaming.fan_out_models` is sent to all of
+//! them in parallel, each stream read by its own task, and the streams are
+//! combined by a [`StreamMultiplexer`] according to
+//! `streaming.fan_out_strategy`:
+//!
+//!  * `return_fastest` forwards the first stream to produce a content delta
+//!    and drops the others; a model answering quickly with an error is never
+//!    chosen.
+//!  * `return_all` (evaluation mode) interleaves every stream as
+//!    `{"provider": "<model>", "delta": {...}}` events.
+//!
+//! Models a restricted client key may not use are left out of the fan-out.
+//! Non-streaming requests and requests for several completions (`n > 1`) are
+//! sent to the requested model only.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::sync::Arc;
+
+use axum::response::Response;
+use axum::response::sse::Event;
+use futures::StreamExt;
+use serde_json::json;
+use tokio::sync::mpsc;
+
+use super::auth_middleware::check_model_access;
+use super::choices::sse_data_stream;
+use super::{
+    AppState, ProviderStream, ResponseOptions, STREAMING_CHANNEL_BUFFER, StreamMultiplexer,
+    handle_collected_streaming_response, in_request_scope, request_vertex, sse_response,
+};
+use crate::config::{Config, FanOutStrategy};
+use crate::converter::openai_to_anthropic::AnthropicRequest;
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+///
+/// Models a streaming request is fanned out to.
+///
+/// # Arguments
+///  * `config` - running configuration
+///  * `requested_model` - model requested by the client, `None` for the default model
+///
+/// # Returns
+///  * The `streaming.fan_out_models` the client may use when the requested
+///    model is one of them, `None` when fewer than two remain
+pub(super) fn fan_out_models(
+    config: &Config,
+    requested_model: Option<&str>,
+) -> Option<Vec<String>> {
+    let fan_out = &config.streaming.fan_out_models;
+    let requested = requested_model.unwrap_or_else(|| config.llm_model());
+    let resolved = config.resolve_model_alias(requested);
+    if !fan_out.iter().any(|model| model == requested || model == resolved) {
+        return None;
+    }
+
+    let models: Vec<String> = fan_out
+        .iter()
+        .filter(|model| check_model_access(config, Some(model)).is_ok())
+        .cloned()
+        .collect();
+    (models.len() > 1).then_some(models)
+}
+
+///
+/// Stream one request from several models, combined by a [`StreamMultiplexer`].
+///
+/// # Arguments
+///  * `state` - application state
+///  * `request` - converted request
+///  * `models` - models the request is sent to
+///  * `strategy` - how the streams are combined
+///  * `options` - model name, `functions` API and `seed` of the request
+///  * `include_usage` - whether each stream ends with a usage chunk
+///
+/// # Returns
+///  * Server-Sent Events response of the merged streams
+pub(super) fn stream_fan_out(
+    state: Arc<AppState>,
+    request: AnthropicRequest,
+    models: Vec<String>,
+    strategy: FanOutStrategy,
+    options: ResponseOptions,
+    include_usage: bool,
+) -> Response {
+    let request = Arc::new(request);
+    let streams = models
+        .into_iter()
+        .map(|model| {
+            let stream = model_stream(
+                state.clone(),
+                request.clone(),
+                model.clone(),
+                options.clone(),
+                include_usage,
+            );
+            (model, stream)
+        })
+        .collect();
+
+    let mut merged = StreamMultiplexer::new(strategy).merge(streams);
+    let (tx, rx) = mpsc::channel(STREAMING_CHANNEL_BUFFER);
+    tokio::spawn(async move {
+        while let Some(data) = merged.next().await {
+            if tx.send(Ok(Event::default().data(data))).await.is_err() {
+                return;
+            }
+        }
+    });
+    sse_response(rx)
+}
+
+///
+/// Stream of one model of a fan-out request.
+///
+/// The upstream request is sent when the stream is first polled, so a slow
+/// model does not hold back the others. A failed request yields a single
+/// error event.
+///
+/// # Arguments
+///  * `state` - application state
+///  * `request` - converted request
+///  * `model` - model the request is sent to
+///  * `options` - model name, `functions` API and `seed` of the request
+///  * `include_usage` - whether the stream ends with a usage chunk
+///
+/// # Returns
+///  * SSE data payloads of the model's stream
+fn model_stream(
+    state: Arc<AppState>,
+    request: Arc<AnthropicRequest>,
+    model: String,
+    options: ResponseOptions,
+    include_usage: bool,
+) -> ProviderStream {
+    let response = in_request_scope(async move {
+        let upstream = request_vertex(&state, &request, Some(&model)).await?;
+        let stream_state = options.stream_state(include_usage);
+        handle_collected_streaming_response(upstream, state, options.model, stream_state).await
+    });
+
+    Box::pin(futures::stream::once(response).flat_map(|response| match response {
+        Ok(response) => sse_data_stream(response),
+        Err(e) => {
+            tracing::warn!(error = %e, "Fan-out request failed");
+            let error = json!({ "error": { "message": e.to_string(), "type": "upstream_error" } });
+            Box::pin(futures::stream::iter([error.to_string()]))
+        }
+    }))
+}
@@ -0,0 +1,327 @@
+//!
+//! Idempotency for streaming chat completions.
+//!
+//! A streaming response is not known in full when its idempotency key is first
+//! seen, so instead of caching a finished response the proxy records the SSE
+//! data events as they are sent. A duplicate request (same `Idempotency-Key`
+//! from the same client) arriving while the stream is running receives the
+//! events recorded so far and then follows the live stream through a broadcast
+//! subscription. A duplicate arriving after the stream finished gets the
+//! recorded events replayed.
+//!
+//! Only the first 60 seconds of a stream are recorded; longer streams cannot
+//! be replayed and duplicates of them are processed as new requests.
+//! Finished streams are evicted after `idempotency.streaming_replay_ttl_secs`.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::http::HeaderMap;
+use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+use tokio::sync::broadcast;
+
+use crate::config::IdempotencyConfig;
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// Recorded streaming responses keyed by client and idempotency key.
+pub struct StreamingIdempotencyCache {
+    /** recordings keyed by scoped idempotency key */
+    streams: DashMap<String, Arc<StreamRecording>>,
+    /** how long finished streams are kept for replay */
+    replay_ttl: Duration,
+}
+
+///
+/// SSE data events of one streaming response.
+pub struct StreamRecording {
+    /** recorded events and live fan-out, guarded together so joiners see no gaps */
+    inner: Mutex<RecordingState>,
+}
+
+///
+/// Mutable part of a stream recording.
+struct RecordingState {
+    /** data events sent so far */
+    chunks: Vec<String>,
+    /** when the stream started */
+    started_at: Instant,
+    /** when the stream finished (None while running) */
+    finished_at: Option<Instant>,
+    /** set once events stop being recorded because the window elapsed */
+    truncated: bool,
+    /** live fan-out to joined duplicates (dropped when the stream finishes) */
+    live: Option<broadcast::Sender<String>>,
+}
+
+///
+/// Events for a duplicate request to send.
+pub struct StreamReplay {
+    /** events recorded before the duplicate arrived */
+    pub chunks: Vec<String>,
+    /** subscription to the rest of the stream (None if it already finished) */
+    pub live: Option<broadcast::Receiver<String>>,
+}
+
+///
+/// Outcome of registering a streaming request with an idempotency key.
+pub enum StreamingIdempotency {
+    /// A matching stream exists; send its events instead of calling upstream
+    Replay(StreamReplay),
+    /// First request with this key; record its events here
+    Record(Arc<StreamRecording>),
+}
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** Request header carrying the client's idempotency key */
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/** Portion of a stream that is recorded for replay */
+const MAX_RECORDING_WINDOW: Duration = Duration::from_secs(60);
+
+/** Events buffered per joined duplicate before it is considered lagging */
+const LIVE_CHANNEL_CAPACITY: usize = 1024;
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+impl StreamingIdempotencyCache {
+    ///
+    /// Create an empty cache.
+    ///
+    /// # Arguments
+    ///  * `config` - idempotency configuration
+    pub fn new(config: &IdempotencyConfig) -> Self {
+        Self {
+            streams: DashMap::new(),
+            replay_ttl: Duration::from_secs(config.streaming_replay_ttl_secs),
+        }
+    }
+
+    ///
+    /// Register a streaming request, or join the stream of an earlier one.
+    ///
+    /// Runs atomically per key, so of two concurrent duplicates exactly one
+    /// goes upstream.
+    ///
+    /// # Arguments
+    ///  * `key` - scoped idempotency key (see [`scoped_key`])
+    ///
+    /// # Returns
+    ///  * `Replay` when a replayable stream exists, otherwise `Record`
+    pub fn begin(&self, key: String) -> StreamingIdempotency {
+        self.remove_expired();
+
+        match self.streams.entry(key) {
+            Entry::Occupied(mut entry) => match entry.get().replay() {
+                Some(replay) => StreamingIdempotency::Replay(replay),
+                None => {
+                    let recording = Arc::new(StreamRecording::new());
+                    entry.insert(recording.clone());
+                    StreamingIdempotency::Record(recording)
+                }
+            },
+            Entry::Vacant(entry) => {
+                let recording = Arc::new(StreamRecording::new());
+                entry.insert(recording.clone());
+                StreamingIdempotency::Record(recording)
+            }
+        }
+    }
+
+    ///
+    /// Forget a recording whose request failed before streaming started.
+    ///
+    /// # Arguments
+    ///  * `key` - scoped idempotency key
+    ///  * `recording` - recording returned by [`begin`](Self::begin)
+    pub fn abandon(&self, key: &str, recording: &Arc<StreamRecording>) {
+        self.streams.remove_if(key, |_, current| Arc::ptr_eq(current, recording));
+        recording.finish();
+    }
+
+    ///
+    /// Drop finished streams older than the replay TTL.
+    fn remove_expired(&self) {
+        self.streams.retain(|_, recording| !recording.is_expired(self.replay_ttl));
+    }
+}
+
+impl StreamRecording {
+    ///
+    /// Start an empty recording.
+    fn new() -> Self {
+        let (live, _) = broadcast::channel(LIVE_CHANNEL_CAPACITY);
+        Self {
+            inner: Mutex::new(RecordingState {
+                chunks: Vec::new(),
+                started_at: Instant::now(),
+                finished_at: None,
+                truncated: false,
+                live: Some(live),
+            }),
+        }
+    }
+
+    ///
+    /// Record an SSE data event and forward it to joined duplicates.
+    ///
+    /// # Arguments
+    ///  * `data` - event data as sent to the original client
+    pub fn push(&self, data: &str) {
+        let Ok(mut state) = self.inner.lock() else {
+            return;
+        };
+        if state.truncated {
+            return;
+        }
+        if state.started_at.elapsed() > MAX_RECORDING_WINDOW {
+            // Joined duplicates would miss the rest of the stream; end them now
+            state.truncated = true;
+            state.chunks = Vec::new();
+            state.live = None;
+            return;
+        }
+
+        state.chunks.push(data.to_string());
+        if let Some(live) = &state.live {
+            let _ = live.send(data.to_string());
+        }
+    }
+
+    ///
+    /// Mark the stream as finished, closing live subscriptions.
+    pub fn finish(&self) {
+        if let Ok(mut state) = self.inner.lock() {
+            state.finished_at.get_or_insert_with(Instant::now);
+            state.live = None;
+        }
+    }
+
+    ///
+    /// Snapshot the recording for a duplicate request.
+    ///
+    /// # Returns
+    ///  * Recorded events and a live subscription, or `None` if not replayable
+    fn replay(&self) -> Option<StreamReplay> {
+        let state = self.inner.lock().ok()?;
+        if state.truncated {
+            return None;
+        }
+
+        Some(StreamReplay {
+            chunks: state.chunks.clone(),
+            live: state.live.as_ref().map(|live| live.subscribe()),
+        })
+    }
+
+    ///
+    /// Whether the stream finished more than `ttl` ago.
+    fn is_expired(&self, ttl: Duration) -> bool {
+        self.inner.lock().map_or(true, |state| {
+            state.finished_at.is_some_and(|finished| finished.elapsed() >= ttl)
+                || (state.truncated && state.finished_at.is_some())
+        })
+    }
+}
+
+///
+/// Build the cache key for a request carrying an `Idempotency-Key` header.
+///
+/// Keys are scoped to the caller's `Authorization` header so different clients
+/// cannot receive each other's responses by reusing a key.
+///
+/// # Arguments
+///  * `headers` - HTTP request headers
+///
+/// # Returns
+///  * Scoped key, or `None` when the request has no idempotency key
+pub fn scoped_key(headers: &HeaderMap) -> Option<String> {
+    let key = headers
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|key| !key.is_empty())?;
+    let caller = headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    Some(format!("{}:{}", crate::auth::hash_api_key(caller), key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_cache(ttl_secs: u64) -> StreamingIdempotencyCache {
+        StreamingIdempotencyCache::new(&IdempotencyConfig { streaming_replay_ttl_secs: ttl_secs })
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_joins_live_stream() {
+        let cache = test_cache(300);
+        let StreamingIdempotency::Record(recording) = cache.begin("k".to_string()) else {
+            panic!("first request should record");
+        };
+        recording.push("one");
+
+        let StreamingIdempotency::Replay(mut replay) = cache.begin("k".to_string()) else {
+            panic!("duplicate should replay");
+        };
+        assert_eq!(replay.chunks, vec!["one".to_string()]);
+
+        recording.push("two");
+        recording.finish();
+
+        let mut live = replay.live.take().unwrap();
+        assert_eq!(live.recv().await.unwrap(), "two");
+        assert!(live.recv().await.is_err());
+
+        let StreamingIdempotency::Replay(replay) = cache.begin("k".to_string()) else {
+            panic!("finished stream should replay");
+        };
+        assert_eq!(replay.chunks, vec!["one".to_string(), "two".to_string()]);
+        assert!(replay.live.is_none());
+    }
+
+    #[test]
+    fn test_expired_and_abandoned_streams_are_recorded_again() {
+        let cache = test_cache(0);
+        let StreamingIdempotency::Record(recording) = cache.begin("k".to_string()) else {
+            panic!("first request should record");
+        };
+        recording.finish();
+        assert!(matches!(cache.begin("k".to_string()), StreamingIdempotency::Record(_)));
+
+        let cache = test_cache(300);
+        let StreamingIdempotency::Record(recording) = cache.begin("k".to_string()) else {
+            panic!("first request should record");
+        };
+        cache.abandon("k", &recording);
+        assert!(matches!(cache.begin("k".to_string()), StreamingIdempotency::Record(_)));
+    }
+
+    #[test]
+    fn test_scoped_key() {
+        let mut headers = HeaderMap::new();
+        assert!(scoped_key(&headers).is_none());
+
+        headers.insert(IDEMPOTENCY_KEY_HEADER, "abc".parse().unwrap());
+        let anonymous = scoped_key(&headers).unwrap();
+        headers.insert(axum::http::header::AUTHORIZATION, "Bearer one".parse().unwrap());
+        let authenticated = scoped_key(&headers).unwrap();
+
+        assert!(anonymous.ends_with(":abc"));
+        assert_ne!(anonymous, authenticated);
+    }
+}
@@ -0,0 +1,601 @@
+//!
+//! Asynchronous chat completion jobs.
+//!
+//! `POST /v1/chat/completions/async` queues a chat completion and returns a
+//! job id immediately. A pool of background workers drains the queue, runs
+//! each request non-streaming against the same provider as a synchronous
+//! chat completion and stores the result, which clients can poll at
+//! `GET /v1/jobs/{job_id}` and `GET /v1/jobs/{job_id}/result`. When the
+//! request carries a `callback_url`, the finished job is also POSTed there.
+//! Callbacks are refused unless the host is listed in
+//! `jobs.callback_allowed_hosts`, and never go to loopback, link-local or
+//! private addresses, so clients cannot make the proxy reach internal services.
+//!
+//! Jobs are kept in memory only and expire `jobs.ttl_secs` after they finish.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+use axum::Json;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::Response;
+use dashmap::DashMap;
+use serde_json::{Value, json};
+use tokio::sync::{Mutex, mpsc};
+use uuid::Uuid;
+
+use super::auth_middleware::check_model_access;
+use super::metrics::MetricsRegistry;
+use super::{
+    AppState, complete_chat_request, create_error_response, json_response, parse_openai_request,
+};
+use crate::config::JobsConfig;
+use crate::error::{ProxyError, Result};
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// In-memory queue and status store for async jobs.
+pub struct JobQueue {
+    /** job states keyed by id */
+    jobs: DashMap<Uuid, JobEntry>,
+    /** sending side of the pending job queue */
+    sender: mpsc::Sender<QueuedJob>,
+    /** receiving side, taken once when the workers start */
+    receiver: std::sync::Mutex<Option<mpsc::Receiver<QueuedJob>>>,
+    /** number of workers to start */
+    workers: usize,
+    /** how long finished jobs are kept */
+    ttl: Duration,
+}
+
+///
+/// Lifecycle state of an async job.
+pub enum JobStatus {
+    /// Waiting for a free worker
+    Queued,
+    /// Request sent upstream
+    Processing,
+    /// Finished successfully
+    Completed(Box<Value>),
+    /// Finished with an error message
+    Failed(String),
+}
+
+///
+/// Stored job state with its last update time.
+struct JobEntry {
+    /** current state */
+    status: JobStatus,
+    /** when the state last changed (expiry is measured from here) */
+    updated_at: Instant,
+}
+
+///
+/// Job waiting in the queue.
+struct QueuedJob {
+    /** job id */
+    id: Uuid,
+    /** OpenAI format request JSON */
+    request: Value,
+    /** URL the finished job is POSTed to */
+    callback_url: Option<String>,
+}
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** Maximum number of jobs waiting for a worker */
+const JOB_QUEUE_CAPACITY: usize = 1024;
+
+/** Upper bound on the interval between expiry sweeps */
+const MAX_EXPIRY_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/** Timeout of a callback request */
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+impl JobQueue {
+    ///
+    /// Create an empty job queue. Workers are started separately by [`start_workers`].
+    ///
+    /// # Arguments
+    ///  * `config` - async job configuration
+    pub fn new(config: &JobsConfig) -> Self {
+        let (sender, receiver) = mpsc::channel(JOB_QUEUE_CAPACITY);
+        Self {
+            jobs: DashMap::new(),
+            sender,
+            receiver: std::sync::Mutex::new(Some(receiver)),
+            workers: config.workers.max(1),
+            ttl: Duration::from_secs(config.ttl_secs),
+        }
+    }
+
+    ///
+    /// Queue a chat completion request.
+    ///
+    /// # Arguments
+    ///  * `request` - OpenAI format request JSON
+    ///  * `callback_url` - URL to POST the finished job to
+    ///
+    /// # Returns
+    ///  * Id of the queued job
+    ///  * `ProxyError::Http` if the queue is full
+    fn submit(&self, request: Value, callback_url: Option<String>) -> Result<Uuid> {
+        let id = Uuid::new_v4();
+        self.set_status(id, JobStatus::Queued);
+
+        if self.sender.try_send(QueuedJob { id, request, callback_url }).is_err() {
+            self.jobs.remove(&id);
+            return Err(ProxyError::Http(
+                "Job queue is full, service temporarily unavailable".to_string(),
+                None,
+            ));
+        }
+        Ok(id)
+    }
+
+    ///
+    /// Replace the state of a job.
+    fn set_status(&self, id: Uuid, status: JobStatus) {
+        self.jobs.insert(id, JobEntry { status, updated_at: Instant::now() });
+    }
+
+    ///
+    /// Drop finished jobs older than the configured TTL.
+    ///
+    /// # Returns
+    ///  * Number of jobs removed
+    fn remove_expired(&self) -> usize {
+        let before = self.jobs.len();
+        self.jobs.retain(|_, entry| {
+            !entry.status.is_finished() || entry.updated_at.elapsed() < self.ttl
+        });
+        before.saturating_sub(self.jobs.len())
+    }
+}
+
+impl JobStatus {
+    ///
+    /// Name used in API responses.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Queued => "queued",
+            JobStatus::Processing => "processing",
+            JobStatus::Completed(_) => "completed",
+            JobStatus::Failed(_) => "failed",
+        }
+    }
+
+    ///
+    /// Whether the job has completed or failed.
+    fn is_finished(&self) -> bool {
+        matches!(self, JobStatus::Completed(_) | JobStatus::Failed(_))
+    }
+}
+
+///
+/// Start the job workers and the expiry sweeper.
+///
+/// Has no effect when called more than once for the same state.
+///
+/// # Arguments
+///  * `state` - shared application state owning the job queue
+pub fn start_workers(state: &Arc<AppState>) {
+    let Some(receiver) = state.jobs.receiver.lock().ok().and_then(|mut r| r.take()) else {
+        tracing::warn!("Async job workers already started");
+        return;
+    };
+
+    let receiver = Arc::new(Mutex::new(receiver));
+    for _ in 0..state.jobs.workers {
+        tokio::spawn(run_worker(state.clone(), receiver.clone()));
+    }
+
+    let sweep_interval = state.jobs.ttl.clamp(Duration::from_secs(1), MAX_EXPIRY_SWEEP_INTERVAL);
+    let sweeper_state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(sweep_interval);
+        loop {
+            interval.tick().await;
+            let removed = sweeper_state.jobs.remove_expired();
+            if removed > 0 {
+                tracing::debug!("Expired {} async job(s)", removed);
+            }
+        }
+    });
+
+    tracing::info!(workers = state.jobs.workers, "Started async job workers");
+}
+
+///
+/// Take jobs from the queue until it is closed.
+async fn run_worker(state: Arc<AppState>, receiver: Arc<Mutex<mpsc::Receiver<QueuedJob>>>) {
+    loop {
+        let Some(job) = receiver.lock().await.recv().await else {
+            break;
+        };
+        process_job(&state, job).await;
+    }
+}
+
+///
+/// Run a queued job, store its result and notify the callback URL.
+#[tracing::instrument(
+    skip_all,
+    fields(job_id = %job.id, model = job.request.get("model").and_then(serde_json::Value::as_str))
+)]
+async fn process_job(state: &Arc<AppState>, job: QueuedJob) {
+    tracing::debug!("Processing async job {}", job.id);
+    state.jobs.set_status(job.id, JobStatus::Processing);
+    state.metrics.total_requests.fetch_add(1, Ordering::Relaxed);
+    let labels = MetricsRegistry::labels(
+        &*state.config.read().await,
+        job.request.get("model").and_then(Value::as_str),
+        false,
+    );
+    state.labeled_metrics.requests_total.inc(&labels);
+    let started = std::time::Instant::now();
+
+    let result = complete_chat_request(state.clone(), job.request).await;
+    state.labeled_metrics.request_duration.observe(&labels, started.elapsed().as_secs_f64(), None);
+    let status = match result {
+        Ok(response) => {
+            state.metrics.successful_requests.fetch_add(1, Ordering::Relaxed);
+            state.labeled_metrics.requests_succeeded.inc(&labels);
+            JobStatus::Completed(Box::new(response))
+        }
+        Err(e) => {
+            state.metrics.failed_requests.fetch_add(1, Ordering::Relaxed);
+            state.labeled_metrics.requests_failed.inc(&labels);
+            tracing::warn!("Async job {} failed: {}", job.id, e);
+            JobStatus::Failed(e.to_string())
+        }
+    };
+
+    let payload = job.callback_url.as_ref().map(|_| job_to_json(job.id, &status, true));
+    state.jobs.set_status(job.id, status);
+
+    if let (Some(url), Some(payload)) = (job.callback_url, payload) {
+        send_callback(job.id, &url, &payload).await;
+    }
+}
+
+///
+/// POST a finished job to its callback URL. Failures are logged, not retried.
+///
+/// The host is resolved here and the request is pinned to the checked
+/// address, so a DNS answer cannot redirect it to an internal service;
+/// redirects are not followed for the same reason.
+async fn send_callback(id: Uuid, url: &str, payload: &Value) {
+    let client = match callback_client(url).await {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!("Callback for async job {} refused: {}", id, e);
+            return;
+        }
+    };
+    match client.post(url).json(payload).send().await {
+        Ok(response) if response.status().is_success() => {
+            tracing::debug!("Delivered async job {} to {}", id, url);
+        }
+        Ok(response) => {
+            tracing::warn!("Callback for async job {} returned {}", id, response.status());
+        }
+        Err(e) => {
+            tracing::warn!("Callback for async job {} failed: {}", id, e);
+        }
+    }
+}
+
+///
+/// Build an HTTP client that can only reach the public address of a callback host.
+///
+/// # Arguments
+///  * `url` - callback URL accepted by [`take_callback_url`]
+///
+/// # Returns
+///  * Client resolving the host to a checked public address
+///  * `ProxyError::Http` if the host does not resolve or resolves to a non-public address
+async fn callback_client(url: &str) -> Result<reqwest::Client> {
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|e| ProxyError::http_with_source("Invalid callback URL", e))?;
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| ProxyError::Http("Callback URL has no host".to_string(), None))?;
+    let port = parsed.port_or_known_default().unwrap_or(443);
+
+    let addresses: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| ProxyError::http_with_source(format!("Cannot resolve {}", host), e))?
+        .collect();
+    if let Some(address) = addresses.iter().find(|address| !is_public_address(address.ip())) {
+        return Err(ProxyError::Http(
+            format!("{} resolves to non-public address {}", host, address.ip()),
+            None,
+        ));
+    }
+    let address = addresses
+        .first()
+        .ok_or_else(|| ProxyError::Http(format!("{} has no address", host), None))?;
+
+    reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .resolve(host, *address)
+        .timeout(CALLBACK_TIMEOUT)
+        .build()
+        .map_err(|e| ProxyError::http_with_source("Failed to create callback client", e))
+}
+
+///
+/// Whether an address is reachable on the public internet.
+///
+/// Loopback, link-local (including cloud metadata endpoints), private,
+/// shared, unspecified, broadcast and multicast addresses are not.
+fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let shared = ip.octets()[0] == 100 && (ip.octets()[1] & 0xc0) == 64;
+            !(ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_private()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || shared)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(v4) => is_public_address(IpAddr::V4(v4)),
+            None => {
+                let segment = ip.segments()[0];
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || (segment & 0xfe00) == 0xfc00
+                    || (segment & 0xffc0) == 0xfe80)
+            }
+        },
+    }
+}
+
+///
+/// Build the JSON description of a job.
+///
+/// # Arguments
+///  * `id` - job id
+///  * `status` - job state
+///  * `include_result` - include the completion for finished jobs
+fn job_to_json(id: Uuid, status: &JobStatus, include_result: bool) -> Value {
+    let mut body = json!({ "job_id": id, "status": status.as_str() });
+    match status {
+        JobStatus::Completed(response) if include_result => {
+            body["result"] = serde_json::to_value(response).unwrap_or(Value::Null);
+        }
+        JobStatus::Failed(message) => body["error"] = json!(message),
+        _ => {}
+    }
+    body
+}
+
+///
+/// Remove and validate the `callback_url` field of an async request.
+///
+/// # Arguments
+///  * `request` - async request JSON
+///  * `allowed_hosts` - hosts callbacks may be sent to (`jobs.callback_allowed_hosts`)
+///
+/// # Returns
+///  * Callback URL, or `None` when not set
+///  * `ProxyError::Conversion` if the value is not an http(s) URL, callbacks are
+///    disabled, the host is not allowed or is a non-public IP address
+fn take_callback_url(request: &mut Value, allowed_hosts: &[String]) -> Result<Option<String>> {
+    let Some(value) = request.as_object_mut().and_then(|obj| obj.remove("callback_url")) else {
+        return Ok(None);
+    };
+    let url = match value {
+        Value::Null => return Ok(None),
+        Value::String(url) if url.starts_with("http://") || url.starts_with("https://") => url,
+        _ => return Err(ProxyError::Conversion("callback_url must be an http(s) URL".to_string())),
+    };
+
+    if allowed_hosts.is_empty() {
+        return Err(ProxyError::Conversion(
+            "callback_url is not enabled on this server".to_string(),
+        ));
+    }
+    let parsed = reqwest::Url::parse(&url)
+        .map_err(|e| ProxyError::Conversion(format!("Invalid callback_url: {}", e)))?;
+    let host = parsed.host_str().unwrap_or_default().to_string();
+    let ip = host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>().ok();
+    if host.is_empty() || ip.is_some_and(|ip| !is_public_address(ip)) {
+        return Err(ProxyError::Conversion("callback_url must point to a public host".to_string()));
+    }
+    if !allowed_hosts.iter().any(|allowed| allowed.trim().eq_ignore_ascii_case(&host)) {
+        return Err(ProxyError::Conversion(format!("callback_url host '{}' is not allowed", host)));
+    }
+    Ok(Some(url))
+}
+
+///
+/// Build an error response with an explicit status code.
+fn job_error(status: StatusCode, error_type: &str, message: String) -> Response {
+    let error = json!({
+      "error": {
+        "message": message,
+        "type": error_type,
+        "code": status.as_u16()
+      }
+    });
+    json_response(status, error)
+}
+
+///
+/// Handle `POST /v1/chat/completions/async`.
+///
+/// Validates the request, queues it and returns `202 Accepted` with the job id.
+///
+/// # Arguments
+///  * `state` - shared application state
+///  * `request` - OpenAI format request JSON with an optional `callback_url`
+///
+/// # Returns
+///  * `{"job_id": ..., "status": "queued"}` or an error response
+pub async fn create_async_completion(
+    State(state): State<Arc<AppState>>,
+    Json(mut request): Json<Value>,
+) -> Response {
+    let allowed_hosts = state.config.read().await.jobs.callback_allowed_hosts.clone();
+    let callback_url = match take_callback_url(&mut request, &allowed_hosts) {
+        Ok(url) => url,
+        Err(e) => return create_error_response(&e),
+    };
+    let checked = {
+        let config = state.config.read().await;
+        check_model_access(&config, request.get("model").and_then(Value::as_str))
+            .and_then(|()| parse_openai_request(request.clone(), &config.limits))
+    };
+    if let Err(e) = checked {
+        return create_error_response(&e);
+    }
+
+    match state.jobs.submit(request, callback_url) {
+        Ok(id) => {
+            tracing::debug!("Queued async job {}", id);
+            json_response(StatusCode::ACCEPTED, job_to_json(id, &JobStatus::Queued, false))
+        }
+        Err(e) => create_error_response(&e),
+    }
+}
+
+///
+/// Handle `GET /v1/jobs/{job_id}`.
+///
+/// # Returns
+///  * Job status, or `404` for unknown and expired jobs
+pub async fn job_status(State(state): State<Arc<AppState>>, Path(id): Path<Uuid>) -> Response {
+    match state.jobs.jobs.get(&id) {
+        Some(entry) => json_response(StatusCode::OK, job_to_json(id, &entry.status, false)),
+        None => job_error(
+            StatusCode::NOT_FOUND,
+            "not_found_error",
+            format!("Job {} not found or expired", id),
+        ),
+    }
+}
+
+///
+/// Handle `GET /v1/jobs/{job_id}/result`.
+///
+/// # Returns
+///  * OpenAI chat completion for completed jobs
+///  * `425 Too Early` while the job is queued or processing
+///  * Error response for failed, unknown and expired jobs
+pub async fn job_result(State(state): State<Arc<AppState>>, Path(id): Path<Uuid>) -> Response {
+    let Some(entry) = state.jobs.jobs.get(&id) else {
+        return job_error(
+            StatusCode::NOT_FOUND,
+            "not_found_error",
+            format!("Job {} not found or expired", id),
+        );
+    };
+
+    match &entry.status {
+        JobStatus::Completed(response) => json_response(StatusCode::OK, response),
+        JobStatus::Failed(message) => {
+            create_error_response(&ProxyError::Http(message.clone(), None))
+        }
+        status => job_error(
+            StatusCode::TOO_EARLY,
+            "job_not_ready",
+            format!("Job {} is still {}", id, status.as_str()),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_queue(ttl_secs: u64) -> JobQueue {
+        JobQueue::new(&JobsConfig { workers: 1, ttl_secs, ..Default::default() })
+    }
+
+    #[test]
+    fn test_submit_and_expire_jobs() {
+        let queue = test_queue(0);
+        let queued = queue.submit(json!({"messages": []}), None).unwrap();
+        let failed = queue.submit(json!({"messages": []}), None).unwrap();
+        queue.set_status(failed, JobStatus::Failed("boom".to_string()));
+
+        assert_eq!(queue.jobs.get(&queued).unwrap().status.as_str(), "queued");
+        assert_eq!(queue.remove_expired(), 1);
+        assert!(queue.jobs.contains_key(&queued));
+        assert!(!queue.jobs.contains_key(&failed));
+    }
+
+    #[test]
+    fn test_take_callback_url() {
+        let allowed = vec!["example.com".to_string(), "8.8.8.8".to_string()];
+        let mut request = json!({"messages": [], "callback_url": "https://example.com/hook"});
+        assert_eq!(
+            take_callback_url(&mut request, &allowed).unwrap().as_deref(),
+            Some("https://example.com/hook")
+        );
+        assert!(request.get("callback_url").is_none());
+
+        assert_eq!(take_callback_url(&mut json!({"messages": []}), &allowed).unwrap(), None);
+        assert!(take_callback_url(&mut json!({"callback_url": "ftp://x"}), &allowed).is_err());
+        assert!(take_callback_url(&mut json!({"callback_url": 42}), &allowed).is_err());
+    }
+
+    #[test]
+    fn test_take_callback_url_refuses_unlisted_and_internal_hosts() {
+        let take = |url: &str, allowed: &[String]| {
+            take_callback_url(&mut json!({"callback_url": url}), allowed)
+        };
+        let allowed = vec!["hooks.example.com".to_string(), "127.0.0.1".to_string()];
+
+        assert!(take("https://hooks.example.com/done", &[]).is_err(), "disabled without allowlist");
+        assert!(take("https://other.example.com/done", &allowed).is_err());
+        assert!(take("http://127.0.0.1/done", &allowed).is_err(), "loopback even when listed");
+        assert!(take("http://169.254.169.254/latest/meta-data", &allowed).is_err());
+        assert!(take("http://10.0.0.1/", &allowed).is_err());
+        assert!(take("http://[::1]/", &allowed).is_err());
+        assert!(take("https://HOOKS.example.com/done", &allowed).is_ok());
+    }
+
+    #[test]
+    fn test_is_public_address() {
+        for internal in
+            ["127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.1", "169.254.169.254", "100.64.0.1"]
+        {
+            assert!(!is_public_address(internal.parse().unwrap()), "{}", internal);
+        }
+        for internal in ["::1", "fe80::1", "fd00::1", "::ffff:10.0.0.1"] {
+            assert!(!is_public_address(internal.parse().unwrap()), "{}", internal);
+        }
+        assert!(is_public_address("8.8.8.8".parse().unwrap()));
+        assert!(is_public_address("2001:4860:4860::8888".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_job_to_json() {
+        let id = Uuid::new_v4();
+        let body = job_to_json(id, &JobStatus::Failed("upstream error".to_string()), true);
+        assert_eq!(body["status"], "failed");
+        assert_eq!(body["error"], "upstream error");
+        assert_eq!(body["job_id"], id.to_string());
+    }
+}
@@ -0,0 +1,99 @@
+//!
+//! Request size limits of the JSON endpoints.
+//!
+//! Bodies above `limits.max_request_body_bytes` are rejected with `413`
+//! before they are buffered, and chat completions with more than
+//! `limits.max_messages` messages, or asking for more than `limits.max_n`
+//! completions, are rejected with `400` before conversion.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use axum::extract::DefaultBodyLimit;
+use tower_http::limit::RequestBodyLimitLayer;
+
+use crate::config::RequestLimitsConfig;
+use crate::error::{ProxyError, Result};
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+///
+/// Body limit layers of the JSON endpoints.
+///
+/// `RequestBodyLimitLayer` rejects bodies by `Content-Length` up front and
+/// stops streamed bodies at the limit; the matching `DefaultBodyLimit` lifts
+/// axum's 2 MB default of the `Json` extractor to the same limit.
+///
+/// # Arguments
+///  * `config` - request limits section
+///
+/// # Returns
+///  * Layers to apply to the routes of the JSON endpoints
+pub fn body_limit(config: &RequestLimitsConfig) -> (DefaultBodyLimit, RequestBodyLimitLayer) {
+    (
+        DefaultBodyLimit::max(config.max_request_body_bytes),
+        RequestBodyLimitLayer::new(config.max_request_body_bytes),
+    )
+}
+
+///
+/// Body limit layer of the batch output conversion endpoint.
+///
+/// Batch files above `limits.max_request_body_bytes` are converted as they
+/// arrive rather than buffered, so they get their own, larger limit.
+///
+/// # Arguments
+///  * `config` - request limits section
+///
+/// # Returns
+///  * Layer to apply to the `/v1/batches/convert` route
+pub fn batch_body_limit(config: &RequestLimitsConfig) -> RequestBodyLimitLayer {
+    RequestBodyLimitLayer::new(config.max_batch_bytes)
+}
+
+///
+/// Check the number of messages of a chat completion request.
+///
+/// # Arguments
+///  * `count` - number of messages in the request
+///  * `max_messages` - most messages accepted
+///
+/// # Returns
+///  * `Ok(())` within the limit
+///  * `ProxyError::Conversion` ("Too many messages", mapped to `400`) otherwise
+pub fn check_message_count(count: usize, max_messages: usize) -> Result<()> {
+    if count > max_messages {
+        return Err(ProxyError::Conversion(format!(
+            "Too many messages: {} exceeds the limit of {}",
+            count, max_messages
+        )));
+    }
+    Ok(())
+}
+
+///
+/// Check the number of completions (`n`) of a chat completion request.
+///
+/// # Arguments
+///  * `n` - completions asked for
+///  * `max_n` - most completions accepted
+///
+/// # Returns
+///  * `Ok(())` for `1..=max_n`
+///  * `ProxyError::Conversion` (mapped to `400`) otherwise
+pub fn check_choice_count(n: u32, max_n: u32) -> Result<()> {
+    if n == 0 {
+        return Err(ProxyError::Conversion("Invalid n: must be at least 1".to_string()));
+    }
+    if n > max_n {
+        return Err(ProxyError::Conversion(format!(
+            "Invalid n: {} exceeds the limit of {}",
+            n, max_n
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,996 @@
+//!
+//! Labeled request metrics in the Prometheus text exposition format.
+//!
+//! `AppMetrics` keeps global counters for the `/health` JSON endpoint. The
+//! counters here are additionally split by resolved model, provider and
+//! whether the request was streamed, so error rates can be compared across
+//! models. They are served by `GET /metrics`, e.g.
+//!
+//! ```text
+//! modelmux_requests_total{model="claude-sonnet",provider="vertex",streaming="true"} 42
+//! ```
+//!
+//! The global `AppMetrics` counters that have no labeled counterpart, and the
+//! chat completion latency summary with p50/p95/p99 quantiles, are exported
+//! next to them:
+//!
+//! ```text
+//! modelmux_request_outcomes_total{status="success"} 40
+//! modelmux_chat_completion_latency_seconds{quantile="0.95"} 2.5
+//! ```
+//!
+//! Chat completion and upstream latencies are also kept in fixed millisecond
+//! buckets ([LatencyHistogram]), which `/health` reads p50/p95/p99 from:
+//!
+//! ```text
+//! modelmux_request_duration_ms_bucket{le="100"} 12
+//! modelmux_provider_request_duration_ms_bucket{provider="vertex",le="100"} 12
+//! ```
+//!
+//! With `telemetry.prometheus_exemplars` the endpoint switches to the
+//! OpenMetrics format and latency buckets carry the trace ID of their latest
+//! observation, taken from the request's W3C `traceparent` header:
+//!
+//! ```text
+//! modelmux_request_duration_seconds_bucket{...,le="1.0"} 42 # {TraceID="4bf92f..."} 0.95 1706000000.123
+//! ```
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::http::header::CONTENT_TYPE;
+use axum::response::IntoResponse;
+use serde_json::{Value, json};
+
+use super::{AppMetrics, AppState};
+use crate::config::Config;
+use crate::provider::LlmProviderBackend;
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+/// Label values of a request: `(model, provider, is_streaming)`
+pub type MetricLabels = (String, String, bool);
+
+///
+/// Counter with one value per label set.
+#[derive(Debug, Default)]
+pub struct LabeledCounter {
+    /** counter values keyed by label set */
+    values: RwLock<HashMap<MetricLabels, AtomicU64>>,
+}
+
+///
+/// Counter with one value per upstream region.
+#[derive(Debug, Default)]
+pub struct RegionCounter {
+    /** counter values keyed by region */
+    values: RwLock<HashMap<String, AtomicU64>>,
+}
+
+///
+/// Latest observation of a histogram bucket, linking it to a trace.
+#[derive(Debug, Clone)]
+struct Exemplar {
+    /** trace ID of the observed request */
+    trace_id: String,
+    /** observed value in seconds */
+    value: f64,
+    /** observation time in seconds since the Unix epoch */
+    timestamp: f64,
+}
+
+///
+/// Observations of one label set of a histogram.
+#[derive(Debug, Clone, Default)]
+struct HistogramValues {
+    /** observations per bucket (not cumulative), the last one is `+Inf` */
+    buckets: Vec<u64>,
+    /** latest traced observation per bucket */
+    exemplars: Vec<Option<Exemplar>>,
+    /** sum of all observations */
+    sum: f64,
+    /** number of observations */
+    count: u64,
+}
+
+///
+/// Latency histogram with one set of buckets per label set.
+#[derive(Debug, Default)]
+pub struct LabeledHistogram {
+    /** observations keyed by label set */
+    values: Mutex<HashMap<MetricLabels, HistogramValues>>,
+}
+
+///
+/// Lock-free latency histogram with fixed millisecond buckets.
+///
+/// Backs the p50/p95/p99 values of `/health`, the `*_duration_ms`
+/// histograms and the chat completion latency summary of `/metrics`.
+#[derive(Debug, Default)]
+pub struct LatencyHistogram {
+    /** observations per entry of `LATENCY_HISTOGRAM_BUCKETS_MS` plus `+Inf` (not cumulative) */
+    counts: [AtomicU64; LATENCY_HISTOGRAM_BUCKETS_MS.len() + 1],
+    /** sum of all observations in milliseconds */
+    sum_ms: AtomicU64,
+    /** largest observation in milliseconds */
+    max_ms: AtomicU64,
+}
+
+///
+/// Latency histogram per provider.
+#[derive(Debug, Default)]
+pub struct ProviderLatency {
+    /** histograms keyed by provider id */
+    values: RwLock<HashMap<String, LatencyHistogram>>,
+}
+
+///
+/// Request counters labeled by model, provider and streaming mode.
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    /** requests received */
+    pub requests_total: LabeledCounter,
+    /** requests that completed successfully */
+    pub requests_succeeded: LabeledCounter,
+    /** requests that failed */
+    pub requests_failed: LabeledCounter,
+    /** upstream quota errors that were retried */
+    pub quota_errors: LabeledCounter,
+    /** upstream retry attempts */
+    pub retry_attempts: LabeledCounter,
+    /** time until the response (or the start of the stream) was ready */
+    pub request_duration: LabeledHistogram,
+    /** duration of streamed extended thinking blocks */
+    pub thinking_duration: LabeledHistogram,
+    /** time from the end of thinking to the first response text */
+    pub post_thinking_latency: LabeledHistogram,
+    /** upstream requests per Vertex region (`[[vertex.regions]]`) */
+    pub region_requests: RegionCounter,
+}
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** Content type of the Prometheus text exposition format */
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/** Content type of the OpenMetrics text format, required for exemplars */
+const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/** Model label of requests for a model the configuration does not know */
+const OTHER_MODEL_LABEL: &str = "other";
+
+/** Upper bounds in seconds of the latency histogram buckets, `+Inf` is implied */
+const LATENCY_BUCKETS_SECONDS: &[f64] = &[0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0];
+
+/** Upper bounds in milliseconds of the request and upstream latency histograms, `+Inf` is implied */
+const LATENCY_HISTOGRAM_BUCKETS_MS: [u64; 9] = [10, 50, 100, 250, 500, 1_000, 2_000, 5_000, 10_000];
+
+/** Quantiles exported for the chat completion latency summary */
+const LATENCY_QUANTILES: [f64; 3] = [0.5, 0.95, 0.99];
+
+/** W3C trace context header carrying the trace ID of a request */
+const TRACEPARENT_HEADER: &str = "traceparent";
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+impl LabeledCounter {
+    ///
+    /// Increment the counter of a label set by one.
+    ///
+    /// # Arguments
+    ///  * `labels` - label set to increment
+    pub fn inc(&self, labels: &MetricLabels) {
+        if let Some(value) = self.values.read().unwrap_or_else(|e| e.into_inner()).get(labels) {
+            value.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        self.values
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(labels.clone())
+            .or_default()
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    ///
+    /// Current value of a label set.
+    ///
+    /// # Arguments
+    ///  * `labels` - label set to read
+    ///
+    /// # Returns
+    ///  * Counter value, 0 for label sets never incremented
+    #[allow(dead_code)]
+    pub fn get(&self, labels: &MetricLabels) -> u64 {
+        let values = self.values.read().unwrap_or_else(|e| e.into_inner());
+        values.get(labels).map_or(0, |value| value.load(Ordering::Relaxed))
+    }
+
+    ///
+    /// Append the counter in Prometheus text format.
+    ///
+    /// Label sets are sorted so the output is stable between scrapes. In
+    /// OpenMetrics the metric family is named without the `_total` suffix.
+    fn render(&self, out: &mut String, name: &str, help: &str, openmetrics: bool) {
+        let family = if openmetrics { name.trim_end_matches("_total") } else { name };
+        let _ = writeln!(out, "# HELP {} {}", family, help);
+        let _ = writeln!(out, "# TYPE {} counter", family);
+
+        let values = self.values.read().unwrap_or_else(|e| e.into_inner());
+        let mut entries: Vec<_> = values.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        for ((model, provider, streaming), value) in entries {
+            let _ = writeln!(
+                out,
+                "{}{{model=\"{}\",provider=\"{}\",streaming=\"{}\"}} {}",
+                name,
+                escape_label_value(model),
+                escape_label_value(provider),
+                streaming,
+                value.load(Ordering::Relaxed)
+            );
+        }
+    }
+}
+
+impl RegionCounter {
+    ///
+    /// Increment the counter of a region by one.
+    ///
+    /// # Arguments
+    ///  * `region` - region the request was sent to
+    pub fn inc(&self, region: &str) {
+        if let Some(value) = self.values.read().unwrap_or_else(|e| e.into_inner()).get(region) {
+            value.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        self.values
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(region.to_string())
+            .or_default()
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    ///
+    /// Append the counter in Prometheus text format.
+    ///
+    /// Nothing is written before the first request, so single-region setups
+    /// export no empty metric family.
+    fn render(&self, out: &mut String, name: &str, help: &str, openmetrics: bool) {
+        let values = self.values.read().unwrap_or_else(|e| e.into_inner());
+        if values.is_empty() {
+            return;
+        }
+        let family = if openmetrics { name.trim_end_matches("_total") } else { name };
+        let _ = writeln!(out, "# HELP {} {}", family, help);
+        let _ = writeln!(out, "# TYPE {} counter", family);
+
+        let mut entries: Vec<_> = values.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        for (region, value) in entries {
+            let _ = writeln!(
+                out,
+                "{}{{region=\"{}\"}} {}",
+                name,
+                escape_label_value(region),
+                value.load(Ordering::Relaxed)
+            );
+        }
+    }
+}
+
+impl LabeledHistogram {
+    ///
+    /// Record an observation.
+    ///
+    /// # Arguments
+    ///  * `labels` - label set of the observation
+    ///  * `seconds` - observed duration
+    ///  * `trace_id` - trace of the observed request, kept as the bucket's exemplar
+    pub fn observe(&self, labels: &MetricLabels, seconds: f64, trace_id: Option<&str>) {
+        let bucket = LATENCY_BUCKETS_SECONDS
+            .iter()
+            .position(|bound| seconds <= *bound)
+            .unwrap_or(LATENCY_BUCKETS_SECONDS.len());
+
+        let mut values = self.values.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = values.entry(labels.clone()).or_insert_with(|| HistogramValues {
+            buckets: vec![0; LATENCY_BUCKETS_SECONDS.len() + 1],
+            exemplars: vec![None; LATENCY_BUCKETS_SECONDS.len() + 1],
+            ..Default::default()
+        });
+        entry.buckets[bucket] += 1;
+        entry.sum += seconds;
+        entry.count += 1;
+        if let Some(trace_id) = trace_id {
+            let timestamp = chrono::Utc::now().timestamp_millis() as f64 / 1000.0;
+            entry.exemplars[bucket] =
+                Some(Exemplar { trace_id: trace_id.to_string(), value: seconds, timestamp });
+        }
+    }
+
+    ///
+    /// Append the histogram in Prometheus text format.
+    ///
+    /// # Arguments
+    ///  * `out` - output buffer
+    ///  * `name` - metric family name
+    ///  * `help` - metric description
+    ///  * `exemplars` - append bucket exemplars (OpenMetrics only)
+    fn render(&self, out: &mut String, name: &str, help: &str, exemplars: bool) {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+
+        let values = self.values.lock().unwrap_or_else(|e| e.into_inner());
+        let mut entries: Vec<_> = values.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        for ((model, provider, streaming), histogram) in entries {
+            let labels = format!(
+                "model=\"{}\",provider=\"{}\",streaming=\"{}\"",
+                escape_label_value(model),
+                escape_label_value(provider),
+                streaming
+            );
+            let mut cumulative = 0;
+            for (i, count) in histogram.buckets.iter().enumerate() {
+                cumulative += count;
+                let le = LATENCY_BUCKETS_SECONDS
+                    .get(i)
+                    .map_or_else(|| "+Inf".to_string(), |bound| format!("{:?}", bound));
+                let _ = write!(out, "{}_bucket{{{},le=\"{}\"}} {}", name, labels, le, cumulative);
+                if let Some(exemplar) = histogram.exemplars[i].as_ref().filter(|_| exemplars) {
+                    let _ = write!(
+                        out,
+                        " # {{TraceID=\"{}\"}} {} {:.3}",
+                        escape_label_value(&exemplar.trace_id),
+                        exemplar.value,
+                        exemplar.timestamp
+                    );
+                }
+                out.push('\n');
+            }
+            let _ = writeln!(out, "{}_sum{{{}}} {}", name, labels, histogram.sum);
+            let _ = writeln!(out, "{}_count{{{}}} {}", name, labels, histogram.count);
+        }
+    }
+}
+
+impl LatencyHistogram {
+    ///
+    /// Record an observation.
+    ///
+    /// # Arguments
+    ///  * `latency` - observed duration
+    pub fn record(&self, latency: Duration) {
+        let millis = u64::try_from(latency.as_millis()).unwrap_or(u64::MAX);
+        let index = LATENCY_HISTOGRAM_BUCKETS_MS.iter().position(|&bound| millis <= bound);
+        self.counts[index.unwrap_or(LATENCY_HISTOGRAM_BUCKETS_MS.len())]
+            .fetch_add(1, Ordering::Relaxed);
+        self.sum_ms.fetch_add(millis, Ordering::Relaxed);
+        self.max_ms.fetch_max(millis, Ordering::Relaxed);
+    }
+
+    ///
+    /// Estimate a percentile of the observations.
+    ///
+    /// # Arguments
+    ///  * `quantile` - quantile between 0 and 1, e.g. `0.95`
+    ///
+    /// # Returns
+    ///  * Upper bound in milliseconds of the bucket holding the percentile,
+    ///    capped at the largest observation (which is also the value for the
+    ///    `+Inf` bucket), or `None` before the first observation
+    pub fn percentile(&self, quantile: f64) -> Option<u64> {
+        let counts: Vec<u64> =
+            self.counts.iter().map(|count| count.load(Ordering::Relaxed)).collect();
+        let total: u64 = counts.iter().sum();
+        if total == 0 {
+            return None;
+        }
+
+        let max = self.max_ms.load(Ordering::Relaxed);
+        let target = ((total as f64 * quantile).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (index, count) in counts.iter().enumerate() {
+            seen += count;
+            if seen >= target {
+                return Some(LATENCY_HISTOGRAM_BUCKETS_MS.get(index).map_or(max, |&b| b.min(max)));
+            }
+        }
+        Some(max)
+    }
+
+    ///
+    /// Median latency in milliseconds.
+    pub fn p50(&self) -> Option<u64> {
+        self.percentile(0.5)
+    }
+
+    ///
+    /// 95th percentile latency in milliseconds.
+    pub fn p95(&self) -> Option<u64> {
+        self.percentile(0.95)
+    }
+
+    ///
+    /// 99th percentile latency in milliseconds.
+    pub fn p99(&self) -> Option<u64> {
+        self.percentile(0.99)
+    }
+
+    ///
+    /// Append the histogram samples in Prometheus text format.
+    ///
+    /// # Arguments
+    ///  * `out` - output buffer
+    ///  * `name` - metric family name
+    ///  * `labels` - labels placed before `le`, e.g. `provider="vertex",`
+    fn render_samples(&self, out: &mut String, name: &str, labels: &str) {
+        let mut cumulative = 0;
+        for (i, count) in self.counts.iter().enumerate() {
+            cumulative += count.load(Ordering::Relaxed);
+            let le = LATENCY_HISTOGRAM_BUCKETS_MS
+                .get(i)
+                .map_or_else(|| "+Inf".to_string(), |bound| bound.to_string());
+            let _ = writeln!(out, "{}_bucket{{{}le=\"{}\"}} {}", name, labels, le, cumulative);
+        }
+        let labels = labels.trim_end_matches(',');
+        let labels = if labels.is_empty() { String::new() } else { format!("{{{}}}", labels) };
+        let _ = writeln!(out, "{}_sum{} {}", name, labels, self.sum_ms.load(Ordering::Relaxed));
+        let _ = writeln!(out, "{}_count{} {}", name, labels, cumulative);
+    }
+
+    ///
+    /// Append the histogram in Prometheus text format.
+    ///
+    /// # Arguments
+    ///  * `out` - output buffer
+    ///  * `name` - metric family name
+    ///  * `help` - metric description
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        self.render_samples(out, name, "");
+    }
+}
+
+impl ProviderLatency {
+    ///
+    /// Record an observation of a provider.
+    ///
+    /// # Arguments
+    ///  * `provider` - provider id, e.g. `vertex`
+    ///  * `latency` - observed duration
+    pub fn record(&self, provider: &str, latency: Duration) {
+        if let Some(histogram) = self.values.read().unwrap_or_else(|e| e.into_inner()).get(provider)
+        {
+            histogram.record(latency);
+            return;
+        }
+        self.values
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(provider.to_string())
+            .or_default()
+            .record(latency);
+    }
+
+    ///
+    /// Percentiles per provider for the `/health` endpoint.
+    ///
+    /// # Returns
+    ///  * `{"<provider>": {"p50_ms", "p95_ms", "p99_ms"}}`
+    pub fn to_json(&self) -> Value {
+        let values = self.values.read().unwrap_or_else(|e| e.into_inner());
+        let providers: serde_json::Map<String, Value> = values
+            .iter()
+            .map(|(provider, histogram)| {
+                let percentiles = json!({
+                    "p50_ms": histogram.p50(),
+                    "p95_ms": histogram.p95(),
+                    "p99_ms": histogram.p99(),
+                });
+                (provider.clone(), percentiles)
+            })
+            .collect();
+        Value::Object(providers)
+    }
+
+    ///
+    /// Append the histograms in Prometheus text format, labeled by provider.
+    ///
+    /// Nothing is written before the first request.
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        let values = self.values.read().unwrap_or_else(|e| e.into_inner());
+        if values.is_empty() {
+            return;
+        }
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+
+        let mut entries: Vec<_> = values.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        for (provider, histogram) in entries {
+            let labels = format!("provider=\"{}\",", escape_label_value(provider));
+            histogram.render_samples(out, name, &labels);
+        }
+    }
+}
+
+impl AppMetrics {
+    ///
+    /// Append the global counters and the latency summary in Prometheus text format.
+    ///
+    /// Counters that also exist labeled by model (requests, quota errors,
+    /// retries) are exported only once, by [MetricsRegistry].
+    ///
+    /// # Arguments
+    ///  * `out` - output buffer
+    ///  * `openmetrics` - name counter families without the `_total` suffix
+    pub fn render(&self, out: &mut String, openmetrics: bool) {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        render_counter(
+            out,
+            "modelmux_request_outcomes_total",
+            "Requests of all endpoints by outcome",
+            openmetrics,
+            &[
+                ("status=\"success\"", load(&self.successful_requests)),
+                ("status=\"failure\"", load(&self.failed_requests)),
+            ],
+        );
+        render_counter(
+            out,
+            "modelmux_semantic_cache_lookups_total",
+            "Semantic cache lookups by result",
+            openmetrics,
+            &[
+                ("result=\"hit\"", load(&self.semantic_cache_hits)),
+                ("result=\"miss\"", load(&self.semantic_cache_misses)),
+            ],
+        );
+        let semantic_cache_lookups =
+            load(&self.semantic_cache_hits) + load(&self.semantic_cache_misses);
+        if semantic_cache_lookups > 0 {
+            let name = "modelmux_semantic_cache_hit_ratio";
+            let _ = writeln!(out, "# HELP {} Share of semantic cache lookups that were hits", name);
+            let _ = writeln!(out, "# TYPE {} gauge", name);
+            let ratio = load(&self.semantic_cache_hits) as f64 / semantic_cache_lookups as f64;
+            let _ = writeln!(out, "{} {}", name, ratio);
+        }
+        render_counter(
+            out,
+            "modelmux_failover_activations_total",
+            "Requests sent to the failover provider after the primary failed",
+            openmetrics,
+            &[("", load(&self.failover_activations))],
+        );
+        render_counter(
+            out,
+            "modelmux_response_cache_hits_total",
+            "Requests answered from the upstream response cache",
+            openmetrics,
+            &[("", load(&self.anthropic_cache_hits))],
+        );
+        render_counter(
+            out,
+            "modelmux_response_cache_misses_total",
+            "Cacheable requests not found in the upstream response cache",
+            openmetrics,
+            &[("", load(&self.anthropic_cache_misses))],
+        );
+        render_counter(
+            out,
+            "modelmux_streaming_idempotency_hits_total",
+            "Streams served from an earlier stream with the same idempotency key",
+            openmetrics,
+            &[("", load(&self.streaming_idempotency_hits))],
+        );
+        render_counter(
+            out,
+            "modelmux_dedup_coalesced_requests_total",
+            "Requests answered with the upstream response of an identical concurrent request",
+            openmetrics,
+            &[("", load(&self.dedup_coalesced_requests))],
+        );
+        render_counter(
+            out,
+            "modelmux_sse_dropped_chunks_total",
+            "SSE chunks dropped because the client did not read them in time",
+            openmetrics,
+            &[("", load(&self.sse_dropped_chunks))],
+        );
+        render_counter(
+            out,
+            "modelmux_sse_truncated_streams_total",
+            "SSE streams closed with an error event because chunks were dropped",
+            openmetrics,
+            &[("", load(&self.sse_truncated_streams))],
+        );
+
+        let name = "modelmux_chat_completion_latency_seconds";
+        let _ = writeln!(out, "# HELP {} Wall-clock duration of chat completions", name);
+        let _ = writeln!(out, "# TYPE {} summary", name);
+        for quantile in LATENCY_QUANTILES {
+            if let Some(millis) = self.request_latency.percentile(quantile) {
+                let seconds = millis as f64 / 1_000.0;
+                let _ = writeln!(out, "{}{{quantile=\"{}\"}} {}", name, quantile, seconds);
+            }
+        }
+        let sum = load(&self.total_latency_us) as f64 / 1_000_000.0;
+        let _ = writeln!(out, "{}_sum {}", name, sum);
+        let _ = writeln!(out, "{}_count {}", name, load(&self.total_latency_count));
+
+        self.request_latency.render(
+            out,
+            "modelmux_request_duration_ms",
+            "Wall-clock duration of chat completions in milliseconds",
+        );
+        self.upstream_latency.render(
+            out,
+            "modelmux_upstream_duration_ms",
+            "Time from sending the upstream request to its last response byte in milliseconds",
+        );
+        self.provider_latency.render(
+            out,
+            "modelmux_provider_request_duration_ms",
+            "Wall-clock duration of chat completions per provider in milliseconds",
+        );
+    }
+}
+
+impl MetricsRegistry {
+    ///
+    /// Resolve the label set of a request.
+    ///
+    /// # Arguments
+    ///  * `config` - application configuration
+    ///  * `requested_model` - model requested by the client, if any
+    ///  * `is_streaming` - whether the response is streamed
+    ///
+    /// Only model names the configuration knows (default model, aliases,
+    /// routes, `[models]` entries) become label values; any other requested
+    /// name is counted as `"other"`, so clients cannot create
+    /// an unbounded number of series.
+    ///
+    /// # Returns
+    ///  * Label set, using the default model when none was requested
+    pub fn labels(
+        config: &Config,
+        requested_model: Option<&str>,
+        is_streaming: bool,
+    ) -> MetricLabels {
+        let model = match requested_model {
+            None => config.llm_model(),
+            Some(requested) => config.known_model_name(requested).unwrap_or(OTHER_MODEL_LABEL),
+        };
+        let provider = config.llm_provider.as_ref().map_or("unknown", |p| p.id());
+        (model.to_string(), provider.to_string(), is_streaming)
+    }
+
+    ///
+    /// Render all metrics in Prometheus text format.
+    ///
+    /// # Arguments
+    ///  * `app` - global counters and latency summary exported after the labeled metrics
+    ///  * `openmetrics` - use the OpenMetrics format with latency exemplars
+    ///
+    /// # Returns
+    ///  * Metrics text
+    pub fn render(&self, app: &AppMetrics, openmetrics: bool) -> String {
+        let mut out = String::new();
+        self.render_families(&mut out, openmetrics);
+        app.render(&mut out, openmetrics);
+        if openmetrics {
+            out.push_str("# EOF\n");
+        }
+        out
+    }
+
+    ///
+    /// Append all labeled metric families in Prometheus text format.
+    ///
+    /// # Arguments
+    ///  * `out` - output buffer
+    ///  * `openmetrics` - use the OpenMetrics format with latency exemplars
+    fn render_families(&self, out: &mut String, openmetrics: bool) {
+        self.requests_total.render(
+            out,
+            "modelmux_requests_total",
+            "Requests received",
+            openmetrics,
+        );
+        self.requests_succeeded.render(
+            out,
+            "modelmux_requests_succeeded_total",
+            "Requests that completed successfully",
+            openmetrics,
+        );
+        self.requests_failed.render(
+            out,
+            "modelmux_requests_failed_total",
+            "Requests that failed",
+            openmetrics,
+        );
+        self.quota_errors.render(
+            out,
+            "modelmux_quota_errors_total",
+            "Upstream quota errors that were retried",
+            openmetrics,
+        );
+        self.retry_attempts.render(
+            out,
+            "modelmux_retry_attempts_total",
+            "Upstream retry attempts",
+            openmetrics,
+        );
+        self.request_duration.render(
+            out,
+            "modelmux_request_duration_seconds",
+            "Time until the response or the start of the stream was ready",
+            openmetrics,
+        );
+        self.thinking_duration.render(
+            out,
+            "modelmux_thinking_duration_seconds",
+            "Duration of streamed extended thinking blocks",
+            openmetrics,
+        );
+        self.post_thinking_latency.render(
+            out,
+            "modelmux_post_thinking_latency_seconds",
+            "Time from the end of extended thinking to the first response text",
+            openmetrics,
+        );
+        self.region_requests.render(
+            out,
+            "modelmux_region_requests_total",
+            "Upstream requests sent to each Vertex region",
+            openmetrics,
+        );
+    }
+}
+
+///
+/// Extract the trace ID of a request from its W3C `traceparent` header.
+///
+/// # Arguments
+///  * `headers` - request headers
+///
+/// # Returns
+///  * 32-character hex trace ID, or `None` when the header is missing or invalid
+pub fn trace_id_from_headers(headers: &HeaderMap) -> Option<String> {
+    let traceparent = headers.get(TRACEPARENT_HEADER)?.to_str().ok()?;
+    let mut parts = traceparent.trim().split('-');
+    let (_version, trace_id) = (parts.next()?, parts.next()?);
+    let valid = trace_id.len() == 32
+        && trace_id.bytes().all(|b| b.is_ascii_hexdigit())
+        && trace_id.bytes().any(|b| b != b'0');
+    valid.then(|| trace_id.to_ascii_lowercase())
+}
+
+///
+/// Append an unlabeled or statically labeled counter in Prometheus text format.
+///
+/// # Arguments
+///  * `out` - output buffer
+///  * `name` - metric name, ending in `_total`
+///  * `help` - metric description
+///  * `openmetrics` - name the family without the `_total` suffix
+///  * `samples` - label string (without braces, empty for none) and value per sample
+fn render_counter(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    openmetrics: bool,
+    samples: &[(&str, u64)],
+) {
+    let family = if openmetrics { name.trim_end_matches("_total") } else { name };
+    let _ = writeln!(out, "# HELP {} {}", family, help);
+    let _ = writeln!(out, "# TYPE {} counter", family);
+    for (labels, value) in samples {
+        if labels.is_empty() {
+            let _ = writeln!(out, "{} {}", name, value);
+        } else {
+            let _ = writeln!(out, "{}{{{}}} {}", name, labels, value);
+        }
+    }
+}
+
+///
+/// Escape a label value for the Prometheus text format.
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+///
+/// Prometheus metrics endpoint.
+///
+/// # Arguments
+///  * `state` - application state
+///
+/// # Returns
+///  * Labeled request counters, global counters and the latency summary in
+///    Prometheus text format
+pub async fn prometheus_metrics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let openmetrics = state.config.read().await.telemetry.prometheus_exemplars;
+    let content_type = if openmetrics { OPENMETRICS_CONTENT_TYPE } else { PROMETHEUS_CONTENT_TYPE };
+    ([(CONTENT_TYPE, content_type)], state.labeled_metrics.render(&state.metrics, openmetrics))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_labeled_counters_render() {
+        let registry = MetricsRegistry::default();
+        let streaming = ("claude-sonnet".to_string(), "vertex".to_string(), true);
+        let blocking = ("claude-opus".to_string(), "vertex".to_string(), false);
+
+        registry.requests_total.inc(&streaming);
+        registry.requests_total.inc(&streaming);
+        registry.requests_total.inc(&blocking);
+        registry.requests_failed.inc(&blocking);
+        assert_eq!(registry.requests_total.get(&streaming), 2);
+        assert_eq!(registry.requests_failed.get(&streaming), 0);
+
+        let output = registry.render(&AppMetrics::default(), false);
+        assert!(output.contains("# TYPE modelmux_requests_total counter\n"));
+        assert!(output.contains(
+            "modelmux_requests_total{model=\"claude-sonnet\",provider=\"vertex\",streaming=\"true\"} 2\n"
+        ));
+        assert!(output.contains(
+            "modelmux_requests_failed_total{model=\"claude-opus\",provider=\"vertex\",streaming=\"false\"} 1\n"
+        ));
+    }
+
+    #[test]
+    fn test_labels_bucket_unknown_models() {
+        let mut config = Config::default();
+        config.model_aliases.insert("fast".to_string(), "claude-haiku".to_string());
+
+        let model = |requested| MetricsRegistry::labels(&config, requested, false).0;
+        assert_eq!(model(Some("FAST")), "fast");
+        assert_eq!(model(Some("claude-haiku")), "claude-haiku");
+        assert_eq!(model(Some("random-4f9a")), OTHER_MODEL_LABEL);
+        assert_eq!(model(None), config.llm_model());
+    }
+
+    #[test]
+    fn test_latency_histogram_exemplars() {
+        let registry = MetricsRegistry::default();
+        let labels = ("claude-sonnet".to_string(), "vertex".to_string(), false);
+        registry.request_duration.observe(&labels, 0.95, Some("4bf92f3577b34da6a3ce929d0e0e4736"));
+        registry.request_duration.observe(&labels, 0.05, None);
+        registry.requests_total.inc(&labels);
+
+        let prefix = "modelmux_request_duration_seconds_bucket{model=\"claude-sonnet\",\
+                      provider=\"vertex\",streaming=\"false\",";
+        let classic = registry.render(&AppMetrics::default(), false);
+        assert!(classic.contains(&format!("{}le=\"0.1\"}} 1\n", prefix)));
+        assert!(classic.contains(&format!("{}le=\"1.0\"}} 2\n", prefix)));
+        assert!(classic.contains(&format!("{}le=\"+Inf\"}} 2\n", prefix)));
+        assert!(!classic.contains("TraceID"));
+        assert!(classic.contains("# TYPE modelmux_requests_total counter\n"));
+
+        let openmetrics = registry.render(&AppMetrics::default(), true);
+        assert!(openmetrics.contains(&format!(
+            "{}le=\"1.0\"}} 2 # {{TraceID=\"4bf92f3577b34da6a3ce929d0e0e4736\"}} 0.95 ",
+            prefix
+        )));
+        assert!(openmetrics.contains("# TYPE modelmux_requests counter\n"));
+        assert!(openmetrics.ends_with("# EOF\n"));
+    }
+
+    #[test]
+    fn test_app_metrics_latency_summary() {
+        let app = AppMetrics::default();
+        assert_eq!(app.request_latency.p50(), None);
+        for millis in 1..=100 {
+            app.record_latency(std::time::Duration::from_millis(millis * 10));
+        }
+        app.successful_requests.fetch_add(7, Ordering::Relaxed);
+        assert_eq!(app.total_latency_count.load(Ordering::Relaxed), 100);
+        assert_eq!(app.total_latency_us.load(Ordering::Relaxed), 50_500_000);
+        assert_eq!(app.request_latency.p50(), Some(500));
+        assert_eq!(app.request_latency.p99(), Some(1_000));
+
+        app.record_latency(std::time::Duration::from_secs(600));
+        assert_eq!(app.request_latency.percentile(1.0), Some(600_000));
+
+        let output = MetricsRegistry::default().render(&app, false);
+        assert!(output.contains("modelmux_request_outcomes_total{status=\"success\"} 7\n"));
+        assert!(output.contains("# TYPE modelmux_chat_completion_latency_seconds summary\n"));
+        assert!(output.contains("modelmux_chat_completion_latency_seconds{quantile=\"0.99\"} 1\n"));
+        assert!(output.contains("modelmux_chat_completion_latency_seconds_sum 650.5\n"));
+        assert!(output.contains("modelmux_chat_completion_latency_seconds_count 101\n"));
+
+        let openmetrics = MetricsRegistry::default().render(&app, true);
+        assert!(openmetrics.contains("# TYPE modelmux_sse_dropped_chunks counter\n"));
+        assert!(openmetrics.ends_with("# EOF\n"));
+    }
+
+    #[test]
+    fn test_latency_histogram_percentiles() {
+        let histogram = LatencyHistogram::default();
+        assert_eq!(histogram.p50(), None);
+
+        // 60 requests of 40ms, 30 of 200ms, 8 of 800ms and 2 of 15s
+        for (millis, count) in [(40, 60), (200, 30), (800, 8), (15_000, 2)] {
+            for _ in 0..count {
+                histogram.record(Duration::from_millis(millis));
+            }
+        }
+        assert_eq!(histogram.p50(), Some(50));
+        assert_eq!(histogram.percentile(0.6), Some(50));
+        assert_eq!(histogram.percentile(0.61), Some(250));
+        assert_eq!(histogram.p95(), Some(1_000));
+        assert_eq!(histogram.p99(), Some(15_000));
+
+        // Bucket bounds above the largest observation are capped
+        let fast = LatencyHistogram::default();
+        fast.record(Duration::from_millis(3));
+        assert_eq!(fast.p99(), Some(3));
+
+        let providers = ProviderLatency::default();
+        providers.record("vertex", Duration::from_millis(80));
+        assert_eq!(providers.to_json()["vertex"]["p50_ms"], 80);
+
+        let app = AppMetrics::default();
+        app.record_latency(Duration::from_millis(80));
+        app.provider_latency.record("vertex", Duration::from_millis(80));
+        let output = MetricsRegistry::default().render(&app, false);
+        assert!(output.contains("# TYPE modelmux_request_duration_ms histogram\n"));
+        assert!(output.contains("modelmux_request_duration_ms_bucket{le=\"50\"} 0\n"));
+        assert!(output.contains("modelmux_request_duration_ms_bucket{le=\"100\"} 1\n"));
+        assert!(output.contains("modelmux_request_duration_ms_bucket{le=\"+Inf\"} 1\n"));
+        assert!(output.contains("modelmux_request_duration_ms_sum 80\n"));
+        assert!(output.contains("modelmux_upstream_duration_ms_count 0\n"));
+        assert!(output.contains(
+            "modelmux_provider_request_duration_ms_bucket{provider=\"vertex\",le=\"100\"} 1\n"
+        ));
+        assert!(
+            output.contains("modelmux_provider_request_duration_ms_count{provider=\"vertex\"} 1\n")
+        );
+    }
+
+    #[test]
+    fn test_trace_id_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(trace_id_from_headers(&headers), None);
+
+        headers.insert(
+            TRACEPARENT_HEADER,
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01".parse().unwrap(),
+        );
+        assert_eq!(
+            trace_id_from_headers(&headers).as_deref(),
+            Some("4bf92f3577b34da6a3ce929d0e0e4736")
+        );
+
+        headers.insert(
+            TRACEPARENT_HEADER,
+            format!("00-{}-00f067aa0ba902b7-01", "0".repeat(32)).parse().unwrap(),
+        );
+        assert_eq!(trace_id_from_headers(&headers), None);
+    }
+
+    #[test]
+    fn test_escape_label_value() {
+        assert_eq!(escape_label_value("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
@@ -0,0 +1,168 @@
+//!
+//! Chat completions forwarded to OpenAI-compatible upstreams.
+//!
+//! When the LLM provider is `openai_compatible` or `azure_openai` the upstream
+//! already speaks the OpenAI format, so requests skip both converters: the body
+//! is sent unchanged (only `model` is filled in when missing) with the
+//! provider's Bearer token (Azure: `api-key` header), and the upstream status,
+//! content type and body, streamed or not, are returned to the client as they
+//! are.
+//!
+//! The request is still parsed and checked against `[limits]`, and request
+//! hooks run on the parsed copy so they can reject it; changes a hook makes
+//! are not forwarded. Upstream `5xx` responses and unreachable upstreams count
+//! against the circuit breaker.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::http::header::{CACHE_CONTROL, CONTENT_TYPE};
+use axum::http::{HeaderValue, StatusCode};
+use axum::response::Response;
+use serde_json::Value;
+use tokio_stream::StreamExt;
+
+use super::circuit_breaker::is_upstream_failure;
+use super::{
+    AppState, JSON_CONTENT_TYPE, SSE_CACHE_CONTROL, SSE_CONTENT_TYPE, get_request_authorization,
+    parse_openai_request, run_request_hooks, upstream_request_error,
+};
+use crate::auth::Authorization;
+use crate::error::{ProxyError, Result};
+use crate::provider::{LlmProviderBackend, LlmProviderConfig};
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+///
+/// Forward a chat completion request to an OpenAI-compatible upstream.
+///
+/// # Arguments
+///  * `state` - shared application state
+///  * `provider` - OpenAI-compatible or Azure OpenAI provider to forward to
+///  * `request` - OpenAI format request JSON
+///
+/// # Returns
+///  * Upstream response, including upstream error responses
+///  * `ProxyError` when the request breaks `[limits]`, a hook rejects it, the
+///    circuit is open or the upstream cannot be reached
+#[tracing::instrument(
+    name = "modelmux.upstream_request",
+    skip_all,
+    fields(url = tracing::field::Empty, status = tracing::field::Empty)
+)]
+pub async fn forward_chat_completion(
+    state: Arc<AppState>,
+    provider: &LlmProviderConfig,
+    mut request: Value,
+) -> Result<Response> {
+    let obj = request.as_object_mut().ok_or_else(|| {
+        ProxyError::Conversion("Chat completion request must be a JSON object".to_string())
+    })?;
+    if !obj.get("model").is_some_and(Value::is_string) {
+        obj.insert("model".to_string(), Value::String(provider.display_model_name().to_string()));
+    }
+    let is_streaming = obj.get("stream").and_then(Value::as_bool).unwrap_or(false);
+
+    let limits = state.config.read().await.limits.clone();
+    let mut openai_request = parse_openai_request(request.clone(), &limits)?;
+    run_request_hooks(&state, &mut openai_request).await?;
+
+    let url = provider.build_request_url(is_streaming);
+    tracing::Span::current().record("url", url.as_str());
+    tracing::debug!("Forwarding chat completion to OpenAI-compatible upstream: {}", url);
+
+    let authorization = match provider.api_key_header() {
+        Some(api_key) => Authorization::ApiKey(api_key.to_string()),
+        None => get_request_authorization(state.clone()).await?,
+    };
+    let body = serde_json::to_vec(&request)?;
+    let request = authorization
+        .apply(state.http_client.post(&url).header(CONTENT_TYPE, JSON_CONTENT_TYPE), &url, &body)?
+        .body(body);
+
+    state.circuit_breaker.try_acquire()?;
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(e) => {
+            let error = upstream_request_error(&url, e);
+            state.circuit_breaker.record_outcome(is_upstream_failure(&error));
+            return Err(error);
+        }
+    };
+
+    let status = response.status();
+    tracing::Span::current().record("status", status.as_u16());
+    state.circuit_breaker.record_outcome(status.is_server_error());
+    if !status.is_success() {
+        tracing::warn!("OpenAI-compatible upstream returned {}", status);
+    }
+    Ok(into_response(response, state))
+}
+
+///
+/// Records the size of a relayed response body once the body is dropped.
+struct RelayedBytes {
+    /** shared application state with the body size metrics */
+    state: Arc<AppState>,
+    /** body bytes relayed so far */
+    bytes: u64,
+}
+
+impl Drop for RelayedBytes {
+    fn drop(&mut self) {
+        self.state.metrics.record_response_bytes(self.bytes);
+    }
+}
+
+///
+/// Relay an upstream response, streaming its body through.
+///
+/// JSON and SSE content types are normalized to the proxy's own, with an
+/// explicit UTF-8 charset; other content types are relayed unchanged. The
+/// body size of successful responses is recorded in the metrics.
+fn into_response(response: reqwest::Response, state: Arc<AppState>) -> Response {
+    let status =
+        StatusCode::from_u16(response.status().as_u16()).unwrap_or(StatusCode::BAD_GATEWAY);
+    let mut counter = status.is_success().then_some(RelayedBytes { state, bytes: 0 });
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| HeaderValue::from_bytes(value.as_bytes()).ok());
+    let media_type = content_type
+        .as_ref()
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(|value| value.trim().to_ascii_lowercase());
+
+    let body = response.bytes_stream().map(move |chunk| {
+        if let (Some(counter), Ok(chunk)) = (counter.as_mut(), chunk.as_ref()) {
+            counter.bytes += chunk.len() as u64;
+        }
+        chunk
+    });
+    let mut relayed = Response::new(Body::from_stream(body));
+    *relayed.status_mut() = status;
+    let headers = relayed.headers_mut();
+    match media_type.as_deref() {
+        Some("application/json") => {
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static(JSON_CONTENT_TYPE));
+        }
+        Some("text/event-stream") => {
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static(SSE_CONTENT_TYPE));
+            headers.insert(CACHE_CONTROL, HeaderValue::from_static(SSE_CACHE_CONTROL));
+        }
+        _ => {
+            if let Some(content_type) = content_type {
+                headers.insert(CONTENT_TYPE, content_type);
+            }
+        }
+    }
+    relayed
+}
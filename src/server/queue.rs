@@ -0,0 +1,383 @@
+//!
+//! Priority queue for chat completion requests.
+//!
+//! Agent frameworks issuing many parallel requests can starve interactive
+//! users. With `[queue] enabled = true`, chat completions are queued by the
+//! `X-Priority: high|normal|low` header and a single dispatcher task starts at
+//! most `queue.max_concurrent` of them at a time, always draining `high`
+//! before `normal` before `low`. `high` is only honoured for requests carrying
+//! the admin secret; others are queued as `normal`.
+//!
+//! A request still waiting after `queue.max_wait_ms` is rejected with 503 and
+//! a `Retry-After` header. A request counts as running until its response is
+//! ready, so streams release their slot once streaming starts.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use axum::http::HeaderMap;
+use axum::response::Response;
+use serde_json::Value;
+use tokio::sync::{Semaphore, mpsc, oneshot};
+use tracing::Instrument;
+
+use super::{
+    ADMIN_SECRET_HEADER, AppState, admin_secret_matches, process_chat_completion,
+    request_id_from_headers, with_request_id,
+};
+use crate::config::QueueConfig;
+use crate::error::{ProxyError, Result};
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// Scheduling priority of a queued request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    /// Interactive requests, requires the admin secret
+    High,
+    /// Default priority
+    Normal,
+    /// Background work such as agent fan-out
+    Low,
+}
+
+///
+/// Chat completion request waiting for the dispatcher.
+pub struct QueuedRequest {
+    /** OpenAI format request JSON */
+    request: Value,
+    /** headers of the original request, used for client detection */
+    headers: HeaderMap,
+    /** notified when the request leaves the queue; closed once the client gave up */
+    dispatched: oneshot::Sender<()>,
+    /** receives the response once the request ran */
+    response_sender: oneshot::Sender<Result<Response>>,
+    /** when the request was queued */
+    enqueued_at: Instant,
+    /** span of the submitting handler, parent of the spans of the run */
+    span: tracing::Span,
+}
+
+///
+/// Per-priority request queues feeding one dispatcher.
+pub struct RequestQueue {
+    /** sending sides, indexed by `Priority::index` */
+    senders: [mpsc::Sender<QueuedRequest>; 3],
+    /** receiving sides, taken once when the dispatcher starts */
+    receivers: std::sync::Mutex<Option<[mpsc::Receiver<QueuedRequest>; 3]>>,
+    /** requests running at once */
+    max_concurrent: usize,
+    /** longest time a request may wait for a slot */
+    max_wait: Duration,
+    /** queue wait histogram, one counter per entry of `WAIT_BUCKETS_MS` plus overflow */
+    wait_buckets: [AtomicU64; WAIT_BUCKETS_MS.len() + 1],
+}
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** Request header selecting the queue priority */
+const PRIORITY_HEADER: &str = "X-Priority";
+
+/** Upper bounds of the queue wait histogram buckets, in milliseconds */
+const WAIT_BUCKETS_MS: [u64; 14] =
+    [1, 2, 5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000, 10000, 30000];
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+impl Priority {
+    ///
+    /// Priority requested by a client.
+    ///
+    /// # Arguments
+    ///  * `headers` - request headers
+    ///  * `admin_secret` - configured admin secret, required for `high`
+    ///
+    /// # Returns
+    ///  * Requested priority; `normal` when missing, unknown, or `high` without
+    ///    a matching `X-Admin-Secret`
+    pub fn from_headers(headers: &HeaderMap, admin_secret: Option<&str>) -> Self {
+        let requested = headers.get(PRIORITY_HEADER).and_then(|value| value.to_str().ok());
+        match requested.map(|value| value.trim().to_ascii_lowercase()).as_deref() {
+            Some("low") => Priority::Low,
+            Some("high") => {
+                let provided = headers.get(ADMIN_SECRET_HEADER).and_then(|v| v.to_str().ok());
+                match (provided, admin_secret) {
+                    (Some(provided), Some(expected))
+                        if admin_secret_matches(provided, expected) =>
+                    {
+                        Priority::High
+                    }
+                    _ => {
+                        tracing::debug!("X-Priority: high without admin secret, using normal");
+                        Priority::Normal
+                    }
+                }
+            }
+            _ => Priority::Normal,
+        }
+    }
+
+    ///
+    /// Name used in metrics.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Priority::High => "high",
+            Priority::Normal => "normal",
+            Priority::Low => "low",
+        }
+    }
+
+    ///
+    /// Position of the priority's queue.
+    fn index(self) -> usize {
+        match self {
+            Priority::High => 0,
+            Priority::Normal => 1,
+            Priority::Low => 2,
+        }
+    }
+}
+
+impl RequestQueue {
+    ///
+    /// Create empty queues. The dispatcher is started separately by [`start_dispatcher`].
+    ///
+    /// # Arguments
+    ///  * `config` - request queue configuration
+    pub fn new(config: &QueueConfig) -> Self {
+        let capacity = config.capacity.max(1);
+        let (high_tx, high_rx) = mpsc::channel(capacity);
+        let (normal_tx, normal_rx) = mpsc::channel(capacity);
+        let (low_tx, low_rx) = mpsc::channel(capacity);
+        Self {
+            senders: [high_tx, normal_tx, low_tx],
+            receivers: std::sync::Mutex::new(Some([high_rx, normal_rx, low_rx])),
+            max_concurrent: config.max_concurrent.max(1),
+            max_wait: Duration::from_millis(config.max_wait_ms),
+            wait_buckets: Default::default(),
+        }
+    }
+
+    ///
+    /// Queue a chat completion and wait for its response.
+    ///
+    /// # Arguments
+    ///  * `priority` - queue to wait in
+    ///  * `request` - OpenAI format request JSON
+    ///  * `headers` - headers of the original request
+    ///
+    /// # Returns
+    ///  * Response of the request
+    ///  * `ProxyError::QueueTimeout` when the queue is full or no slot freed
+    ///    up within `queue.max_wait_ms`
+    pub async fn submit(
+        &self,
+        priority: Priority,
+        request: Value,
+        headers: HeaderMap,
+    ) -> Result<Response> {
+        let (dispatched, mut dispatched_receiver) = oneshot::channel();
+        let (response_sender, response_receiver) = oneshot::channel();
+        let enqueued_at = Instant::now();
+        let span = tracing::Span::current();
+        let queued =
+            QueuedRequest { request, headers, dispatched, response_sender, enqueued_at, span };
+        if self.senders[priority.index()].try_send(queued).is_err() {
+            tracing::warn!("Request queue '{}' is full, rejecting request", priority.as_str());
+            return Err(self.timeout_error(Duration::ZERO));
+        }
+
+        // The wait limit only covers queueing; running requests are not cut off
+        if tokio::time::timeout(self.max_wait, &mut dispatched_receiver).await.is_err() {
+            dispatched_receiver.close();
+            if dispatched_receiver.try_recv().is_err() {
+                return Err(self.timeout_error(enqueued_at.elapsed()));
+            }
+        }
+        response_receiver.await.unwrap_or_else(|_| {
+            Err(ProxyError::Http("Queued request was dropped".to_string(), None))
+        })
+    }
+
+    ///
+    /// Requests waiting in a queue.
+    ///
+    /// # Arguments
+    ///  * `priority` - queue to inspect
+    pub fn depth(&self, priority: Priority) -> usize {
+        let sender = &self.senders[priority.index()];
+        sender.max_capacity() - sender.capacity()
+    }
+
+    ///
+    /// 99th percentile of the time requests waited for a slot.
+    ///
+    /// # Returns
+    ///  * Upper bound of the bucket holding the 99th percentile, in milliseconds
+    ///    (0 before any request was dispatched)
+    pub fn wait_ms_p99(&self) -> u64 {
+        let counts: Vec<u64> =
+            self.wait_buckets.iter().map(|bucket| bucket.load(Ordering::Relaxed)).collect();
+        let total: u64 = counts.iter().sum();
+        if total == 0 {
+            return 0;
+        }
+
+        let target = total.saturating_mul(99).div_ceil(100);
+        let mut seen = 0;
+        for (index, count) in counts.iter().enumerate() {
+            seen += count;
+            if seen >= target {
+                return WAIT_BUCKETS_MS.get(index).copied().unwrap_or(u64::MAX);
+            }
+        }
+        u64::MAX
+    }
+
+    ///
+    /// Record how long a dispatched request waited.
+    fn record_wait(&self, wait: Duration) {
+        let wait_ms = u64::try_from(wait.as_millis()).unwrap_or(u64::MAX);
+        let index = WAIT_BUCKETS_MS.iter().position(|&bound| wait_ms <= bound);
+        self.wait_buckets[index.unwrap_or(WAIT_BUCKETS_MS.len())].fetch_add(1, Ordering::Relaxed);
+    }
+
+    ///
+    /// Error returned for a request that could not be scheduled in time.
+    fn timeout_error(&self, waited: Duration) -> ProxyError {
+        ProxyError::QueueTimeout {
+            waited_ms: u64::try_from(waited.as_millis()).unwrap_or(u64::MAX),
+            retry_after_secs: self.max_wait.as_secs().max(1),
+        }
+    }
+}
+
+///
+/// Start the dispatcher task running queued requests.
+///
+/// Does nothing when the queue is disabled or the dispatcher already runs.
+///
+/// # Arguments
+///  * `state` - shared application state owning the request queue
+pub fn start_dispatcher(state: &Arc<AppState>) {
+    let Some(queue) = &state.request_queue else {
+        return;
+    };
+    let Some(receivers) = queue.receivers.lock().ok().and_then(|mut r| r.take()) else {
+        tracing::warn!("Request queue dispatcher already started");
+        return;
+    };
+
+    tracing::info!(
+        max_concurrent = queue.max_concurrent,
+        max_wait_ms = queue.max_wait.as_millis() as u64,
+        "Request queue enabled"
+    );
+    tokio::spawn(dispatch(state.clone(), receivers));
+}
+
+///
+/// Run queued requests, highest priority first, within the concurrency limit.
+async fn dispatch(state: Arc<AppState>, receivers: [mpsc::Receiver<QueuedRequest>; 3]) {
+    let Some(queue) = &state.request_queue else {
+        return;
+    };
+    let slots = Arc::new(Semaphore::new(queue.max_concurrent));
+    let [mut high, mut normal, mut low] = receivers;
+
+    loop {
+        let Ok(slot) = slots.clone().acquire_owned().await else {
+            break;
+        };
+        let queued = tokio::select! {
+            biased;
+            Some(queued) = high.recv() => queued,
+            Some(queued) = normal.recv() => queued,
+            Some(queued) = low.recv() => queued,
+            else => break,
+        };
+
+        // The client gave up (queue.max_wait_ms) while the request was queued
+        if queued.dispatched.send(()).is_err() {
+            continue;
+        }
+        queue.record_wait(queued.enqueued_at.elapsed());
+
+        let state = state.clone();
+        tokio::spawn(async move {
+            let request_id = request_id_from_headers(&queued.headers);
+            let result = with_request_id(
+                request_id,
+                process_chat_completion(state, queued.request, &queued.headers),
+            )
+            .instrument(queued.span)
+            .await;
+            let _ = queued.response_sender.send(result);
+            drop(slot);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_priority_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(Priority::from_headers(&headers, Some("secret")), Priority::Normal);
+
+        headers.insert(PRIORITY_HEADER, "LOW".parse().unwrap());
+        assert_eq!(Priority::from_headers(&headers, Some("secret")), Priority::Low);
+
+        // High priority needs the admin secret
+        headers.insert(PRIORITY_HEADER, "high".parse().unwrap());
+        assert_eq!(Priority::from_headers(&headers, Some("secret")), Priority::Normal);
+        headers.insert(ADMIN_SECRET_HEADER, "wrong".parse().unwrap());
+        assert_eq!(Priority::from_headers(&headers, Some("secret")), Priority::Normal);
+        headers.insert(ADMIN_SECRET_HEADER, "secret".parse().unwrap());
+        assert_eq!(Priority::from_headers(&headers, Some("secret")), Priority::High);
+        assert_eq!(Priority::from_headers(&headers, None), Priority::Normal);
+    }
+
+    #[tokio::test]
+    async fn test_submit_times_out_without_dispatcher() {
+        let queue = RequestQueue::new(&QueueConfig {
+            enabled: true,
+            max_concurrent: 1,
+            max_wait_ms: 10,
+            capacity: 1,
+        });
+
+        let result = queue.submit(Priority::Low, Value::Null, HeaderMap::new()).await;
+        assert!(matches!(result, Err(ProxyError::QueueTimeout { retry_after_secs: 1, .. })));
+        assert_eq!(queue.depth(Priority::Low), 1);
+        assert_eq!(queue.depth(Priority::High), 0);
+
+        // The queue is full now, so the next request is rejected at once
+        let result = queue.submit(Priority::Low, Value::Null, HeaderMap::new()).await;
+        assert!(matches!(result, Err(ProxyError::QueueTimeout { waited_ms: 0, .. })));
+    }
+
+    #[test]
+    fn test_wait_p99() {
+        let queue = RequestQueue::new(&QueueConfig::default());
+        assert_eq!(queue.wait_ms_p99(), 0);
+        for _ in 0..99 {
+            queue.record_wait(Duration::from_millis(3));
+        }
+        queue.record_wait(Duration::from_millis(800));
+        assert_eq!(queue.wait_ms_p99(), 5);
+        queue.record_wait(Duration::from_millis(800));
+        assert_eq!(queue.wait_ms_p99(), 1000);
+    }
+}
@@ -0,0 +1,352 @@
+//!
+//! Server-side accumulation of streamed chat completions.
+//!
+//! With `streaming.mode = "always"` every upstream call uses
+//! `streamRawPredict`, also for clients that sent `stream: false`. Their
+//! streamed response is collected here and returned as one
+//! `chat.completion` JSON body, so operators get streaming time-to-first-byte
+//! metrics without clients having to handle SSE.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use axum::http::StatusCode;
+use axum::response::Response;
+use serde_json::Value;
+
+use super::json_response;
+use crate::converter::anthropic_to_openai::{
+    OpenAiChoice, OpenAiFunctionCall, OpenAiResponse, OpenAiResponseMessage, OpenAiToolCall,
+    OpenAiUsage,
+};
+use crate::error::{ProxyError, Result};
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// Merges the chunks of one streamed chat completion into a single response.
+#[derive(Debug, Default)]
+pub struct StreamAccumulator {
+    /** id of the first chunk */
+    id: Option<String>,
+    /** creation timestamp of the first chunk */
+    created: i64,
+    /** model of the first chunk */
+    model: String,
+    /** `system_fingerprint` of the first chunk */
+    system_fingerprint: Option<String>,
+    /** concatenated text deltas */
+    content: String,
+    /** concatenated `reasoning_content` deltas */
+    reasoning_content: String,
+    /** tool calls, by their index in the deltas */
+    tool_calls: Vec<AccumulatedToolCall>,
+    /** legacy function call, from `function_call` deltas */
+    function_call: Option<AccumulatedToolCall>,
+    /** last finish reason seen */
+    finish_reason: Option<String>,
+    /** token usage of the usage chunk */
+    usage: Option<OpenAiUsage>,
+    /** error payload sent in place of a chunk */
+    error: Option<Value>,
+}
+
+///
+/// Tool call assembled from its deltas.
+#[derive(Debug, Default)]
+struct AccumulatedToolCall {
+    /** tool call identifier, from the first delta */
+    id: String,
+    /** function name, from the first delta */
+    name: String,
+    /** concatenated argument fragments */
+    arguments: String,
+}
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** Object type of the accumulated response */
+const CHAT_COMPLETION_OBJECT: &str = "chat.completion";
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+impl StreamAccumulator {
+    ///
+    /// Add the `data:` lines of an SSE body.
+    ///
+    /// # Arguments
+    ///  * `body` - SSE text; comments, `retry:` lines and `[DONE]` are skipped
+    pub fn push_sse(&mut self, body: &str) {
+        for line in body.lines() {
+            if let Some(data) = line.strip_prefix("data:") {
+                self.push(data.trim_start());
+            }
+        }
+    }
+
+    ///
+    /// Add one chunk.
+    ///
+    /// # Arguments
+    ///  * `data` - data of one SSE event, a `chat.completion.chunk` or error JSON
+    pub fn push(&mut self, data: &str) {
+        if data == "[DONE]" {
+            return;
+        }
+        let Ok(chunk) = serde_json::from_str::<Value>(data) else {
+            tracing::warn!("Skipping unparseable stream chunk: {}", data);
+            return;
+        };
+        if let Some(error) = chunk.get("error") {
+            self.error = Some(error.clone());
+            return;
+        }
+
+        if self.id.is_none() {
+            self.id = chunk["id"].as_str().map(str::to_string);
+            self.created = chunk["created"].as_i64().unwrap_or_default();
+            self.model = chunk["model"].as_str().unwrap_or_default().to_string();
+            self.system_fingerprint = chunk["system_fingerprint"].as_str().map(str::to_string);
+        }
+        if let Some(usage) = chunk.get("usage").filter(|usage| !usage.is_null()) {
+            let tokens = |field: &str| usage[field].as_u64().unwrap_or_default() as u32;
+            self.usage = Some(OpenAiUsage {
+                prompt_tokens: tokens("prompt_tokens"),
+                completion_tokens: tokens("completion_tokens"),
+                total_tokens: tokens("total_tokens"),
+            });
+        }
+
+        for choice in chunk["choices"].as_array().into_iter().flatten() {
+            if let Some(reason) = choice["finish_reason"].as_str() {
+                self.finish_reason = Some(reason.to_string());
+            }
+            let delta = &choice["delta"];
+            if let Some(text) = delta["content"].as_str() {
+                self.content.push_str(text);
+            }
+            if let Some(reasoning) = delta["reasoning_content"].as_str() {
+                self.reasoning_content.push_str(reasoning);
+            }
+            for tool_call in delta["tool_calls"].as_array().into_iter().flatten() {
+                self.push_tool_call(tool_call);
+            }
+            if delta["function_call"].is_object() {
+                let function_call = self.function_call.get_or_insert_with(Default::default);
+                function_call.push_function(&delta["function_call"]);
+            }
+        }
+    }
+
+    ///
+    /// Merge one tool call delta.
+    ///
+    /// # Arguments
+    ///  * `delta` - entry of `delta.tool_calls`
+    fn push_tool_call(&mut self, delta: &Value) {
+        let index = delta["index"].as_u64().unwrap_or_default() as usize;
+        if self.tool_calls.len() <= index {
+            self.tool_calls.resize_with(index + 1, AccumulatedToolCall::default);
+        }
+        let tool_call = &mut self.tool_calls[index];
+        if let Some(id) = delta["id"].as_str() {
+            tool_call.id = id.to_string();
+        }
+        tool_call.push_function(&delta["function"]);
+    }
+
+    ///
+    /// Build the non-streaming response.
+    ///
+    /// # Returns
+    ///  * `chat.completion` response with the accumulated message
+    ///  * Error payload of the stream, if the upstream failed before any output
+    pub fn finish(self) -> std::result::Result<OpenAiResponse, Value> {
+        let has_output =
+            !self.content.is_empty() || !self.tool_calls.is_empty() || self.function_call.is_some();
+        if let Some(error) = self.error.filter(|_| !has_output) {
+            return Err(error);
+        }
+
+        let tool_calls: Vec<OpenAiToolCall> = self
+            .tool_calls
+            .into_iter()
+            .map(|tool_call| OpenAiToolCall {
+                id: tool_call.id,
+                call_type: "function".to_string(),
+                function: OpenAiFunctionCall {
+                    name: tool_call.name,
+                    arguments: tool_call.arguments,
+                },
+            })
+            .collect();
+        let finish_reason = self.finish_reason.unwrap_or_else(|| {
+            if tool_calls.is_empty() { "stop" } else { "tool_calls" }.to_string()
+        });
+
+        Ok(OpenAiResponse {
+            id: self.id.unwrap_or_default(),
+            object: CHAT_COMPLETION_OBJECT.to_string(),
+            created: self.created,
+            model: self.model,
+            choices: vec![OpenAiChoice {
+                index: 0,
+                message: OpenAiResponseMessage {
+                    role: "assistant".to_string(),
+                    content: (!self.content.is_empty()).then_some(self.content),
+                    tool_calls: (!tool_calls.is_empty()).then_some(tool_calls),
+                    function_call: self.function_call.map(|function_call| OpenAiFunctionCall {
+                        name: function_call.name,
+                        arguments: function_call.arguments,
+                    }),
+                    reasoning_content: (!self.reasoning_content.is_empty())
+                        .then_some(self.reasoning_content),
+                },
+                finish_reason,
+            }],
+            usage: self.usage.unwrap_or(OpenAiUsage {
+                prompt_tokens: 0,
+                completion_tokens: 0,
+                total_tokens: 0,
+            }),
+            system_fingerprint: self.system_fingerprint,
+        })
+    }
+}
+
+impl AccumulatedToolCall {
+    ///
+    /// Merge the name and argument fragments of a function delta.
+    ///
+    /// # Arguments
+    ///  * `function` - `function` of a tool call delta, or a `function_call` delta
+    fn push_function(&mut self, function: &Value) {
+        if let Some(name) = function["name"].as_str() {
+            self.name.push_str(name);
+        }
+        if let Some(arguments) = function["arguments"].as_str() {
+            self.arguments.push_str(arguments);
+        }
+    }
+}
+
+///
+/// Collect an SSE chat completion response into a JSON one.
+///
+/// Waits until the stream ends.
+///
+/// # Arguments
+///  * `response` - SSE response of `handle_streaming_response`
+///
+/// # Returns
+///  * `chat.completion` JSON response with the original headers other than
+///    the content type, or a 502 with the stream's error payload
+///  * `ProxyError::Http` if the stream body cannot be read
+pub async fn collect_streaming_response(response: Response) -> Result<Response> {
+    let (mut parts, body) = response.into_parts();
+    let mut response = match collect_sse_body(body).await? {
+        Ok(completion) => json_response(StatusCode::OK, completion),
+        Err(error) => json_response(StatusCode::BAD_GATEWAY, serde_json::json!({ "error": error })),
+    };
+    parts.headers.remove(axum::http::header::CONTENT_TYPE);
+    parts.headers.remove(axum::http::header::CACHE_CONTROL);
+    response.headers_mut().extend(parts.headers);
+    Ok(response)
+}
+
+///
+/// Collect the body of an SSE chat completion response into one completion.
+///
+/// Waits until the stream ends.
+///
+/// # Arguments
+///  * `body` - SSE body of `handle_streaming_response`
+///
+/// # Returns
+///  * `chat.completion` response, or the stream's error payload
+///  * `ProxyError::Http` if the stream body cannot be read
+pub async fn collect_sse_body(
+    body: axum::body::Body,
+) -> Result<std::result::Result<OpenAiResponse, Value>> {
+    let bytes = axum::body::to_bytes(body, usize::MAX)
+        .await
+        .map_err(|e| ProxyError::Http(format!("Failed to read streamed response: {}", e), None))?;
+
+    let mut accumulator = StreamAccumulator::default();
+    accumulator.push_sse(&String::from_utf8_lossy(&bytes));
+    Ok(accumulator.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accumulates_text_and_tool_calls() {
+        let mut accumulator = StreamAccumulator::default();
+        accumulator.push_sse(concat!(
+            "retry: 3000\n\n",
+            "data: {\"id\":\"chatcmpl-1\",\"object\":\"chat.completion.chunk\",\"created\":7,",
+            "\"model\":\"claude\",\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Hel\"}}]}\n\n",
+            "data: {\"id\":\"chatcmpl-2\",\"created\":8,\"model\":\"claude\",",
+            "\"choices\":[{\"index\":0,\"delta\":{\"content\":\"lo\"}}]}\n\n",
+            "data: {\"choices\":[{\"index\":0,\"delta\":{\"tool_calls\":[{\"index\":0,",
+            "\"id\":\"toolu_1\",\"type\":\"function\",",
+            "\"function\":{\"name\":\"get_weather\",\"arguments\":\"\"}}]}}]}\n\n",
+            "data: {\"choices\":[{\"index\":0,\"delta\":{\"tool_calls\":[{\"index\":0,",
+            "\"function\":{\"arguments\":\"{\\\"city\\\":\"}}]}}]}\n\n",
+            "data: {\"choices\":[{\"index\":0,\"delta\":{\"tool_calls\":[{\"index\":0,",
+            "\"function\":{\"arguments\":\"\\\"Oslo\\\"}\"}}]}}]}\n\n",
+            "data: {\"choices\":[{\"index\":0,\"delta\":{},\"finish_reason\":\"tool_calls\"}]}\n\n",
+            "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":5,\"completion_tokens\":3,",
+            "\"total_tokens\":8}}\n\n",
+            "data: [DONE]\n\n",
+        ));
+
+        let response = serde_json::to_value(accumulator.finish().unwrap()).unwrap();
+        assert_eq!(response["id"], "chatcmpl-1");
+        assert_eq!(response["object"], "chat.completion");
+        assert_eq!(response["created"], 7);
+        assert_eq!(response["model"], "claude");
+        let choice = &response["choices"][0];
+        assert_eq!(choice["message"]["content"], "Hello");
+        assert_eq!(choice["message"]["tool_calls"][0]["id"], "toolu_1");
+        assert_eq!(choice["message"]["tool_calls"][0]["function"]["name"], "get_weather");
+        assert_eq!(
+            choice["message"]["tool_calls"][0]["function"]["arguments"],
+            "{\"city\":\"Oslo\"}"
+        );
+        assert_eq!(choice["finish_reason"], "tool_calls");
+        assert_eq!(response["usage"]["total_tokens"], 8);
+    }
+
+    #[test]
+    fn test_accumulates_legacy_function_call() {
+        let mut accumulator = StreamAccumulator::default();
+        accumulator.push_sse(concat!(
+            "data: {\"choices\":[{\"index\":0,\"delta\":{\"function_call\":",
+            "{\"name\":\"get_weather\",\"arguments\":\"\"}}}]}\n\n",
+            "data: {\"choices\":[{\"index\":0,\"delta\":{\"function_call\":",
+            "{\"arguments\":\"{}\"}}}]}\n\n",
+            "data: {\"choices\":[{\"index\":0,\"delta\":{},\"finish_reason\":\"function_call\"}]}\n\n",
+        ));
+
+        let response = serde_json::to_value(accumulator.finish().unwrap()).unwrap();
+        let choice = &response["choices"][0];
+        assert_eq!(choice["message"]["function_call"]["name"], "get_weather");
+        assert_eq!(choice["message"]["function_call"]["arguments"], "{}");
+        assert!(choice["message"].get("tool_calls").is_none());
+        assert_eq!(choice["finish_reason"], "function_call");
+    }
+
+    #[test]
+    fn test_error_without_output_is_returned() {
+        let mut accumulator = StreamAccumulator::default();
+        accumulator.push(r#"{"error":{"message":"overloaded"}}"#);
+        assert_eq!(accumulator.finish().unwrap_err()["message"], "overloaded");
+    }
+}
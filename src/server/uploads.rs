@@ -0,0 +1,308 @@
+//!
+//! File uploads attached to chat completions.
+//!
+//! `POST /v1/files/upload-and-chat` accepts `multipart/form-data` with a
+//! `file` part (PDF or image) and a `request` part holding an OpenAI chat
+//! completion request. The file is base64-encoded into a `document` or
+//! `image` content block, appended to the last user message, and the request
+//! then runs through the regular chat completion pipeline.
+//!
+//! Uploads are held in memory only and never written to disk.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::sync::Arc;
+
+use axum::Json;
+use axum::extract::multipart::{Field, MultipartError};
+use axum::extract::{DefaultBodyLimit, Multipart, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::Response;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde_json::{Value, json};
+
+use super::{AppState, chat_completions, create_error_response};
+use crate::config::UploadConfig;
+use crate::error::{ProxyError, Result};
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// File received in the `file` part of an upload.
+struct UploadedFile {
+    /** MIME type declared for the part, without parameters */
+    media_type: String,
+    /** file content */
+    data: Vec<u8>,
+}
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** Name of the multipart part carrying the file */
+const FILE_PART: &str = "file";
+
+/** Name of the multipart part carrying the chat completion request */
+const REQUEST_PART: &str = "request";
+
+/** Bytes allowed for the request part and multipart framing on top of the file */
+const REQUEST_PART_MAX_BYTES: usize = 2 * 1024 * 1024;
+
+/** Accepted MIME types and the Anthropic content block type they become */
+const SUPPORTED_MEDIA_TYPES: &[(&str, &str)] = &[
+    ("application/pdf", "document"),
+    ("image/jpeg", "image"),
+    ("image/png", "image"),
+    ("image/gif", "image"),
+    ("image/webp", "image"),
+];
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+///
+/// Request body limit of the upload endpoint.
+///
+/// # Arguments
+///  * `config` - upload configuration
+///
+/// # Returns
+///  * Body limit layer admitting a maximum-size file plus the request part
+pub fn body_limit(config: &UploadConfig) -> DefaultBodyLimit {
+    DefaultBodyLimit::max(config.max_file_bytes.saturating_add(REQUEST_PART_MAX_BYTES))
+}
+
+///
+/// Handle `POST /v1/files/upload-and-chat`.
+///
+/// # Arguments
+///  * `state` - shared application state
+///  * `headers` - request headers, passed on to the chat completion
+///  * `multipart` - `file` and `request` parts
+///
+/// # Returns
+///  * Chat completion response, `415` for unsupported file types, `413` for
+///    files above `upload.max_file_bytes`, or `400` for malformed uploads
+pub async fn upload_and_chat(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    multipart: Multipart,
+) -> Response {
+    let upload_config = state.config.read().await.upload.clone();
+    let request = match read_upload(multipart, &upload_config).await {
+        Ok((file, mut request)) => match attach_file(&mut request, &file) {
+            Ok(()) => request,
+            Err(e) => return create_error_response(&e),
+        },
+        Err(e) => return create_error_response(&e),
+    };
+
+    chat_completions(State(state), headers, Json(request)).await
+}
+
+///
+/// Read the `file` and `request` parts of an upload.
+///
+/// # Arguments
+///  * `multipart` - multipart request body
+///  * `config` - upload configuration
+///
+/// # Returns
+///  * Uploaded file and parsed request JSON
+///  * `ProxyError::UploadTooLarge` when the file exceeds `upload.max_file_bytes`
+///  * `ProxyError::Conversion` for missing or malformed parts
+async fn read_upload(
+    mut multipart: Multipart,
+    config: &UploadConfig,
+) -> Result<(UploadedFile, Value)> {
+    let mut file = None;
+    let mut request = None;
+
+    while let Some(field) = multipart.next_field().await.map_err(|e| multipart_error(e, config))? {
+        match field.name() {
+            Some(FILE_PART) => file = Some(read_file(field, config).await?),
+            Some(REQUEST_PART) => {
+                let text = field.text().await.map_err(|e| multipart_error(e, config))?;
+                request = Some(serde_json::from_str::<Value>(&text).map_err(|e| {
+                    ProxyError::Conversion(format!("Invalid JSON in the request part: {}", e))
+                })?);
+            }
+            _ => {}
+        }
+    }
+
+    let file = file.ok_or_else(|| missing_part(FILE_PART))?;
+    let request = request.ok_or_else(|| missing_part(REQUEST_PART))?;
+    Ok((file, request))
+}
+
+///
+/// Read the file part, enforcing the MIME type and size limit.
+///
+/// The MIME type is checked first, so unsupported files are rejected
+/// without reading them.
+async fn read_file(mut field: Field<'_>, config: &UploadConfig) -> Result<UploadedFile> {
+    let media_type = field
+        .content_type()
+        .and_then(|content_type| content_type.split(';').next())
+        .map(|media_type| media_type.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    if block_type(&media_type).is_none() {
+        return Err(ProxyError::UnsupportedMediaType(format!(
+            "'{}', expected one of {}",
+            media_type,
+            SUPPORTED_MEDIA_TYPES
+                .iter()
+                .map(|(media_type, _)| *media_type)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )));
+    }
+
+    let mut data = Vec::new();
+    while let Some(chunk) = field.chunk().await.map_err(|e| multipart_error(e, config))? {
+        if data.len() + chunk.len() > config.max_file_bytes {
+            return Err(ProxyError::UploadTooLarge { limit: config.max_file_bytes });
+        }
+        data.extend_from_slice(&chunk);
+    }
+    Ok(UploadedFile { media_type, data })
+}
+
+///
+/// Append an uploaded file to the last user message of a request.
+///
+/// String content is turned into a text block first, so the message keeps
+/// its text and gains the file.
+///
+/// # Arguments
+///  * `request` - OpenAI chat completion request, modified in place
+///  * `file` - uploaded file
+///
+/// # Returns
+///  * `Ok(())`, or `ProxyError::Conversion` when the request has no user message
+fn attach_file(request: &mut Value, file: &UploadedFile) -> Result<()> {
+    let block_type = block_type(&file.media_type)
+        .ok_or_else(|| ProxyError::UnsupportedMediaType(file.media_type.clone()))?;
+    let block = json!({
+        "type": block_type,
+        "source": {
+            "type": "base64",
+            "media_type": file.media_type,
+            "data": BASE64.encode(&file.data)
+        }
+    });
+
+    let message = request
+        .get_mut("messages")
+        .and_then(Value::as_array_mut)
+        .and_then(|messages| messages.iter_mut().rev().find(|message| message["role"] == "user"))
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| {
+            ProxyError::Conversion(
+                "Upload request needs a user message to attach the file to".to_string(),
+            )
+        })?;
+
+    let mut content = match message.remove("content") {
+        Some(Value::Array(blocks)) => blocks,
+        Some(Value::String(text)) if !text.is_empty() => {
+            vec![json!({ "type": "text", "text": text })]
+        }
+        _ => Vec::new(),
+    };
+    content.push(block);
+    message.insert("content".to_string(), Value::Array(content));
+    Ok(())
+}
+
+///
+/// Anthropic content block type of a supported MIME type.
+fn block_type(media_type: &str) -> Option<&'static str> {
+    SUPPORTED_MEDIA_TYPES
+        .iter()
+        .find(|(supported, _)| *supported == media_type)
+        .map(|(_, block)| *block)
+}
+
+///
+/// Error for a required multipart part that was not sent.
+fn missing_part(name: &str) -> ProxyError {
+    ProxyError::Conversion(format!("Upload is missing the '{}' part", name))
+}
+
+///
+/// Convert a multipart parsing error, keeping body limit violations as `413`.
+fn multipart_error(error: MultipartError, config: &UploadConfig) -> ProxyError {
+    if error.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        ProxyError::UploadTooLarge { limit: config.max_file_bytes }
+    } else {
+        ProxyError::Conversion(format!("Invalid multipart upload: {}", error.body_text()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attach_file_to_last_user_message() {
+        let mut request = json!({
+            "messages": [
+                {"role": "user", "content": "first"},
+                {"role": "assistant", "content": "ok"},
+                {"role": "user", "content": "Summarize this"}
+            ]
+        });
+        let file =
+            UploadedFile { media_type: "application/pdf".to_string(), data: b"%PDF".to_vec() };
+        attach_file(&mut request, &file).unwrap();
+
+        assert_eq!(request["messages"][0]["content"], "first");
+        let content = request["messages"][2]["content"].as_array().unwrap();
+        assert_eq!(content[0], json!({"type": "text", "text": "Summarize this"}));
+        assert_eq!(content[1]["type"], "document");
+        assert_eq!(content[1]["source"]["media_type"], "application/pdf");
+        assert_eq!(content[1]["source"]["data"], "JVBERg==");
+
+        let image = UploadedFile { media_type: "image/png".to_string(), data: vec![0x89] };
+        attach_file(&mut request, &image).unwrap();
+        assert_eq!(request["messages"][2]["content"][2]["type"], "image");
+
+        // The blocks reach the Anthropic request with their base64 source
+        let converter =
+            crate::converter::OpenAiToAnthropicConverter::new(crate::config::LogLevel::Info);
+        let converted = converter
+            .convert(
+                serde_json::from_value(request).unwrap(),
+                crate::converter::TargetFormat::Vertex,
+                None,
+            )
+            .unwrap();
+        let body = converted.to_request_body().unwrap();
+        let content = &body["messages"][2]["content"];
+        assert_eq!(content[1]["type"], "document");
+        assert_eq!(
+            content[1]["source"],
+            json!({
+                "type": "base64", "media_type": "application/pdf", "data": "JVBERg=="
+            })
+        );
+        assert_eq!(content[2]["source"]["type"], "base64");
+
+        let mut no_user = json!({"messages": [{"role": "system", "content": "s"}]});
+        assert!(matches!(attach_file(&mut no_user, &image), Err(ProxyError::Conversion(_))));
+    }
+
+    #[test]
+    fn test_supported_media_types() {
+        assert_eq!(block_type("application/pdf"), Some("document"));
+        assert_eq!(block_type("image/webp"), Some("image"));
+        assert_eq!(block_type("image/svg+xml"), None);
+        assert_eq!(block_type(""), None);
+    }
+}
@@ -0,0 +1,112 @@
+//!
+//! OpenTelemetry trace export.
+//!
+//! With `telemetry.enabled` the tracing spans of the proxy are exported to an
+//! OpenTelemetry collector over OTLP/HTTP (`telemetry.endpoint`), next to the
+//! regular log output. Each chat completion becomes a `chat_completions` trace
+//! (request ID, model, stream flag) whose `modelmux.chat_completion` span has
+//! the provider, latency and token counts as attributes, and the auth token
+//! fetch and upstream HTTP request as child spans. Tasks relaying a stream
+//! run in the same trace.
+//!
+//! Export needs a build with the `telemetry` feature; without it [`layer`]
+//! returns no layer and configuration validation warns about the setting.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+#[cfg(feature = "telemetry")]
+use opentelemetry::trace::TracerProvider as _;
+#[cfg(feature = "telemetry")]
+use opentelemetry_otlp::WithExportConfig;
+#[cfg(feature = "telemetry")]
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use tracing_subscriber::{Layer, Registry};
+
+use crate::config::TelemetryConfig;
+use crate::error::Result;
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// Handle of the span exporter; [`shutdown`](Self::shutdown) flushes pending spans.
+#[derive(Debug, Default)]
+pub struct TelemetryGuard {
+    /** tracer provider owning the batch exporter, when export is enabled */
+    #[cfg(feature = "telemetry")]
+    provider: Option<SdkTracerProvider>,
+}
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** Instrumentation scope name of the exported spans */
+#[cfg(feature = "telemetry")]
+const TRACER_NAME: &str = "modelmux";
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+///
+/// Build the tracing layer exporting spans to the configured collector.
+///
+/// # Arguments
+///  * `config` - telemetry section
+///
+/// # Returns
+///  * Export layer (`None` when export is disabled) and the guard flushing it
+///  * `ProxyError::Config` when the exporter cannot be created
+#[cfg(feature = "telemetry")]
+pub fn layer(
+    config: &TelemetryConfig,
+) -> Result<(Option<impl Layer<Registry> + Send + Sync>, TelemetryGuard)> {
+    use crate::config::TelemetryExporter;
+    use crate::error::ProxyError;
+
+    if !config.enabled {
+        return Ok((None, TelemetryGuard::default()));
+    }
+
+    let exporter = match config.exporter {
+        TelemetryExporter::Otlp => opentelemetry_otlp::SpanExporter::builder()
+            .with_http()
+            .with_endpoint(&config.endpoint)
+            .build()
+            .map_err(|e| ProxyError::config_with_source("OTLP span exporter", e))?,
+    };
+    let resource = opentelemetry_sdk::Resource::builder()
+        .with_service_name(config.service_name.clone())
+        .build();
+    let provider =
+        SdkTracerProvider::builder().with_batch_exporter(exporter).with_resource(resource).build();
+
+    let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer(TRACER_NAME));
+    Ok((Some(layer), TelemetryGuard { provider: Some(provider) }))
+}
+
+///
+/// Without the `telemetry` feature no spans are exported.
+#[cfg(not(feature = "telemetry"))]
+pub fn layer(
+    _config: &TelemetryConfig,
+) -> Result<(Option<impl Layer<Registry> + Send + Sync>, TelemetryGuard)> {
+    Ok((None::<tracing_subscriber::layer::Identity>, TelemetryGuard::default()))
+}
+
+impl TelemetryGuard {
+    ///
+    /// Flush pending spans and stop the exporter.
+    ///
+    /// Blocks until the batch exporter has sent its queue, so call it once the
+    /// server has stopped accepting requests.
+    pub fn shutdown(self) {
+        #[cfg(feature = "telemetry")]
+        if let Some(provider) = self.provider
+            && let Err(e) = provider.shutdown()
+        {
+            eprintln!("[modelmux] failed to flush telemetry spans: {}", e);
+        }
+    }
+}
@@ -0,0 +1,290 @@
+//!
+//! Token usage aggregation for billing and reporting.
+//!
+//! Collects input/output token counts reported by the upstream provider into
+//! hourly buckets per model. Buckets can be queried for a time range and
+//! rolled up to hourly or daily granularity, optionally priced using the
+//! `[usage.pricing]` cost table.
+//!
+//! Usage is kept in memory only and resets when the server restarts.
+//!
+//! Authors:
+//!   Jaro <yarenty@gmail.com>
+//!
+//! Copyright (c) 2026 SkyCorp
+
+/* --- uses ------------------------------------------------------------------------------------ */
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+
+use crate::config::ModelPricing;
+use crate::error::ProxyError;
+
+/* --- types ----------------------------------------------------------------------------------- */
+
+///
+/// In-memory usage aggregator keyed by hour and model.
+pub struct UsageAggregator {
+    /** time the aggregator was created (usage before this is unknown) */
+    started_at: DateTime<Utc>,
+    /** token counts keyed by (hour start epoch seconds, model) */
+    buckets: Mutex<BTreeMap<(i64, String), UsageCounts>>,
+}
+
+///
+/// Accumulated token counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UsageCounts {
+    /** prompt tokens consumed */
+    pub input_tokens: u64,
+    /** completion tokens generated */
+    pub output_tokens: u64,
+}
+
+///
+/// Aggregated usage for one time bucket and model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageRow {
+    /** start of the bucket (epoch seconds) */
+    pub bucket_start: i64,
+    /** model name */
+    pub model: String,
+    /** token counts within the bucket */
+    pub counts: UsageCounts,
+}
+
+///
+/// Bucket size used when reporting usage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageGranularity {
+    /// One row per hour and model
+    Hour,
+    /// One row per UTC day and model
+    Day,
+}
+
+/* --- constants ------------------------------------------------------------------------------ */
+
+/** Seconds in an hour bucket */
+const SECONDS_PER_HOUR: i64 = 3_600;
+
+/** Seconds in a day bucket */
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/* --- start of code -------------------------------------------------------------------------- */
+
+impl UsageAggregator {
+    ///
+    /// Create an empty aggregator.
+    pub fn new() -> Self {
+        Self { started_at: Utc::now(), buckets: Mutex::new(BTreeMap::new()) }
+    }
+
+    ///
+    /// Time the aggregator started collecting usage.
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.started_at
+    }
+
+    ///
+    /// Record token usage for a model at the current time.
+    ///
+    /// # Arguments
+    ///  * `model` - model name the usage is attributed to
+    ///  * `input_tokens` - prompt tokens consumed
+    ///  * `output_tokens` - completion tokens generated
+    pub fn record(&self, model: &str, input_tokens: u64, output_tokens: u64) {
+        self.record_at(Utc::now().timestamp(), model, input_tokens, output_tokens);
+    }
+
+    ///
+    /// Record token usage for a model at a given time.
+    ///
+    /// # Arguments
+    ///  * `timestamp` - epoch seconds of the usage
+    ///  * `model` - model name the usage is attributed to
+    ///  * `input_tokens` - prompt tokens consumed
+    ///  * `output_tokens` - completion tokens generated
+    pub fn record_at(&self, timestamp: i64, model: &str, input_tokens: u64, output_tokens: u64) {
+        if input_tokens == 0 && output_tokens == 0 {
+            return;
+        }
+
+        let hour = timestamp.div_euclid(SECONDS_PER_HOUR) * SECONDS_PER_HOUR;
+        if let Ok(mut buckets) = self.buckets.lock() {
+            let counts = buckets.entry((hour, model.to_string())).or_default();
+            counts.input_tokens += input_tokens;
+            counts.output_tokens += output_tokens;
+        }
+    }
+
+    ///
+    /// Query aggregated usage for a time range.
+    ///
+    /// # Arguments
+    ///  * `start` - inclusive range start (epoch seconds)
+    ///  * `end` - exclusive range end (epoch seconds)
+    ///  * `model` - only include this model when set
+    ///  * `granularity` - bucket size of the returned rows
+    ///
+    /// # Returns
+    ///  * Rows ordered by bucket start, then model name
+    pub fn query(
+        &self,
+        start: i64,
+        end: i64,
+        model: Option<&str>,
+        granularity: UsageGranularity,
+    ) -> Vec<UsageRow> {
+        let Ok(buckets) = self.buckets.lock() else {
+            return Vec::new();
+        };
+
+        let mut rolled_up: BTreeMap<(i64, String), UsageCounts> = BTreeMap::new();
+        for ((hour, bucket_model), counts) in buckets.iter() {
+            if *hour < start || *hour >= end {
+                continue;
+            }
+            if model.is_some_and(|m| m != bucket_model) {
+                continue;
+            }
+
+            let bucket_start = granularity.bucket_start(*hour);
+            let total = rolled_up.entry((bucket_start, bucket_model.clone())).or_default();
+            total.input_tokens += counts.input_tokens;
+            total.output_tokens += counts.output_tokens;
+        }
+
+        rolled_up
+            .into_iter()
+            .map(|((bucket_start, model), counts)| UsageRow { bucket_start, model, counts })
+            .collect()
+    }
+}
+
+impl Default for UsageAggregator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UsageCounts {
+    ///
+    /// Estimate the cost of these tokens.
+    ///
+    /// # Arguments
+    ///  * `pricing` - per-million-token prices for the model
+    ///
+    /// # Returns
+    ///  * Estimated cost in USD
+    pub fn cost_usd(&self, pricing: &ModelPricing) -> f64 {
+        (self.input_tokens as f64 * pricing.input_per_million_usd
+            + self.output_tokens as f64 * pricing.output_per_million_usd)
+            / 1_000_000.0
+    }
+}
+
+impl UsageGranularity {
+    ///
+    /// Align a timestamp to the start of its bucket.
+    fn bucket_start(self, timestamp: i64) -> i64 {
+        let size = match self {
+            UsageGranularity::Hour => SECONDS_PER_HOUR,
+            UsageGranularity::Day => SECONDS_PER_DAY,
+        };
+        timestamp.div_euclid(size) * size
+    }
+
+    ///
+    /// Format a bucket start as a label (`2025-01-29` or `2025-01-29T13:00:00Z`).
+    pub fn label(self, bucket_start: i64) -> String {
+        let Some(time) = DateTime::<Utc>::from_timestamp(bucket_start, 0) else {
+            return bucket_start.to_string();
+        };
+        match self {
+            UsageGranularity::Hour => time.format("%Y-%m-%dT%H:00:00Z").to_string(),
+            UsageGranularity::Day => time.format("%Y-%m-%d").to_string(),
+        }
+    }
+
+    ///
+    /// Name used in query parameters and responses.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            UsageGranularity::Hour => "hour",
+            UsageGranularity::Day => "day",
+        }
+    }
+}
+
+impl FromStr for UsageGranularity {
+    type Err = ProxyError;
+
+    /// Parse from string (case-insensitive).
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "hour" | "hourly" => Ok(UsageGranularity::Hour),
+            "day" | "daily" => Ok(UsageGranularity::Day),
+            _ => Err(ProxyError::Conversion(format!(
+                "Invalid granularity '{}'. Valid values are: hour, day",
+                s
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /** 2025-01-29T13:20:00Z */
+    const T0: i64 = 1_738_156_800;
+
+    #[test]
+    fn test_query_rolls_up_by_day_and_filters_model() {
+        let usage = UsageAggregator::new();
+        usage.record_at(T0, "sonnet", 100, 10);
+        usage.record_at(T0 + SECONDS_PER_HOUR, "sonnet", 50, 5);
+        usage.record_at(T0, "opus", 7, 3);
+
+        let rows = usage.query(0, i64::MAX, None, UsageGranularity::Day);
+        assert_eq!(rows.len(), 2);
+
+        let rows = usage.query(0, i64::MAX, Some("sonnet"), UsageGranularity::Day);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].counts, UsageCounts { input_tokens: 150, output_tokens: 15 });
+        assert_eq!(UsageGranularity::Day.label(rows[0].bucket_start), "2025-01-29");
+
+        let rows = usage.query(0, i64::MAX, Some("sonnet"), UsageGranularity::Hour);
+        assert_eq!(rows.len(), 2);
+    }
+
+    #[test]
+    fn test_query_respects_time_range() {
+        let usage = UsageAggregator::new();
+        usage.record_at(T0, "sonnet", 100, 10);
+
+        assert!(
+            usage.query(T0 + SECONDS_PER_HOUR, i64::MAX, None, UsageGranularity::Hour).is_empty()
+        );
+        assert_eq!(usage.query(0, T0 + 1, None, UsageGranularity::Hour).len(), 1);
+    }
+
+    #[test]
+    fn test_cost_usd() {
+        let counts = UsageCounts { input_tokens: 1_000_000, output_tokens: 100_000 };
+        let pricing = ModelPricing { input_per_million_usd: 3.0, output_per_million_usd: 15.0 };
+        assert!((counts.cost_usd(&pricing) - 4.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_granularity_from_str() {
+        assert_eq!("HOUR".parse::<UsageGranularity>().unwrap(), UsageGranularity::Hour);
+        assert_eq!("day".parse::<UsageGranularity>().unwrap(), UsageGranularity::Day);
+        assert!("week".parse::<UsageGranularity>().is_err());
+    }
+}
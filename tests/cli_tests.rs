@@ -175,3 +175,34 @@ fn test_validate_command() {
         stdout, stderr
     );
 }
+
+/// Test that keygen prints a key and its SHA-256 hash without touching config
+#[test]
+fn test_keygen_command() {
+    let mut cmd = get_binary_command();
+    cmd.arg("keygen");
+
+    let output = cmd.output().expect("Failed to execute command");
+
+    assert!(output.status.success(), "Keygen command should succeed");
+    let stdout = str::from_utf8(&output.stdout).expect("Invalid UTF-8");
+    assert!(
+        stdout.contains("will not be shown again"),
+        "Keygen output should warn about saving the key, got: {}",
+        stdout
+    );
+    let hash_line = stdout.lines().find(|l| l.starts_with("SHA-256:")).unwrap_or("");
+    let hash = hash_line.trim_start_matches("SHA-256:").trim();
+    assert_eq!(hash.len(), 64, "Keygen should print a SHA-256 hex hash, got: {}", stdout);
+}
+
+/// Test that keygen rejects unknown options
+#[test]
+fn test_keygen_unknown_option() {
+    let mut cmd = get_binary_command();
+    cmd.args(["keygen", "--bogus"]);
+
+    let output = cmd.output().expect("Failed to execute command");
+
+    assert!(!output.status.success(), "Keygen with unknown option should fail");
+}
@@ -1,6 +1,7 @@
 //! Configuration tests for ModelMux
 
-use modelmux::config::{Config, LogLevel, StreamingMode};
+use modelmux::config::{AuthConfig, Config, LogFormat, LogLevel, StreamingMode};
+use modelmux::provider::LlmProviderBackend;
 use temp_env::with_vars;
 use tempfile::TempDir;
 
@@ -303,6 +304,17 @@ fn test_log_level_from_str() {
     assert!("unknown".parse::<LogLevel>().is_err()); // Should fail for invalid input
 }
 
+/// Test LogFormat::from_str function
+#[test]
+fn test_log_format_from_str() {
+    assert_eq!("json".parse::<LogFormat>().unwrap(), LogFormat::Json);
+    assert_eq!("JSON".parse::<LogFormat>().unwrap(), LogFormat::Json); // Case insensitive
+    assert_eq!("pretty".parse::<LogFormat>().unwrap(), LogFormat::Pretty);
+    assert_eq!("text".parse::<LogFormat>().unwrap(), LogFormat::Pretty);
+    assert_eq!("compact".parse::<LogFormat>().unwrap(), LogFormat::Compact);
+    assert!("xml".parse::<LogFormat>().is_err());
+}
+
 /// Test build_predict_url functionality
 #[test]
 fn test_build_predict_url() {
@@ -336,3 +348,129 @@ fn test_build_predict_url() {
         },
     );
 }
+
+/// Test that MODELMUX_CONFIG_FILE replaces the user config file
+#[test]
+fn test_custom_config_file_skips_user_config() {
+    let temp_dir = TempDir::new().expect("temp dir");
+    let home = temp_dir.path().join("home");
+    let user_config_dir = home.join(".config").join("modelmux");
+    std::fs::create_dir_all(&user_config_dir).unwrap();
+    std::fs::write(
+        user_config_dir.join("config.toml"),
+        "[server]\nport = 7070\nlog_level = \"error\"\n\n[auth]\n\n[streaming]\n",
+    )
+    .unwrap();
+    let custom = temp_dir.path().join("modelmux.toml");
+    std::fs::write(&custom, "[server]\nport = 9191\n\n[auth]\n\n[streaming]\n").unwrap();
+
+    let vars = |config_file: &std::path::Path| {
+        vec![
+            ("HOME", Some(home.to_string_lossy().to_string())),
+            ("XDG_CONFIG_HOME", None),
+            ("MODELMUX_CONFIG_FILE", Some(config_file.to_string_lossy().to_string())),
+            ("MODELMUX_AUTH_SERVICE_ACCOUNT_JSON", Some(get_test_key_json().to_string())),
+            ("LLM_PROVIDER", Some("vertex".to_string())),
+            ("VERTEX_REGION", Some("us-central1".to_string())),
+            ("VERTEX_PROJECT", Some("test-project".to_string())),
+            ("VERTEX_LOCATION", Some("us-central1".to_string())),
+            ("VERTEX_PUBLISHER", Some("anthropic".to_string())),
+            ("VERTEX_MODEL_ID", Some("claude-3-5-sonnet@20241022".to_string())),
+        ]
+    };
+
+    with_vars(vars(&custom), || {
+        let config = Config::load().expect("Should load the custom config file");
+        assert_eq!(config.server.port, 9191);
+        assert_eq!(config.server.log_level, LogLevel::Info);
+    });
+
+    let missing = temp_dir.path().join("missing.toml");
+    with_vars(vars(&missing), || {
+        let error = Config::load().expect_err("A missing custom config file is an error");
+        assert!(error.to_string().contains("missing.toml"));
+    });
+}
+
+#[test]
+fn test_model_routes_inherit_primary_vertex_settings() {
+    let temp_dir = TempDir::new().expect("temp dir");
+    let custom = temp_dir.path().join("modelmux.toml");
+    std::fs::write(
+        &custom,
+        r#"[server]
+
+[auth]
+
+[streaming]
+
+[model_aliases]
+fast = "claude-fast"
+
+[model_routes.claude-fast]
+model = "claude-3-5-haiku@20241022"
+display_name = "Claude Fast"
+
+[model_routes.claude-smart]
+project = "opus-project"
+model = "claude-opus-4@20250514"
+"#,
+    )
+    .unwrap();
+
+    let vars = vec![
+        ("MODELMUX_CONFIG_FILE", Some(custom.to_string_lossy().to_string())),
+        ("MODELMUX_AUTH_SERVICE_ACCOUNT_JSON", Some(get_test_key_json().to_string())),
+        ("LLM_PROVIDER", Some("vertex".to_string())),
+        ("VERTEX_REGION", Some("us-central1".to_string())),
+        ("VERTEX_PROJECT", Some("test-project".to_string())),
+        ("VERTEX_LOCATION", Some("us-central1".to_string())),
+        ("VERTEX_PUBLISHER", Some("anthropic".to_string())),
+        ("VERTEX_MODEL_ID", Some("claude-3-5-sonnet@20241022".to_string())),
+    ];
+
+    with_vars(vars, || {
+        let config = Config::load().expect("Should load the model routes");
+        let (name, fast) = config.model_route(Some("fast")).expect("alias of a route");
+        assert_eq!(name, "claude-fast");
+        assert_eq!(fast.display_name, "Claude Fast");
+        let fast_url = fast.provider_config.build_request_url(false);
+        assert!(fast_url.contains("/projects/test-project/locations/us-central1/"));
+        assert!(fast_url.ends_with("/models/claude-3-5-haiku@20241022:rawPredict"));
+
+        let (_, smart) = config.model_route(Some("Claude-Smart")).expect("route");
+        assert_eq!(smart.display_name, "claude-smart");
+        assert!(smart.provider_config.build_request_url(false).contains("/projects/opus-project/"));
+        assert_eq!(config.response_model(Some("claude-smart")), "claude-smart");
+        assert!(config.model_route(Some("claude-3-5-sonnet@20241022")).is_none());
+    });
+}
+
+#[test]
+fn test_service_account_private_key_from_separate_file() {
+    let temp_dir = TempDir::new().expect("temp dir");
+    let mut metadata: serde_json::Value = serde_json::from_str(get_test_key_json()).unwrap();
+    let private_key = metadata.as_object_mut().unwrap().remove("private_key").unwrap();
+    let metadata_file = temp_dir.path().join("service-account.json");
+    std::fs::write(&metadata_file, metadata.to_string()).unwrap();
+    let key_file = temp_dir.path().join("private-key.pem");
+    std::fs::write(&key_file, private_key.as_str().unwrap()).unwrap();
+
+    let auth = AuthConfig {
+        service_account_file: Some(metadata_file.to_string_lossy().to_string()),
+        service_account_key_file: Some(key_file.to_string_lossy().to_string()),
+        ..Default::default()
+    };
+    let key = Config::load_service_account_key_from_auth(&auth).expect("split key files");
+    assert_eq!(key.client_email, "test@test-project.iam.gserviceaccount.com");
+    assert_eq!(key.private_key, private_key.as_str().unwrap());
+
+    let missing = AuthConfig {
+        service_account_key_file: Some(
+            temp_dir.path().join("missing.pem").to_string_lossy().to_string(),
+        ),
+        ..auth
+    };
+    let error = Config::load_service_account_key_from_auth(&missing).expect_err("missing key file");
+    assert!(error.to_string().contains("missing.pem"));
+}
@@ -45,11 +45,998 @@ async fn test_create_app_handles_invalid_config() {
     assert!(app.is_ok() || app.is_err(), "create_app should return Result");
 }
 
-/// Helper function to create test configuration
-fn create_test_config() -> Config {
+/// Test that an OpenAI-compatible provider forwards requests unchanged with its Bearer token
+#[tokio::test]
+async fn test_openai_compatible_passthrough() {
+    use axum::Json;
+    use axum::http::HeaderMap;
+    use axum::routing::post;
+    use modelmux::provider::{AuthStrategy, LlmProviderConfig, OpenAiCompatibleProvider};
+    use serde_json::{Value, json};
+
+    // Mock upstream echoing the request body and Authorization header
+    async fn mock_chat(headers: HeaderMap, Json(body): Json<Value>) -> axum::response::Response {
+        use axum::response::IntoResponse;
+        let auth = headers["authorization"].to_str().unwrap().to_string();
+        if body["stream"] == true {
+            let sse = "data: {\"choices\":[{\"delta\":{\"content\":\"hi\"}}]}\n\ndata: [DONE]\n\n";
+            return ([("content-type", "text/event-stream")], sse).into_response();
+        }
+        Json(json!({"object": "chat.completion", "auth": auth, "echo": body})).into_response()
+    }
+    let upstream = serve(axum::Router::new().route("/v1/chat/completions", post(mock_chat))).await;
+
+    let provider = OpenAiCompatibleProvider::new(
+        format!("http://{}/v1", upstream),
+        "/chat/completions".to_string(),
+        "mock-model".to_string(),
+        AuthStrategy::BearerToken("sk-test".to_string()),
+    );
+    let config = Config {
+        llm_provider: Some(LlmProviderConfig::OpenAiCompatible(provider)),
+        ..Default::default()
+    };
+    let proxy = serve(modelmux::create_app(config).await.unwrap()).await;
+    let client = reqwest::Client::new();
+    let url = format!("http://{}/v1/chat/completions", proxy);
+
+    let request = json!({
+        "messages": [{"role": "user", "content": "Hello"}],
+        "logit_bias": {"50256": -100},
+        "n": 2
+    });
+    let response = client.post(&url).json(&request).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "application/json; charset=utf-8");
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["auth"], "Bearer sk-test");
+    assert_eq!(body["echo"]["model"], "mock-model");
+    assert_eq!(body["echo"]["logit_bias"], request["logit_bias"]);
+    assert_eq!(body["echo"]["n"], 2);
+
+    let stream_request = json!({"model": "gpt-4o", "stream": true, "messages": []});
+    let response = client.post(&url).json(&stream_request).send().await.unwrap();
+    assert_eq!(response.headers()["content-type"], "text/event-stream; charset=utf-8");
+    assert_eq!(response.headers()["cache-control"], "no-cache");
+    let text = response.text().await.unwrap();
+    assert!(text.contains("\"content\":\"hi\""));
+    assert!(text.ends_with("data: [DONE]\n\n"));
+}
+
+/// Test that async jobs go to an OpenAI-compatible provider like synchronous requests
+#[tokio::test]
+async fn test_async_job_openai_compatible() {
+    use axum::Json;
+    use axum::http::HeaderMap;
+    use axum::routing::post;
+    use modelmux::provider::{AuthStrategy, LlmProviderConfig, OpenAiCompatibleProvider};
+    use serde_json::{Value, json};
+
+    async fn mock_chat(headers: HeaderMap, Json(body): Json<Value>) -> Json<Value> {
+        Json(json!({
+            "id": "chatcmpl-1", "object": "chat.completion", "model": body["model"],
+            "choices": [{"index": 0, "finish_reason": "stop",
+                "message": {"role": "assistant", "content": "from upstream"}}],
+            "auth": headers["authorization"].to_str().unwrap(), "echo": body
+        }))
+    }
+    let upstream = serve(axum::Router::new().route("/v1/chat/completions", post(mock_chat))).await;
+
+    let provider = OpenAiCompatibleProvider::new(
+        format!("http://{}/v1", upstream),
+        "/chat/completions".to_string(),
+        "mock-model".to_string(),
+        AuthStrategy::BearerToken("sk-test".to_string()),
+    );
+    let config = Config {
+        llm_provider: Some(LlmProviderConfig::OpenAiCompatible(provider)),
+        ..Default::default()
+    };
+    let proxy = serve(modelmux::create_app(config).await.unwrap()).await;
+    let client = reqwest::Client::new();
+
+    let request = json!({
+        "messages": [{"role": "user", "content": "Hello"}],
+        "logit_bias": {"50256": -100},
+        "stream": true
+    });
+    let response = client
+        .post(format!("http://{}/v1/chat/completions/async", proxy))
+        .json(&request)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 202);
+    let job: Value = response.json().await.unwrap();
+    let result_url = format!("http://{}/v1/jobs/{}/result", proxy, job["job_id"].as_str().unwrap());
+
+    let mut result = client.get(&result_url).send().await.unwrap();
+    for _ in 0..50 {
+        if result.status() != 425 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        result = client.get(&result_url).send().await.unwrap();
+    }
+    assert_eq!(result.status(), 200);
+    let body: Value = result.json().await.unwrap();
+    assert_eq!(body["choices"][0]["message"]["content"], "from upstream");
+    assert_eq!(body["auth"], "Bearer sk-test");
+    assert_eq!(body["echo"]["model"], "mock-model");
+    assert_eq!(body["echo"]["logit_bias"], request["logit_bias"]);
+    assert_eq!(body["echo"]["stream"], false);
+}
+
+/// Test that Azure OpenAI requests go to the deployment URL with the api-key header
+#[tokio::test]
+async fn test_azure_openai_passthrough() {
+    use std::collections::HashMap;
+
+    use axum::Json;
+    use axum::extract::{Path, Query};
+    use axum::http::HeaderMap;
+    use axum::routing::post;
+    use modelmux::provider::{AzureOpenAiProvider, LlmProviderConfig};
+    use serde_json::{Value, json};
+
+    // Mock Azure OpenAI echoing the deployment, api-version and credentials it received
+    async fn mock_chat(
+        Path(deployment): Path<String>,
+        Query(query): Query<HashMap<String, String>>,
+        headers: HeaderMap,
+        Json(body): Json<Value>,
+    ) -> Json<Value> {
+        Json(json!({
+            "object": "chat.completion",
+            "deployment": deployment,
+            "api_version": query.get("api-version"),
+            "api_key": headers.get("api-key").map(|value| value.to_str().unwrap()),
+            "has_authorization": headers.contains_key("authorization"),
+            "echo": body
+        }))
+    }
+    let upstream = serve(
+        axum::Router::new()
+            .route("/openai/deployments/{deployment}/chat/completions", post(mock_chat)),
+    )
+    .await;
+
+    let provider = AzureOpenAiProvider::new(
+        format!("http://{}", upstream),
+        "prod-gpt4o".to_string(),
+        "2024-10-21".to_string(),
+        "azure-key".to_string(),
+    );
+    let config = Config {
+        llm_provider: Some(LlmProviderConfig::AzureOpenAi(provider)),
+        ..Default::default()
+    };
+    let proxy = serve(modelmux::create_app(config).await.unwrap()).await;
+
+    let request = json!({"messages": [{"role": "user", "content": "Hello"}], "n": 2});
+    let response = reqwest::Client::new()
+        .post(format!("http://{}/v1/chat/completions", proxy))
+        .json(&request)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["deployment"], "prod-gpt4o");
+    assert_eq!(body["api_version"], "2024-10-21");
+    assert_eq!(body["api_key"], "azure-key");
+    assert_eq!(body["has_authorization"], false);
+    assert_eq!(body["echo"]["messages"], request["messages"]);
+    assert_eq!(body["echo"]["n"], 2);
+}
+
+/// Test that passthrough enforces `[limits]` and counts upstream errors as failures
+#[tokio::test]
+async fn test_openai_compatible_passthrough_errors() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::extract::State;
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use modelmux::provider::{AuthStrategy, LlmProviderConfig, OpenAiCompatibleProvider};
+    use serde_json::{Value, json};
+
+    async fn mock_chat(State(calls): State<Arc<AtomicUsize>>) -> (StatusCode, &'static str) {
+        calls.fetch_add(1, Ordering::SeqCst);
+        (StatusCode::INTERNAL_SERVER_ERROR, "{\"error\":{\"message\":\"upstream broke\"}}")
+    }
+    let calls = Arc::new(AtomicUsize::new(0));
+    let upstream = serve(
+        axum::Router::new()
+            .route("/v1/chat/completions", post(mock_chat))
+            .with_state(calls.clone()),
+    )
+    .await;
+
+    let provider = OpenAiCompatibleProvider::new(
+        format!("http://{}/v1", upstream),
+        "/chat/completions".to_string(),
+        "mock-model".to_string(),
+        AuthStrategy::BearerToken("sk-test".to_string()),
+    );
+    let mut config = Config {
+        llm_provider: Some(LlmProviderConfig::OpenAiCompatible(provider)),
+        ..Default::default()
+    };
+    config.limits.max_messages = 1;
+    let proxy = serve(modelmux::create_app(config).await.unwrap()).await;
+    let client = reqwest::Client::new();
+    let url = format!("http://{}/v1/chat/completions", proxy);
+
+    let too_many = json!({"messages": [
+        {"role": "user", "content": "Hello"},
+        {"role": "user", "content": "Again"}
+    ]});
+    let response = client.post(&url).json(&too_many).send().await.unwrap();
+    assert_eq!(response.status(), 400);
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+    let request = json!({"messages": [{"role": "user", "content": "Hello"}]});
+    let response = client.post(&url).json(&request).send().await.unwrap();
+    assert_eq!(response.status(), 500);
+    assert!(response.text().await.unwrap().contains("upstream broke"));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    let health: Value =
+        client.get(format!("http://{}/health", proxy)).send().await.unwrap().json().await.unwrap();
+    assert_eq!(health["metrics"]["successful_requests"], 0);
+    assert_eq!(health["metrics"]["failed_requests"], 2);
+}
+
+/// Test that the request ID is forwarded to Vertex AI and echoed back to the client
+#[tokio::test]
+async fn test_request_id_propagation() {
+    use axum::Json;
+    use axum::http::HeaderMap;
+    use axum::routing::post;
+    use modelmux::provider::{AuthStrategy, LlmProviderConfig, VertexProvider};
+    use serde_json::{Value, json};
+
+    // Mock Vertex AI answering with the X-Goog-Request-Id it received
+    async fn mock_predict(headers: HeaderMap) -> Json<Value> {
+        let request_id = headers
+            .get("x-goog-request-id")
+            .map_or("missing", |value| value.to_str().unwrap())
+            .to_string();
+        Json(json!({
+            "id": "msg_1",
+            "type": "message",
+            "role": "assistant",
+            "model": "test-model",
+            "content": [{"type": "text", "text": request_id}],
+            "stop_reason": "end_turn",
+            "usage": {"input_tokens": 1, "output_tokens": 1}
+        }))
+    }
+    let upstream =
+        serve(axum::Router::new().route("/v1/test-model:rawPredict", post(mock_predict))).await;
+
+    let vertex = VertexProvider {
+        predict_resource_url: format!("http://{}/v1/test-model", upstream),
+        display_model: "test-model".to_string(),
+        auth: AuthStrategy::BearerToken("token".to_string()),
+        max_output_tokens: None,
+    };
+    let config =
+        Config { llm_provider: Some(LlmProviderConfig::Vertex(vertex)), ..Default::default() };
+    let proxy = serve(modelmux::create_app(config).await.unwrap()).await;
+    let client = reqwest::Client::new();
+    let url = format!("http://{}/v1/chat/completions", proxy);
+
+    let request = json!({"messages": [{"role": "user", "content": "Hello"}], "stream": false});
+    let response =
+        client.post(&url).header("X-Request-ID", "req-123").json(&request).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["x-request-id"], "req-123");
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["choices"][0]["message"]["content"], "req-123");
+
+    // Without a client ID one is generated, and error bodies carry it too
+    let response = client.post(&url).json(&json!({"messages": "invalid"})).send().await.unwrap();
+    assert_eq!(response.status(), 400);
+    let request_id = response.headers()["x-request-id"].to_str().unwrap().to_string();
+    assert_eq!(request_id.len(), 36);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["request_id"], request_id);
+}
+
+/// Test that Anthropic beta features are forwarded to Vertex AI in the anthropic-beta header
+#[tokio::test]
+async fn test_anthropic_beta_forwarding() {
+    use axum::Json;
+    use axum::http::HeaderMap;
+    use axum::routing::post;
     use modelmux::provider::{AuthStrategy, LlmProviderConfig, VertexProvider};
+    use serde_json::{Value, json};
+
+    // Mock Vertex AI answering with the anthropic-beta header it received
+    async fn mock_predict(headers: HeaderMap) -> Json<Value> {
+        let beta = headers
+            .get("anthropic-beta")
+            .map_or("missing", |value| value.to_str().unwrap())
+            .to_string();
+        Json(json!({
+            "id": "msg_1",
+            "type": "message",
+            "role": "assistant",
+            "model": "test-model",
+            "content": [{"type": "text", "text": beta}],
+            "stop_reason": "end_turn",
+            "usage": {"input_tokens": 1, "output_tokens": 1}
+        }))
+    }
+    let upstream =
+        serve(axum::Router::new().route("/v1/test-model:rawPredict", post(mock_predict))).await;
+
+    let vertex = VertexProvider {
+        predict_resource_url: format!("http://{}/v1/test-model", upstream),
+        display_model: "test-model".to_string(),
+        auth: AuthStrategy::BearerToken("token".to_string()),
+        max_output_tokens: None,
+    };
+    let config =
+        Config { llm_provider: Some(LlmProviderConfig::Vertex(vertex)), ..Default::default() };
+    let proxy = serve(modelmux::create_app(config).await.unwrap()).await;
+    let client = reqwest::Client::new();
+    let url = format!("http://{}/v1/chat/completions", proxy);
+    let content = |body: &Value| body["choices"][0]["message"]["content"].clone();
+
+    let request = json!({"messages": [{"role": "user", "content": "Hello"}], "stream": false});
+    let response = client.post(&url).json(&request).send().await.unwrap();
+    assert_eq!(content(&response.json().await.unwrap()), "missing");
+
+    let response = client
+        .post(&url)
+        .header("X-Anthropic-Beta", "interleaved-thinking-2025-05-14")
+        .json(&request)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(content(&response.json().await.unwrap()), "interleaved-thinking-2025-05-14");
+
+    // Header and body features are merged without duplicates
+    let request = json!({
+        "messages": [{"role": "user", "content": "Hello"}],
+        "stream": false,
+        "x_anthropic_beta": "output-128k-2025-02-19, interleaved-thinking-2025-05-14"
+    });
+    let response = client
+        .post(&url)
+        .header("anthropic-beta", "interleaved-thinking-2025-05-14")
+        .json(&request)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        content(&response.json().await.unwrap()),
+        "interleaved-thinking-2025-05-14,output-128k-2025-02-19"
+    );
+}
+
+/// Test that oversized bodies get 413 and too many messages get 400
+#[tokio::test]
+async fn test_request_limits() {
+    use modelmux::config::RequestLimitsConfig;
+    use modelmux::provider::{AuthStrategy, LlmProviderConfig, VertexProvider};
+    use serde_json::{Value, json};
+
+    let vertex = VertexProvider {
+        predict_resource_url: "http://127.0.0.1:9/v1/test-model".to_string(),
+        display_model: "test-model".to_string(),
+        auth: AuthStrategy::BearerToken("token".to_string()),
+        max_output_tokens: None,
+    };
+    let config = Config {
+        llm_provider: Some(LlmProviderConfig::Vertex(vertex)),
+        limits: RequestLimitsConfig {
+            max_request_body_bytes: 1024,
+            max_messages: 2,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let proxy = serve(modelmux::create_app(config).await.unwrap()).await;
+    let client = reqwest::Client::new();
+    let url = format!("http://{}/v1/chat/completions", proxy);
+
+    let oversized = json!({"messages": [{"role": "user", "content": "x".repeat(2048)}]});
+    let response = client.post(&url).json(&oversized).send().await.unwrap();
+    assert_eq!(response.status(), 413);
+    let response = client
+        .post(format!("http://{}/v1/embeddings", proxy))
+        .json(&json!({"input": "x".repeat(2048)}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 413);
+
+    let message = json!({"role": "user", "content": "Hi"});
+    let response = client
+        .post(&url)
+        .json(&json!({"messages": [message, message, message], "stream": false}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    let body: Value = response.json().await.unwrap();
+    assert!(body["error"]["message"].as_str().unwrap().contains("Too many messages"));
+}
+
+/// Test that batch output files are converted whether buffered or streamed
+#[tokio::test]
+async fn test_batch_output_conversion() {
+    use modelmux::config::RequestLimitsConfig;
+    use modelmux::provider::{AuthStrategy, LlmProviderConfig, VertexProvider};
+    use serde_json::Value;
+
+    let vertex = VertexProvider {
+        predict_resource_url: "http://127.0.0.1:9/v1/test-model".to_string(),
+        display_model: "test-model".to_string(),
+        auth: AuthStrategy::BearerToken("token".to_string()),
+        max_output_tokens: None,
+    };
+    let config = Config {
+        llm_provider: Some(LlmProviderConfig::Vertex(vertex)),
+        limits: RequestLimitsConfig {
+            max_request_body_bytes: 512,
+            max_batch_bytes: 2048,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let proxy = serve(modelmux::create_app(config).await.unwrap()).await;
+    let client = reqwest::Client::new();
+    let url = format!("http://{}/v1/batches/convert", proxy);
+
+    let line = r#"{"custom_id":"a","result":{"type":"succeeded","message":{"content":[{"type":"text","text":"hello"}],"stop_reason":"end_turn","usage":{"input_tokens":3,"output_tokens":1}}}}"#;
+    let failed =
+        r#"{"custom_id":"b","result":{"type":"errored","error":{"type":"invalid_request"}}}"#;
+    let parse = |text: String| -> Vec<Value> {
+        text.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+    };
+
+    // Under the body limit the file is read at once
+    let small = format!("{}\nnot json\n{}\n", line, failed);
+    let response = client.post(&url).body(small).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "application/x-ndjson");
+    let converted = parse(response.text().await.unwrap());
+    assert_eq!(converted.len(), 1);
+    assert_eq!(converted[0]["model"], "test-model");
+    assert_eq!(converted[0]["choices"][0]["message"]["content"], "hello");
+    assert_eq!(converted[0]["usage"]["total_tokens"], 4);
+
+    // Over the body limit it is converted line by line
+    let large = format!("{}\n{}\n", line, failed).repeat(5);
+    assert!(large.len() > 512);
+    let response = client.post(format!("{}?model=claude", url)).body(large).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let converted = parse(response.text().await.unwrap());
+    assert_eq!(converted.len(), 5);
+    assert!(converted.iter().all(|response| response["model"] == "claude"));
+
+    // Over the batch limit it is rejected
+    let oversized = format!("{}\n", line).repeat(20);
+    assert!(oversized.len() > 2048);
+    let response = client.post(&url).body(oversized).send().await.unwrap();
+    assert_eq!(response.status(), 413);
+}
+
+/// Test that only configured origins get CORS headers
+#[tokio::test]
+async fn test_cors_allowed_origins() {
+    use modelmux::config::CorsConfig;
+    use modelmux::provider::{AuthStrategy, LlmProviderConfig, VertexProvider};
+
+    let vertex = VertexProvider {
+        predict_resource_url: "http://127.0.0.1:9/v1/test-model".to_string(),
+        display_model: "test-model".to_string(),
+        auth: AuthStrategy::BearerToken("token".to_string()),
+        max_output_tokens: None,
+    };
+    let config = Config {
+        llm_provider: Some(LlmProviderConfig::Vertex(vertex)),
+        cors: CorsConfig {
+            allowed_origins: vec!["https://app.example.com".to_string()],
+            allowed_methods: vec!["GET".to_string(), "POST".to_string()],
+            allow_credentials: true,
+            max_age_secs: 600,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let proxy = serve(modelmux::create_app(config).await.unwrap()).await;
+    let client = reqwest::Client::new();
+    let preflight = |origin: &'static str| {
+        client
+            .request(reqwest::Method::OPTIONS, format!("http://{}/v1/chat/completions", proxy))
+            .header("Origin", origin)
+            .header("Access-Control-Request-Method", "POST")
+            .header("Access-Control-Request-Headers", "content-type")
+            .send()
+    };
 
-    let service_account_key = ServiceAccountKey {
+    let allowed = preflight("https://app.example.com").await.unwrap();
+    let headers = allowed.headers();
+    assert_eq!(headers["access-control-allow-origin"], "https://app.example.com");
+    assert_eq!(headers["access-control-allow-credentials"], "true");
+    assert_eq!(headers["access-control-allow-headers"], "content-type");
+    assert_eq!(headers["access-control-max-age"], "600");
+
+    // Browsers block the request without an allow-origin header
+    let denied = preflight("https://evil.example.com").await.unwrap();
+    assert!(!denied.headers().contains_key("access-control-allow-origin"));
+    let denied = client
+        .get(format!("http://{}/v1/models", proxy))
+        .header("Origin", "https://evil.example.com")
+        .send()
+        .await
+        .unwrap();
+    assert!(!denied.headers().contains_key("access-control-allow-origin"));
+}
+
+/// Test that the proxy API key guards /v1/* but not /health or /metrics
+#[tokio::test]
+async fn test_proxy_api_key() {
+    use modelmux::provider::{AuthStrategy, LlmProviderConfig, VertexProvider};
+
+    let vertex = VertexProvider {
+        predict_resource_url: "http://127.0.0.1:9/v1/test-model".to_string(),
+        display_model: "test-model".to_string(),
+        auth: AuthStrategy::BearerToken("token".to_string()),
+        max_output_tokens: None,
+    };
+    let mut config =
+        Config { llm_provider: Some(LlmProviderConfig::Vertex(vertex)), ..Default::default() };
+    config.auth.proxy_api_key = Some("proxy-secret".to_string());
+    let proxy = serve(modelmux::create_app(config).await.unwrap()).await;
+    let client = reqwest::Client::new();
+    let models = format!("http://{}/v1/models", proxy);
+
+    let response = client.get(&models).bearer_auth("proxy-secret").send().await.unwrap();
+    assert_eq!(response.status(), 200);
+
+    let response = client.get(&models).bearer_auth("wrong-secret").send().await.unwrap();
+    assert_eq!(response.status(), 401);
+    assert_eq!(response.headers()["www-authenticate"], "Bearer");
+
+    let response = client.get(&models).send().await.unwrap();
+    assert_eq!(response.status(), 401);
+
+    for path in ["health", "metrics"] {
+        let response = client.get(format!("http://{}/{}", proxy, path)).send().await.unwrap();
+        assert_eq!(response.status(), 200, "/{} should not need the API key", path);
+    }
+}
+
+/// Test that client API keys authenticate requests and restrict them to their allowed models
+#[tokio::test]
+async fn test_client_api_keys() {
+    use axum::Json;
+    use axum::routing::post;
+    use modelmux::auth::hash_api_key;
+    use modelmux::config::ApiKeyEntry;
+    use modelmux::provider::{AuthStrategy, LlmProviderConfig, OpenAiCompatibleProvider};
+    use serde_json::{Value, json};
+
+    let upstream = serve(axum::Router::new().route(
+        "/v1/chat/completions",
+        post(|Json(body): Json<Value>| async move { Json(json!({"echo": body})) }),
+    ))
+    .await;
+    let provider = OpenAiCompatibleProvider::new(
+        format!("http://{}/v1", upstream),
+        "/chat/completions".to_string(),
+        "mock-model".to_string(),
+        AuthStrategy::BearerToken("sk-test".to_string()),
+    );
+    let mut config = Config {
+        llm_provider: Some(LlmProviderConfig::OpenAiCompatible(provider)),
+        ..Default::default()
+    };
+    config.auth.api_keys = vec![
+        ApiKeyEntry {
+            name: "ci".to_string(),
+            key_hash: hash_api_key("ci-key"),
+            allowed_models: vec!["mock-model".to_string()],
+        },
+        ApiKeyEntry {
+            name: "ops".to_string(),
+            key_hash: hash_api_key("ops-key"),
+            allowed_models: Vec::new(),
+        },
+    ];
+    let proxy = serve(modelmux::create_app(config).await.unwrap()).await;
+    let client = reqwest::Client::new();
+    let url = format!("http://{}/v1/chat/completions", proxy);
+    let chat = |model: Option<&str>| {
+        let mut request = json!({"messages": [{"role": "user", "content": "Hi"}]});
+        if let Some(model) = model {
+            request["model"] = json!(model);
+        }
+        request
+    };
+
+    let response = client.post(&url).json(&chat(None)).send().await.unwrap();
+    assert_eq!(response.status(), 401);
+    let response =
+        client.post(&url).bearer_auth("other-key").json(&chat(None)).send().await.unwrap();
+    assert_eq!(response.status(), 401);
+
+    for model in [Some("mock-model"), None] {
+        let response =
+            client.post(&url).bearer_auth("ci-key").json(&chat(model)).send().await.unwrap();
+        assert_eq!(response.status(), 200, "{:?}", model);
+    }
+    let response =
+        client.post(&url).bearer_auth("ci-key").json(&chat(Some("gpt-4o"))).send().await.unwrap();
+    assert_eq!(response.status(), 403);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["error"]["type"], "permission_error");
+    let response = client
+        .post(format!("{}/async", url))
+        .bearer_auth("ci-key")
+        .json(&chat(Some("gpt-4o")))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 403);
+
+    let response =
+        client.post(&url).bearer_auth("ops-key").json(&chat(Some("gpt-4o"))).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+}
+
+/// Test that requests fail over to the secondary provider while the primary returns 5xx
+#[tokio::test]
+async fn test_provider_failover() {
+    use axum::Json;
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use modelmux::provider::{AuthStrategy, LlmProviderConfig, VertexProvider};
+    use serde_json::{Value, json};
+
+    async fn mock_unavailable() -> StatusCode {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+    async fn mock_predict() -> Json<Value> {
+        Json(json!({
+            "id": "msg_1",
+            "type": "message",
+            "role": "assistant",
+            "model": "test-model",
+            "content": [{"type": "text", "text": "from secondary"}],
+            "stop_reason": "end_turn",
+            "usage": {"input_tokens": 1, "output_tokens": 1}
+        }))
+    }
+    let primary =
+        serve(axum::Router::new().route("/v1/test-model:rawPredict", post(mock_unavailable))).await;
+    let secondary =
+        serve(axum::Router::new().route("/v1/test-model:rawPredict", post(mock_predict))).await;
+
+    let vertex = |addr: std::net::SocketAddr| {
+        LlmProviderConfig::Vertex(VertexProvider {
+            predict_resource_url: format!("http://{}/v1/test-model", addr),
+            display_model: "test-model".to_string(),
+            auth: AuthStrategy::BearerToken("token".to_string()),
+            max_output_tokens: None,
+        })
+    };
+    let mut config = Config {
+        llm_provider: Some(vertex(primary)),
+        failover_provider: Some(vertex(secondary)),
+        ..Default::default()
+    };
+    config.server.enable_retries = false;
+    let proxy = serve(modelmux::create_app(config).await.unwrap()).await;
+    let client = reqwest::Client::new();
+
+    let request = json!({"messages": [{"role": "user", "content": "Hello"}], "stream": false});
+    let response = client
+        .post(format!("http://{}/v1/chat/completions", proxy))
+        .json(&request)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["choices"][0]["message"]["content"], "from secondary");
+
+    let health: Value =
+        client.get(format!("http://{}/health", proxy)).send().await.unwrap().json().await.unwrap();
+    assert_eq!(health["metrics"]["failover_activations"], 1);
+}
+
+/// Test that `StreamingMode::Always` streams upstream but answers `stream: false` with JSON
+#[tokio::test]
+async fn test_streaming_mode_always_returns_json() {
+    use axum::routing::post;
+    use modelmux::provider::{AuthStrategy, LlmProviderConfig, VertexProvider};
+    use serde_json::{Value, json};
+
+    async fn mock_stream_predict() -> ([(&'static str, &'static str); 1], String) {
+        let events = [
+            json!({"type": "message_start", "message": {"usage": {"input_tokens": 4}}}),
+            json!({"type": "content_block_start", "content_block": {"type": "text"}}),
+            json!({"type": "content_block_delta", "delta": {"type": "text_delta", "text": "Hello "}}),
+            json!({"type": "content_block_delta", "delta": {"type": "text_delta", "text": "there"}}),
+            json!({"type": "content_block_stop"}),
+            json!({"type": "content_block_start",
+                   "content_block": {"type": "tool_use", "id": "toolu_1", "name": "lookup"}}),
+            json!({"type": "content_block_delta",
+                   "delta": {"type": "input_json_delta", "partial_json": "{\"q\":"}}),
+            json!({"type": "content_block_delta",
+                   "delta": {"type": "input_json_delta", "partial_json": "\"rust\"}"}}),
+            json!({"type": "content_block_stop"}),
+            json!({"type": "message_delta", "delta": {"stop_reason": "tool_use"},
+                   "usage": {"output_tokens": 9}}),
+            json!({"type": "message_stop"}),
+        ];
+        let body = events.iter().map(|event| format!("data: {}\n\n", event)).collect();
+        ([("content-type", "text/event-stream")], body)
+    }
+    let upstream = serve(
+        axum::Router::new().route("/v1/test-model:streamRawPredict", post(mock_stream_predict)),
+    )
+    .await;
+
+    let mut config = Config {
+        llm_provider: Some(LlmProviderConfig::Vertex(VertexProvider {
+            predict_resource_url: format!("http://{}/v1/test-model", upstream),
+            display_model: "test-model".to_string(),
+            auth: AuthStrategy::BearerToken("token".to_string()),
+            max_output_tokens: None,
+        })),
+        ..Default::default()
+    };
+    config.streaming.mode = StreamingMode::Always;
+    config.server.enable_retries = false;
+    let proxy = serve(modelmux::create_app(config).await.unwrap()).await;
+
+    let request = json!({"messages": [{"role": "user", "content": "Hello"}], "stream": false});
+    let response = reqwest::Client::new()
+        .post(format!("http://{}/v1/chat/completions", proxy))
+        .json(&request)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["x-stream-forced"], "true");
+    assert_eq!(response.headers()["content-type"], "application/json; charset=utf-8");
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["object"], "chat.completion");
+    let message = &body["choices"][0]["message"];
+    assert_eq!(message["role"], "assistant");
+    assert_eq!(message["content"], "Hello there");
+    assert_eq!(message["tool_calls"][0]["id"], "toolu_1");
+    assert_eq!(message["tool_calls"][0]["function"]["name"], "lookup");
+    assert_eq!(message["tool_calls"][0]["function"]["arguments"], r#"{"q":"rust"}"#);
+    assert_eq!(body["choices"][0]["finish_reason"], "tool_calls");
+    assert_eq!(body["usage"]["prompt_tokens"], 4);
+    assert_eq!(body["usage"]["completion_tokens"], 9);
+}
+
+/// Test that identical concurrent non-streaming requests reach Vertex AI only once
+#[tokio::test]
+async fn test_identical_concurrent_requests_are_coalesced() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::Json;
+    use axum::routing::post;
+    use modelmux::provider::{AuthStrategy, LlmProviderConfig, VertexProvider};
+    use serde_json::{Value, json};
+
+    static UPSTREAM_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    // Slow mock Vertex AI, so the second request arrives while the first is in flight
+    async fn mock_predict() -> Json<Value> {
+        let call = UPSTREAM_CALLS.fetch_add(1, Ordering::SeqCst) + 1;
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        Json(json!({
+            "id": "msg_1",
+            "type": "message",
+            "role": "assistant",
+            "model": "test-model",
+            "content": [{"type": "text", "text": format!("call {}", call)}],
+            "stop_reason": "end_turn",
+            "usage": {"input_tokens": 1, "output_tokens": 1}
+        }))
+    }
+    let upstream =
+        serve(axum::Router::new().route("/v1/test-model:rawPredict", post(mock_predict))).await;
+
+    let mut config = Config {
+        llm_provider: Some(LlmProviderConfig::Vertex(VertexProvider {
+            predict_resource_url: format!("http://{}/v1/test-model", upstream),
+            display_model: "test-model".to_string(),
+            auth: AuthStrategy::BearerToken("token".to_string()),
+            max_output_tokens: None,
+        })),
+        ..Default::default()
+    };
+    config.streaming.dedup_enabled = true;
+    let proxy = serve(modelmux::create_app(config).await.unwrap()).await;
+    let client = reqwest::Client::new();
+    let url = format!("http://{}/v1/chat/completions", proxy);
+
+    let request = json!({"messages": [{"role": "user", "content": "Hello"}], "stream": false});
+    let send = || async {
+        let response = client.post(&url).json(&request).send().await.unwrap();
+        assert_eq!(response.status(), 200);
+        response.json::<Value>().await.unwrap()
+    };
+    let (first, second) = tokio::join!(send(), send());
+    assert_eq!(UPSTREAM_CALLS.load(Ordering::SeqCst), 1);
+    assert_eq!(first["choices"][0]["message"]["content"], "call 1");
+    assert_eq!(second["choices"][0]["message"]["content"], "call 1");
+
+    // Once the response was shared, the next identical request goes upstream again
+    let third = send().await;
+    assert_eq!(third["choices"][0]["message"]["content"], "call 2");
+}
+
+/// Test that hooks registered with `create_app_with_hooks` see requests and responses
+#[tokio::test]
+async fn test_request_hooks_fire() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::Json;
+    use axum::routing::post;
+    use modelmux::converter::anthropic_to_openai::OpenAiResponse;
+    use modelmux::converter::openai_to_anthropic::OpenAiRequest;
+    use modelmux::hooks::{RequestHook, async_trait};
+    use modelmux::provider::{AuthStrategy, LlmProviderConfig, VertexProvider};
+    use serde_json::{Value, json};
+
+    #[derive(Default)]
+    struct TestHook {
+        requests: AtomicUsize,
+        responses: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl RequestHook for TestHook {
+        async fn on_request(&self, req: &mut OpenAiRequest) -> modelmux::error::Result<()> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            req.max_tokens = Some(7);
+            Ok(())
+        }
+
+        async fn on_response(&self, resp: &mut OpenAiResponse) -> modelmux::error::Result<()> {
+            self.responses.fetch_add(1, Ordering::SeqCst);
+            resp.choices[0].message.content = Some("rewritten".to_string());
+            Ok(())
+        }
+    }
+
+    async fn mock_predict(Json(body): Json<Value>) -> Json<Value> {
+        assert_eq!(body["max_tokens"], 7);
+        Json(json!({
+            "content": [{"type": "text", "text": "original"}],
+            "stop_reason": "end_turn",
+            "usage": {"input_tokens": 1, "output_tokens": 1}
+        }))
+    }
+    async fn mock_stream_predict() -> ([(&'static str, &'static str); 1], String) {
+        let events = [
+            json!({"type": "content_block_delta", "delta": {"type": "text_delta", "text": "Hi"}}),
+            json!({"type": "message_stop"}),
+        ];
+        let body = events.iter().map(|event| format!("data: {}\n\n", event)).collect();
+        ([("content-type", "text/event-stream")], body)
+    }
+    let upstream = serve(
+        axum::Router::new()
+            .route("/v1/test-model:rawPredict", post(mock_predict))
+            .route("/v1/test-model:streamRawPredict", post(mock_stream_predict)),
+    )
+    .await;
+
+    let mut config = Config {
+        llm_provider: Some(LlmProviderConfig::Vertex(VertexProvider {
+            predict_resource_url: format!("http://{}/v1/test-model", upstream),
+            display_model: "test-model".to_string(),
+            auth: AuthStrategy::BearerToken("token".to_string()),
+            max_output_tokens: None,
+        })),
+        ..Default::default()
+    };
+    config.streaming.mode = StreamingMode::Standard;
+    config.server.enable_retries = false;
+    let hook = Arc::new(TestHook::default());
+    let app = modelmux::create_app_with_hooks(config, vec![hook.clone()]).await.unwrap();
+    let proxy = serve(app).await;
+    let client = reqwest::Client::new();
+    let url = format!("http://{}/v1/chat/completions", proxy);
+
+    let request = json!({"messages": [{"role": "user", "content": "Hello"}], "stream": false});
+    let response = client.post(&url).json(&request).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["choices"][0]["message"]["content"], "rewritten");
+    assert_eq!(hook.requests.load(Ordering::SeqCst), 1);
+    assert_eq!(hook.responses.load(Ordering::SeqCst), 1);
+
+    let request = json!({"messages": [{"role": "user", "content": "Hello"}], "stream": true});
+    let response = client.post(&url).json(&request).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert!(response.text().await.unwrap().contains("\"Hi\""));
+    assert_eq!(hook.requests.load(Ordering::SeqCst), 2);
+    assert_eq!(hook.responses.load(Ordering::SeqCst), 1);
+}
+
+/// Test that the service account key is fetched from a mocked Secret Manager
+#[tokio::test]
+async fn test_secret_manager_service_account_key() {
+    use axum::Json;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::get;
+    use base64::Engine;
+    use serde_json::{Value, json};
+
+    async fn mock_token(headers: HeaderMap) -> Json<Value> {
+        assert_eq!(headers["metadata-flavor"], "Google");
+        Json(json!({"access_token": "metadata-token", "expires_in": 3600, "token_type": "Bearer"}))
+    }
+    async fn mock_access(headers: HeaderMap) -> Result<Json<Value>, StatusCode> {
+        if headers["authorization"] != "Bearer metadata-token" {
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+        let key = serde_json::to_vec(&create_test_service_account_key()).unwrap();
+        let data = base64::engine::general_purpose::STANDARD.encode(key);
+        Ok(Json(json!({
+            "name": "projects/p/secrets/sa-key/versions/3",
+            "payload": {"data": data}
+        })))
+    }
+    let mock = serve(
+        axum::Router::new()
+            .route("/token", get(mock_token))
+            .route("/v1/projects/p/secrets/sa-key/versions/latest:access", get(mock_access)),
+    )
+    .await;
+
+    let mut auth = modelmux::config::AuthConfig {
+        secret_manager_resource: Some("projects/p/secrets/sa-key/versions/latest".to_string()),
+        secret_manager_endpoint: Some(format!("http://{}", mock)),
+        ..Default::default()
+    };
+    auth.workload_identity.token_url = Some(format!("http://{}/token", mock));
+
+    // Configuration loading is synchronous; keep this runtime free to serve the mock
+    let load = |auth: modelmux::config::AuthConfig| {
+        tokio::task::spawn_blocking(move || Config::load_service_account_key_from_auth(&auth))
+    };
+    let key = load(auth.clone()).await.unwrap().unwrap();
+    assert_eq!(key.client_email, "test@test-project.iam.gserviceaccount.com");
+
+    // Unreachable metadata server without a fallback key fails with a clear error
+    auth.secret_manager_resource = Some("projects/p/secrets/other/versions/1".to_string());
+    auth.workload_identity.token_url = Some("http://127.0.0.1:9/token".to_string());
+    let error = load(auth).await.unwrap().unwrap_err().to_string();
+    assert!(error.contains("metadata server"), "{}", error);
+}
+
+/// Serve a router on a random local port, returning its address
+async fn serve(app: axum::Router) -> std::net::SocketAddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    addr
+}
+
+/// Helper function to create a test service account key
+fn create_test_service_account_key() -> ServiceAccountKey {
+    ServiceAccountKey {
         account_type: "service_account".to_string(),
         project_id: "test-project".to_string(),
         private_key_id: "test-key-id".to_string(),
@@ -61,11 +1048,19 @@ fn create_test_config() -> Config {
         auth_provider_x509_cert_url: "https://www.googleapis.com/oauth2/v1/certs".to_string(),
         client_x509_cert_url: "https://www.googleapis.com/robot/v1/metadata/x509/test%40test-project.iam.gserviceaccount.com".to_string(),
         universe_domain: None,
-    };
+    }
+}
+
+/// Helper function to create test configuration
+fn create_test_config() -> Config {
+    use modelmux::provider::{AuthStrategy, LlmProviderConfig, VertexProvider};
+
+    let service_account_key = create_test_service_account_key();
     let vertex = VertexProvider {
         predict_resource_url: "https://test.example.com/v1/test-model".to_string(),
         display_model: "test-model".to_string(),
         auth: AuthStrategy::GcpOAuth2(Box::new(service_account_key)),
+        max_output_tokens: None,
     };
     Config {
         server: modelmux::config::ServerConfig {
@@ -73,14 +1068,17 @@ fn create_test_config() -> Config {
             log_level: LogLevel::Info,
             enable_retries: true,
             max_retry_attempts: 3,
+            ..Default::default()
         },
         auth: modelmux::config::AuthConfig::default(),
         streaming: modelmux::config::StreamingConfig {
             mode: StreamingMode::Auto,
             buffer_size: 65536,
             chunk_timeout_ms: 5000,
+            ..Default::default()
         },
         vertex: None,
         llm_provider: Some(LlmProviderConfig::Vertex(vertex)),
+        ..Default::default()
     }
 }